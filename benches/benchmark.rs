@@ -6,7 +6,7 @@ use std::io::Cursor;
 use std::time::Instant;
 
 use bq_schema_gen::{
-    generate_schema_from_csv, generate_schema_from_json, GeneratorConfig, InputFormat,
+    generate_schema_from_csv, generate_schema_from_json, GeneratorConfig, InputFormat, RawRecord,
     SchemaGenerator, SchemaMap,
 };
 use serde_json::json;
@@ -497,6 +497,53 @@ fn bench_watch_file_change_handling() {
     }
 }
 
+fn bench_raw_records() {
+    println!("\n11. Parsed vs Raw Records (nested, parsing included)");
+    println!("{:-<60}", "");
+
+    let lines: Vec<String> = (0..100_000)
+        .map(|i| {
+            format!(
+                r#"{{"id": {}, "name": "user_{}", "created": "2024-01-15T10:30:00Z", "user": {{"profile": {{"city": "NYC", "age": {}}}, "tags": ["a", "b"]}}, "metadata": {{"version": "{}", "source": "web"}}}}"#,
+                i,
+                i,
+                i % 90,
+                i % 7
+            )
+        })
+        .collect();
+
+    for &count in &[10_000, 100_000] {
+        let mut generator = SchemaGenerator::new(GeneratorConfig::default());
+        let mut schema_map = SchemaMap::new();
+        let start = Instant::now();
+        for line in &lines[..count] {
+            let record: serde_json::Value = serde_json::from_str(line).unwrap();
+            generator.process_record(&record, &mut schema_map).unwrap();
+        }
+        let parsed = start.elapsed();
+
+        let mut generator = SchemaGenerator::new(GeneratorConfig::default());
+        let mut schema_map = SchemaMap::new();
+        let start = Instant::now();
+        for line in &lines[..count] {
+            let record = RawRecord::parse(line).unwrap();
+            generator
+                .process_raw_record(&record, &mut schema_map)
+                .unwrap();
+        }
+        let raw = start.elapsed();
+
+        println!(
+            "  {:>7} records: parsed {:>8.2}ms, raw {:>8.2}ms ({:.1}x)",
+            count,
+            parsed.as_secs_f64() * 1000.0,
+            raw.as_secs_f64() * 1000.0,
+            parsed.as_secs_f64() / raw.as_secs_f64()
+        );
+    }
+}
+
 fn main() {
     println!("BigQuery Schema Generator Benchmarks\n");
     println!("{:=<60}", "");
@@ -511,6 +558,7 @@ fn main() {
     bench_schema_merging();
     bench_watch_state_creation();
     bench_watch_file_change_handling();
+    bench_raw_records();
 
     println!("\n{:=<60}", "");
     println!("Benchmarks complete.");
@@ -17,24 +17,32 @@ fn main() {
             field_type: "INTEGER".to_string(),
             mode: "REQUIRED".to_string(),
             fields: None,
+            precision: None,
+            scale: None,
         },
         BqSchemaField {
             name: "name".to_string(),
             field_type: "STRING".to_string(),
             mode: "NULLABLE".to_string(),
             fields: None,
+            precision: None,
+            scale: None,
         },
         BqSchemaField {
             name: "email".to_string(),
             field_type: "STRING".to_string(),
             mode: "NULLABLE".to_string(),
             fields: None,
+            precision: None,
+            scale: None,
         },
         BqSchemaField {
             name: "count".to_string(),
             field_type: "INTEGER".to_string(),
             mode: "NULLABLE".to_string(),
             fields: None,
+            precision: None,
+            scale: None,
         },
     ];
 
@@ -45,12 +53,16 @@ fn main() {
             field_type: "INTEGER".to_string(),
             mode: "REQUIRED".to_string(),
             fields: None,
+            precision: None,
+            scale: None,
         },
         BqSchemaField {
             name: "name".to_string(),
             field_type: "STRING".to_string(),
             mode: "REQUIRED".to_string(), // Changed: NULLABLE -> REQUIRED (BREAKING)
             fields: None,
+            precision: None,
+            scale: None,
         },
         // "email" field removed (BREAKING)
         BqSchemaField {
@@ -58,12 +70,16 @@ fn main() {
             field_type: "FLOAT".to_string(), // Changed: INTEGER -> FLOAT (safe widening)
             mode: "NULLABLE".to_string(),
             fields: None,
+            precision: None,
+            scale: None,
         },
         BqSchemaField {
             name: "created_at".to_string(), // New field added (safe)
             field_type: "TIMESTAMP".to_string(),
             mode: "NULLABLE".to_string(),
             fields: None,
+            precision: None,
+            scale: None,
         },
     ];
 
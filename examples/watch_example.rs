@@ -20,6 +20,8 @@ fn main() {
         on_change: None,            // No command to run after schema generation
         quiet: false,               // Show progress messages
         ignore_invalid_lines: true, // Skip malformed JSON lines
+        per_file: false,            // Write one merged schema
+        output_dir: None,           // Only used with per_file
     };
     println!("  Debounce: {} ms", config.debounce_ms);
     println!("  Quiet mode: {}", config.quiet);
@@ -33,6 +35,8 @@ fn main() {
         on_change: Some("echo 'Schema updated!'".to_string()),
         quiet: true,
         ignore_invalid_lines: false,
+        per_file: false,
+        output_dir: None,
     };
     println!("  On change: {:?}", config_with_command.on_change);
     println!();
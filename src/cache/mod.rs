@@ -0,0 +1,395 @@
+//! On-disk cache of per-file schemas.
+//!
+//! Regenerating a schema for many input files that rarely change mostly
+//! repeats work. The cache keeps each file's own `SchemaMap`, with the error
+//! logs and conflicts it produced, in one JSON entry per input path. An entry
+//! is reused while the file's size and modification time are unchanged. With
+//! content hashing, it is also reused when only the modification time changed
+//! and the content hash still matches, as after re-syncing a bucket mirror.
+//!
+//! Entries record a fingerprint of the generator config and existing schema,
+//! so changing either regenerates every file. Stats and provenance are not
+//! cached.
+
+use std::collections::hash_map::DefaultHasher;
+use std::fs::{self, File};
+use std::hash::{Hash, Hasher};
+use std::io::{self, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ErrorLog, Result};
+use crate::schema::{FieldConflict, GeneratorConfig, SchemaGenerator, SchemaMap};
+
+/// Schema of a single input file, as stored in the cache.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileSchema {
+    /// Number of records processed
+    pub lines: usize,
+    /// Schema built from the file alone
+    #[serde(with = "crate::schema::state::schema_map_format")]
+    pub schema_map: SchemaMap,
+    /// Non-fatal errors, numbered from the file's first record
+    pub error_logs: Vec<ErrorLog>,
+    /// Fields whose values conflicted within the file
+    pub conflicts: Vec<FieldConflict>,
+}
+
+impl FileSchema {
+    /// Take the schema a generator built from one file.
+    pub fn from_generator(generator: &SchemaGenerator, schema_map: SchemaMap) -> Self {
+        Self {
+            lines: generator.line_number(),
+            schema_map,
+            error_logs: generator.error_logs().to_vec(),
+            conflicts: generator.conflicts().to_vec(),
+        }
+    }
+}
+
+/// Size and modification time of an input file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileStamp {
+    /// Size in bytes
+    pub size: u64,
+    /// Modification time, in seconds since the Unix epoch
+    pub modified_secs: u64,
+    /// Sub-second part of the modification time
+    pub modified_nanos: u32,
+}
+
+impl FileStamp {
+    /// Read the stamp of a file.
+    pub fn of(path: &Path) -> io::Result<Self> {
+        let metadata = fs::metadata(path)?;
+        let modified = metadata
+            .modified()?
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        Ok(Self {
+            size: metadata.len(),
+            modified_secs: modified.as_secs(),
+            modified_nanos: modified.subsec_nanos(),
+        })
+    }
+}
+
+/// Result of looking up a file in the cache.
+#[derive(Debug)]
+pub enum CacheLookup {
+    /// The file is unchanged since its schema was cached
+    Hit(FileSchema),
+    /// The file must be read; its stamp is passed to [`SchemaCache::put`]
+    Miss(FileStamp),
+}
+
+/// A cache entry file.
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    /// Input path, to tell apart paths whose hashes collide
+    path: PathBuf,
+    /// Fingerprint of the config the schema was generated with
+    fingerprint: String,
+    stamp: FileStamp,
+    /// Hash of the file's bytes, if content hashing was enabled
+    content_hash: Option<u64>,
+    schema: FileSchema,
+}
+
+/// Directory of cached per-file schemas.
+#[derive(Debug, Clone)]
+pub struct SchemaCache {
+    dir: PathBuf,
+    /// Fingerprint of the config and options schemas are generated with
+    fingerprint: String,
+    /// Compare content hashes of files whose modification time changed
+    hash_contents: bool,
+}
+
+impl SchemaCache {
+    /// Open (creating if needed) a cache directory for schemas generated with `config`.
+    ///
+    /// `ignore_invalid_lines` is part of the fingerprint, since a file with
+    /// invalid lines only has a schema when they are skipped. So is the
+    /// `known_schema` the file's generator is given, since inference looks
+    /// at it even though the cached schema doesn't include it.
+    pub fn open(
+        dir: &Path,
+        config: &GeneratorConfig,
+        known_schema: Option<&SchemaMap>,
+        ignore_invalid_lines: bool,
+        hash_contents: bool,
+    ) -> Result<Self> {
+        fs::create_dir_all(dir)?;
+        let fingerprint = format!(
+            "{}-{:016x}",
+            env!("CARGO_PKG_VERSION"),
+            hash_of(&format!(
+                "{:?}|{:?}|{}",
+                config, known_schema, ignore_invalid_lines
+            ))
+        );
+        Ok(Self {
+            dir: dir.to_owned(),
+            fingerprint,
+            hash_contents,
+        })
+    }
+
+    /// Look up the cached schema of a file.
+    ///
+    /// Returns an error only if the file itself can't be read; a missing,
+    /// stale or unreadable entry is a miss.
+    pub fn lookup(&self, path: &Path) -> io::Result<CacheLookup> {
+        let stamp = FileStamp::of(path)?;
+        let Some(mut entry) = self.read_entry(path) else {
+            return Ok(CacheLookup::Miss(stamp));
+        };
+
+        if entry.stamp == stamp {
+            return Ok(CacheLookup::Hit(entry.schema));
+        }
+        // Only the modification time changed: compare contents
+        if entry.stamp.size == stamp.size
+            && entry.content_hash.is_some()
+            && entry.content_hash == self.content_hash(path)?
+        {
+            entry.stamp = stamp;
+            // Saves hashing the file again next time; the entry is still valid without it
+            let _ = self.write_entry(path, &entry);
+            return Ok(CacheLookup::Hit(entry.schema));
+        }
+        Ok(CacheLookup::Miss(stamp))
+    }
+
+    /// Store the schema of a file read after `lookup` returned `stamp`.
+    ///
+    /// Returns `false` without storing it if the file changed since, as the
+    /// schema may not match its new content.
+    pub fn put(&self, path: &Path, stamp: FileStamp, schema: &FileSchema) -> Result<bool> {
+        let content_hash = self.content_hash(path)?;
+        if FileStamp::of(path)? != stamp {
+            return Ok(false);
+        }
+
+        let entry = CacheEntry {
+            path: entry_path_key(path),
+            fingerprint: self.fingerprint.clone(),
+            stamp,
+            content_hash,
+            schema: schema.clone(),
+        };
+        self.write_entry(path, &entry)?;
+        Ok(true)
+    }
+
+    /// Read the entry for `path`, if it exists and was made with the same config.
+    fn read_entry(&self, path: &Path) -> Option<CacheEntry> {
+        let file = File::open(self.entry_file(path)).ok()?;
+        let entry: CacheEntry = serde_json::from_reader(BufReader::new(file)).ok()?;
+        (entry.fingerprint == self.fingerprint && entry.path == entry_path_key(path))
+            .then_some(entry)
+    }
+
+    /// Write an entry, replacing any previous one at once.
+    fn write_entry(&self, path: &Path, entry: &CacheEntry) -> Result<()> {
+        let entry_file = self.entry_file(path);
+        let temp_file = entry_file.with_extension(format!("tmp{}", std::process::id()));
+        let json = serde_json::to_vec(entry).map_err(io::Error::other)?;
+        fs::write(&temp_file, json)?;
+        fs::rename(&temp_file, &entry_file).inspect_err(|_| {
+            let _ = fs::remove_file(&temp_file);
+        })?;
+        Ok(())
+    }
+
+    /// Path of the entry file for an input path.
+    fn entry_file(&self, path: &Path) -> PathBuf {
+        self.dir
+            .join(format!("{:016x}.json", hash_of(&entry_path_key(path))))
+    }
+
+    /// Hash of a file's bytes, if content hashing is enabled.
+    fn content_hash(&self, path: &Path) -> io::Result<Option<u64>> {
+        if !self.hash_contents {
+            return Ok(None);
+        }
+
+        let mut file = File::open(path)?;
+        let mut hasher = DefaultHasher::new();
+        let mut buf = vec![0; 64 * 1024];
+        loop {
+            let n = file.read(&mut buf)?;
+            if n == 0 {
+                break;
+            }
+            hasher.write(&buf[..n]);
+        }
+        Ok(Some(hasher.finish()))
+    }
+}
+
+/// The path an entry is keyed by: absolute, so relative paths from different
+/// working directories don't share entries.
+fn entry_path_key(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_owned())
+}
+
+fn hash_of<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{BqMode, BqType, SchemaEntry};
+    use std::time::{Duration, SystemTime};
+
+    fn file_schema(bq_type: BqType) -> FileSchema {
+        let mut schema_map = SchemaMap::new();
+        schema_map.insert(
+            "a".to_string(),
+            SchemaEntry::new("a".to_string(), bq_type, BqMode::Nullable),
+        );
+        FileSchema {
+            lines: 1,
+            schema_map,
+            error_logs: vec![ErrorLog {
+                line_number: 1,
+                msg: "problem".to_string(),
+            }],
+            conflicts: vec![],
+        }
+    }
+
+    fn lookup_hit(cache: &SchemaCache, path: &Path) -> Option<FileSchema> {
+        match cache.lookup(path).unwrap() {
+            CacheLookup::Hit(schema) => Some(schema),
+            CacheLookup::Miss(_) => None,
+        }
+    }
+
+    fn put(cache: &SchemaCache, path: &Path, schema: &FileSchema) {
+        let CacheLookup::Miss(stamp) = cache.lookup(path).unwrap() else {
+            panic!("expected a miss");
+        };
+        assert!(cache.put(path, stamp, schema).unwrap());
+    }
+
+    fn set_modified(path: &Path, offset_secs: u64) {
+        File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(offset_secs))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_hit_until_file_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("data.json");
+        fs::write(&input, "{\"a\": 1}\n").unwrap();
+        let cache = SchemaCache::open(
+            &dir.path().join("cache"),
+            &GeneratorConfig::default(),
+            None,
+            false,
+            false,
+        )
+        .unwrap();
+
+        assert!(lookup_hit(&cache, &input).is_none());
+        put(&cache, &input, &file_schema(BqType::Integer));
+        let cached = lookup_hit(&cache, &input).unwrap();
+        assert_eq!(cached.schema_map["a"].bq_type, BqType::Integer);
+        assert_eq!(cached.error_logs[0].msg, "problem");
+
+        fs::write(&input, "{\"a\": \"x\"}\n").unwrap();
+        set_modified(&input, 10);
+        assert!(lookup_hit(&cache, &input).is_none());
+    }
+
+    #[test]
+    fn test_config_change_misses() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("data.json");
+        fs::write(&input, "{\"a\": 1}\n").unwrap();
+        let cache_dir = dir.path().join("cache");
+        let cache = SchemaCache::open(&cache_dir, &GeneratorConfig::default(), None, false, false);
+        put(&cache.unwrap(), &input, &file_schema(BqType::Integer));
+
+        let keep_nulls = GeneratorConfig {
+            keep_nulls: true,
+            ..Default::default()
+        };
+        let known = file_schema(BqType::Bytes).schema_map;
+        for cache in [
+            SchemaCache::open(&cache_dir, &keep_nulls, None, false, false),
+            SchemaCache::open(
+                &cache_dir,
+                &GeneratorConfig::default(),
+                Some(&known),
+                false,
+                false,
+            ),
+            SchemaCache::open(&cache_dir, &GeneratorConfig::default(), None, true, false),
+        ] {
+            assert!(lookup_hit(&cache.unwrap(), &input).is_none());
+        }
+        let cache = SchemaCache::open(&cache_dir, &GeneratorConfig::default(), None, false, false);
+        assert!(lookup_hit(&cache.unwrap(), &input).is_some());
+    }
+
+    #[test]
+    fn test_content_hash_survives_touch() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("data.json");
+        fs::write(&input, "{\"a\": 1}\n").unwrap();
+        let cache_dir = dir.path().join("cache");
+        let config = GeneratorConfig::default();
+        let hashed = SchemaCache::open(&cache_dir, &config, None, false, true).unwrap();
+        put(&hashed, &input, &file_schema(BqType::Integer));
+
+        // Same content, new modification time
+        set_modified(&input, 10);
+        let unhashed = SchemaCache::open(&cache_dir, &config, None, false, false).unwrap();
+        assert!(lookup_hit(&unhashed, &input).is_none());
+        assert!(lookup_hit(&hashed, &input).is_some());
+        // The entry now has the new stamp
+        assert!(lookup_hit(&unhashed, &input).is_some());
+
+        // Same size, different content
+        fs::write(&input, "{\"a\": 2}\n").unwrap();
+        set_modified(&input, 20);
+        assert!(lookup_hit(&hashed, &input).is_none());
+    }
+
+    #[test]
+    fn test_put_skips_file_changed_while_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("data.json");
+        fs::write(&input, "{\"a\": 1}\n").unwrap();
+        let cache = SchemaCache::open(
+            &dir.path().join("cache"),
+            &GeneratorConfig::default(),
+            None,
+            false,
+            false,
+        )
+        .unwrap();
+
+        let CacheLookup::Miss(stamp) = cache.lookup(&input).unwrap() else {
+            panic!("expected a miss");
+        };
+        fs::write(&input, "{\"a\": 1, \"b\": 2}\n").unwrap();
+        assert!(!cache
+            .put(&input, stamp, &file_schema(BqType::Integer))
+            .unwrap());
+        assert!(lookup_hit(&cache, &input).is_none());
+    }
+}
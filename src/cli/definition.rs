@@ -22,11 +22,15 @@ pub struct Cli {
     #[arg(value_name = "FILE")]
     pub files: Vec<String>,
 
-    /// Input format: 'json' or 'csv'
+    /// Input format: 'json' (NDJSON, arrays and pretty-printed JSON are detected), 'json-array'
+    /// (always read as a JSON array or concatenated values) or 'csv'
     #[arg(long, alias = "input_format", default_value = "json")]
     pub input_format: String,
 
-    /// Output format: 'json' (default), 'ddl', 'debug-map', or 'json-schema'
+    #[command(flatten)]
+    pub csv: CsvArgs,
+
+    /// Output format: 'json' (default), 'ddl', 'debug-map', 'json-schema', or 'state'
     #[arg(long, alias = "output_format", default_value = "json")]
     pub output_format: String,
 
@@ -62,10 +66,57 @@ pub struct Cli {
     #[arg(long, alias = "existing_schema_path")]
     pub existing_schema_path: Option<PathBuf>,
 
+    /// Schema state written with '--output-format state' to continue from (repeatable; merged in order)
+    #[arg(long, value_name = "FILE")]
+    pub seed_state: Vec<PathBuf>,
+
     /// Preserve the original ordering of columns from input instead of sorting alphabetically
     #[arg(long, alias = "preserve_input_sort_order")]
     pub preserve_input_sort_order: bool,
 
+    /// Infer NUMERIC/BIGNUMERIC for decimals that would lose precision as FLOAT
+    #[arg(long, alias = "infer_numeric")]
+    pub infer_numeric: bool,
+
+    /// Infer BYTES for base64 strings at least this many characters long
+    #[arg(long, alias = "bytes_min_length", value_name = "N")]
+    pub bytes_min_length: Option<usize>,
+
+    /// Infer BYTES for base64 strings in fields whose name matches this regex
+    #[arg(long, alias = "bytes_field_pattern", value_name = "REGEX")]
+    pub bytes_field_pattern: Option<String>,
+
+    /// Infer GEOGRAPHY for WKT strings and GeoJSON geometry objects
+    #[arg(long, alias = "infer_geography")]
+    pub infer_geography: bool,
+
+    #[command(flatten)]
+    pub epochs: EpochArgs,
+
+    /// Only detect epoch timestamps in fields whose name matches this regex (implies --infer-epochs)
+    #[arg(long, alias = "epoch_field_pattern", value_name = "REGEX")]
+    pub epoch_field_pattern: Option<String>,
+
+    /// Extra date/time format as TYPE[@PATH]=FORMAT, e.g. 'DATE=%m/%d/%Y' (repeatable)
+    #[arg(long, alias = "datetime_format", value_name = "SPEC")]
+    pub datetime_format: Vec<String>,
+
+    /// Match dates and times by layout only, accepting values like 2024-02-31 (pre-calendar-check behavior)
+    #[arg(long, alias = "regex_only_dates")]
+    pub regex_only_dates: bool,
+
+    /// How to resolve fields with conflicting types: 'ignore' (default), 'string', 'json', 'fail', or 'keep-first'
+    #[arg(long, alias = "conflict_policy", default_value = "ignore")]
+    pub conflict_policy: String,
+
+    /// Dotted field paths that are always typed JSON (comma-separated or repeated)
+    #[arg(long, alias = "json_paths", value_name = "PATH", value_delimiter = ',')]
+    pub json_paths: Vec<String>,
+
+    /// JSON or TOML file mapping field paths or glob patterns to a forced type and/or mode
+    #[arg(long, alias = "type_hints", value_name = "FILE")]
+    pub type_hints: Option<PathBuf>,
+
     /// Suppress progress messages (only output schema and errors)
     #[arg(short, long)]
     pub quiet: bool,
@@ -86,10 +137,20 @@ pub struct Cli {
     #[arg(long)]
     pub output_dir: Option<PathBuf>,
 
-    /// Number of threads for parallel processing (default: auto-detect CPUs)
+    /// Number of threads for parallel processing (default: auto-detect CPUs).
+    /// Conflict counts are then summed over files or chunks; input is read on
+    /// one thread with --conflict-policy keep-first
     #[arg(long)]
     pub threads: Option<usize>,
 
+    /// Cache each input file's schema in DIR and reuse it while the file's size and modification time are unchanged
+    #[arg(long, value_name = "DIR")]
+    pub cache_dir: Option<PathBuf>,
+
+    /// With --cache-dir, also reuse schemas of files whose modification time changed but whose content hash didn't
+    #[arg(long)]
+    pub cache_hash: bool,
+
     /// Enable watch mode to automatically regenerate schema on file changes
     #[arg(long)]
     pub watch: bool,
@@ -101,10 +162,24 @@ pub struct Cli {
     /// Command to run after schema regeneration in watch mode
     #[arg(long)]
     pub on_change: Option<String>,
+
+    /// Write per-field statistics (presence, nulls, distinct estimate, min/max, samples) to FILE
+    #[arg(long, value_name = "FILE")]
+    pub stats: Option<PathBuf>,
+
+    /// Stats report format: 'json' (default) or 'markdown'
+    #[arg(long, alias = "stats_format", default_value = "json")]
+    pub stats_format: String,
+
+    /// Record where each field first appeared and changed, and show it in the
+    /// 'debug-map' output. Input is then read on a single thread
+    #[arg(long)]
+    pub provenance: bool,
 }
 
 /// Available subcommands
 #[derive(Subcommand, Debug)]
+#[allow(clippy::large_enum_variant)] // Parsed once at startup
 pub enum Commands {
     /// Compare two BigQuery schemas and show differences
     Diff {
@@ -131,6 +206,23 @@ pub enum Commands {
         output: Option<PathBuf>,
     },
 
+    /// Show where each field's type and mode came from (file and line)
+    ///
+    /// Generator flags such as --input-format go before the subcommand.
+    Explain {
+        /// Input data file(s) (supports glob patterns)
+        #[arg(value_name = "FILE")]
+        files: Vec<String>,
+
+        /// Only explain this dotted field path and its nested fields (repeatable)
+        #[arg(long, value_name = "PATH")]
+        field: Vec<String>,
+
+        /// Output format: 'text' (default) or 'json'
+        #[arg(long, default_value = "text")]
+        format: String,
+    },
+
     /// Validate data against an existing BigQuery schema
     Validate {
         /// Input data file(s) (supports glob patterns)
@@ -153,6 +245,24 @@ pub enum Commands {
         #[arg(long, default_value = "100")]
         max_errors: usize,
 
+        /// Also report values that don't satisfy the types in this hints file
+        #[arg(long, value_name = "FILE")]
+        type_hints: Option<PathBuf>,
+
+        #[command(flatten)]
+        input: ValidateInputArgs,
+
+        #[command(flatten)]
+        epochs: EpochArgs,
+
+        /// Also accept date/time values in this format, as TYPE[@PATH]=FORMAT (repeatable)
+        #[arg(long, value_name = "SPEC")]
+        datetime_format: Vec<String>,
+
+        /// Accept dates and times by layout only, like 2024-02-31 (pre-calendar-check behavior)
+        #[arg(long)]
+        regex_only_dates: bool,
+
         /// Output format: 'text' (default) or 'json'
         #[arg(long, default_value = "text")]
         format: String,
@@ -162,3 +272,79 @@ pub enum Commands {
         quiet: bool,
     },
 }
+
+/// Input flags of the validate subcommand
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct ValidateInputArgs {
+    /// Input format: 'json' (default), 'json-array' or 'csv'
+    #[arg(long, value_name = "FORMAT")]
+    pub input_format: Option<String>,
+
+    #[command(flatten)]
+    pub csv: CsvArgs,
+
+    /// Match CSV columns to schema fields by 'name' (header) or 'position' (default: name with a header row, position without)
+    #[arg(long, value_name = "MODE")]
+    pub source_column_match: Option<String>,
+}
+
+/// CSV dialect flags, shared by generation and validation
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct CsvArgs {
+    /// CSV field delimiter: a single character, or 'tab' (default: ',')
+    #[arg(long, alias = "csv_delimiter", value_name = "CHAR")]
+    pub csv_delimiter: Option<String>,
+
+    /// CSV quote character (default: '"')
+    #[arg(long, alias = "csv_quote", value_name = "CHAR")]
+    pub csv_quote: Option<String>,
+
+    /// CSV escape character inside quoted fields, e.g. '\' (default: quotes are doubled)
+    #[arg(long, alias = "csv_escape", value_name = "CHAR")]
+    pub csv_escape: Option<String>,
+
+    /// Skip this many lines before the CSV header (or first row without --csv-no-header)
+    #[arg(long, alias = "skip_leading_rows", value_name = "N", default_value_t = 0)]
+    pub skip_leading_rows: usize,
+
+    /// CSV input has no header row; columns are named column_1, column_2, ...
+    #[arg(long, alias = "csv_no_header")]
+    pub csv_no_header: bool,
+
+    /// Skip CSV lines starting with this character, e.g. '#'
+    #[arg(long, alias = "csv_comment", value_name = "CHAR")]
+    pub csv_comment: Option<String>,
+
+    /// CSV field value read as null, e.g. '\N' or 'NULL' (repeatable; like bq load --null_marker)
+    #[arg(
+        long,
+        visible_alias = "null-marker",
+        aliases = ["csv_null_marker", "null_marker"],
+        value_name = "MARKER"
+    )]
+    pub csv_null_marker: Vec<String>,
+
+    /// Read empty CSV fields as null instead of empty strings, as bq load does
+    #[arg(long, alias = "csv_empty_as_null")]
+    pub csv_empty_as_null: bool,
+}
+
+/// Unix epoch TIMESTAMP detection flags, shared by generation and validation
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct EpochArgs {
+    /// Treat integers in the plausible epoch range as TIMESTAMP
+    #[arg(long, alias = "infer_epochs")]
+    pub infer_epochs: bool,
+
+    /// Earliest plausible epoch time, as YYYY-MM-DD or seconds (default: 2000-01-01)
+    #[arg(long, alias = "epoch_min", value_name = "DATE")]
+    pub epoch_min: Option<String>,
+
+    /// Latest plausible epoch time, as YYYY-MM-DD or seconds (default: 2100-01-01)
+    #[arg(long, alias = "epoch_max", value_name = "DATE")]
+    pub epoch_max: Option<String>,
+
+    /// Epoch units to detect: 'seconds', 'millis', 'micros' (comma-separated; default: all)
+    #[arg(long, alias = "epoch_units", value_name = "UNIT", value_delimiter = ',')]
+    pub epoch_units: Vec<String>,
+}
@@ -0,0 +1,257 @@
+//! Explain subcommand implementation.
+//!
+//! Shows, for each field path, the file and line where it first appeared
+//! and where its type or mode changed.
+
+use std::path::PathBuf;
+
+use bq_schema_gen::input::open_input;
+use bq_schema_gen::{FieldProvenance, SchemaGenerator};
+
+use super::generate::{process_input_impl, validate_cli_args, GenerateError};
+use super::Cli;
+
+/// Errors that can occur while explaining a schema
+#[derive(Debug)]
+pub enum ExplainError {
+    /// Invalid output format specified
+    InvalidFormat(String),
+    /// Invalid generator options
+    Generate(GenerateError),
+    /// Invalid input file pattern
+    InputFiles(String),
+    /// No input files found
+    NoInputFiles,
+    /// Failed to open input file
+    InputFileOpen(PathBuf, std::io::Error),
+}
+
+impl std::fmt::Display for ExplainError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExplainError::InvalidFormat(fmt) => {
+                write!(f, "Unknown format '{}'. Use 'text' or 'json'.", fmt)
+            }
+            ExplainError::Generate(e) => write!(f, "{}", e),
+            ExplainError::InputFiles(e) => write!(f, "{}", e),
+            ExplainError::NoInputFiles => write!(f, "No input files specified"),
+            ExplainError::InputFileOpen(path, e) => {
+                write!(f, "Cannot open input file '{}': {}", path.display(), e)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ExplainError {}
+
+impl From<GenerateError> for ExplainError {
+    fn from(e: GenerateError) -> Self {
+        ExplainError::Generate(e)
+    }
+}
+
+/// Run the explain subcommand implementation (testable version)
+///
+/// Generator options (input format, inference flags, existing schema) are
+/// taken from the top-level `cli` flags. `fields` restricts the output to
+/// those paths and their nested fields.
+pub fn run_impl(
+    cli: &Cli,
+    file_patterns: &[String],
+    fields: &[String],
+    format: &str,
+) -> Result<Vec<FieldProvenance>, ExplainError> {
+    if format != "text" && format != "json" {
+        return Err(ExplainError::InvalidFormat(format.to_string()));
+    }
+
+    let args = validate_cli_args(cli)?;
+    let mut config = args.config;
+    config.track_provenance = true;
+
+    let files = super::validate::collect_input_files_impl(file_patterns)
+        .map_err(|e| ExplainError::InputFiles(e.to_string()))?;
+    if files.is_empty() {
+        return Err(ExplainError::NoInputFiles);
+    }
+
+    let mut generator = SchemaGenerator::new(config.clone());
+    let mut schema_map = args.existing_schema.unwrap_or_default();
+
+    for path in &files {
+        let file = open_input(path).map_err(|e| ExplainError::InputFileOpen(path.clone(), e))?;
+        generator.set_source_file(Some(path.display().to_string()));
+        process_input_impl(
+            file,
+            config.input_format,
+            &mut generator,
+            &mut schema_map,
+            args.ignore_invalid_lines,
+            args.debugging_interval,
+            true,
+        )?;
+    }
+
+    let fields: Vec<String> = fields.iter().map(|f| f.to_lowercase()).collect();
+    let mut provenance: Vec<_> = generator.provenance().iter().collect();
+    provenance.sort_by(|a, b| a.0.cmp(b.0));
+
+    Ok(provenance
+        .into_iter()
+        .filter(|(key, _)| {
+            fields.is_empty()
+                || fields.iter().any(|field| {
+                    *key == field
+                        || key
+                            .strip_prefix(field.as_str())
+                            .is_some_and(|rest| rest.starts_with('.'))
+                })
+        })
+        .map(|(_, field)| field.clone())
+        .collect())
+}
+
+/// Run the explain subcommand
+pub fn run(cli: &Cli, file_patterns: &[String], fields: &[String], format: &str) {
+    let provenance = match run_impl(cli, file_patterns, fields, format) {
+        Ok(provenance) => provenance,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    match format {
+        "json" => {
+            println!("{}", serde_json::to_string_pretty(&provenance).unwrap());
+        }
+        _ => print!("{}", format_text(&provenance)),
+    }
+}
+
+/// Format provenance as human-readable text
+fn format_text(provenance: &[FieldProvenance]) -> String {
+    let mut out = String::new();
+    for field in provenance {
+        out.push_str(&field.path);
+        out.push('\n');
+        match &field.first_seen {
+            Some(location) => {
+                out.push_str(&format!(
+                    "  first seen at {} as {}\n",
+                    location, field.initial
+                ));
+            }
+            None => {
+                out.push_str(&format!("  from existing schema as {}\n", field.initial));
+            }
+        }
+        for change in &field.changes {
+            out.push_str(&format!(
+                "  changed at {} from {} to {}\n",
+                change.location, change.from, change.to
+            ));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn create_temp_data_file(content: &str) -> NamedTempFile {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+        file.flush().unwrap();
+        file
+    }
+
+    fn default_cli() -> Cli {
+        Cli::parse_from(["bq-schema-gen"])
+    }
+
+    #[test]
+    fn test_run_impl_invalid_format() {
+        let result = run_impl(&default_cli(), &[], &[], "xml");
+        assert!(matches!(result, Err(ExplainError::InvalidFormat(_))));
+    }
+
+    #[test]
+    fn test_run_impl_no_input_files() {
+        let result = run_impl(&default_cli(), &[], &[], "text");
+        assert!(matches!(result, Err(ExplainError::NoInputFiles)));
+    }
+
+    #[test]
+    fn test_run_impl_tracks_files_and_lines() {
+        let first = create_temp_data_file(
+            "{\"customer\": {\"id\": 1}}\n{\"customer\": {\"id\": 2, \"name\": \"a\"}}\n",
+        );
+        let second = create_temp_data_file("{\"other\": true}\n{\"customer\": {\"id\": \"x1\"}}\n");
+        let patterns = vec![
+            first.path().display().to_string(),
+            second.path().display().to_string(),
+        ];
+
+        let provenance = run_impl(&default_cli(), &patterns, &[], "text").unwrap();
+        let paths: Vec<_> = provenance.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec!["customer", "customer.id", "customer.name", "other"]
+        );
+
+        let id = &provenance[1];
+        let first_seen = id.first_seen.as_ref().unwrap();
+        assert_eq!(first_seen.file.as_deref(), Some(patterns[0].as_str()));
+        assert_eq!(first_seen.line, 1);
+        assert_eq!(id.initial, "NULLABLE INTEGER");
+
+        // The string id in the second file causes the conflict
+        assert_eq!(id.changes.len(), 1);
+        assert_eq!(
+            id.changes[0].location.file.as_deref(),
+            Some(patterns[1].as_str())
+        );
+        assert_eq!(id.changes[0].location.line, 2);
+        assert_eq!(id.changes[0].to, "ignored");
+
+        let name = &provenance[2];
+        assert_eq!(name.first_seen.as_ref().unwrap().line, 2);
+    }
+
+    #[test]
+    fn test_run_impl_field_filter() {
+        let file = create_temp_data_file("{\"customer\": {\"id\": 1}, \"customers\": 2}\n");
+        let patterns = vec![file.path().display().to_string()];
+
+        let provenance =
+            run_impl(&default_cli(), &patterns, &["Customer".to_string()], "json").unwrap();
+        let paths: Vec<_> = provenance.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["customer", "customer.id"]);
+    }
+
+    #[test]
+    fn test_format_text() {
+        let file = create_temp_data_file("{\"n\": 1}\n{\"n\": 1.5}\n");
+        let patterns = vec![file.path().display().to_string()];
+        let provenance = run_impl(&default_cli(), &patterns, &[], "text").unwrap();
+
+        let text = format_text(&provenance);
+        assert!(text.starts_with("n\n"));
+        assert!(text.contains(":1 as NULLABLE INTEGER"));
+        assert!(text.contains(":2 from NULLABLE INTEGER to NULLABLE FLOAT"));
+    }
+
+    #[test]
+    fn test_explain_error_display() {
+        let err = ExplainError::InvalidFormat("xml".to_string());
+        assert!(err.to_string().contains("xml"));
+
+        let err = ExplainError::NoInputFiles;
+        assert!(err.to_string().contains("No input files"));
+    }
+}
@@ -1,16 +1,22 @@
 //! Generate subcommand implementation (default command).
 
 use std::fs::File;
-use std::io::{self, BufReader, Read};
+use std::io::{self, BufRead, BufReader, IsTerminal, Read};
 use std::path::{Path, PathBuf};
 
+use bq_schema_gen::inference::parse_epoch_bound;
+use bq_schema_gen::input::{decompress, open_input, JsonReader};
 use bq_schema_gen::{
-    read_existing_schema_from_file, write_schema_ddl, write_schema_debug_map, write_schema_json,
-    write_schema_json_schema, BqMode, BqType, CsvRecordIterator, ErrorLog, GeneratorConfig,
-    InputFormat, JsonRecordIterator, OutputFormat, SchemaEntry, SchemaGenerator, SchemaMap,
+    per_file_schema_path, read_existing_schema_from_file, read_schema_state_from_file,
+    write_schema_ddl, write_schema_debug_map_with_provenance, write_schema_json,
+    write_schema_json_schema, write_schema_state, write_stats_json, write_stats_markdown,
+    CacheLookup, ConflictPolicy, CsvDialect, CsvRecordIterator, DataProfile, DateTimeFormats,
+    EpochDetection, EpochUnit, Error, ErrorLog, FieldConflict, FileSchema, GeneratorConfig,
+    InputFormat, JsonLayout, JsonRecordIterator, OutputFormat, SchemaCache, SchemaGenerator,
+    SchemaMap, StatsFormat, TypeHints,
 };
 
-use super::Cli;
+use super::{Cli, CsvArgs, EpochArgs};
 
 /// Errors that can occur during schema generation
 #[derive(Debug)]
@@ -22,16 +28,44 @@ pub enum GenerateError {
     InvalidOutputFormat(String),
     /// Failed to load existing schema
     ExistingSchemaLoad(PathBuf, String),
+    /// Failed to load or merge a --seed-state file
+    SeedStateLoad(PathBuf, String),
+    /// --seed-state used in watch mode
+    SeedStateWithWatch,
     /// Invalid glob pattern
     InvalidGlobPattern(String, String),
+    /// Invalid --bytes-field-pattern regex
+    InvalidBytesFieldPattern(String, String),
+    /// Invalid --conflict-policy value
+    InvalidConflictPolicy(String),
+    /// Invalid --epoch-field-pattern regex
+    InvalidEpochFieldPattern(String, String),
+    /// Invalid --epoch-min/--epoch-max/--epoch-units value
+    InvalidEpochOption(String),
+    /// Invalid --datetime-format spec
+    InvalidDateTimeFormat(String),
+    /// Invalid CSV dialect flag
+    InvalidCsvOption(String),
+    /// Failed to load --type-hints file
+    TypeHintsLoad(PathBuf, String),
+    /// Invalid --stats-format value
+    InvalidStatsFormat(String),
     /// Per-file mode requires input files
     PerFileRequiresInput,
     /// Output directory requires per-file mode
     OutputDirRequiresPerFile,
     /// Watch mode requires input files
     WatchRequiresInput,
-    /// Watch mode cannot be used with per-file mode
-    WatchWithPerFile,
+    /// Watch mode cannot be used with --stats
+    WatchWithStats,
+    /// --cache-hash requires --cache-dir
+    CacheHashRequiresCacheDir,
+    /// The cache requires input files
+    CacheDirRequiresInput,
+    /// The cache cannot be used with the given option
+    CacheDirWith(String),
+    /// Failed to open the cache directory
+    CacheDirOpen(PathBuf, String),
     /// No input files found
     NoInputFiles,
     /// Failed to open input file
@@ -50,15 +84,30 @@ impl std::fmt::Display for GenerateError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             GenerateError::InvalidInputFormat(fmt) => {
-                write!(f, "Unknown input format '{}'. Use 'json' or 'csv'.", fmt)
+                write!(
+                    f,
+                    "Unknown input format '{}'. Use 'json', 'json-array' or 'csv'.",
+                    fmt
+                )
             }
             GenerateError::InvalidOutputFormat(fmt) => {
                 write!(
                     f,
-                    "Unknown output format '{}'. Use 'json', 'ddl', 'debug-map', or 'json-schema'.",
+                    "Unknown output format '{}'. Use 'json', 'ddl', 'debug-map', 'json-schema', or 'state'.",
                     fmt
                 )
             }
+            GenerateError::SeedStateLoad(path, e) => {
+                write!(
+                    f,
+                    "Cannot load schema state from '{}': {}",
+                    path.display(),
+                    e
+                )
+            }
+            GenerateError::SeedStateWithWatch => {
+                write!(f, "--seed-state cannot be used with --watch")
+            }
             GenerateError::ExistingSchemaLoad(path, e) => {
                 write!(
                     f,
@@ -70,6 +119,32 @@ impl std::fmt::Display for GenerateError {
             GenerateError::InvalidGlobPattern(pattern, e) => {
                 write!(f, "Invalid glob pattern '{}': {}", pattern, e)
             }
+            GenerateError::InvalidBytesFieldPattern(pattern, e) => {
+                write!(f, "Invalid --bytes-field-pattern '{}': {}", pattern, e)
+            }
+            GenerateError::InvalidConflictPolicy(policy) => {
+                write!(
+                    f,
+                    "Unknown conflict policy '{}'. Use 'ignore', 'string', 'json', 'fail', or 'keep-first'.",
+                    policy
+                )
+            }
+            GenerateError::InvalidEpochFieldPattern(pattern, e) => {
+                write!(f, "Invalid --epoch-field-pattern '{}': {}", pattern, e)
+            }
+            GenerateError::InvalidEpochOption(e) => write!(f, "{}", e),
+            GenerateError::InvalidDateTimeFormat(e) => write!(f, "{}", e),
+            GenerateError::InvalidCsvOption(e) => write!(f, "{}", e),
+            GenerateError::TypeHintsLoad(path, e) => {
+                write!(f, "Cannot load type hints from '{}': {}", path.display(), e)
+            }
+            GenerateError::InvalidStatsFormat(fmt) => {
+                write!(
+                    f,
+                    "Unknown stats format '{}'. Use 'json' or 'markdown'.",
+                    fmt
+                )
+            }
             GenerateError::PerFileRequiresInput => {
                 write!(
                     f,
@@ -82,8 +157,23 @@ impl std::fmt::Display for GenerateError {
             GenerateError::WatchRequiresInput => {
                 write!(f, "--watch requires input file patterns")
             }
-            GenerateError::WatchWithPerFile => {
-                write!(f, "--watch cannot be used with --per-file")
+            GenerateError::WatchWithStats => {
+                write!(f, "--watch cannot be used with --stats")
+            }
+            GenerateError::CacheHashRequiresCacheDir => {
+                write!(f, "--cache-hash requires --cache-dir")
+            }
+            GenerateError::CacheDirRequiresInput => {
+                write!(
+                    f,
+                    "--cache-dir requires input files (cannot read from stdin)"
+                )
+            }
+            GenerateError::CacheDirWith(option) => {
+                write!(f, "--cache-dir cannot be used with {}", option)
+            }
+            GenerateError::CacheDirOpen(path, e) => {
+                write!(f, "Cannot open cache directory '{}': {}", path.display(), e)
             }
             GenerateError::NoInputFiles => {
                 write!(f, "No input files found")
@@ -124,8 +214,10 @@ pub struct ValidatedArgs {
     pub output_format: OutputFormat,
     /// Generator configuration
     pub config: GeneratorConfig,
-    /// Existing schema map (if provided)
+    /// Existing schema map (if provided), with any seed states merged in
     pub existing_schema: Option<SchemaMap>,
+    /// Schema state files merged into the existing schema
+    pub seed_states: Vec<PathBuf>,
     /// Input files (empty means stdin)
     pub input_files: Vec<PathBuf>,
     /// Output path (None means stdout)
@@ -152,6 +244,14 @@ pub struct ValidatedArgs {
     pub on_change: Option<String>,
     /// Original files patterns (for watch mode)
     pub file_patterns: Vec<String>,
+    /// Stats report path (None means no stats)
+    pub stats_path: Option<PathBuf>,
+    /// Stats report format
+    pub stats_format: StatsFormat,
+    /// Directory caching each input file's schema (None means no cache)
+    pub cache_dir: Option<PathBuf>,
+    /// Reuse cached schemas of files whose content hash is unchanged
+    pub cache_hash: bool,
 }
 
 /// Output from schema generation
@@ -164,6 +264,10 @@ pub struct GenerateOutput {
     pub files_processed: usize,
     /// Error logs from processing
     pub error_logs: Vec<ErrorLog>,
+    /// Fields whose values conflicted, and how they were resolved
+    pub conflicts: Vec<FieldConflict>,
+    /// Per-field statistics across all inputs (if --stats was given)
+    pub profile: Option<DataProfile>,
 }
 
 /// Validate CLI arguments and return validated args
@@ -171,6 +275,7 @@ pub fn validate_cli_args(cli: &Cli) -> Result<ValidatedArgs, GenerateError> {
     // Parse input format
     let input_format = match cli.input_format.to_lowercase().as_str() {
         "json" => InputFormat::Json,
+        "json-array" => InputFormat::JsonArray,
         "csv" => InputFormat::Csv,
         other => return Err(GenerateError::InvalidInputFormat(other.to_string())),
     };
@@ -181,6 +286,41 @@ pub fn validate_cli_args(cli: &Cli) -> Result<ValidatedArgs, GenerateError> {
         .parse()
         .map_err(|_| GenerateError::InvalidOutputFormat(cli.output_format.clone()))?;
 
+    let bytes_field_pattern = match &cli.bytes_field_pattern {
+        Some(pattern) => Some(regex::Regex::new(pattern).map_err(|e| {
+            GenerateError::InvalidBytesFieldPattern(pattern.clone(), e.to_string())
+        })?),
+        None => None,
+    };
+
+    let epoch_field_pattern = match &cli.epoch_field_pattern {
+        Some(pattern) => Some(regex::Regex::new(pattern).map_err(|e| {
+            GenerateError::InvalidEpochFieldPattern(pattern.clone(), e.to_string())
+        })?),
+        None => None,
+    };
+    let epoch_detection = parse_epoch_args(&cli.epochs, epoch_field_pattern.is_some())
+        .map_err(GenerateError::InvalidEpochOption)?;
+    let datetime_formats = DateTimeFormats::from_specs(&cli.datetime_format)
+        .map_err(|e| GenerateError::InvalidDateTimeFormat(e.to_string()))?;
+    let csv_dialect = parse_csv_args(&cli.csv).map_err(GenerateError::InvalidCsvOption)?;
+
+    let conflict_policy: ConflictPolicy = cli
+        .conflict_policy
+        .parse()
+        .map_err(|_| GenerateError::InvalidConflictPolicy(cli.conflict_policy.clone()))?;
+
+    let type_hints = match &cli.type_hints {
+        Some(path) => TypeHints::from_file(path)
+            .map_err(|e| GenerateError::TypeHintsLoad(path.clone(), e.to_string()))?,
+        None => TypeHints::default(),
+    };
+
+    let stats_format: StatsFormat = cli
+        .stats_format
+        .parse()
+        .map_err(|_| GenerateError::InvalidStatsFormat(cli.stats_format.clone()))?;
+
     // Build configuration
     let config = GeneratorConfig {
         input_format,
@@ -189,6 +329,20 @@ pub fn validate_cli_args(cli: &Cli) -> Result<ValidatedArgs, GenerateError> {
         quoted_values_are_strings: cli.quoted_values_are_strings,
         sanitize_names: cli.sanitize_names,
         preserve_input_sort_order: cli.preserve_input_sort_order,
+        infer_numeric: cli.infer_numeric,
+        bytes_min_length: cli.bytes_min_length,
+        bytes_field_pattern,
+        infer_geography: cli.infer_geography,
+        epoch_detection,
+        epoch_field_pattern,
+        datetime_formats,
+        regex_only_dates: cli.regex_only_dates,
+        conflict_policy,
+        json_paths: cli.json_paths.clone(),
+        type_hints,
+        track_provenance: cli.provenance,
+        collect_stats: cli.stats.is_some(),
+        csv_dialect,
     };
 
     // Load existing schema if provided
@@ -200,6 +354,7 @@ pub fn validate_cli_args(cli: &Cli) -> Result<ValidatedArgs, GenerateError> {
         }
         None => None,
     };
+    let existing_schema = merge_seed_states(existing_schema, &cli.seed_state, &config, cli.quiet)?;
 
     // Collect input files from positional args and -i/--input flag
     let input_files = collect_input_files_impl(cli)?;
@@ -218,8 +373,37 @@ pub fn validate_cli_args(cli: &Cli) -> Result<ValidatedArgs, GenerateError> {
         return Err(GenerateError::WatchRequiresInput);
     }
 
-    if cli.watch && cli.per_file {
-        return Err(GenerateError::WatchWithPerFile);
+    if cli.watch && cli.stats.is_some() {
+        return Err(GenerateError::WatchWithStats);
+    }
+
+    if cli.watch && !cli.seed_state.is_empty() {
+        return Err(GenerateError::SeedStateWithWatch);
+    }
+
+    // Cache validation: only per-file schemas are stored, without stats or provenance
+    if cli.cache_hash && cli.cache_dir.is_none() {
+        return Err(GenerateError::CacheHashRequiresCacheDir);
+    }
+    if cli.cache_dir.is_some() {
+        if cli.watch {
+            return Err(GenerateError::CacheDirWith("--watch".to_string()));
+        }
+        if cli.stats.is_some() {
+            return Err(GenerateError::CacheDirWith("--stats".to_string()));
+        }
+        if cli.provenance {
+            return Err(GenerateError::CacheDirWith("--provenance".to_string()));
+        }
+        // A cached file's schema is built without the files before it
+        if conflict_policy == ConflictPolicy::KeepFirst {
+            return Err(GenerateError::CacheDirWith(
+                "--conflict-policy keep-first".to_string(),
+            ));
+        }
+        if input_files.is_empty() {
+            return Err(GenerateError::CacheDirRequiresInput);
+        }
     }
 
     Ok(ValidatedArgs {
@@ -227,6 +411,7 @@ pub fn validate_cli_args(cli: &Cli) -> Result<ValidatedArgs, GenerateError> {
         output_format,
         config,
         existing_schema,
+        seed_states: cli.seed_state.clone(),
         input_files,
         output_path: cli.output.clone(),
         output_dir: cli.output_dir.clone(),
@@ -240,9 +425,117 @@ pub fn validate_cli_args(cli: &Cli) -> Result<ValidatedArgs, GenerateError> {
         debounce: cli.debounce,
         on_change: cli.on_change.clone(),
         file_patterns: cli.files.clone(),
+        stats_path: cli.stats.clone(),
+        stats_format,
+        cache_dir: cli.cache_dir.clone(),
+        cache_hash: cli.cache_hash,
     })
 }
 
+/// Merge `--seed-state` files, in order, onto the existing schema.
+///
+/// Conflicts between the states are resolved with the configured policy, as
+/// when merging the schemas of several input files.
+fn merge_seed_states(
+    existing_schema: Option<SchemaMap>,
+    paths: &[PathBuf],
+    config: &GeneratorConfig,
+    quiet: bool,
+) -> Result<Option<SchemaMap>, GenerateError> {
+    if paths.is_empty() {
+        return Ok(existing_schema);
+    }
+
+    let mut generator = SchemaGenerator::new(config.clone());
+    let mut schema_map = existing_schema.unwrap_or_default();
+    for path in paths {
+        let state = read_schema_state_from_file(path)
+            .map_err(|e| GenerateError::SeedStateLoad(path.clone(), e.to_string()))?;
+        generator
+            .merge_schema_maps(&mut schema_map, state)
+            .map_err(|e| GenerateError::SeedStateLoad(path.clone(), e.to_string()))?;
+    }
+    if !quiet {
+        print_conflict_summary(generator.conflicts());
+    }
+    Ok(Some(schema_map))
+}
+
+/// Build epoch detection rules from the epoch flags.
+///
+/// Returns `None` unless `--infer-epochs` is given or `implied` (e.g. by
+/// `--epoch-field-pattern`); range and unit flags are an error without it.
+pub(super) fn parse_epoch_args(
+    args: &EpochArgs,
+    implied: bool,
+) -> Result<Option<EpochDetection>, String> {
+    if !args.infer_epochs && !implied {
+        if args.epoch_min.is_some() || args.epoch_max.is_some() || !args.epoch_units.is_empty() {
+            return Err(
+                "--epoch-min, --epoch-max and --epoch-units require --infer-epochs".to_string(),
+            );
+        }
+        return Ok(None);
+    }
+
+    let mut detection = EpochDetection::default();
+    if let Some(min) = &args.epoch_min {
+        detection.min_seconds = parse_epoch_bound(min)?;
+    }
+    if let Some(max) = &args.epoch_max {
+        detection.max_seconds = parse_epoch_bound(max)?;
+    }
+    if detection.min_seconds > detection.max_seconds {
+        return Err("--epoch-min must not be after --epoch-max".to_string());
+    }
+    if !args.epoch_units.is_empty() {
+        detection.units = args
+            .epoch_units
+            .iter()
+            .map(|unit| unit.parse::<EpochUnit>())
+            .collect::<Result<_, _>>()?;
+    }
+    Ok(Some(detection))
+}
+
+/// Build a CSV dialect from the CSV flags.
+pub(super) fn parse_csv_args(args: &CsvArgs) -> Result<CsvDialect, String> {
+    let mut dialect = CsvDialect {
+        escape: parse_csv_char("--csv-escape", args.csv_escape.as_deref())?,
+        skip_leading_rows: args.skip_leading_rows,
+        has_header: !args.csv_no_header,
+        comment: parse_csv_char("--csv-comment", args.csv_comment.as_deref())?,
+        null_markers: args.csv_null_marker.clone(),
+        empty_as_null: args.csv_empty_as_null,
+        ..Default::default()
+    };
+    if let Some(delimiter) = parse_csv_char("--csv-delimiter", args.csv_delimiter.as_deref())? {
+        dialect.delimiter = delimiter;
+    }
+    if let Some(quote) = parse_csv_char("--csv-quote", args.csv_quote.as_deref())? {
+        dialect.quote = quote;
+    }
+    if dialect.delimiter == dialect.quote {
+        return Err("--csv-delimiter and --csv-quote must differ".to_string());
+    }
+    Ok(dialect)
+}
+
+/// Parse a single-byte CSV flag value; 'tab' and '\t' stand for a tab.
+fn parse_csv_char(flag: &str, value: Option<&str>) -> Result<Option<u8>, String> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+    match value {
+        "tab" | "\\t" | "\t" => Ok(Some(b'\t')),
+        _ if value.len() == 1 && value.is_ascii() => Ok(Some(value.as_bytes()[0])),
+        _ => Err(format!(
+            "{} must be a single ASCII character, got '{}'",
+            flag, value
+        )),
+    }
+}
+
 /// Generate schema from validated arguments (testable entry point)
 pub fn generate_schema(args: &ValidatedArgs) -> Result<GenerateOutput, GenerateError> {
     if args.per_file {
@@ -290,6 +583,8 @@ fn run_watch_mode(cli: &Cli, config: &GeneratorConfig) {
         on_change: cli.on_change.clone(),
         quiet: cli.quiet,
         ignore_invalid_lines: cli.ignore_invalid_lines,
+        per_file: cli.per_file,
+        output_dir: cli.output_dir.clone(),
     };
 
     let output_path = cli.output.as_deref();
@@ -355,11 +650,13 @@ fn process_single_input_impl(
 ) -> Result<GenerateOutput, GenerateError> {
     let input: Box<dyn Read> = match input_path {
         Some(path) => {
-            let file =
-                File::open(path).map_err(|e| GenerateError::InputFileOpen(path.to_owned(), e))?;
-            Box::new(file)
+            open_input(path).map_err(|e| GenerateError::InputFileOpen(path.to_owned(), e))?
+        }
+        // Only seed states to merge: don't wait for input typed at a terminal
+        None if !args.seed_states.is_empty() && io::stdin().is_terminal() => Box::new(io::empty()),
+        None => {
+            decompress(io::stdin()).map_err(|e| GenerateError::ProcessingError(e.to_string()))?
         }
-        None => Box::new(io::stdin()),
     };
 
     let mut output: Box<dyn io::Write> = match &args.output_path {
@@ -373,6 +670,7 @@ fn process_single_input_impl(
 
     let mut generator = SchemaGenerator::new(args.config.clone());
     let mut schema_map = args.existing_schema.clone().unwrap_or_default();
+    generator.set_source_file(input_path.map(|path| path.display().to_string()));
 
     process_input_impl(
         input,
@@ -390,6 +688,7 @@ fn process_single_input_impl(
 
     let error_logs = generator.error_logs().to_vec();
     print_errors(&generator);
+    print_conflict_summary(generator.conflicts());
     write_output(
         &generator,
         &schema_map,
@@ -397,23 +696,36 @@ fn process_single_input_impl(
         &args.table_name,
         &mut output,
     )?;
+    let profile = write_stats(args, generator.profile())?;
 
     Ok(GenerateOutput {
         lines_processed: generator.line_number(),
         files_processed: if input_path.is_some() { 1 } else { 0 },
         error_logs,
+        conflicts: generator.conflicts().to_vec(),
+        profile,
     })
 }
 
 /// Process multiple files and merge into single schema - implementation
 fn process_merged_files_impl(args: &ValidatedArgs) -> Result<GenerateOutput, GenerateError> {
     let num_threads = args.threads.unwrap_or_else(num_cpus::get);
-    let use_parallel = num_threads > 1 && args.input_files.len() > 1;
-
-    if use_parallel {
-        process_files_parallel_impl(args, num_threads)
-    } else {
-        process_files_sequential_impl(args)
+    // Provenance needs records in input order, which parallel merging loses.
+    // Keep-first needs the types of earlier records: a chunk or file processed
+    // on its own would keep its own first type and drop fields the earlier type kept
+    let use_parallel = num_threads > 1
+        && !args.config.track_provenance
+        && args.config.conflict_policy != ConflictPolicy::KeepFirst;
+
+    match args.input_files.as_slice() {
+        _ if args.cache_dir.is_some() => {
+            process_files_cached_impl(args, if use_parallel { num_threads } else { 1 })
+        }
+        [path] if use_parallel && is_splittable(path, args.config.input_format) => {
+            process_file_chunked_impl(path, args, num_threads)
+        }
+        files if use_parallel && files.len() > 1 => process_files_parallel_impl(args, num_threads),
+        _ => process_files_sequential_impl(args),
     }
 }
 
@@ -442,7 +754,8 @@ fn process_files_sequential_impl(args: &ValidatedArgs) -> Result<GenerateOutput,
             );
         }
 
-        let file = File::open(path).map_err(|e| GenerateError::InputFileOpen(path.clone(), e))?;
+        let file = open_input(path).map_err(|e| GenerateError::InputFileOpen(path.clone(), e))?;
+        generator.set_source_file(Some(path.display().to_string()));
 
         let lines_before = generator.line_number();
         process_input_impl(
@@ -467,6 +780,7 @@ fn process_files_sequential_impl(args: &ValidatedArgs) -> Result<GenerateOutput,
 
     let error_logs = generator.error_logs().to_vec();
     print_errors(&generator);
+    print_conflict_summary(generator.conflicts());
     write_output(
         &generator,
         &schema_map,
@@ -474,11 +788,14 @@ fn process_files_sequential_impl(args: &ValidatedArgs) -> Result<GenerateOutput,
         &args.table_name,
         &mut output,
     )?;
+    let profile = write_stats(args, generator.profile())?;
 
     Ok(GenerateOutput {
         lines_processed: total_lines,
         files_processed: args.input_files.len(),
         error_logs,
+        conflicts: generator.conflicts().to_vec(),
+        profile,
     })
 }
 
@@ -524,6 +841,9 @@ fn process_files_parallel_impl(
 
     let total_records = AtomicUsize::new(0);
     let all_errors: Mutex<Vec<ErrorLog>> = Mutex::new(Vec::new());
+    let all_conflicts: Mutex<Vec<FieldConflict>> = Mutex::new(Vec::new());
+    let all_profiles: Mutex<Vec<DataProfile>> = Mutex::new(Vec::new());
+    let conflict_error: Mutex<Option<Error>> = Mutex::new(None);
     let config = args.config.clone();
     let ignore_invalid_lines = args.ignore_invalid_lines;
 
@@ -532,7 +852,7 @@ fn process_files_parallel_impl(
         .input_files
         .par_iter()
         .filter_map(|path| {
-            let file = match File::open(path) {
+            let file = match open_input(path) {
                 Ok(f) => f,
                 Err(e) => {
                     eprintln!("Error: Cannot open input file '{}': {}", path.display(), e);
@@ -540,18 +860,42 @@ fn process_files_parallel_impl(
                 }
             };
 
+            // The file's schema is merged onto the existing schema afterwards,
+            // but inference needs to see the existing types now
             let mut generator = SchemaGenerator::new(config.clone());
+            if let Some(existing) = &args.existing_schema {
+                generator.set_known_schema(existing.clone());
+            }
             let mut schema_map = SchemaMap::new();
 
-            // Process the file
+            // Process the file; a conflict under the fail policy aborts the whole run
+            let check = |result: bq_schema_gen::Result<()>| match result {
+                Err(e @ Error::FieldConflict { .. }) => {
+                    if let Ok(mut slot) = conflict_error.lock() {
+                        slot.get_or_insert(e);
+                    }
+                    Err(())
+                }
+                _ => Ok(()),
+            };
             let result: Result<(), ()> = match config.input_format {
-                InputFormat::Json => {
+                InputFormat::Json | InputFormat::JsonArray => {
                     let buf_reader = BufReader::new(file);
-                    let iter = JsonRecordIterator::new(buf_reader, ignore_invalid_lines);
-                    for record_result in iter {
+                    let mut records = JsonRecordIterator::with_layout(
+                        buf_reader,
+                        ignore_invalid_lines,
+                        config.input_format.json_layout(),
+                    );
+                    let mut result = Ok(());
+                    while let Some(record_result) = records.next_raw(|_line_num, record| {
+                        generator.process_raw_record(&record, &mut schema_map)
+                    }) {
                         match record_result {
-                            Ok((_line_num, record)) => {
-                                let _ = generator.process_record(&record, &mut schema_map);
+                            Ok(record_result) => {
+                                result = check(record_result);
+                                if result.is_err() {
+                                    break;
+                                }
                             }
                             Err(_) if ignore_invalid_lines => continue,
                             Err(e) => {
@@ -560,20 +904,24 @@ fn process_files_parallel_impl(
                             }
                         }
                     }
-                    Ok(())
+                    result
                 }
                 InputFormat::Csv => {
-                    let iter = match CsvRecordIterator::new(file) {
+                    let iter = match CsvRecordIterator::with_dialect(file, &config.csv_dialect) {
                         Ok(i) => i,
                         Err(e) => {
                             eprintln!("Error processing '{}': {}", path.display(), e);
                             return None;
                         }
                     };
+                    let mut result = Ok(());
                     for record_result in iter {
                         match record_result {
                             Ok((_line_num, record)) => {
-                                let _ = generator.process_record(&record, &mut schema_map);
+                                result = check(generator.process_record(&record, &mut schema_map));
+                                if result.is_err() {
+                                    break;
+                                }
                             }
                             Err(e) => {
                                 eprintln!("Error processing '{}': {}", path.display(), e);
@@ -581,7 +929,7 @@ fn process_files_parallel_impl(
                             }
                         }
                     }
-                    Ok(())
+                    result
                 }
             };
 
@@ -594,6 +942,14 @@ fn process_files_parallel_impl(
                         errors.extend(generator.error_logs().iter().cloned());
                     }
                 }
+                if let Ok(mut conflicts) = all_conflicts.lock() {
+                    conflicts.extend(generator.conflicts().iter().cloned());
+                }
+                if config.collect_stats {
+                    if let Ok(mut profiles) = all_profiles.lock() {
+                        profiles.push(generator.profile().clone());
+                    }
+                }
 
                 if let Some(ref pb) = progress {
                     pb.inc(1);
@@ -610,12 +966,21 @@ fn process_files_parallel_impl(
         pb.finish_with_message("Done");
     }
 
-    // Merge all schema maps
-    let mut final_generator = SchemaGenerator::new(config.clone());
+    if let Some(e) = conflict_error.into_inner().ok().flatten() {
+        return Err(GenerateError::ProcessingError(e.to_string()));
+    }
+
+    // Merge all schema maps; the merge records are synthetic, so keep them out of the stats
+    let mut final_generator = SchemaGenerator::new(GeneratorConfig {
+        collect_stats: false,
+        ..config.clone()
+    });
     let mut final_schema = args.existing_schema.clone().unwrap_or_default();
 
     for schema_map in results {
-        merge_schema_maps(&mut final_generator, &mut final_schema, schema_map);
+        final_generator
+            .merge_schema_maps(&mut final_schema, schema_map)
+            .map_err(|e| GenerateError::ProcessingError(e.to_string()))?;
     }
 
     let total = total_records.load(Ordering::Relaxed);
@@ -634,6 +999,11 @@ fn process_files_parallel_impl(
         eprintln!("Problem on line {}: {}", error.line_number, error.msg);
     }
 
+    let mut conflicts = all_conflicts.into_inner().unwrap_or_default();
+    conflicts.extend(final_generator.conflicts().iter().cloned());
+    let conflicts = combine_conflicts(conflicts);
+    print_conflict_summary(&conflicts);
+
     write_output(
         &final_generator,
         &final_schema,
@@ -642,61 +1012,585 @@ fn process_files_parallel_impl(
         &mut output,
     )?;
 
+    let mut profile = DataProfile::new(!config.quoted_values_are_strings);
+    for file_profile in all_profiles.into_inner().unwrap_or_default() {
+        profile.merge(file_profile);
+    }
+    let profile = write_stats(args, &profile)?;
+
     Ok(GenerateOutput {
         lines_processed: total,
         files_processed: args.input_files.len(),
         error_logs,
+        conflicts,
+        profile,
     })
 }
 
-/// Merge source schema map into target schema map
-fn merge_schema_maps(generator: &mut SchemaGenerator, target: &mut SchemaMap, source: SchemaMap) {
-    use serde_json::Value;
+/// Process files through the --cache-dir cache and merge their schemas in input order - implementation
+///
+/// Each file's own schema is read from the cache, or generated and stored,
+/// then merged as the parallel path does. Files are processed on
+/// `num_threads` threads, or a single large file is split into chunks.
+fn process_files_cached_impl(
+    args: &ValidatedArgs,
+    num_threads: usize,
+) -> Result<GenerateOutput, GenerateError> {
+    use rayon::prelude::*;
+
+    let cache = open_cache(args)?.expect("--cache-dir is set");
+    let mut output: Box<dyn io::Write> = match &args.output_path {
+        Some(path) => {
+            let file =
+                File::create(path).map_err(|e| GenerateError::OutputFileCreate(path.clone(), e))?;
+            Box::new(file)
+        }
+        None => Box::new(io::stdout()),
+    };
+
+    let files = &args.input_files;
+    let schemas: Vec<(FileSchema, bool)> = if num_threads > 1 && files.len() > 1 {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .build_global()
+            .ok(); // Ignore error if already initialized
 
-    for (_key, entry) in source {
-        // Convert entry to a JSON value to trigger merge logic
-        let json_value = schema_entry_to_json(&entry);
+        // Line progress from several files at once would interleave
+        let file_args = ValidatedArgs {
+            quiet: true,
+            ..args.clone()
+        };
+        files
+            .par_iter()
+            .map(|path| cached_file_schema(path, &file_args, &cache, 1))
+            .collect::<Result<_, _>>()?
+    } else {
+        files
+            .iter()
+            .enumerate()
+            .map(|(idx, path)| {
+                if !args.quiet {
+                    eprintln!(
+                        "Processing file {}/{}: {}",
+                        idx + 1,
+                        files.len(),
+                        path.display()
+                    );
+                }
+                cached_file_schema(path, args, &cache, num_threads)
+            })
+            .collect::<Result<_, _>>()?
+    };
+
+    let mut generator = SchemaGenerator::new(args.config.clone());
+    let mut schema_map = args.existing_schema.clone().unwrap_or_default();
+    let mut total_lines = 0;
+    let mut cached_files = 0;
+    let mut error_logs = Vec::new();
+    let mut conflicts = Vec::new();
+    for (schema, cached) in schemas {
+        generator
+            .merge_schema_maps(&mut schema_map, schema.schema_map)
+            .map_err(|e| GenerateError::ProcessingError(e.to_string()))?;
+        total_lines += schema.lines;
+        cached_files += usize::from(cached);
+        error_logs.extend(schema.error_logs);
+        conflicts.extend(schema.conflicts);
+    }
 
-        // Process the record through the generator to trigger merge logic
-        let mut temp_map = serde_json::Map::new();
-        temp_map.insert(entry.name.clone(), json_value);
+    if !args.quiet {
+        eprintln!(
+            "Processed {} lines from {} files ({} from cache)",
+            total_lines,
+            files.len(),
+            cached_files
+        );
+    }
 
-        let record = Value::Object(temp_map);
-        let _ = generator.process_record(&record, target);
+    for error in &error_logs {
+        eprintln!("Problem on line {}: {}", error.line_number, error.msg);
     }
+
+    conflicts.extend(generator.conflicts().iter().cloned());
+    let conflicts = combine_conflicts(conflicts);
+    print_conflict_summary(&conflicts);
+
+    write_output(
+        &generator,
+        &schema_map,
+        &args.output_format,
+        &args.table_name,
+        &mut output,
+    )?;
+
+    Ok(GenerateOutput {
+        lines_processed: total_lines,
+        files_processed: files.len(),
+        error_logs,
+        conflicts,
+        profile: None,
+    })
 }
 
-/// Convert a SchemaEntry to a representative JSON value for merging
-fn schema_entry_to_json(entry: &SchemaEntry) -> serde_json::Value {
-    use serde_json::Value;
+/// Open the --cache-dir cache, if given
+fn open_cache(args: &ValidatedArgs) -> Result<Option<SchemaCache>, GenerateError> {
+    let Some(dir) = &args.cache_dir else {
+        return Ok(None);
+    };
+    SchemaCache::open(
+        dir,
+        &args.config,
+        args.existing_schema.as_ref(),
+        args.ignore_invalid_lines,
+        args.cache_hash,
+    )
+    .map(Some)
+    .map_err(|e| GenerateError::CacheDirOpen(dir.clone(), e.to_string()))
+}
 
-    match &entry.bq_type {
-        BqType::Boolean | BqType::QBoolean => Value::Bool(true),
-        BqType::Integer | BqType::QInteger => Value::Number(serde_json::Number::from(0i64)),
-        BqType::Float | BqType::QFloat => {
-            Value::Number(serde_json::Number::from_f64(0.0).unwrap_or(serde_json::Number::from(0)))
+/// Get a file's own schema from the cache, or generate and store it.
+///
+/// Returns the schema and whether it came from the cache. A large file is
+/// split into chunks when `num_threads` is more than one.
+fn cached_file_schema(
+    path: &Path,
+    args: &ValidatedArgs,
+    cache: &SchemaCache,
+    num_threads: usize,
+) -> Result<(FileSchema, bool), GenerateError> {
+    let stamp = match cache
+        .lookup(path)
+        .map_err(|e| GenerateError::InputFileOpen(path.to_owned(), e))?
+    {
+        CacheLookup::Hit(schema) => return Ok((schema, true)),
+        CacheLookup::Miss(stamp) => stamp,
+    };
+
+    let input = open_input(path).map_err(|e| GenerateError::InputFileOpen(path.to_owned(), e))?;
+    // The cached schema is the file's alone, without the existing schema,
+    // but inference still sees the existing types
+    let schema = if num_threads > 1 && is_splittable(path, args.config.input_format) {
+        let chunked = process_chunks(input, args, SchemaMap::new(), num_threads, CHUNK_BYTES)?;
+        let mut conflicts = chunked.conflicts;
+        conflicts.extend(chunked.generator.conflicts().iter().cloned());
+        FileSchema {
+            lines: chunked.records,
+            schema_map: chunked.schema_map,
+            error_logs: chunked.error_logs,
+            conflicts: combine_conflicts(conflicts),
         }
-        BqType::String => Value::String(String::new()),
-        BqType::Timestamp => Value::String("2024-01-01T00:00:00".to_string()),
-        BqType::Date => Value::String("2024-01-01".to_string()),
-        BqType::Time => Value::String("00:00:00".to_string()),
-        BqType::Record(fields) => {
-            let mut obj = serde_json::Map::new();
-            for (_, field_entry) in fields {
-                obj.insert(field_entry.name.clone(), schema_entry_to_json(field_entry));
-            }
-            if entry.mode == BqMode::Repeated {
-                Value::Array(vec![Value::Object(obj)])
-            } else {
-                Value::Object(obj)
+    } else {
+        let mut generator = SchemaGenerator::new(args.config.clone());
+        if let Some(existing) = &args.existing_schema {
+            generator.set_known_schema(existing.clone());
+        }
+        let mut schema_map = SchemaMap::new();
+        generator.set_source_file(Some(path.display().to_string()));
+        process_input_impl(
+            input,
+            args.config.input_format,
+            &mut generator,
+            &mut schema_map,
+            args.ignore_invalid_lines,
+            args.debugging_interval,
+            args.quiet,
+        )?;
+        FileSchema::from_generator(&generator, schema_map)
+    };
+
+    // A schema that can't be stored is still good for this run
+    if let Err(e) = cache.put(path, stamp, &schema) {
+        eprintln!(
+            "Warning: Cannot cache schema of '{}': {}",
+            path.display(),
+            e
+        );
+    }
+    Ok((schema, false))
+}
+
+/// Combine conflicts reported by several generators, summing counts per path and resolution
+fn combine_conflicts(conflicts: Vec<FieldConflict>) -> Vec<FieldConflict> {
+    let mut combined: Vec<FieldConflict> = Vec::new();
+    for conflict in conflicts {
+        match combined
+            .iter_mut()
+            .find(|c| c.path == conflict.path && c.resolution == conflict.resolution)
+        {
+            Some(existing) => existing.count += conflict.count,
+            None => combined.push(conflict),
+        }
+    }
+    combined
+}
+
+/// Smallest file that is split into chunks when it is the only input
+const MIN_SPLIT_FILE_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Approximate size of the chunks a split file is read in
+const CHUNK_BYTES: usize = 4 * 1024 * 1024;
+
+/// Check if a file is large newline-delimited JSON, which can be split at line boundaries
+fn is_splittable(path: &Path, input_format: InputFormat) -> bool {
+    if input_format != InputFormat::Json
+        || !std::fs::metadata(path).is_ok_and(|m| m.len() >= MIN_SPLIT_FILE_BYTES)
+    {
+        return false;
+    }
+
+    // Arrays and pretty-printed values can't be cut at arbitrary lines
+    open_input(path).is_ok_and(|input| {
+        let mut reader = JsonReader::new(BufReader::new(input));
+        reader.read_record().is_ok() && reader.layout() == JsonLayout::Lines
+    })
+}
+
+/// Consecutive whole lines of a split input
+struct Chunk {
+    index: usize,
+    /// Line number of the first line in `data`
+    first_line: usize,
+    lines: usize,
+    data: Vec<u8>,
+}
+
+/// Schema built from one chunk by its own generator
+struct ChunkResult {
+    /// The chunk read; its data is only kept under the fail policy, for `ChunkedSchema::replay`
+    chunk: Chunk,
+    generator: SchemaGenerator,
+    schema_map: SchemaMap,
+    result: bq_schema_gen::Result<()>,
+}
+
+/// Chunk results merged in input order
+struct ChunkedSchema {
+    /// Generator used for merging; its conflicts come from the merge itself
+    generator: SchemaGenerator,
+    schema_map: SchemaMap,
+    /// Records processed in the chunks merged so far
+    records: usize,
+    error_logs: Vec<ErrorLog>,
+    conflicts: Vec<FieldConflict>,
+    profile: DataProfile,
+}
+
+impl ChunkedSchema {
+    /// Merge the next chunk, numbering its records after those already merged
+    fn merge_chunk(
+        &mut self,
+        result: ChunkResult,
+        known_schema: &SchemaMap,
+        ignore_invalid_lines: bool,
+    ) -> bq_schema_gen::Result<()> {
+        let offset = self.records;
+        let merged = result.result.and_then(|()| {
+            self.generator
+                .merge_schema_maps(&mut self.schema_map, result.schema_map)
+        });
+        if let Err(e) = merged {
+            // A conflict may be with an earlier chunk, which neither the chunk's
+            // generator nor the merge knows the line of. A failed merge leaves
+            // the schema as it was before the chunk.
+            let e = match &e {
+                Error::FieldConflict { .. } => self
+                    .replay(
+                        &result.chunk,
+                        self.schema_map.clone(),
+                        known_schema,
+                        ignore_invalid_lines,
+                    )
+                    .err()
+                    .unwrap_or(e),
+                _ => e,
+            };
+            return Err(match e {
+                Error::FieldConflict {
+                    line,
+                    path,
+                    message,
+                } => Error::FieldConflict {
+                    line: line + offset,
+                    path,
+                    message,
+                },
+                e => e,
+            });
+        }
+
+        let generator = result.generator;
+        self.error_logs
+            .extend(generator.error_logs().iter().map(|log| ErrorLog {
+                line_number: log.line_number + offset,
+                msg: log.msg.clone(),
+            }));
+        self.conflicts.extend(generator.conflicts().iter().cloned());
+        self.profile.merge(generator.profile().clone());
+        self.records += generator.line_number();
+        Ok(())
+    }
+
+    /// Process a chunk again on top of the schema merged before it, as a single pass would
+    fn replay(
+        &self,
+        chunk: &Chunk,
+        mut schema_map: SchemaMap,
+        known_schema: &SchemaMap,
+        ignore_invalid_lines: bool,
+    ) -> bq_schema_gen::Result<()> {
+        let mut generator = SchemaGenerator::new(self.generator.config().clone());
+        generator.set_known_schema(known_schema.clone());
+        process_json_records(
+            chunk_records(chunk, ignore_invalid_lines),
+            &mut generator,
+            &mut schema_map,
+            ignore_invalid_lines,
+            usize::MAX,
+            true,
+        )
+    }
+}
+
+/// Process one large file by splitting it into chunks across threads - implementation
+fn process_file_chunked_impl(
+    path: &Path,
+    args: &ValidatedArgs,
+    num_threads: usize,
+) -> Result<GenerateOutput, GenerateError> {
+    let mut output: Box<dyn io::Write> = match &args.output_path {
+        Some(path) => {
+            let file =
+                File::create(path).map_err(|e| GenerateError::OutputFileCreate(path.clone(), e))?;
+            Box::new(file)
+        }
+        None => Box::new(io::stdout()),
+    };
+
+    let input = open_input(path).map_err(|e| GenerateError::InputFileOpen(path.to_owned(), e))?;
+    let existing = args.existing_schema.clone().unwrap_or_default();
+    let chunked = process_chunks(input, args, existing, num_threads, CHUNK_BYTES)?;
+
+    if !args.quiet {
+        eprintln!(
+            "Processed {} lines using {} threads",
+            chunked.records, num_threads
+        );
+    }
+
+    for error in &chunked.error_logs {
+        eprintln!("Problem on line {}: {}", error.line_number, error.msg);
+    }
+
+    let mut conflicts = chunked.conflicts;
+    conflicts.extend(chunked.generator.conflicts().iter().cloned());
+    let conflicts = combine_conflicts(conflicts);
+    print_conflict_summary(&conflicts);
+
+    write_output(
+        &chunked.generator,
+        &chunked.schema_map,
+        &args.output_format,
+        &args.table_name,
+        &mut output,
+    )?;
+    let profile = write_stats(args, &chunked.profile)?;
+
+    Ok(GenerateOutput {
+        lines_processed: chunked.records,
+        files_processed: 1,
+        error_logs: chunked.error_logs,
+        conflicts,
+        profile,
+    })
+}
+
+/// Build the schema of newline-delimited JSON on several threads.
+///
+/// A reader thread cuts the input into chunks of whole lines and worker
+/// threads build a schema for each. Results are merged in input order, so
+/// field order and line numbers match a single pass. Chunks are merged onto
+/// `schema_map`. Chunk generators see the existing schema, but not fields
+/// whose type depends on values in earlier chunks: conflicts are counted per
+/// chunk, and keep-first would keep each chunk's own first type, so callers
+/// don't split input under that policy.
+fn process_chunks<R: Read + Send>(
+    input: R,
+    args: &ValidatedArgs,
+    schema_map: SchemaMap,
+    num_threads: usize,
+    chunk_bytes: usize,
+) -> Result<ChunkedSchema, GenerateError> {
+    use std::collections::BTreeMap;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{mpsc, Mutex};
+
+    let config = &args.config;
+    let ignore_invalid_lines = args.ignore_invalid_lines;
+    let known_schema = args.existing_schema.clone().unwrap_or_default();
+    let mut merged = ChunkedSchema {
+        // Chunk profiles are merged instead, so the merge records no stats of its own
+        generator: SchemaGenerator::new(GeneratorConfig {
+            collect_stats: false,
+            ..config.clone()
+        }),
+        schema_map,
+        records: 0,
+        error_logs: Vec::new(),
+        conflicts: Vec::new(),
+        profile: DataProfile::new(!config.quoted_values_are_strings),
+    };
+    let mut failure = None;
+    let abort = AtomicBool::new(false);
+    // Bounded so the reader doesn't run far ahead of the workers
+    let (chunk_tx, chunk_rx) = mpsc::sync_channel(num_threads * 2);
+    let chunk_rx = Mutex::new(chunk_rx);
+
+    let read_result = std::thread::scope(|scope| {
+        let abort = &abort;
+        let (result_tx, result_rx) = mpsc::channel();
+        let reader = scope.spawn(move || read_chunks(input, chunk_bytes, chunk_tx, abort));
+
+        for _ in 0..num_threads {
+            let chunk_rx = &chunk_rx;
+            let known_schema = &known_schema;
+            let result_tx = result_tx.clone();
+            scope.spawn(move || {
+                while let Ok(Ok(chunk)) = chunk_rx.lock().map(|rx| rx.recv()) {
+                    // Keep draining after a failure so the reader can't block
+                    if !abort.load(Ordering::Relaxed) {
+                        let _ = result_tx.send(process_chunk(
+                            chunk,
+                            config,
+                            known_schema,
+                            ignore_invalid_lines,
+                        ));
+                    }
+                }
+            });
+        }
+        drop(result_tx);
+
+        // Chunks finish out of order; hold each until the ones before it are merged
+        let mut pending = BTreeMap::new();
+        let mut next_index = 0;
+        let mut lines_done = 0;
+        for result in result_rx {
+            pending.insert(result.chunk.index, result);
+            while let Some(result) = pending.remove(&next_index) {
+                next_index += 1;
+                if failure.is_some() {
+                    continue;
+                }
+
+                let lines_before = lines_done;
+                lines_done = result.chunk.first_line + result.chunk.lines - 1;
+                if let Err(e) = merged.merge_chunk(result, &known_schema, ignore_invalid_lines) {
+                    failure = Some(e);
+                    abort.store(true, Ordering::Relaxed);
+                } else if !args.quiet
+                    && lines_done / args.debugging_interval > lines_before / args.debugging_interval
+                {
+                    eprintln!(
+                        "Processing line {}",
+                        lines_done / args.debugging_interval * args.debugging_interval
+                    );
+                }
             }
         }
-        BqType::Null => Value::Null,
-        BqType::EmptyArray => Value::Array(vec![]),
-        BqType::EmptyRecord => Value::Object(serde_json::Map::new()),
+
+        reader
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    });
+
+    // A failing chunk comes before the point where reading stopped
+    if let Some(e) = failure {
+        return Err(GenerateError::ProcessingError(e.to_string()));
+    }
+    read_result.map_err(|e| GenerateError::ProcessingError(Error::Io(e).to_string()))?;
+
+    Ok(merged)
+}
+
+/// Read `input` into chunks of whole lines of about `chunk_bytes` each.
+fn read_chunks<R: Read>(
+    input: R,
+    chunk_bytes: usize,
+    chunks: std::sync::mpsc::SyncSender<Chunk>,
+    abort: &std::sync::atomic::AtomicBool,
+) -> io::Result<()> {
+    let mut reader = BufReader::new(input);
+    let mut first_line = 1;
+
+    for index in 0.. {
+        if abort.load(std::sync::atomic::Ordering::Relaxed) {
+            break;
+        }
+
+        let mut data = Vec::new();
+        let mut lines = 0;
+        while data.len() < chunk_bytes && reader.read_until(b'\n', &mut data)? > 0 {
+            lines += 1;
+        }
+        if lines == 0 {
+            break;
+        }
+
+        let chunk = Chunk {
+            index,
+            first_line,
+            lines,
+            data,
+        };
+        if chunks.send(chunk).is_err() {
+            break;
+        }
+        first_line += lines;
+    }
+
+    Ok(())
+}
+
+/// Build the schema of one chunk with a generator of its own
+fn process_chunk(
+    mut chunk: Chunk,
+    config: &GeneratorConfig,
+    known_schema: &SchemaMap,
+    ignore_invalid_lines: bool,
+) -> ChunkResult {
+    let mut generator = SchemaGenerator::new(config.clone());
+    generator.set_known_schema(known_schema.clone());
+    let mut schema_map = SchemaMap::new();
+    let result = process_json_records(
+        chunk_records(&chunk, ignore_invalid_lines),
+        &mut generator,
+        &mut schema_map,
+        ignore_invalid_lines,
+        usize::MAX,
+        true,
+    );
+    if config.conflict_policy != ConflictPolicy::Fail {
+        chunk.data = Vec::new();
+    }
+
+    ChunkResult {
+        chunk,
+        generator,
+        schema_map,
+        result,
     }
 }
 
+/// Iterate over the records of a chunk, numbering lines from the chunk's start
+fn chunk_records(chunk: &Chunk, ignore_invalid_lines: bool) -> JsonRecordIterator<&[u8]> {
+    JsonRecordIterator::with_layout(
+        chunk.data.as_slice(),
+        ignore_invalid_lines,
+        JsonLayout::Lines,
+    )
+    .starting_at_line(chunk.first_line)
+}
+
 /// Process each file separately - implementation
 fn process_per_file_impl(args: &ValidatedArgs) -> Result<GenerateOutput, GenerateError> {
     if let Some(output_dir) = &args.output_dir {
@@ -704,8 +1598,13 @@ fn process_per_file_impl(args: &ValidatedArgs) -> Result<GenerateOutput, Generat
             .map_err(|e| GenerateError::OutputDirCreate(output_dir.clone(), e))?;
     }
 
+    let cache = open_cache(args)?;
     let mut total_lines = 0;
+    let mut cached_files = 0;
     let mut all_error_logs = Vec::new();
+    let mut all_conflicts = Vec::new();
+    // One report covers every file, like the merged schema would
+    let mut profile = DataProfile::new(!args.config.quoted_values_are_strings);
 
     for (idx, path) in args.input_files.iter().enumerate() {
         if !args.quiet {
@@ -717,40 +1616,63 @@ fn process_per_file_impl(args: &ValidatedArgs) -> Result<GenerateOutput, Generat
             );
         }
 
-        let file = File::open(path).map_err(|e| GenerateError::InputFileOpen(path.clone(), e))?;
+        let mut generator = SchemaGenerator::new(args.config.clone());
+        let mut schema_map = args.existing_schema.clone().unwrap_or_default();
+        // Lines, errors and conflicts of the file; the schema itself goes into `schema_map`
+        let schema = match &cache {
+            Some(cache) => {
+                let (mut schema, cached) = cached_file_schema(path, args, cache, 1)?;
+                cached_files += usize::from(cached);
+                generator
+                    .merge_schema_maps(&mut schema_map, std::mem::take(&mut schema.schema_map))
+                    .map_err(|e| GenerateError::ProcessingError(e.to_string()))?;
+                schema
+                    .conflicts
+                    .extend(generator.conflicts().iter().cloned());
+                schema.conflicts = combine_conflicts(schema.conflicts);
+                schema
+            }
+            None => {
+                let file =
+                    open_input(path).map_err(|e| GenerateError::InputFileOpen(path.clone(), e))?;
+                generator.set_source_file(Some(path.display().to_string()));
+                process_input_impl(
+                    file,
+                    args.config.input_format,
+                    &mut generator,
+                    &mut schema_map,
+                    args.ignore_invalid_lines,
+                    args.debugging_interval,
+                    args.quiet,
+                )?;
+                FileSchema::from_generator(&generator, SchemaMap::new())
+            }
+        };
 
-        let output_path = get_per_file_output_path(path, &args.output_dir);
+        let output_path = per_file_schema_path(path, args.output_dir.as_deref());
         let mut output: Box<dyn io::Write> = {
             let file = File::create(&output_path)
                 .map_err(|e| GenerateError::OutputFileCreate(output_path.clone(), e))?;
             Box::new(file)
         };
 
-        let mut generator = SchemaGenerator::new(args.config.clone());
-        let mut schema_map = args.existing_schema.clone().unwrap_or_default();
-
-        process_input_impl(
-            file,
-            args.config.input_format,
-            &mut generator,
-            &mut schema_map,
-            args.ignore_invalid_lines,
-            args.debugging_interval,
-            args.quiet,
-        )?;
-
         if !args.quiet {
             eprintln!(
                 "  Processed {} lines -> {}",
-                generator.line_number(),
+                schema.lines,
                 output_path.display()
             );
         }
 
-        total_lines += generator.line_number();
-        all_error_logs.extend(generator.error_logs().iter().cloned());
+        for error in &schema.error_logs {
+            eprintln!("Problem on line {}: {}", error.line_number, error.msg);
+        }
+        print_conflict_summary(&schema.conflicts);
+        total_lines += schema.lines;
+        all_error_logs.extend(schema.error_logs);
+        all_conflicts.extend(schema.conflicts);
+        profile.merge(generator.profile().clone());
 
-        print_errors(&generator);
         write_output(
             &generator,
             &schema_map,
@@ -760,26 +1682,27 @@ fn process_per_file_impl(args: &ValidatedArgs) -> Result<GenerateOutput, Generat
         )?;
     }
 
+    if !args.quiet && cache.is_some() {
+        eprintln!(
+            "Read {} of {} files from cache",
+            cached_files,
+            args.input_files.len()
+        );
+    }
+
+    let profile = write_stats(args, &profile)?;
+
     Ok(GenerateOutput {
         lines_processed: total_lines,
         files_processed: args.input_files.len(),
         error_logs: all_error_logs,
+        conflicts: all_conflicts,
+        profile,
     })
 }
 
-/// Get output path for per-file mode
-fn get_per_file_output_path(input_path: &Path, output_dir: &Option<PathBuf>) -> PathBuf {
-    let file_stem = input_path.file_stem().unwrap_or_default();
-    let schema_filename = format!("{}.schema.json", file_stem.to_string_lossy());
-
-    match output_dir {
-        Some(dir) => dir.join(schema_filename),
-        None => input_path.with_file_name(schema_filename),
-    }
-}
-
 /// Process input and update schema - implementation (returns Result)
-fn process_input_impl<R: Read>(
+pub(super) fn process_input_impl<R: Read>(
     input: R,
     input_format: InputFormat,
     generator: &mut SchemaGenerator,
@@ -789,10 +1712,11 @@ fn process_input_impl<R: Read>(
     quiet: bool,
 ) -> Result<(), GenerateError> {
     let result = match input_format {
-        InputFormat::Json => {
+        InputFormat::Json | InputFormat::JsonArray => {
             let buf_reader = BufReader::new(input);
             process_json_input(
                 buf_reader,
+                input_format.json_layout(),
                 generator,
                 schema_map,
                 ignore_invalid_lines,
@@ -815,6 +1739,24 @@ fn print_errors(generator: &SchemaGenerator) {
     }
 }
 
+/// Print every dropped or coerced field path with its count
+fn print_conflict_summary(conflicts: &[FieldConflict]) {
+    if conflicts.is_empty() {
+        return;
+    }
+
+    eprintln!("Conflicting fields:");
+    for conflict in conflicts {
+        eprintln!(
+            "  {}: {} ({} value{})",
+            conflict.path,
+            conflict.resolution,
+            conflict.count,
+            if conflict.count == 1 { "" } else { "s" }
+        );
+    }
+}
+
 /// Write schema output (returns Result)
 fn write_output<W: io::Write>(
     generator: &SchemaGenerator,
@@ -832,36 +1774,88 @@ fn write_output<W: io::Write>(
             let schema = generator.flatten_schema(schema_map);
             write_schema_ddl(&schema, table_name, output)
         }
-        OutputFormat::DebugMap => write_schema_debug_map(schema_map, output),
+        OutputFormat::DebugMap => {
+            write_schema_debug_map_with_provenance(schema_map, generator.provenance(), output)
+        }
         OutputFormat::JsonSchema => {
             let schema = generator.flatten_schema(schema_map);
             write_schema_json_schema(&schema, output)
         }
+        OutputFormat::State => write_schema_state(schema_map, output),
     };
 
     write_result.map_err(|e| GenerateError::ProcessingError(format!("Error writing output: {}", e)))
 }
 
+/// Write the stats report if --stats was given, returning the profile written
+fn write_stats(
+    args: &ValidatedArgs,
+    profile: &DataProfile,
+) -> Result<Option<DataProfile>, GenerateError> {
+    let Some(path) = &args.stats_path else {
+        return Ok(None);
+    };
+
+    let mut file =
+        File::create(path).map_err(|e| GenerateError::OutputFileCreate(path.clone(), e))?;
+    let write_result = match args.stats_format {
+        StatsFormat::Json => write_stats_json(profile, &mut file),
+        StatsFormat::Markdown => write_stats_markdown(profile, &mut file),
+    };
+    write_result
+        .map_err(|e| GenerateError::ProcessingError(format!("Error writing stats: {}", e)))?;
+
+    if !args.quiet {
+        eprintln!(
+            "Wrote stats for {} fields to {}",
+            profile.fields.len(),
+            path.display()
+        );
+    }
+
+    Ok(Some(profile.clone()))
+}
+
 /// Process JSON input records
-fn process_json_input<R: std::io::BufRead>(
+fn process_json_input<R: BufRead>(
     input: R,
+    layout: JsonLayout,
     generator: &mut SchemaGenerator,
     schema_map: &mut SchemaMap,
     ignore_invalid_lines: bool,
     debugging_interval: usize,
     quiet: bool,
 ) -> bq_schema_gen::Result<()> {
-    let iter = JsonRecordIterator::new(input, ignore_invalid_lines);
-
-    for result in iter {
-        let (line_num, record) = result?;
+    let records = JsonRecordIterator::with_layout(input, ignore_invalid_lines, layout);
+    process_json_records(
+        records,
+        generator,
+        schema_map,
+        ignore_invalid_lines,
+        debugging_interval,
+        quiet,
+    )
+}
 
+/// Process the records of a JSON iterator
+fn process_json_records<R: BufRead>(
+    mut records: JsonRecordIterator<R>,
+    generator: &mut SchemaGenerator,
+    schema_map: &mut SchemaMap,
+    ignore_invalid_lines: bool,
+    debugging_interval: usize,
+    quiet: bool,
+) -> bq_schema_gen::Result<()> {
+    while let Some(result) = records.next_raw(|line_num, record| {
         if !quiet && line_num % debugging_interval == 0 {
             eprintln!("Processing line {}", line_num);
         }
 
-        if let Err(e) = generator.process_record(&record, schema_map) {
-            if !ignore_invalid_lines {
+        generator.process_raw_record(&record, schema_map)
+    }) {
+        if let Err(e) = result? {
+            // Conflicts under the fail policy are fatal even when skipping invalid lines
+            if !ignore_invalid_lines || matches!(e, Error::FieldConflict { .. }) {
                 return Err(e);
             }
         }
@@ -878,7 +1872,7 @@ fn process_csv_input<R: Read>(
     debugging_interval: usize,
     quiet: bool,
 ) -> bq_schema_gen::Result<()> {
-    let iter = CsvRecordIterator::new(input)?;
+    let iter = CsvRecordIterator::with_dialect(input, &generator.config().csv_dialect)?;
 
     for result in iter {
         let (line_num, record) = result?;
@@ -896,6 +1890,7 @@ fn process_csv_input<R: Read>(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use bq_schema_gen::{BqMode, BqType, SchemaEntry};
     use std::io::Write;
     use tempfile::{NamedTempFile, TempDir};
 
@@ -917,23 +1912,70 @@ mod tests {
         let config = GeneratorConfig::default();
         let mut generator = SchemaGenerator::new(config);
         let mut target = SchemaMap::new();
-
-        // Empty source should not change target
-        let source = SchemaMap::new();
-        merge_schema_maps(&mut generator, &mut target, source);
-
-        assert!(target.is_empty());
-
-        // Non-empty source into empty target
-        let mut source2 = SchemaMap::new();
-        source2.insert(
+
+        // Empty source should not change target
+        let source = SchemaMap::new();
+        generator.merge_schema_maps(&mut target, source).unwrap();
+
+        assert!(target.is_empty());
+
+        // Non-empty source into empty target
+        let mut source2 = SchemaMap::new();
+        source2.insert(
+            "field1".to_string(),
+            make_entry("field1", BqType::Integer, BqMode::Nullable),
+        );
+        generator.merge_schema_maps(&mut target, source2).unwrap();
+
+        assert!(!target.is_empty());
+        assert!(target.contains_key("field1"));
+    }
+
+    #[test]
+    fn test_merge_schema_maps_fail_policy() {
+        let config = GeneratorConfig {
+            conflict_policy: ConflictPolicy::Fail,
+            ..Default::default()
+        };
+        let mut generator = SchemaGenerator::new(config);
+        let mut target = SchemaMap::new();
+        target.insert(
             "field1".to_string(),
             make_entry("field1", BqType::Integer, BqMode::Nullable),
         );
-        merge_schema_maps(&mut generator, &mut target, source2);
 
-        assert!(!target.is_empty());
-        assert!(target.contains_key("field1"));
+        let mut source = SchemaMap::new();
+        source.insert(
+            "field1".to_string(),
+            make_entry("field1", BqType::Boolean, BqMode::Nullable),
+        );
+        let err = generator
+            .merge_schema_maps(&mut target, source)
+            .unwrap_err();
+        assert!(matches!(err, Error::FieldConflict { .. }));
+    }
+
+    #[test]
+    fn test_combine_conflicts() {
+        use bq_schema_gen::ConflictResolution;
+
+        let conflict = |path: &str, resolution, count| FieldConflict {
+            path: path.to_string(),
+            resolution,
+            count,
+        };
+        let combined = combine_conflicts(vec![
+            conflict("a", ConflictResolution::Dropped, 1),
+            conflict("b", ConflictResolution::CoercedToString, 2),
+            conflict("a", ConflictResolution::Dropped, 3),
+        ]);
+        assert_eq!(
+            combined,
+            vec![
+                conflict("a", ConflictResolution::Dropped, 4),
+                conflict("b", ConflictResolution::CoercedToString, 2),
+            ]
+        );
     }
 
     #[test]
@@ -955,7 +1997,7 @@ mod tests {
             make_entry("field_b", BqType::Integer, BqMode::Nullable),
         );
 
-        merge_schema_maps(&mut generator, &mut target, source);
+        generator.merge_schema_maps(&mut target, source).unwrap();
 
         // Both fields should exist
         assert!(target.contains_key("field_a"));
@@ -963,134 +2005,6 @@ mod tests {
         assert_eq!(target.len(), 2);
     }
 
-    #[test]
-    fn test_get_per_file_output_path() {
-        // Without output_dir
-        let input = PathBuf::from("/data/file.json");
-        let result = get_per_file_output_path(&input, &None);
-        assert_eq!(result, PathBuf::from("/data/file.schema.json"));
-
-        // With output_dir
-        let output_dir = Some(PathBuf::from("/output"));
-        let result = get_per_file_output_path(&input, &output_dir);
-        assert_eq!(result, PathBuf::from("/output/file.schema.json"));
-    }
-
-    #[test]
-    fn test_schema_entry_to_json_primitives() {
-        // Boolean
-        let entry = make_entry("bool_field", BqType::Boolean, BqMode::Nullable);
-        let json = schema_entry_to_json(&entry);
-        assert!(json.is_boolean());
-
-        // Integer
-        let entry = make_entry("int_field", BqType::Integer, BqMode::Nullable);
-        let json = schema_entry_to_json(&entry);
-        assert!(json.is_number());
-
-        // Float
-        let entry = make_entry("float_field", BqType::Float, BqMode::Nullable);
-        let json = schema_entry_to_json(&entry);
-        assert!(json.is_number());
-
-        // String
-        let entry = make_entry("string_field", BqType::String, BqMode::Nullable);
-        let json = schema_entry_to_json(&entry);
-        assert!(json.is_string());
-
-        // Null
-        let entry = make_entry("null_field", BqType::Null, BqMode::Nullable);
-        let json = schema_entry_to_json(&entry);
-        assert!(json.is_null());
-    }
-
-    #[test]
-    fn test_schema_entry_to_json_date_types() {
-        // Timestamp
-        let entry = make_entry("ts_field", BqType::Timestamp, BqMode::Nullable);
-        let json = schema_entry_to_json(&entry);
-        assert!(json.is_string());
-        assert!(json.as_str().unwrap().contains("2024-01-01"));
-
-        // Date
-        let entry = make_entry("date_field", BqType::Date, BqMode::Nullable);
-        let json = schema_entry_to_json(&entry);
-        assert!(json.is_string());
-        assert_eq!(json.as_str().unwrap(), "2024-01-01");
-
-        // Time
-        let entry = make_entry("time_field", BqType::Time, BqMode::Nullable);
-        let json = schema_entry_to_json(&entry);
-        assert!(json.is_string());
-        assert_eq!(json.as_str().unwrap(), "00:00:00");
-    }
-
-    #[test]
-    fn test_schema_entry_to_json_quoted_types() {
-        // QBoolean
-        let entry = make_entry("qbool_field", BqType::QBoolean, BqMode::Nullable);
-        let json = schema_entry_to_json(&entry);
-        assert!(json.is_boolean());
-
-        // QInteger
-        let entry = make_entry("qint_field", BqType::QInteger, BqMode::Nullable);
-        let json = schema_entry_to_json(&entry);
-        assert!(json.is_number());
-
-        // QFloat
-        let entry = make_entry("qfloat_field", BqType::QFloat, BqMode::Nullable);
-        let json = schema_entry_to_json(&entry);
-        assert!(json.is_number());
-    }
-
-    #[test]
-    fn test_schema_entry_to_json_empty_types() {
-        // EmptyArray
-        let entry = make_entry("empty_arr", BqType::EmptyArray, BqMode::Nullable);
-        let json = schema_entry_to_json(&entry);
-        assert!(json.is_array());
-        assert!(json.as_array().unwrap().is_empty());
-
-        // EmptyRecord
-        let entry = make_entry("empty_rec", BqType::EmptyRecord, BqMode::Nullable);
-        let json = schema_entry_to_json(&entry);
-        assert!(json.is_object());
-        assert!(json.as_object().unwrap().is_empty());
-    }
-
-    #[test]
-    fn test_schema_entry_to_json_nested_record() {
-        let nested_field = make_entry("inner", BqType::String, BqMode::Nullable);
-        let mut inner_map = SchemaMap::new();
-        inner_map.insert("inner".to_string(), nested_field);
-
-        let entry = SchemaEntry::new(
-            "outer".to_string(),
-            BqType::Record(inner_map),
-            BqMode::Nullable,
-        );
-        let json = schema_entry_to_json(&entry);
-        assert!(json.is_object());
-        assert!(json.as_object().unwrap().contains_key("inner"));
-    }
-
-    #[test]
-    fn test_schema_entry_to_json_repeated_record() {
-        let nested_field = make_entry("item", BqType::Integer, BqMode::Nullable);
-        let mut inner_map = SchemaMap::new();
-        inner_map.insert("item".to_string(), nested_field);
-
-        let entry = SchemaEntry::new(
-            "items".to_string(),
-            BqType::Record(inner_map),
-            BqMode::Repeated,
-        );
-        let json = schema_entry_to_json(&entry);
-        assert!(json.is_array());
-        assert_eq!(json.as_array().unwrap().len(), 1);
-        assert!(json.as_array().unwrap()[0].is_object());
-    }
-
     #[test]
     fn test_merge_schema_maps_overlapping() {
         let config = GeneratorConfig::default();
@@ -1110,26 +2024,10 @@ mod tests {
             make_entry("field", BqType::String, BqMode::Nullable),
         );
 
-        merge_schema_maps(&mut generator, &mut target, source);
+        generator.merge_schema_maps(&mut target, source).unwrap();
         assert!(target.contains_key("field"));
     }
 
-    #[test]
-    fn test_get_per_file_output_path_no_extension() {
-        // File without extension
-        let input = PathBuf::from("/data/file");
-        let result = get_per_file_output_path(&input, &None);
-        assert_eq!(result, PathBuf::from("/data/file.schema.json"));
-    }
-
-    #[test]
-    fn test_get_per_file_output_path_nested_dir() {
-        let input = PathBuf::from("/a/b/c/file.json");
-        let output_dir = Some(PathBuf::from("/out"));
-        let result = get_per_file_output_path(&input, &output_dir);
-        assert_eq!(result, PathBuf::from("/out/file.schema.json"));
-    }
-
     #[test]
     fn test_process_json_input_empty() {
         let input = std::io::Cursor::new("");
@@ -1139,6 +2037,7 @@ mod tests {
 
         let result = process_json_input(
             std::io::BufReader::new(input),
+            JsonLayout::Auto,
             &mut generator,
             &mut schema_map,
             false,
@@ -1159,6 +2058,7 @@ mod tests {
 
         let result = process_json_input(
             std::io::BufReader::new(input),
+            JsonLayout::Auto,
             &mut generator,
             &mut schema_map,
             false,
@@ -1184,6 +2084,7 @@ mod tests {
 
         let result = process_json_input(
             std::io::BufReader::new(input),
+            JsonLayout::Auto,
             &mut generator,
             &mut schema_map,
             false,
@@ -1204,6 +2105,7 @@ mod tests {
 
         let result = process_json_input(
             std::io::BufReader::new(input),
+            JsonLayout::Auto,
             &mut generator,
             &mut schema_map,
             false, // Don't ignore invalid lines
@@ -1227,6 +2129,7 @@ invalid json
 
         let result = process_json_input(
             std::io::BufReader::new(input),
+            JsonLayout::Auto,
             &mut generator,
             &mut schema_map,
             true, // Ignore invalid lines
@@ -1238,6 +2141,204 @@ invalid json
         assert_eq!(schema_map.len(), 2);
     }
 
+    fn chunk_test_args(config: GeneratorConfig, ignore_invalid_lines: bool) -> ValidatedArgs {
+        ValidatedArgs {
+            input_format: InputFormat::Json,
+            output_format: OutputFormat::Json,
+            config,
+            existing_schema: None,
+            seed_states: Vec::new(),
+            input_files: vec![],
+            output_path: None,
+            output_dir: None,
+            per_file: false,
+            watch: false,
+            threads: Some(4),
+            table_name: "test_table".to_string(),
+            quiet: true,
+            ignore_invalid_lines,
+            debugging_interval: 1000,
+            debounce: 100,
+            on_change: None,
+            file_patterns: vec![],
+            stats_path: None,
+            stats_format: StatsFormat::Json,
+            cache_dir: None,
+            cache_hash: false,
+        }
+    }
+
+    /// Process `input` in one pass, as the sequential path does
+    fn single_pass(
+        input: &str,
+        args: &ValidatedArgs,
+    ) -> (SchemaGenerator, SchemaMap, bq_schema_gen::Result<()>) {
+        let mut generator = SchemaGenerator::new(args.config.clone());
+        let mut schema_map = args.existing_schema.clone().unwrap_or_default();
+        let result = process_json_input(
+            std::io::Cursor::new(input.to_string()),
+            JsonLayout::Lines,
+            &mut generator,
+            &mut schema_map,
+            args.ignore_invalid_lines,
+            1000,
+            true,
+        );
+        (generator, schema_map, result)
+    }
+
+    #[test]
+    fn test_process_chunks_matches_single_pass() {
+        let mut input = String::new();
+        for i in 0..300 {
+            let extra = match i % 7 {
+                0 => format!(", \"f{}\": {}", i % 5, i),
+                3 => format!(", \"f{}\": {}.5", i % 5, i),
+                _ => String::new(),
+            };
+            let late = match i {
+                0..=149 => "",
+                150..=199 => ", \"late\": null",
+                _ => ", \"late\": \"2024-01-01\"",
+            };
+            input.push_str(&format!(
+                "{{\"id\": {}, \"nested\": {{\"n{}\": true}}{}{}}}\n",
+                i,
+                i % 3,
+                extra,
+                late
+            ));
+        }
+
+        for preserve_input_sort_order in [false, true] {
+            let config = GeneratorConfig {
+                preserve_input_sort_order,
+                collect_stats: true,
+                ..Default::default()
+            };
+            let args = chunk_test_args(config, false);
+            let (generator, schema_map, result) = single_pass(&input, &args);
+            result.unwrap();
+
+            let chunked = process_chunks(
+                std::io::Cursor::new(input.clone()),
+                &args,
+                SchemaMap::new(),
+                4,
+                100,
+            )
+            .unwrap();
+            assert_eq!(chunked.records, 300);
+            assert_eq!(
+                serde_json::to_value(chunked.generator.flatten_schema(&chunked.schema_map))
+                    .unwrap(),
+                serde_json::to_value(generator.flatten_schema(&schema_map)).unwrap()
+            );
+            assert_eq!(chunked.profile.record_count, 300);
+            assert_eq!(chunked.profile.fields["id"].presence_count, 300);
+        }
+    }
+
+    #[test]
+    fn test_process_chunks_with_existing_schema() {
+        let input: String = (0..60)
+            .map(|i| {
+                format!(
+                    "{{\"id\": {}, \"blob\": \"aGVsbG8=\", \"at\": {}}}\n",
+                    i,
+                    1_700_000_000 + i
+                )
+            })
+            .collect();
+        let existing = bq_schema_gen::schema::bq_schema_to_map(&serde_json::json!([
+            {"name": "blob", "type": "BYTES"},
+            {"name": "at", "type": "TIMESTAMP"}
+        ]))
+        .unwrap();
+
+        // Every chunk sees the existing BYTES column and epoch field, not just the first
+        let config = GeneratorConfig {
+            epoch_detection: Some(Default::default()),
+            epoch_field_pattern: Some(regex::Regex::new("_ts$").unwrap()),
+            ..Default::default()
+        };
+        let args = ValidatedArgs {
+            existing_schema: Some(existing.clone()),
+            ..chunk_test_args(config, false)
+        };
+        let (generator, schema_map, result) = single_pass(&input, &args);
+        result.unwrap();
+
+        let chunked = process_chunks(std::io::Cursor::new(input), &args, existing, 3, 100).unwrap();
+        assert!(chunked.conflicts.is_empty());
+        let schema = serde_json::to_value(generator.flatten_schema(&schema_map)).unwrap();
+        assert_eq!(
+            serde_json::to_value(chunked.generator.flatten_schema(&chunked.schema_map)).unwrap(),
+            schema
+        );
+        assert_eq!(schema[0]["type"], "TIMESTAMP");
+        assert_eq!(schema[1]["name"], "blob");
+        assert_eq!(schema[1]["type"], "BYTES");
+    }
+
+    #[test]
+    fn test_process_chunks_line_numbers() {
+        let mut lines: Vec<String> = (0..60).map(|i| format!("{{\"id\": {}}}", i)).collect();
+        lines[10] = String::new();
+        lines[25] = "[1, 2]".to_string();
+        lines[41] = "not json".to_string();
+        lines[52] = "\"text\"".to_string();
+        let input = lines.join("\n");
+
+        // Skipped lines and non-object records keep their numbers across chunks
+        let args = chunk_test_args(GeneratorConfig::default(), true);
+        let (generator, _, _) = single_pass(&input, &args);
+        let chunked = process_chunks(
+            std::io::Cursor::new(input.clone()),
+            &args,
+            SchemaMap::new(),
+            3,
+            40,
+        )
+        .unwrap();
+        assert_eq!(chunked.records, generator.line_number());
+        let logged = |logs: &[ErrorLog]| {
+            logs.iter()
+                .map(|log| (log.line_number, log.msg.clone()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(logged(&chunked.error_logs), logged(generator.error_logs()));
+        assert_eq!(chunked.error_logs.len(), 2);
+
+        // The first invalid line is reported, with its line in the file
+        let args = chunk_test_args(GeneratorConfig::default(), false);
+        lines[25] = "{\"id\": 25}".to_string();
+        let input = lines.join("\n");
+        let (_, _, result) = single_pass(&input, &args);
+        let expected = result.unwrap_err().to_string();
+        assert!(expected.contains("42"), "{}", expected);
+        match process_chunks(std::io::Cursor::new(input), &args, SchemaMap::new(), 3, 40) {
+            Err(GenerateError::ProcessingError(msg)) => assert_eq!(msg, expected),
+            other => panic!("expected a processing error, got {:?}", other.err()),
+        }
+
+        // Conflicts under the fail policy count records across chunks
+        let config = GeneratorConfig {
+            conflict_policy: ConflictPolicy::Fail,
+            ..Default::default()
+        };
+        let args = chunk_test_args(config, false);
+        lines[41] = "{\"id\": [1]}".to_string();
+        lines[52] = "{\"id\": {\"a\": 1}}".to_string();
+        let input = lines.join("\n");
+        let (_, _, result) = single_pass(&input, &args);
+        let expected = result.unwrap_err().to_string();
+        match process_chunks(std::io::Cursor::new(input), &args, SchemaMap::new(), 3, 40) {
+            Err(GenerateError::ProcessingError(msg)) => assert_eq!(msg, expected),
+            other => panic!("expected a processing error, got {:?}", other.err()),
+        }
+    }
+
     #[test]
     fn test_process_csv_input_basic() {
         let input = std::io::Cursor::new("name,value\ntest,42\nfoo,123");
@@ -1352,6 +2453,7 @@ invalid json
             command: None,
             files: vec![],
             input_format: "json".to_string(),
+            csv: CsvArgs::default(),
             output_format: "json".to_string(),
             table_name: "test_table".to_string(),
             keep_nulls: false,
@@ -1361,16 +2463,33 @@ invalid json
             sanitize_names: false,
             ignore_invalid_lines: false,
             existing_schema_path: None,
+            seed_state: Vec::new(),
             preserve_input_sort_order: false,
+            infer_numeric: false,
+            bytes_min_length: None,
+            bytes_field_pattern: None,
+            infer_geography: false,
+            epochs: EpochArgs::default(),
+            epoch_field_pattern: None,
+            datetime_format: vec![],
+            regex_only_dates: false,
+            conflict_policy: "ignore".to_string(),
+            json_paths: vec![],
+            type_hints: None,
             quiet: true,
             input: None,
             output: None,
             per_file: false,
             output_dir: None,
             threads: None,
+            cache_dir: None,
+            cache_hash: false,
             watch: false,
             debounce: 100,
             on_change: None,
+            stats: None,
+            stats_format: "json".to_string(),
+            provenance: false,
         }
     }
 
@@ -1387,6 +2506,182 @@ invalid json
         ));
     }
 
+    #[test]
+    fn test_validate_cli_args_invalid_bytes_field_pattern() {
+        let mut cli = create_test_cli();
+        cli.bytes_field_pattern = Some("(unclosed".to_string());
+
+        let result = validate_cli_args(&cli);
+        assert!(matches!(
+            result.unwrap_err(),
+            GenerateError::InvalidBytesFieldPattern(_, _)
+        ));
+
+        cli.bytes_field_pattern = Some("_b64$".to_string());
+        let args = validate_cli_args(&cli).unwrap();
+        assert!(args.config.bytes_field_pattern.is_some());
+    }
+
+    #[test]
+    fn test_validate_cli_args_conflict_policy() {
+        let mut cli = create_test_cli();
+        cli.conflict_policy = "widen".to_string();
+        assert!(matches!(
+            validate_cli_args(&cli).unwrap_err(),
+            GenerateError::InvalidConflictPolicy(_)
+        ));
+
+        cli.conflict_policy = "JSON".to_string();
+        cli.json_paths = vec!["payload.attributes".to_string()];
+        let args = validate_cli_args(&cli).unwrap();
+        assert_eq!(args.config.conflict_policy, ConflictPolicy::Json);
+        assert_eq!(args.config.json_paths, vec!["payload.attributes"]);
+    }
+
+    #[test]
+    fn test_validate_cli_args_type_hints() {
+        let mut cli = create_test_cli();
+        cli.type_hints = Some(PathBuf::from("/nonexistent/hints.json"));
+        assert!(matches!(
+            validate_cli_args(&cli).unwrap_err(),
+            GenerateError::TypeHintsLoad(_, _)
+        ));
+
+        let hints = create_temp_file(r#"{"zip_code": "STRING"}"#);
+        cli.type_hints = Some(hints.path().to_path_buf());
+        let args = validate_cli_args(&cli).unwrap();
+        assert!(args.config.type_hints.lookup("zip_code").is_some());
+    }
+
+    #[test]
+    fn test_validate_cli_args_csv_dialect() {
+        let args = validate_cli_args(&create_test_cli()).unwrap();
+        assert_eq!(args.config.csv_dialect, CsvDialect::default());
+
+        let mut cli = create_test_cli();
+        cli.input_format = "csv".to_string();
+        cli.csv = CsvArgs {
+            csv_delimiter: Some("tab".to_string()),
+            csv_quote: Some("'".to_string()),
+            csv_escape: Some("\\".to_string()),
+            skip_leading_rows: 2,
+            csv_no_header: true,
+            csv_comment: Some("#".to_string()),
+            csv_null_marker: vec!["\\N".to_string()],
+            csv_empty_as_null: true,
+        };
+        let dialect = validate_cli_args(&cli).unwrap().config.csv_dialect;
+        assert_eq!(
+            dialect,
+            CsvDialect {
+                delimiter: b'\t',
+                quote: b'\'',
+                escape: Some(b'\\'),
+                skip_leading_rows: 2,
+                has_header: false,
+                comment: Some(b'#'),
+                null_markers: vec!["\\N".to_string()],
+                empty_as_null: true,
+            }
+        );
+
+        for (delimiter, quote) in [("||", None), ("é", None), (",", Some(","))] {
+            let mut cli = create_test_cli();
+            cli.csv.csv_delimiter = Some(delimiter.to_string());
+            cli.csv.csv_quote = quote.map(str::to_string);
+            assert!(matches!(
+                validate_cli_args(&cli),
+                Err(GenerateError::InvalidCsvOption(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_validate_cli_args_epochs() {
+        let mut cli = create_test_cli();
+        assert!(validate_cli_args(&cli)
+            .unwrap()
+            .config
+            .epoch_detection
+            .is_none());
+
+        // Range and unit flags need detection turned on
+        cli.epochs.epoch_units = vec!["seconds".to_string()];
+        assert!(matches!(
+            validate_cli_args(&cli).unwrap_err(),
+            GenerateError::InvalidEpochOption(_)
+        ));
+
+        // A field pattern implies detection
+        cli.epoch_field_pattern = Some("_(at|ts)$".to_string());
+        cli.epochs.epoch_min = Some("2020-01-01".to_string());
+        let args = validate_cli_args(&cli).unwrap();
+        let detection = args.config.epoch_detection.unwrap();
+        assert_eq!(detection.min_seconds, 1_577_836_800);
+        assert_eq!(detection.units, vec![EpochUnit::Seconds]);
+        assert!(args.config.epoch_field_pattern.is_some());
+
+        cli.epoch_field_pattern = Some("(".to_string());
+        assert!(matches!(
+            validate_cli_args(&cli).unwrap_err(),
+            GenerateError::InvalidEpochFieldPattern(_, _)
+        ));
+
+        cli.epoch_field_pattern = None;
+        cli.epochs.infer_epochs = true;
+        for (min, max, unit) in [
+            ("2020-02-30", "2100-01-01", "seconds"),
+            ("2100-01-01", "2000-01-01", "seconds"),
+            ("2000-01-01", "2100-01-01", "nanos"),
+        ] {
+            cli.epochs.epoch_min = Some(min.to_string());
+            cli.epochs.epoch_max = Some(max.to_string());
+            cli.epochs.epoch_units = vec![unit.to_string()];
+            assert!(matches!(
+                validate_cli_args(&cli).unwrap_err(),
+                GenerateError::InvalidEpochOption(_)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_validate_cli_args_datetime_formats() {
+        let mut cli = create_test_cli();
+        cli.datetime_format = vec!["DATE=%m/%d/%Y".to_string(), "TIME@t=%H%M".to_string()];
+        let args = validate_cli_args(&cli).unwrap();
+        assert!(args.config.datetime_formats.has_scoped());
+
+        cli.datetime_format = vec!["STRING=%Y".to_string()];
+        assert!(matches!(
+            validate_cli_args(&cli).unwrap_err(),
+            GenerateError::InvalidDateTimeFormat(_)
+        ));
+    }
+
+    #[test]
+    fn test_validate_cli_args_stats() {
+        let mut cli = create_test_cli();
+        cli.stats_format = "csv".to_string();
+        assert!(matches!(
+            validate_cli_args(&cli).unwrap_err(),
+            GenerateError::InvalidStatsFormat(_)
+        ));
+
+        cli.stats_format = "markdown".to_string();
+        cli.stats = Some(PathBuf::from("stats.md"));
+        let args = validate_cli_args(&cli).unwrap();
+        assert!(args.config.collect_stats);
+        assert_eq!(args.stats_format, StatsFormat::Markdown);
+
+        let temp_file = create_temp_file(r#"{"id": 1}"#);
+        cli.watch = true;
+        cli.files = vec![temp_file.path().to_string_lossy().to_string()];
+        assert!(matches!(
+            validate_cli_args(&cli).unwrap_err(),
+            GenerateError::WatchWithStats
+        ));
+    }
+
     #[test]
     fn test_validate_cli_args_invalid_output_format() {
         let mut cli = create_test_cli();
@@ -1428,6 +2723,78 @@ invalid json
         ));
     }
 
+    #[test]
+    fn test_validate_cli_args_cache_dir() {
+        let mut cli = create_test_cli();
+        cli.cache_hash = true;
+        assert!(matches!(
+            validate_cli_args(&cli).unwrap_err(),
+            GenerateError::CacheHashRequiresCacheDir
+        ));
+
+        cli.cache_dir = Some(PathBuf::from("/cache"));
+        assert!(matches!(
+            validate_cli_args(&cli).unwrap_err(),
+            GenerateError::CacheDirRequiresInput
+        ));
+
+        let temp_file = create_temp_file(r#"{"id": 1}"#);
+        cli.files = vec![temp_file.path().to_string_lossy().to_string()];
+        let args = validate_cli_args(&cli).unwrap();
+        assert_eq!(args.cache_dir, Some(PathBuf::from("/cache")));
+        assert!(args.cache_hash);
+
+        cli.output_format = "debug-map".to_string();
+        assert!(validate_cli_args(&cli).is_ok());
+        cli.provenance = true;
+        assert!(matches!(
+            validate_cli_args(&cli).unwrap_err(),
+            GenerateError::CacheDirWith(option) if option == "--provenance"
+        ));
+        cli.provenance = false;
+        cli.conflict_policy = "keep-first".to_string();
+        assert!(matches!(
+            validate_cli_args(&cli).unwrap_err(),
+            GenerateError::CacheDirWith(option) if option == "--conflict-policy keep-first"
+        ));
+        cli.conflict_policy = "ignore".to_string();
+        cli.output_format = "json".to_string();
+        cli.stats = Some(PathBuf::from("stats.json"));
+        assert!(matches!(
+            validate_cli_args(&cli).unwrap_err(),
+            GenerateError::CacheDirWith(option) if option == "--stats"
+        ));
+    }
+
+    #[test]
+    fn test_validate_cli_args_seed_state() {
+        let state = create_temp_file(
+            r#"{"version": 1, "schema": {"id": {"status": "hard", "filled": true, "name": "id", "type": "INTEGER", "mode": "NULLABLE"}}}"#,
+        );
+        let mut cli = create_test_cli();
+        cli.seed_state = vec![state.path().to_owned(), state.path().to_owned()];
+        let args = validate_cli_args(&cli).unwrap();
+        let schema = args.existing_schema.unwrap();
+        assert_eq!(schema.len(), 1);
+        assert_eq!(schema["id"].bq_type, BqType::Integer);
+        assert_eq!(args.seed_states.len(), 2);
+
+        cli.seed_state
+            .push(PathBuf::from("/nonexistent/state.json"));
+        assert!(matches!(
+            validate_cli_args(&cli).unwrap_err(),
+            GenerateError::SeedStateLoad(_, _)
+        ));
+
+        cli.seed_state.truncate(1);
+        cli.watch = true;
+        cli.files = vec!["data.json".to_string()];
+        assert!(matches!(
+            validate_cli_args(&cli).unwrap_err(),
+            GenerateError::SeedStateWithWatch
+        ));
+    }
+
     #[test]
     fn test_validate_cli_args_watch_no_files() {
         let mut cli = create_test_cli();
@@ -1449,13 +2816,11 @@ invalid json
         cli.watch = true;
         cli.per_file = true;
         cli.files = vec![temp_file.path().to_string_lossy().to_string()];
+        cli.output_dir = Some(PathBuf::from("schemas"));
 
-        let result = validate_cli_args(&cli);
-        assert!(result.is_err());
-        assert!(matches!(
-            result.unwrap_err(),
-            GenerateError::WatchWithPerFile
-        ));
+        let args = validate_cli_args(&cli).unwrap();
+        assert!(args.watch && args.per_file);
+        assert_eq!(args.output_dir, Some(PathBuf::from("schemas")));
     }
 
     #[test]
@@ -1469,6 +2834,16 @@ invalid json
         assert_eq!(args.output_format, OutputFormat::Json);
     }
 
+    #[test]
+    fn test_validate_cli_args_json_array_format() {
+        let mut cli = create_test_cli();
+        cli.input_format = "json-array".to_string();
+
+        let args = validate_cli_args(&cli).unwrap();
+        assert_eq!(args.input_format, InputFormat::JsonArray);
+        assert_eq!(args.input_format.json_layout(), JsonLayout::Stream);
+    }
+
     #[test]
     fn test_validate_cli_args_valid_csv_format() {
         let mut cli = create_test_cli();
@@ -1544,6 +2919,7 @@ invalid json
             output_format: OutputFormat::Json,
             config: GeneratorConfig::default(),
             existing_schema: None,
+            seed_states: Vec::new(),
             input_files: vec![input_file],
             output_path: Some(output_file.clone()),
             output_dir: None,
@@ -1557,6 +2933,10 @@ invalid json
             debounce: 100,
             on_change: None,
             file_patterns: vec![],
+            stats_path: None,
+            stats_format: StatsFormat::Json,
+            cache_dir: None,
+            cache_hash: false,
         };
 
         let result = generate_schema(&args);
@@ -1582,6 +2962,7 @@ invalid json
             output_format: OutputFormat::Json,
             config: GeneratorConfig::default(),
             existing_schema: None,
+            seed_states: Vec::new(),
             input_files: vec![input_file1, input_file2],
             output_path: Some(output_file.clone()),
             output_dir: None,
@@ -1595,6 +2976,10 @@ invalid json
             debounce: 100,
             on_change: None,
             file_patterns: vec![],
+            stats_path: None,
+            stats_format: StatsFormat::Json,
+            cache_dir: None,
+            cache_hash: false,
         };
 
         let result = generate_schema(&args);
@@ -1604,6 +2989,59 @@ invalid json
         assert_eq!(output.files_processed, 2);
     }
 
+    #[test]
+    fn test_generate_schema_stats_parallel() {
+        let temp_dir = TempDir::new().unwrap();
+        let input_file1 = temp_dir.path().join("input1.json");
+        let input_file2 = temp_dir.path().join("input2.json");
+        let stats_file = temp_dir.path().join("stats.json");
+
+        std::fs::write(&input_file1, "{\"id\": 5}\n{\"id\": null}").unwrap();
+        std::fs::write(&input_file2, r#"{"id": -2, "name": "test"}"#).unwrap();
+
+        let args = ValidatedArgs {
+            input_format: InputFormat::Json,
+            output_format: OutputFormat::Json,
+            config: GeneratorConfig {
+                collect_stats: true,
+                ..Default::default()
+            },
+            existing_schema: None,
+            seed_states: Vec::new(),
+            input_files: vec![input_file1, input_file2],
+            output_path: Some(temp_dir.path().join("output.json")),
+            output_dir: None,
+            per_file: false,
+            watch: false,
+            threads: Some(2),
+            table_name: "test_table".to_string(),
+            quiet: true,
+            ignore_invalid_lines: false,
+            debugging_interval: 1000,
+            debounce: 100,
+            on_change: None,
+            file_patterns: vec![],
+            stats_path: Some(stats_file.clone()),
+            stats_format: StatsFormat::Json,
+            cache_dir: None,
+            cache_hash: false,
+        };
+
+        let profile = generate_schema(&args).unwrap().profile.unwrap();
+        assert_eq!(profile.record_count, 3);
+        let id = &profile.fields["id"];
+        assert_eq!(id.presence_count, 3);
+        assert_eq!(id.null_count, 1);
+        assert_eq!(id.min(), Some(serde_json::json!(-2)));
+        assert_eq!(id.max(), Some(serde_json::json!(5)));
+
+        let report: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&stats_file).unwrap()).unwrap();
+        assert_eq!(report["record_count"], 3);
+        assert_eq!(report["fields"][0]["path"], "id");
+        assert_eq!(report["fields"][1]["path"], "name");
+    }
+
     #[test]
     fn test_generate_schema_per_file_mode() {
         let temp_dir = TempDir::new().unwrap();
@@ -1619,6 +3057,7 @@ invalid json
             output_format: OutputFormat::Json,
             config: GeneratorConfig::default(),
             existing_schema: None,
+            seed_states: Vec::new(),
             input_files: vec![input_file1.clone(), input_file2.clone()],
             output_path: None,
             output_dir: Some(output_dir.clone()),
@@ -1632,6 +3071,10 @@ invalid json
             debounce: 100,
             on_change: None,
             file_patterns: vec![],
+            stats_path: None,
+            stats_format: StatsFormat::Json,
+            cache_dir: None,
+            cache_hash: false,
         };
 
         let result = generate_schema(&args);
@@ -1651,6 +3094,7 @@ invalid json
             output_format: OutputFormat::Json,
             config: GeneratorConfig::default(),
             existing_schema: None,
+            seed_states: Vec::new(),
             input_files: vec![],
             output_path: None,
             output_dir: None,
@@ -1664,6 +3108,10 @@ invalid json
             debounce: 100,
             on_change: None,
             file_patterns: vec![],
+            stats_path: None,
+            stats_format: StatsFormat::Json,
+            cache_dir: None,
+            cache_hash: false,
         };
 
         // This will try to read from stdin and will timeout/block
@@ -1679,6 +3127,7 @@ invalid json
 
         let err = GenerateError::InvalidOutputFormat("yaml".to_string());
         assert!(err.to_string().contains("yaml"));
+        assert!(err.to_string().contains("'state'"));
 
         let err = GenerateError::ExistingSchemaLoad(
             PathBuf::from("/path/schema.json"),
@@ -1689,6 +3138,12 @@ invalid json
         let err = GenerateError::InvalidGlobPattern("**[".to_string(), "unclosed".to_string());
         assert!(err.to_string().contains("**["));
 
+        let err = GenerateError::InvalidBytesFieldPattern("(".to_string(), "unclosed".to_string());
+        assert!(err.to_string().contains("--bytes-field-pattern"));
+
+        let err = GenerateError::InvalidConflictPolicy("widen".to_string());
+        assert!(err.to_string().contains("widen"));
+
         let err = GenerateError::PerFileRequiresInput;
         assert!(err.to_string().contains("--per-file"));
 
@@ -1698,9 +3153,6 @@ invalid json
         let err = GenerateError::WatchRequiresInput;
         assert!(err.to_string().contains("--watch"));
 
-        let err = GenerateError::WatchWithPerFile;
-        assert!(err.to_string().contains("--watch"));
-
         let err = GenerateError::NoInputFiles;
         assert!(err.to_string().contains("No input files"));
 
@@ -3,6 +3,7 @@
 //! This module handles command-line argument parsing and subcommand dispatch.
 
 pub mod diff;
+pub mod explain;
 pub mod generate;
 pub mod validate;
 
@@ -18,7 +19,7 @@ include!("definition.rs");
 pub fn run() {
     let cli = Cli::parse();
 
-    match cli.command {
+    match &cli.command {
         Some(Commands::Diff {
             old_schema,
             new_schema,
@@ -28,11 +29,11 @@ pub fn run() {
             output,
         }) => {
             diff::run(
-                &old_schema,
-                &new_schema,
-                &format,
-                &color,
-                strict,
+                old_schema,
+                new_schema,
+                format,
+                color,
+                *strict,
                 output.as_ref(),
             );
         }
@@ -42,18 +43,35 @@ pub fn run() {
             allow_unknown,
             strict_types,
             max_errors,
+            type_hints,
+            input,
+            epochs,
+            datetime_format,
+            regex_only_dates,
             format,
             quiet,
         }) => {
-            validate::run(
-                &files,
-                &schema,
-                allow_unknown,
-                strict_types,
-                max_errors,
-                &format,
-                quiet,
-            );
+            validate::run(&validate::ValidateOptions {
+                files: files.clone(),
+                schema: schema.clone(),
+                allow_unknown: *allow_unknown,
+                strict_types: *strict_types,
+                max_errors: *max_errors,
+                type_hints: type_hints.clone(),
+                input: input.clone(),
+                epochs: epochs.clone(),
+                datetime_formats: datetime_format.clone(),
+                regex_only_dates: *regex_only_dates,
+                format: format.clone(),
+                quiet: *quiet,
+            });
+        }
+        Some(Commands::Explain {
+            files,
+            field,
+            format,
+        }) => {
+            explain::run(&cli, files, field, format);
         }
         None => {
             generate::run(&cli);
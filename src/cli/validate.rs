@@ -4,20 +4,35 @@ use std::fs::File;
 use std::io::BufReader;
 use std::path::{Path, PathBuf};
 
+use bq_schema_gen::input::open_input;
 use bq_schema_gen::validate::{SchemaValidator, ValidationResult};
 use bq_schema_gen::{
-    BqSchemaField, JsonRecordIterator, ValidationError, ValidationErrorType, ValidationOptions,
+    BqSchemaField, CsvDialect, CsvRecordIterator, DateTimeFormats, InputFormat, JsonRecordIterator,
+    TypeHints, ValidationError, ValidationErrorType, ValidationOptions,
 };
 
+use super::generate::{parse_csv_args, parse_epoch_args};
+use super::{EpochArgs, ValidateInputArgs};
+
 /// Errors that can occur during validation
 #[derive(Debug)]
 pub enum ValidateError {
     /// Invalid output format specified
     InvalidFormat(String),
+    /// Invalid --input-format value
+    InvalidInputFormat(String),
     /// Failed to open schema file
     SchemaOpen(PathBuf, std::io::Error),
     /// Failed to parse schema file
     SchemaParse(PathBuf, String),
+    /// Failed to load type hints file
+    TypeHintsLoad(PathBuf, String),
+    /// Invalid --epoch-min/--epoch-max/--epoch-units value
+    InvalidEpochOption(String),
+    /// Invalid --datetime-format spec
+    InvalidDateTimeFormat(String),
+    /// Invalid CSV dialect flag
+    InvalidCsvOption(String),
     /// Invalid glob pattern
     InvalidGlobPattern(String, String),
     /// No input files found
@@ -32,12 +47,25 @@ impl std::fmt::Display for ValidateError {
             ValidateError::InvalidFormat(fmt) => {
                 write!(f, "Unknown format '{}'. Use 'text' or 'json'.", fmt)
             }
+            ValidateError::InvalidInputFormat(fmt) => {
+                write!(
+                    f,
+                    "Unknown input format '{}'. Use 'json', 'json-array' or 'csv'.",
+                    fmt
+                )
+            }
             ValidateError::SchemaOpen(path, e) => {
                 write!(f, "Cannot open schema file '{}': {}", path.display(), e)
             }
             ValidateError::SchemaParse(path, e) => {
                 write!(f, "Cannot parse schema file '{}': {}", path.display(), e)
             }
+            ValidateError::TypeHintsLoad(path, e) => {
+                write!(f, "Cannot load type hints from '{}': {}", path.display(), e)
+            }
+            ValidateError::InvalidEpochOption(e) => write!(f, "{}", e),
+            ValidateError::InvalidDateTimeFormat(e) => write!(f, "{}", e),
+            ValidateError::InvalidCsvOption(e) => write!(f, "{}", e),
             ValidateError::InvalidGlobPattern(pattern, e) => {
                 write!(f, "Invalid glob pattern '{}': {}", pattern, e)
             }
@@ -62,66 +90,191 @@ pub struct ValidateOutput {
     pub total_lines: usize,
 }
 
+/// Flags of the validate subcommand
+#[derive(Debug, Clone)]
+pub struct ValidateOptions {
+    /// Input data file patterns
+    pub files: Vec<String>,
+    /// BigQuery schema file to validate against
+    pub schema: PathBuf,
+    /// Warn about fields not in the schema instead of failing
+    pub allow_unknown: bool,
+    /// Don't accept JSON strings for non-string types
+    pub strict_types: bool,
+    /// Stop after this many errors
+    pub max_errors: usize,
+    /// Type hints file whose types values must also satisfy
+    pub type_hints: Option<PathBuf>,
+    /// Input format and CSV flags
+    pub input: ValidateInputArgs,
+    /// Epoch TIMESTAMP detection flags
+    pub epochs: EpochArgs,
+    /// Extra date/time format specs
+    pub datetime_formats: Vec<String>,
+    /// Accept dates and times by layout only
+    pub regex_only_dates: bool,
+    /// Output format: "text" or "json"
+    pub format: String,
+    /// Only set the exit code
+    pub quiet: bool,
+}
+
+impl Default for ValidateOptions {
+    fn default() -> Self {
+        Self {
+            files: Vec::new(),
+            schema: PathBuf::new(),
+            allow_unknown: false,
+            strict_types: false,
+            max_errors: 100,
+            type_hints: None,
+            input: ValidateInputArgs::default(),
+            epochs: EpochArgs::default(),
+            datetime_formats: Vec::new(),
+            regex_only_dates: false,
+            format: "text".to_string(),
+            quiet: false,
+        }
+    }
+}
+
 /// Run the validate subcommand implementation (testable version)
-pub fn run_impl(
-    file_patterns: &[String],
-    schema_path: &Path,
-    allow_unknown: bool,
-    strict_types: bool,
-    max_errors: usize,
-    format: &str,
-) -> Result<ValidateOutput, ValidateError> {
+pub fn run_impl(options: &ValidateOptions) -> Result<ValidateOutput, ValidateError> {
+    let format = options.format.as_str();
+    let input = &options.input;
+    let max_errors = options.max_errors;
+
     // Validate format
     if format != "text" && format != "json" {
         return Err(ValidateError::InvalidFormat(format.to_string()));
     }
 
     // Load schema
-    let schema = load_schema_file_impl(schema_path)?;
+    let schema = load_schema_file_impl(&options.schema)?;
+
+    let type_hints = match &options.type_hints {
+        Some(path) => TypeHints::from_file(path)
+            .map_err(|e| ValidateError::TypeHintsLoad(path.clone(), e.to_string()))?,
+        None => TypeHints::default(),
+    };
+
+    let epoch_detection =
+        parse_epoch_args(&options.epochs, false).map_err(ValidateError::InvalidEpochOption)?;
+    let datetime_formats = DateTimeFormats::from_specs(&options.datetime_formats)
+        .map_err(|e| ValidateError::InvalidDateTimeFormat(e.to_string()))?;
+
+    let input_format = match input
+        .input_format
+        .as_deref()
+        .unwrap_or("json")
+        .to_lowercase()
+        .as_str()
+    {
+        "json" => InputFormat::Json,
+        "json-array" => InputFormat::JsonArray,
+        "csv" => InputFormat::Csv,
+        other => return Err(ValidateError::InvalidInputFormat(other.to_string())),
+    };
+    let csv_dialect = parse_csv_args(&input.csv).map_err(ValidateError::InvalidCsvOption)?;
+    let match_by_position = match input.source_column_match.as_deref() {
+        None => !csv_dialect.has_header,
+        Some("position") => true,
+        Some("name") => false,
+        Some(other) => {
+            return Err(ValidateError::InvalidCsvOption(format!(
+                "Unknown --source-column-match '{}'. Use 'name' or 'position'.",
+                other
+            )))
+        }
+    };
+    let match_by_position = match_by_position && input_format == InputFormat::Csv;
 
     // Build validation options
-    let options = ValidationOptions {
-        allow_unknown,
-        strict_types,
+    let validation_options = ValidationOptions {
+        allow_unknown: options.allow_unknown,
+        strict_types: options.strict_types,
         max_errors,
     };
 
     // Collect input files
-    let files = collect_input_files_impl(file_patterns)?;
+    let files = collect_input_files_impl(&options.files)?;
 
     if files.is_empty() {
         return Err(ValidateError::NoInputFiles);
     }
 
     // Create validator
-    let validator = SchemaValidator::new(&schema, options.clone());
+    let mut validator = SchemaValidator::new(&schema, validation_options)
+        .with_type_hints(type_hints)
+        .with_datetime_formats(datetime_formats)
+        .with_regex_only_dates(options.regex_only_dates);
+    if let Some(epoch_detection) = epoch_detection {
+        validator = validator.with_epoch_detection(epoch_detection);
+    }
     let mut result = ValidationResult::new();
     let mut total_lines = 0;
 
     // Process each file
     for path in &files {
-        let file = File::open(path).map_err(|e| ValidateError::InputFileOpen(path.clone(), e))?;
-
-        let reader = BufReader::new(file);
-        let iter = JsonRecordIterator::new(reader, true);
+        let file = open_input(path).map_err(|e| ValidateError::InputFileOpen(path.clone(), e))?;
+
+        // Errors carry the line they were read from
+        let iter: Box<dyn Iterator<Item = RecordResult>> = match input_format {
+            InputFormat::Csv => match CsvRecordIterator::with_dialect(file, &csv_dialect) {
+                Ok(mut records) => Box::new(std::iter::from_fn(move || {
+                    let record = records.next()?;
+                    Some(record.map_err(|e| (records.line_number(), e)))
+                })),
+                // The header row is the first line after the skipped rows
+                Err(e) => Box::new(std::iter::once(Err((csv_dialect.skip_leading_rows + 1, e)))),
+            },
+            _ => {
+                let mut records = JsonRecordIterator::with_layout(
+                    BufReader::new(file),
+                    true,
+                    input_format.json_layout(),
+                );
+                Box::new(std::iter::from_fn(move || {
+                    let record = records.next()?;
+                    Some(record.map_err(|e| (records.line_number(), e)))
+                }))
+            }
+        };
+        let kind = if input_format == InputFormat::Csv {
+            "CSV"
+        } else {
+            "JSON"
+        };
 
         for record_result in iter {
             match record_result {
+                Ok((line, record)) if match_by_position => {
+                    total_lines += 1;
+                    let (record, columns) = match_columns_by_position(record, &schema);
+                    let first_new_error = result.errors.len();
+                    let more = validator.validate_record(&record, line, &mut result);
+                    for error in &mut result.errors[first_new_error..] {
+                        describe_csv_column(error, &columns);
+                    }
+                    if !more {
+                        break; // Max errors reached
+                    }
+                }
                 Ok((line, record)) => {
                     total_lines += 1;
                     if !validator.validate_record(&record, line, &mut result) {
                         break; // Max errors reached
                     }
                 }
-                Err(e) => {
+                Err((line, e)) => {
                     result.add_error(ValidationError {
-                        line: 0,
+                        line,
                         path: path.display().to_string(),
                         error_type: ValidationErrorType::TypeMismatch {
-                            expected: "valid JSON".to_string(),
+                            expected: format!("valid {}", kind),
                             actual: "parse error".to_string(),
                         },
-                        message: format!("JSON parse error: {}", e),
+                        message: format!("{} parse error: {}", kind, e),
                     });
                     if result.reached_max_errors(max_errors) {
                         break;
@@ -142,24 +295,65 @@ pub fn run_impl(
     })
 }
 
+/// A record and its line number, or the error reading it and the line it was read from.
+type RecordResult = std::result::Result<(usize, serde_json::Value), (usize, bq_schema_gen::Error)>;
+
+/// Rename the columns of a CSV record to the schema fields at the same position,
+/// as BigQuery does when loading CSV.
+///
+/// Columns past the end of the schema keep their own names, unless a schema
+/// field already has that name; such a column is renamed to its synthetic
+/// `column_N` name so it can't stand in for the field. Also returns, for each
+/// renamed column, its new name, its 1-based position and its name in the CSV.
+fn match_columns_by_position(
+    record: serde_json::Value,
+    schema: &[BqSchemaField],
+) -> (serde_json::Value, Vec<(String, usize, String)>) {
+    let serde_json::Value::Object(columns) = record else {
+        return (record, Vec::new());
+    };
+    let mut renamed = serde_json::Map::new();
+    let mut origins = Vec::new();
+    for (i, (column, value)) in columns.into_iter().enumerate() {
+        let taken = |name: &str| {
+            schema.iter().any(|f| f.name.eq_ignore_ascii_case(name))
+                || renamed.keys().any(|k| k.eq_ignore_ascii_case(name))
+        };
+        match schema.get(i) {
+            Some(field) => {
+                origins.push((field.name.clone(), i + 1, column));
+                renamed.insert(field.name.clone(), value);
+            }
+            None if taken(&column) => {
+                let mut name = CsvDialect::synthetic_column_name(i);
+                while taken(&name) {
+                    name.push('_');
+                }
+                origins.push((name.clone(), i + 1, column));
+                renamed.insert(name, value);
+            }
+            None => {
+                renamed.insert(column, value);
+            }
+        }
+    }
+    (serde_json::Value::Object(renamed), origins)
+}
+
+/// Name the CSV column behind an error on a position-matched field.
+fn describe_csv_column(error: &mut ValidationError, columns: &[(String, usize, String)]) {
+    let top_level = error.path.split(['.', '[']).next().unwrap_or(&error.path);
+    if let Some((_, position, column)) = columns
+        .iter()
+        .find(|(field, _, _)| field.eq_ignore_ascii_case(top_level))
+    {
+        error.message = format!("{} (CSV column {} '{}')", error.message, position, column);
+    }
+}
+
 /// Run the validate subcommand
-pub fn run(
-    file_patterns: &[String],
-    schema_path: &Path,
-    allow_unknown: bool,
-    strict_types: bool,
-    max_errors: usize,
-    format: &str,
-    quiet: bool,
-) {
-    let output = match run_impl(
-        file_patterns,
-        schema_path,
-        allow_unknown,
-        strict_types,
-        max_errors,
-        format,
-    ) {
+pub fn run(options: &ValidateOptions) {
+    let output = match run_impl(options) {
         Ok(output) => output,
         Err(e) => {
             eprintln!("Error: {}", e);
@@ -169,12 +363,12 @@ pub fn run(
     };
 
     // Output results
-    if quiet {
+    if options.quiet {
         // Exit code only
         std::process::exit(if output.valid { 0 } else { 1 });
     }
 
-    match format {
+    match options.format.as_str() {
         "json" => {
             let json_output = serde_json::json!({
                 "valid": output.result.valid,
@@ -222,7 +416,7 @@ pub fn run(
 }
 
 /// Collect input files from patterns (returns Result)
-fn collect_input_files_impl(patterns: &[String]) -> Result<Vec<PathBuf>, ValidateError> {
+pub(super) fn collect_input_files_impl(patterns: &[String]) -> Result<Vec<PathBuf>, ValidateError> {
     let mut files = Vec::new();
 
     for pattern in patterns {
@@ -275,6 +469,7 @@ fn load_schema_file_impl(path: &Path) -> Result<Vec<BqSchemaField>, ValidateErro
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cli::CsvArgs;
     use std::io::Write;
     use tempfile::{NamedTempFile, TempDir};
 
@@ -288,8 +483,12 @@ mod tests {
 
     /// Helper to create a temporary data file with given content
     fn create_temp_data_file(content: &str) -> NamedTempFile {
+        create_temp_data_file_bytes(content.as_bytes())
+    }
+
+    fn create_temp_data_file_bytes(content: &[u8]) -> NamedTempFile {
         let mut file = NamedTempFile::new().unwrap();
-        file.write_all(content.as_bytes()).unwrap();
+        file.write_all(content).unwrap();
         file.flush().unwrap();
         file
     }
@@ -448,14 +647,12 @@ mod tests {
             create_temp_schema_file(r#"[{"name": "id", "type": "INTEGER", "mode": "NULLABLE"}]"#);
         let data = create_temp_data_file(r#"{"id": 1}"#);
 
-        let result = run_impl(
-            &[data.path().to_string_lossy().to_string()],
-            schema.path(),
-            false,
-            false,
-            100,
-            "invalid_format",
-        );
+        let result = run_impl(&ValidateOptions {
+            files: vec![data.path().to_string_lossy().to_string()],
+            schema: schema.path().to_path_buf(),
+            format: "invalid_format".to_string(),
+            ..Default::default()
+        });
 
         assert!(result.is_err());
         let err = result.unwrap_err();
@@ -467,14 +664,11 @@ mod tests {
     fn test_run_impl_schema_not_found() {
         let data = create_temp_data_file(r#"{"id": 1}"#);
 
-        let result = run_impl(
-            &[data.path().to_string_lossy().to_string()],
-            Path::new("/nonexistent/schema.json"),
-            false,
-            false,
-            100,
-            "text",
-        );
+        let result = run_impl(&ValidateOptions {
+            files: vec![data.path().to_string_lossy().to_string()],
+            schema: PathBuf::from("/nonexistent/schema.json"),
+            ..Default::default()
+        });
 
         assert!(result.is_err());
         let err = result.unwrap_err();
@@ -486,14 +680,11 @@ mod tests {
         let schema = create_temp_schema_file("not valid json");
         let data = create_temp_data_file(r#"{"id": 1}"#);
 
-        let result = run_impl(
-            &[data.path().to_string_lossy().to_string()],
-            schema.path(),
-            false,
-            false,
-            100,
-            "text",
-        );
+        let result = run_impl(&ValidateOptions {
+            files: vec![data.path().to_string_lossy().to_string()],
+            schema: schema.path().to_path_buf(),
+            ..Default::default()
+        });
 
         assert!(result.is_err());
         let err = result.unwrap_err();
@@ -505,7 +696,11 @@ mod tests {
         let schema =
             create_temp_schema_file(r#"[{"name": "id", "type": "INTEGER", "mode": "NULLABLE"}]"#);
 
-        let result = run_impl(&[], schema.path(), false, false, 100, "text");
+        let result = run_impl(&ValidateOptions {
+            files: vec![],
+            schema: schema.path().to_path_buf(),
+            ..Default::default()
+        });
 
         assert!(result.is_err());
         let err = result.unwrap_err();
@@ -525,14 +720,11 @@ mod tests {
 {"id": 2, "name": "test2"}"#,
         );
 
-        let result = run_impl(
-            &[data.path().to_string_lossy().to_string()],
-            schema.path(),
-            false,
-            false,
-            100,
-            "text",
-        );
+        let result = run_impl(&ValidateOptions {
+            files: vec![data.path().to_string_lossy().to_string()],
+            schema: schema.path().to_path_buf(),
+            ..Default::default()
+        });
 
         assert!(result.is_ok());
         let output = result.unwrap();
@@ -541,6 +733,334 @@ mod tests {
         assert_eq!(output.result.error_count, 0);
     }
 
+    #[test]
+    fn test_run_impl_type_hints() {
+        let schema =
+            create_temp_schema_file(r#"[{"name": "zip", "type": "STRING", "mode": "NULLABLE"}]"#);
+        let data = create_temp_data_file("{\"zip\": \"94103\"}\n{\"zip\": \"SW1A\"}\n");
+        let hints = create_temp_data_file(r#"{"zip": "INTEGER"}"#);
+        let patterns = [data.path().to_string_lossy().to_string()];
+
+        let output = run_impl(&ValidateOptions {
+            files: patterns.to_vec(),
+            schema: schema.path().to_path_buf(),
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(output.valid);
+
+        let output = run_impl(&ValidateOptions {
+            files: patterns.to_vec(),
+            schema: schema.path().to_path_buf(),
+            type_hints: Some(hints.path().to_path_buf()),
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(!output.valid);
+        assert_eq!(output.result.error_count, 1);
+        assert_eq!(output.result.errors[0].line, 2);
+
+        let result = run_impl(&ValidateOptions {
+            files: patterns.to_vec(),
+            schema: schema.path().to_path_buf(),
+            type_hints: Some(PathBuf::from("/nonexistent/hints.json")),
+            ..Default::default()
+        });
+        assert!(matches!(result, Err(ValidateError::TypeHintsLoad(_, _))));
+    }
+
+    #[test]
+    fn test_run_impl_epochs() {
+        let schema =
+            create_temp_schema_file(r#"[{"name": "ts", "type": "TIMESTAMP", "mode": "NULLABLE"}]"#);
+        let data = create_temp_data_file("{\"ts\": 1700000000}\n{\"ts\": 42}\n");
+        let patterns = [data.path().to_string_lossy().to_string()];
+
+        // Without epoch detection any number passes as a Unix timestamp
+        let output = run_impl(&ValidateOptions {
+            files: patterns.to_vec(),
+            schema: schema.path().to_path_buf(),
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(output.valid);
+
+        let mut epochs = EpochArgs {
+            infer_epochs: true,
+            ..Default::default()
+        };
+        let output = run_impl(&ValidateOptions {
+            files: patterns.to_vec(),
+            schema: schema.path().to_path_buf(),
+            strict_types: true,
+            epochs: epochs.clone(),
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(!output.valid);
+        assert_eq!(output.result.error_count, 1);
+        assert_eq!(output.result.errors[0].line, 2);
+
+        epochs.epoch_max = Some("yesterday".to_string());
+        let result = run_impl(&ValidateOptions {
+            files: patterns.to_vec(),
+            schema: schema.path().to_path_buf(),
+            epochs: epochs.clone(),
+            ..Default::default()
+        });
+        assert!(matches!(result, Err(ValidateError::InvalidEpochOption(_))));
+    }
+
+    #[test]
+    fn test_run_impl_datetime_formats() {
+        let schema =
+            create_temp_schema_file(r#"[{"name": "day", "type": "DATE", "mode": "NULLABLE"}]"#);
+        let data = create_temp_data_file("{\"day\": \"03/15/2024\"}\n");
+        let patterns = [data.path().to_string_lossy().to_string()];
+        let run = |formats: &[String]| {
+            run_impl(&ValidateOptions {
+                files: patterns.to_vec(),
+                schema: schema.path().to_path_buf(),
+                datetime_formats: formats.to_vec(),
+                ..Default::default()
+            })
+        };
+
+        assert!(!run(&[]).unwrap().valid);
+        assert!(run(&["DATE=%m/%d/%Y".to_string()]).unwrap().valid);
+        assert!(matches!(
+            run(&["DATE=%Q".to_string()]),
+            Err(ValidateError::InvalidDateTimeFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_run_impl_regex_only_dates() {
+        let schema =
+            create_temp_schema_file(r#"[{"name": "day", "type": "DATE", "mode": "NULLABLE"}]"#);
+        let data = create_temp_data_file("{\"day\": \"2024-02-31\"}\n");
+        let patterns = [data.path().to_string_lossy().to_string()];
+        let run = |regex_only_dates: bool| {
+            run_impl(&ValidateOptions {
+                files: patterns.to_vec(),
+                schema: schema.path().to_path_buf(),
+                regex_only_dates,
+                ..Default::default()
+            })
+            .unwrap()
+        };
+
+        assert!(!run(false).valid);
+        assert!(run(true).valid);
+    }
+
+    #[test]
+    fn test_run_impl_compressed_data() {
+        let schema = create_temp_schema_file(
+            r#"[{"name": "value", "type": "INTEGER", "mode": "NULLABLE"}]"#,
+        );
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder
+            .write_all(b"{\"value\": 1}\n{\"value\": \"x\"}\n")
+            .unwrap();
+        let data = create_temp_data_file_bytes(&encoder.finish().unwrap());
+
+        let result = run_impl(&ValidateOptions {
+            files: vec![data.path().to_string_lossy().to_string()],
+            schema: schema.path().to_path_buf(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert_eq!(result.total_lines, 2);
+        assert_eq!(result.result.error_count, 1);
+    }
+
+    #[test]
+    fn test_run_impl_csv() {
+        let schema = create_temp_schema_file(
+            r#"[
+            {"name": "column_1", "type": "INTEGER", "mode": "REQUIRED"},
+            {"name": "column_2", "type": "DATE", "mode": "NULLABLE"}
+        ]"#,
+        );
+        let data = create_temp_data_file("# export\n1|2024-01-01\n2|\\N\n3|\nx|2024-01-01\n");
+        let mut input = ValidateInputArgs {
+            input_format: Some("csv".to_string()),
+            csv: CsvArgs {
+                csv_delimiter: Some("|".to_string()),
+                csv_no_header: true,
+                csv_comment: Some("#".to_string()),
+                csv_null_marker: vec!["\\N".to_string()],
+                csv_empty_as_null: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let result = run_impl(&ValidateOptions {
+            files: vec![data.path().to_string_lossy().to_string()],
+            schema: schema.path().to_path_buf(),
+            input: input.clone(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        // Null markers and empty fields are NULL; only the non-integer id fails
+        assert_eq!(result.total_lines, 4);
+        assert_eq!(result.result.error_count, 1);
+        assert!(result.result.errors[0].message.contains("column_1"));
+
+        // Without --csv-empty-as-null the empty day is an invalid DATE
+        input.csv.csv_empty_as_null = false;
+        let result = run_impl(&ValidateOptions {
+            files: vec![data.path().to_string_lossy().to_string()],
+            schema: schema.path().to_path_buf(),
+            input: input.clone(),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(result.result.error_count, 2);
+        assert_eq!(result.result.errors[0].line, 4);
+
+        input.input_format = Some("xml".to_string());
+        let err = run_impl(&ValidateOptions {
+            files: vec![data.path().to_string_lossy().to_string()],
+            schema: schema.path().to_path_buf(),
+            input: input.clone(),
+            ..Default::default()
+        })
+        .unwrap_err();
+        assert!(matches!(err, ValidateError::InvalidInputFormat(_)));
+    }
+
+    #[test]
+    fn test_run_impl_csv_column_match() {
+        let schema = create_temp_schema_file(
+            r#"[
+            {"name": "id", "type": "INTEGER", "mode": "REQUIRED"},
+            {"name": "day", "type": "DATE", "mode": "NULLABLE"}
+        ]"#,
+        );
+        let data = create_temp_data_file("Id,Date\n1,2024-01-01\nx,2024-01-02\n");
+        let validate = |source_column_match: Option<&str>| {
+            let input = ValidateInputArgs {
+                input_format: Some("csv".to_string()),
+                source_column_match: source_column_match.map(str::to_string),
+                ..Default::default()
+            };
+            run_impl(&ValidateOptions {
+                files: vec![data.path().to_string_lossy().to_string()],
+                schema: schema.path().to_path_buf(),
+                input: input.clone(),
+                ..Default::default()
+            })
+        };
+
+        // By name, "Date" is not a schema field and "day" is never set
+        let result = validate(None).unwrap();
+        assert!(result
+            .result
+            .errors
+            .iter()
+            .any(|e| e.error_type == ValidationErrorType::UnknownField && e.path == "Date"));
+
+        // By position, the header names don't matter; errors name the column
+        let result = validate(Some("position")).unwrap();
+        assert_eq!(result.result.error_count, 1);
+        let error = &result.result.errors[0];
+        assert_eq!(error.line, 3);
+        assert_eq!(error.path, "id");
+        assert!(
+            error.message.contains("(CSV column 1 'Id')"),
+            "{}",
+            error.message
+        );
+
+        let err = validate(Some("index")).unwrap_err();
+        assert!(matches!(err, ValidateError::InvalidCsvOption(_)));
+    }
+
+    #[test]
+    fn test_run_impl_csv_column_match_extra_column() {
+        let schema = create_temp_schema_file(
+            r#"[
+            {"name": "id", "type": "INTEGER", "mode": "REQUIRED"},
+            {"name": "name", "type": "STRING", "mode": "NULLABLE"}
+        ]"#,
+        );
+        // The third column is named like the first schema field but holds text
+        let data = create_temp_data_file("x,y,ID\n1,a,b\n");
+        let input = ValidateInputArgs {
+            input_format: Some("csv".to_string()),
+            source_column_match: Some("position".to_string()),
+            ..Default::default()
+        };
+
+        let result = run_impl(&ValidateOptions {
+            files: vec![data.path().to_string_lossy().to_string()],
+            schema: schema.path().to_path_buf(),
+            input,
+            ..Default::default()
+        })
+        .unwrap();
+
+        // "id" keeps the first column's value; the extra column is unknown
+        assert_eq!(result.result.error_count, 1);
+        let error = &result.result.errors[0];
+        assert_eq!(error.error_type, ValidationErrorType::UnknownField);
+        assert_eq!(error.path, "column_3");
+        assert!(
+            error.message.contains("(CSV column 3 'ID')"),
+            "{}",
+            error.message
+        );
+    }
+
+    #[test]
+    fn test_run_impl_csv_parse_error_line() {
+        let schema =
+            create_temp_schema_file(r#"[{"name": "name", "type": "STRING", "mode": "NULLABLE"}]"#);
+        let data = create_temp_data_file_bytes(b"name\na\n\xff\nc\n");
+        let input = ValidateInputArgs {
+            input_format: Some("csv".to_string()),
+            ..Default::default()
+        };
+
+        let result = run_impl(&ValidateOptions {
+            files: vec![data.path().to_string_lossy().to_string()],
+            schema: schema.path().to_path_buf(),
+            input,
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert_eq!(result.total_lines, 2);
+        assert_eq!(result.result.error_count, 1);
+        assert_eq!(result.result.errors[0].line, 3);
+        assert!(result.result.errors[0].message.contains("CSV parse error"));
+    }
+
+    #[test]
+    fn test_run_impl_json_array() {
+        let schema = create_temp_schema_file(
+            r#"[{"name": "value", "type": "INTEGER", "mode": "NULLABLE"}]"#,
+        );
+        let data =
+            create_temp_data_file("[\n  {\"value\": 1},\n  {\n    \"value\": \"x\"\n  }\n]\n");
+
+        let result = run_impl(&ValidateOptions {
+            files: vec![data.path().to_string_lossy().to_string()],
+            schema: schema.path().to_path_buf(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert_eq!(result.total_lines, 2);
+        assert_eq!(result.result.error_count, 1);
+        assert_eq!(result.result.errors[0].line, 3);
+    }
+
     #[test]
     fn test_run_impl_valid_data_json_format() {
         let schema = create_temp_schema_file(
@@ -550,14 +1070,12 @@ mod tests {
         );
         let data = create_temp_data_file(r#"{"value": 42}"#);
 
-        let result = run_impl(
-            &[data.path().to_string_lossy().to_string()],
-            schema.path(),
-            false,
-            false,
-            100,
-            "json",
-        );
+        let result = run_impl(&ValidateOptions {
+            files: vec![data.path().to_string_lossy().to_string()],
+            schema: schema.path().to_path_buf(),
+            format: "json".to_string(),
+            ..Default::default()
+        });
 
         assert!(result.is_ok());
         let output = result.unwrap();
@@ -573,14 +1091,12 @@ mod tests {
         );
         let data = create_temp_data_file(r#"{"id": "not_an_integer"}"#);
 
-        let result = run_impl(
-            &[data.path().to_string_lossy().to_string()],
-            schema.path(),
-            false,
-            true, // strict_types
-            100,
-            "text",
-        );
+        let result = run_impl(&ValidateOptions {
+            files: vec![data.path().to_string_lossy().to_string()],
+            schema: schema.path().to_path_buf(),
+            strict_types: true,
+            ..Default::default()
+        });
 
         assert!(result.is_ok());
         let output = result.unwrap();
@@ -598,14 +1114,11 @@ mod tests {
         );
         let data = create_temp_data_file(r#"{"id": 1}"#);
 
-        let result = run_impl(
-            &[data.path().to_string_lossy().to_string()],
-            schema.path(),
-            false,
-            false,
-            100,
-            "text",
-        );
+        let result = run_impl(&ValidateOptions {
+            files: vec![data.path().to_string_lossy().to_string()],
+            schema: schema.path().to_path_buf(),
+            ..Default::default()
+        });
 
         assert!(result.is_ok());
         let output = result.unwrap();
@@ -622,14 +1135,11 @@ mod tests {
         );
         let data = create_temp_data_file(r#"{"id": 1, "unknown_field": "value"}"#);
 
-        let result = run_impl(
-            &[data.path().to_string_lossy().to_string()],
-            schema.path(),
-            false, // allow_unknown = false
-            false,
-            100,
-            "text",
-        );
+        let result = run_impl(&ValidateOptions {
+            files: vec![data.path().to_string_lossy().to_string()],
+            schema: schema.path().to_path_buf(),
+            ..Default::default()
+        });
 
         assert!(result.is_ok());
         let output = result.unwrap();
@@ -645,14 +1155,12 @@ mod tests {
         );
         let data = create_temp_data_file(r#"{"id": 1, "unknown_field": "value"}"#);
 
-        let result = run_impl(
-            &[data.path().to_string_lossy().to_string()],
-            schema.path(),
-            true, // allow_unknown = true
-            false,
-            100,
-            "text",
-        );
+        let result = run_impl(&ValidateOptions {
+            files: vec![data.path().to_string_lossy().to_string()],
+            schema: schema.path().to_path_buf(),
+            allow_unknown: true,
+            ..Default::default()
+        });
 
         assert!(result.is_ok());
         let output = result.unwrap();
@@ -675,14 +1183,13 @@ mod tests {
 {"id": "e"}"#,
         );
 
-        let result = run_impl(
-            &[data.path().to_string_lossy().to_string()],
-            schema.path(),
-            false,
-            true, // strict_types to trigger type errors
-            3,    // max_errors = 3
-            "text",
-        );
+        let result = run_impl(&ValidateOptions {
+            files: vec![data.path().to_string_lossy().to_string()],
+            schema: schema.path().to_path_buf(),
+            strict_types: true,
+            max_errors: 3,
+            ..Default::default()
+        });
 
         assert!(result.is_ok());
         let output = result.unwrap();
@@ -701,17 +1208,14 @@ mod tests {
         let data1 = create_temp_data_file(r#"{"id": 1}"#);
         let data2 = create_temp_data_file(r#"{"id": 2}"#);
 
-        let result = run_impl(
-            &[
+        let result = run_impl(&ValidateOptions {
+            files: vec![
                 data1.path().to_string_lossy().to_string(),
                 data2.path().to_string_lossy().to_string(),
             ],
-            schema.path(),
-            false,
-            false,
-            100,
-            "text",
-        );
+            schema: schema.path().to_path_buf(),
+            ..Default::default()
+        });
 
         assert!(result.is_ok());
         let output = result.unwrap();
@@ -732,14 +1236,11 @@ not valid json
 {"id": 2}"#,
         );
 
-        let result = run_impl(
-            &[data.path().to_string_lossy().to_string()],
-            schema.path(),
-            false,
-            false,
-            100,
-            "text",
-        );
+        let result = run_impl(&ValidateOptions {
+            files: vec![data.path().to_string_lossy().to_string()],
+            schema: schema.path().to_path_buf(),
+            ..Default::default()
+        });
 
         assert!(result.is_ok());
         let output = result.unwrap();
@@ -759,14 +1260,11 @@ not valid json
         );
         let data = create_temp_data_file(r#"{"user": {"name": "John", "age": 30}}"#);
 
-        let result = run_impl(
-            &[data.path().to_string_lossy().to_string()],
-            schema.path(),
-            false,
-            false,
-            100,
-            "text",
-        );
+        let result = run_impl(&ValidateOptions {
+            files: vec![data.path().to_string_lossy().to_string()],
+            schema: schema.path().to_path_buf(),
+            ..Default::default()
+        });
 
         assert!(result.is_ok());
         let output = result.unwrap();
@@ -789,7 +1287,11 @@ not valid json
 
         let pattern = temp_dir.path().join("*.json").to_string_lossy().to_string();
 
-        let result = run_impl(&[pattern], schema.path(), false, false, 100, "text");
+        let result = run_impl(&ValidateOptions {
+            files: vec![pattern],
+            schema: schema.path().to_path_buf(),
+            ..Default::default()
+        });
 
         assert!(result.is_ok());
         let output = result.unwrap();
@@ -810,14 +1312,11 @@ not valid json
         // Remove the file before running validation
         std::fs::remove_file(&file_path).unwrap();
 
-        let result = run_impl(
-            &[file_path.to_string_lossy().to_string()],
-            schema.path(),
-            false,
-            false,
-            100,
-            "text",
-        );
+        let result = run_impl(&ValidateOptions {
+            files: vec![file_path.to_string_lossy().to_string()],
+            schema: schema.path().to_path_buf(),
+            ..Default::default()
+        });
 
         // File doesn't exist, so collect_input_files_impl returns empty (with warning)
         // which then triggers NoInputFiles error
@@ -255,8 +255,14 @@ fn is_type_change_breaking(old_type: &str, new_type: &str, options: &DiffOptions
         (old_type, new_type),
         // Integer to Float is safe (widening)
         ("INTEGER", "FLOAT") |
+        // Integer and NUMERIC widen exactly into wider decimal types
+        ("INTEGER", "NUMERIC") |
+        ("INTEGER", "BIGNUMERIC") |
+        ("NUMERIC", "BIGNUMERIC") |
         // Any type to String is generally safe
-        (_, "STRING")
+        (_, "STRING") |
+        // JSON can hold any value
+        (_, "JSON")
     );
 
     !safe_widening
@@ -293,6 +299,8 @@ mod tests {
             field_type: field_type.to_string(),
             mode: mode.to_string(),
             fields: None,
+            precision: None,
+            scale: None,
         }
     }
 
@@ -302,6 +310,8 @@ mod tests {
             field_type: "RECORD".to_string(),
             mode: mode.to_string(),
             fields: Some(fields),
+            precision: None,
+            scale: None,
         }
     }
 
@@ -367,6 +377,55 @@ mod tests {
         assert!(!diff.has_breaking_changes());
     }
 
+    #[test]
+    fn test_datetime_timestamp_change_detected() {
+        let old = vec![make_field("created", "DATETIME", "NULLABLE")];
+        let new = vec![make_field("created", "TIMESTAMP", "NULLABLE")];
+
+        let diff = diff_schemas(&old, &new, &DiffOptions::default());
+        assert_eq!(diff.summary.modified, 1);
+        // Reinterpreting civil times as UTC instants changes stored values
+        assert!(diff.has_breaking_changes());
+
+        let diff = diff_schemas(&old, &old, &DiffOptions::default());
+        assert!(!diff.has_changes());
+    }
+
+    #[test]
+    fn test_decimal_widening_not_breaking() {
+        for (old_type, new_type) in [
+            ("INTEGER", "NUMERIC"),
+            ("INTEGER", "BIGNUMERIC"),
+            ("NUMERIC", "BIGNUMERIC"),
+        ] {
+            let old = vec![make_field("value", old_type, "NULLABLE")];
+            let new = vec![make_field("value", new_type, "NULLABLE")];
+
+            let diff = diff_schemas(&old, &new, &DiffOptions::default());
+            assert!(diff.has_changes());
+            assert!(!diff.has_breaking_changes(), "{} -> {}", old_type, new_type);
+        }
+
+        // Narrowing is breaking
+        let old = vec![make_field("value", "BIGNUMERIC", "NULLABLE")];
+        let new = vec![make_field("value", "NUMERIC", "NULLABLE")];
+        let diff = diff_schemas(&old, &new, &DiffOptions::default());
+        assert!(diff.has_breaking_changes());
+    }
+
+    #[test]
+    fn test_change_to_json_not_breaking() {
+        let old = vec![make_field("payload", "INTEGER", "NULLABLE")];
+        let new = vec![make_field("payload", "JSON", "NULLABLE")];
+        let diff = diff_schemas(&old, &new, &DiffOptions::default());
+        assert!(diff.has_changes());
+        assert!(!diff.has_breaking_changes());
+
+        // Narrowing JSON back to a concrete type is breaking
+        let diff = diff_schemas(&new, &old, &DiffOptions::default());
+        assert!(diff.has_breaking_changes());
+    }
+
     #[test]
     fn test_mode_nullable_to_required_breaking() {
         let old = vec![make_field("name", "STRING", "NULLABLE")];
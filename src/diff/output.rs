@@ -327,6 +327,8 @@ mod tests {
             field_type: field_type.to_string(),
             mode: mode.to_string(),
             fields: None,
+            precision: None,
+            scale: None,
         }
     }
 
@@ -610,6 +612,8 @@ mod tests {
             field_type: "RECORD".to_string(),
             mode: "NULLABLE".to_string(),
             fields: Some(vec![make_field("child", "STRING", "NULLABLE")]),
+            precision: None,
+            scale: None,
         }];
         let new = vec![BqSchemaField {
             name: "parent".to_string(),
@@ -619,6 +623,8 @@ mod tests {
                 make_field("child", "STRING", "NULLABLE"),
                 make_field("new_child", "INTEGER", "NULLABLE"),
             ]),
+            precision: None,
+            scale: None,
         }];
 
         let diff = diff_schemas(&old, &new, &DiffOptions::default());
@@ -770,6 +776,8 @@ mod tests {
             field_type: "RECORD".to_string(),
             mode: "NULLABLE".to_string(),
             fields: Some(vec![make_field("child", "STRING", "NULLABLE")]),
+            precision: None,
+            scale: None,
         }];
         let new = vec![BqSchemaField {
             name: "parent".to_string(),
@@ -779,6 +787,8 @@ mod tests {
                 make_field("child", "STRING", "NULLABLE"),
                 make_field("new_child", "INTEGER", "NULLABLE"),
             ]),
+            precision: None,
+            scale: None,
         }];
 
         let diff = diff_schemas(&old, &new, &DiffOptions::default());
@@ -1,5 +1,6 @@
 //! Error types for the BigQuery schema generator.
 
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 /// Main error type for the schema generator.
@@ -19,13 +20,26 @@ pub enum Error {
 
     #[error("Schema file error: {0}")]
     SchemaFile(String),
+
+    #[error("Type hints error: {0}")]
+    TypeHints(String),
+
+    #[error("Date/time format error: {0}")]
+    DateTimeFormat(String),
+
+    #[error("Conflicting schema for field \"{path}\" on line {line}: {message}")]
+    FieldConflict {
+        line: usize,
+        path: String,
+        message: String,
+    },
 }
 
 /// Result type alias for this crate.
 pub type Result<T> = std::result::Result<T, Error>;
 
 /// An error log entry for non-fatal issues during schema generation.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ErrorLog {
     /// Line number where the error occurred
     pub line_number: usize,
@@ -120,6 +134,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_error_field_conflict_display() {
+        let error = Error::FieldConflict {
+            line: 3,
+            path: "a.b".to_string(),
+            message: "was NULLABLE INTEGER, now NULLABLE STRING".to_string(),
+        };
+        assert_eq!(
+            format!("{}", error),
+            "Conflicting schema for field \"a.b\" on line 3: was NULLABLE INTEGER, now NULLABLE STRING"
+        );
+    }
+
     #[test]
     fn test_error_from_io_error() {
         let io_error = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "access denied");
@@ -0,0 +1,180 @@
+//! Calendar and clock checks for date/time strings.
+//!
+//! The DATE/TIME/DATETIME/TIMESTAMP regexes only check the layout, so they
+//! accept values like `2024-02-31` or `25:61:61` that BigQuery rejects at load
+//! time. These checks add the field ranges on top of the regexes: years
+//! 0001-9999, days within the month (with leap years), hours 0-23, minutes and
+//! seconds 0-59, and time zone offsets within +/-14:00.
+
+use super::{is_date, is_datetime, is_time, is_timestamp};
+
+/// Largest time zone offset, in minutes.
+const MAX_OFFSET_MINUTES: u32 = 14 * 60;
+
+/// Check if a string is a DATE that exists in the calendar.
+pub fn is_calendar_date(s: &str) -> bool {
+    is_date(s) && is_valid_date_part(s)
+}
+
+/// Check if a string is a TIME within the clock's range.
+pub fn is_calendar_time(s: &str) -> bool {
+    is_time(s) && is_valid_time_part(s)
+}
+
+/// Check if a string is a DATETIME with a real date and time.
+pub fn is_calendar_datetime(s: &str) -> bool {
+    is_datetime(s)
+        && split_date_time(s)
+            .is_some_and(|(date, time)| is_valid_date_part(date) && is_valid_time_part(time))
+}
+
+/// Check if a string is a TIMESTAMP with a real date, time and time zone offset.
+pub fn is_calendar_timestamp(s: &str) -> bool {
+    if !is_timestamp(s) {
+        return false;
+    }
+    let Some((date, rest)) = split_date_time(s) else {
+        return false;
+    };
+    let zone_start = rest
+        .find(|c: char| !(c.is_ascii_digit() || c == ':' || c == '.'))
+        .unwrap_or(rest.len());
+    let (time, zone) = rest.split_at(zone_start);
+    is_valid_date_part(date) && is_valid_time_part(time) && is_valid_zone(zone.trim_start())
+}
+
+/// Number of days in a month of the proleptic Gregorian calendar.
+pub(crate) fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Split `YYYY-MM-DD[T ]rest` into the date and the rest.
+fn split_date_time(s: &str) -> Option<(&str, &str)> {
+    s.split_once(['T', ' '])
+}
+
+/// Check the year, month and day of a `YYYY-M-D` string.
+fn is_valid_date_part(date: &str) -> bool {
+    let mut parts = date.splitn(3, '-');
+    let (Some(year), Some(month), Some(day)) = (parts.next(), parts.next(), parts.next()) else {
+        return false;
+    };
+    let (Ok(year), Ok(month), Ok(day)) = (
+        year.parse::<i64>(),
+        month.parse::<u32>(),
+        day.parse::<u32>(),
+    ) else {
+        return false;
+    };
+    (1..=9999).contains(&year)
+        && (1..=12).contains(&month)
+        && day >= 1
+        && day <= days_in_month(year, month)
+}
+
+/// Check the hour, minute and second of a `H:M:S[.ffffff]` string.
+fn is_valid_time_part(time: &str) -> bool {
+    let seconds = time.split('.').next().unwrap_or(time);
+    let mut parts = seconds.splitn(3, ':');
+    let (Some(hour), Some(minute), Some(second)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return false;
+    };
+    let (Ok(hour), Ok(minute), Ok(second)) = (
+        hour.parse::<u32>(),
+        minute.parse::<u32>(),
+        second.parse::<u32>(),
+    ) else {
+        return false;
+    };
+    hour <= 23 && minute <= 59 && second <= 59
+}
+
+/// Check a time zone suffix: empty, `Z`, `UTC` or an offset up to +/-14:00.
+fn is_valid_zone(zone: &str) -> bool {
+    let offset = match zone {
+        "" | "Z" | "UTC" => return true,
+        _ => &zone[1..],
+    };
+    let (hours, minutes) = offset.split_once(':').unwrap_or((offset, "0"));
+    let (Ok(hours), Ok(minutes)) = (hours.parse::<u32>(), minutes.parse::<u32>()) else {
+        return false;
+    };
+    minutes <= 59 && hours * 60 + minutes <= MAX_OFFSET_MINUTES
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calendar_date() {
+        for valid in [
+            "2024-02-29",
+            "2000-02-29",
+            "0001-01-01",
+            "9999-12-31",
+            "2024-4-30",
+        ] {
+            assert!(is_calendar_date(valid), "{}", valid);
+        }
+        for invalid in [
+            "2023-02-29",
+            "1900-02-29",
+            "2024-02-31",
+            "2024-04-31",
+            "0000-01-01",
+        ] {
+            assert!(is_date(invalid), "{} should match the regex", invalid);
+            assert!(!is_calendar_date(invalid), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_calendar_time() {
+        for valid in ["00:00:00", "23:59:59", "9:5:7", "12:30:45.123456"] {
+            assert!(is_calendar_time(valid), "{}", valid);
+        }
+        for invalid in ["24:00:00", "25:61:61", "12:60:00", "12:00:60", "99:00:00.5"] {
+            assert!(is_time(invalid), "{} should match the regex", invalid);
+            assert!(!is_calendar_time(invalid), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_calendar_datetime() {
+        assert!(is_calendar_datetime("2024-02-29T23:59:59.5"));
+        assert!(is_calendar_datetime("2024-02-29 00:00:00"));
+        assert!(!is_calendar_datetime("2024-02-30T12:00:00"));
+        assert!(!is_calendar_datetime("2024-02-29T99:00:00"));
+    }
+
+    #[test]
+    fn test_calendar_timestamp() {
+        for valid in [
+            "2024-01-15T10:30:00Z",
+            "2024-01-15 10:30:00 UTC",
+            "2024-01-15T10:30:00.123+05:30",
+            "2024-01-15T10:30:00 -14:00",
+            "2024-01-15T10:30:00+9",
+            "2024-01-15T10:30:00",
+        ] {
+            assert!(is_calendar_timestamp(valid), "{}", valid);
+        }
+        for invalid in [
+            "2024-13-15T10:30:00Z",
+            "2024-02-30T10:30:00Z",
+            "2024-01-15T99:30:00Z",
+            "2024-01-15T10:30:00+15:00",
+            "2024-01-15T10:30:00+14:30",
+            "2024-01-15T10:30:00+05:99",
+        ] {
+            assert!(!is_calendar_timestamp(invalid), "{}", invalid);
+        }
+    }
+}
@@ -0,0 +1,199 @@
+//! Detection of Unix epoch TIMESTAMP values.
+//!
+//! Integers like `1700000000` are ambiguous: they may be counts, IDs or epoch
+//! times. Detection is opt-in and only accepts integers that fall inside a
+//! plausible time range when read in one of the enabled units. With the
+//! default range (2000-01-01 to 2100-01-01) the seconds, milliseconds and
+//! microseconds ranges don't overlap, so the unit of a value is unambiguous.
+
+use super::calendar::days_in_month;
+
+/// 2000-01-01T00:00:00Z in epoch seconds.
+const DEFAULT_MIN_SECONDS: i64 = 946_684_800;
+
+/// 2100-01-01T00:00:00Z in epoch seconds.
+const DEFAULT_MAX_SECONDS: i64 = 4_102_444_800;
+
+/// Unit of an epoch integer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EpochUnit {
+    /// Seconds since 1970-01-01T00:00:00Z
+    Seconds,
+    /// Milliseconds since the epoch
+    Millis,
+    /// Microseconds since the epoch
+    Micros,
+}
+
+impl EpochUnit {
+    /// All units, from coarsest to finest.
+    pub const ALL: [EpochUnit; 3] = [EpochUnit::Seconds, EpochUnit::Millis, EpochUnit::Micros];
+
+    /// Number of units per second.
+    pub fn per_second(self) -> i64 {
+        match self {
+            EpochUnit::Seconds => 1,
+            EpochUnit::Millis => 1_000,
+            EpochUnit::Micros => 1_000_000,
+        }
+    }
+}
+
+impl std::str::FromStr for EpochUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "s" | "seconds" => Ok(EpochUnit::Seconds),
+            "ms" | "millis" | "milliseconds" => Ok(EpochUnit::Millis),
+            "us" | "micros" | "microseconds" => Ok(EpochUnit::Micros),
+            _ => Err(format!("Unknown epoch unit: {}", s)),
+        }
+    }
+}
+
+/// Rules for treating integers as epoch TIMESTAMPs.
+///
+/// An integer matches if, for one of `units`, it lies within
+/// `[min_seconds, max_seconds]` once scaled to seconds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EpochDetection {
+    /// Earliest plausible time, in epoch seconds (inclusive)
+    pub min_seconds: i64,
+    /// Latest plausible time, in epoch seconds (inclusive)
+    pub max_seconds: i64,
+    /// Units to try
+    pub units: Vec<EpochUnit>,
+}
+
+impl Default for EpochDetection {
+    fn default() -> Self {
+        Self {
+            min_seconds: DEFAULT_MIN_SECONDS,
+            max_seconds: DEFAULT_MAX_SECONDS,
+            units: EpochUnit::ALL.to_vec(),
+        }
+    }
+}
+
+impl EpochDetection {
+    /// Return the first unit in which `n` is a plausible epoch time.
+    pub fn unit_of(&self, n: i64) -> Option<EpochUnit> {
+        self.units.iter().copied().find(|unit| {
+            let scale = unit.per_second();
+            let min = self.min_seconds.saturating_mul(scale);
+            let max = self.max_seconds.saturating_mul(scale);
+            (min..=max).contains(&n)
+        })
+    }
+
+    /// Check if `n` is a plausible epoch time in any of the enabled units.
+    pub fn matches(&self, n: i64) -> bool {
+        self.unit_of(n).is_some()
+    }
+
+    /// Check if a JSON number is a plausible epoch time.
+    pub fn matches_number(&self, n: &serde_json::Number) -> bool {
+        n.as_i64().is_some_and(|n| self.matches(n))
+    }
+
+    /// Check if an integer string is a plausible epoch time.
+    pub fn matches_str(&self, s: &str) -> bool {
+        s.parse::<i64>().is_ok_and(|n| self.matches(n))
+    }
+}
+
+/// Parse a range bound given as `YYYY-MM-DD` (midnight UTC) or as epoch seconds.
+pub fn parse_epoch_bound(s: &str) -> std::result::Result<i64, String> {
+    let s = s.trim();
+    if let Ok(seconds) = s.parse::<i64>() {
+        return Ok(seconds);
+    }
+
+    let invalid = || {
+        format!(
+            "Invalid epoch bound '{}': expected YYYY-MM-DD or seconds",
+            s
+        )
+    };
+    let mut parts = s.splitn(3, '-');
+    let (Some(year), Some(month), Some(day)) = (parts.next(), parts.next(), parts.next()) else {
+        return Err(invalid());
+    };
+    if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+        return Err(invalid());
+    }
+    let year: i64 = year.parse().map_err(|_| invalid())?;
+    let month: u32 = month.parse().map_err(|_| invalid())?;
+    let day: u32 = day.parse().map_err(|_| invalid())?;
+    if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+        return Err(invalid());
+    }
+    Ok(days_from_civil(year, month, day) * 86_400)
+}
+
+/// Days between 1970-01-01 and the given proleptic Gregorian date.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = i64::from(month);
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unit_of() {
+        let detection = EpochDetection::default();
+        assert_eq!(detection.unit_of(1_700_000_000), Some(EpochUnit::Seconds));
+        assert_eq!(
+            detection.unit_of(1_700_000_000_123),
+            Some(EpochUnit::Millis)
+        );
+        assert_eq!(
+            detection.unit_of(1_700_000_000_123_456),
+            Some(EpochUnit::Micros)
+        );
+        // Outside the plausible range in every unit
+        assert_eq!(detection.unit_of(42), None);
+        assert_eq!(detection.unit_of(-1_700_000_000), None);
+        assert_eq!(detection.unit_of(20_000_000_000), None);
+
+        let seconds_only = EpochDetection {
+            units: vec![EpochUnit::Seconds],
+            ..Default::default()
+        };
+        assert!(seconds_only.matches(1_700_000_000));
+        assert!(!seconds_only.matches(1_700_000_000_123));
+        assert!(seconds_only.matches_str("1700000000"));
+        assert!(!seconds_only.matches_str("1700000000.5"));
+        assert!(!seconds_only.matches_number(&serde_json::Number::from_f64(1.7e9).unwrap()));
+    }
+
+    #[test]
+    fn test_parse_epoch_bound() {
+        assert_eq!(parse_epoch_bound("1970-01-01"), Ok(0));
+        assert_eq!(parse_epoch_bound("2000-01-01"), Ok(DEFAULT_MIN_SECONDS));
+        assert_eq!(parse_epoch_bound("2100-01-01"), Ok(DEFAULT_MAX_SECONDS));
+        assert_eq!(parse_epoch_bound("2024-02-29"), Ok(1_709_164_800));
+        assert_eq!(parse_epoch_bound("1969-12-31"), Ok(-86_400));
+        assert_eq!(parse_epoch_bound("1700000000"), Ok(1_700_000_000));
+
+        for bad in ["2023-02-29", "2024-13-01", "2024-1-1", "yesterday", ""] {
+            assert!(parse_epoch_bound(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_parse_epoch_unit() {
+        assert_eq!("seconds".parse(), Ok(EpochUnit::Seconds));
+        assert_eq!("MS".parse(), Ok(EpochUnit::Millis));
+        assert_eq!("micros".parse(), Ok(EpochUnit::Micros));
+        assert!("nanos".parse::<EpochUnit>().is_err());
+    }
+}
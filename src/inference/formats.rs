@@ -0,0 +1,361 @@
+//! User-registered date and time formats.
+//!
+//! The built-in TIMESTAMP/DATETIME/DATE/TIME patterns only recognize ISO-like
+//! layouts. A `DateTimeFormats` registry adds more, each written as a
+//! strftime-style layout (`%m/%d/%Y`) or as a regex (`regex:^\d{8}$`) and
+//! mapped to one temporal type, optionally only for matching field paths.
+//!
+//! Formats are matched against the whole value. Only the layout is checked,
+//! not that the date exists (`02/30/2024` matches `%m/%d/%Y`).
+
+use glob::Pattern;
+use regex::Regex;
+
+use crate::error::{Error, Result};
+use crate::schema::types::BqType;
+
+/// Prefix marking a format as a regular expression instead of a strftime layout.
+const REGEX_PREFIX: &str = "regex:";
+
+const WEEKDAYS: &str = "Monday|Tuesday|Wednesday|Thursday|Friday|Saturday|Sunday";
+const WEEKDAYS_ABBR: &str = "Mon|Tue|Wed|Thu|Fri|Sat|Sun";
+const MONTHS: &str =
+    "January|February|March|April|May|June|July|August|September|October|November|December";
+const MONTHS_ABBR: &str = "Jan|Feb|Mar|Apr|May|Jun|Jul|Aug|Sep|Oct|Nov|Dec";
+
+/// A registered format for one temporal type.
+#[derive(Debug, Clone)]
+pub struct DateTimeFormat {
+    /// Type inferred for values in this format
+    pub bq_type: BqType,
+    /// Field path or glob pattern the format is limited to, if any
+    pub path: Option<String>,
+    /// The format as written (strftime layout or `regex:` pattern)
+    pub format: String,
+    /// Anchored regex matching the format
+    regex: Regex,
+    /// Lowercased compiled `path`
+    scope: Option<Pattern>,
+}
+
+impl DateTimeFormat {
+    /// Create a format for `bq_type`, optionally limited to a field path or glob.
+    ///
+    /// Only TIMESTAMP, DATETIME, DATE and TIME are accepted.
+    pub fn new(bq_type: BqType, format: &str, path: Option<&str>) -> Result<Self> {
+        if !matches!(
+            bq_type,
+            BqType::Timestamp | BqType::Datetime | BqType::Date | BqType::Time
+        ) {
+            return Err(Error::DateTimeFormat(format!(
+                "'{}' is not a date/time type; use TIMESTAMP, DATETIME, DATE or TIME",
+                bq_type
+            )));
+        }
+
+        let pattern = match format.strip_prefix(REGEX_PREFIX) {
+            Some(pattern) => pattern.to_string(),
+            None => strftime_to_regex(format)?,
+        };
+        let regex = Regex::new(&format!("^(?:{})$", pattern))
+            .map_err(|e| Error::DateTimeFormat(format!("Invalid format '{}': {}", format, e)))?;
+
+        let scope = path
+            .map(|path| {
+                Pattern::new(&path.to_lowercase()).map_err(|e| {
+                    Error::DateTimeFormat(format!("Invalid field pattern '{}': {}", path, e))
+                })
+            })
+            .transpose()?;
+
+        Ok(Self {
+            bq_type,
+            path: path.map(str::to_string),
+            format: format.to_string(),
+            regex,
+            scope,
+        })
+    }
+
+    /// Parse a `TYPE[@PATH]=FORMAT` spec, e.g. `DATE=%m/%d/%Y` or
+    /// `TIMESTAMP@vendor.*=%d.%m.%Y %H:%M`.
+    pub fn parse_spec(spec: &str) -> Result<Self> {
+        let (target, format) = spec.split_once('=').ok_or_else(|| {
+            Error::DateTimeFormat(format!(
+                "Invalid format spec '{}': expected TYPE[@PATH]=FORMAT",
+                spec
+            ))
+        })?;
+        let (type_name, path) = match target.split_once('@') {
+            Some((type_name, path)) => (type_name, Some(path.trim())),
+            None => (target, None),
+        };
+        let bq_type = match type_name.trim().to_uppercase().as_str() {
+            "TIMESTAMP" => BqType::Timestamp,
+            "DATETIME" => BqType::Datetime,
+            "DATE" => BqType::Date,
+            "TIME" => BqType::Time,
+            _ => {
+                return Err(Error::DateTimeFormat(format!(
+                    "Invalid type '{}' in format spec '{}'; use TIMESTAMP, DATETIME, DATE or TIME",
+                    type_name.trim(),
+                    spec
+                )))
+            }
+        };
+        Self::new(bq_type, format, path)
+    }
+
+    /// Check if a string is in this format.
+    pub fn is_match(&self, s: &str) -> bool {
+        self.regex.is_match(s)
+    }
+
+    /// Check if the format applies to a dotted field path (case-insensitive).
+    pub fn applies_to(&self, path: &str) -> bool {
+        self.scope
+            .as_ref()
+            .is_none_or(|scope| scope.matches(&path.to_lowercase()))
+    }
+}
+
+/// A registry of extra date and time formats.
+///
+/// Formats are tried in registration order, after the built-in ISO patterns;
+/// formats limited to a field path take precedence over unscoped ones.
+#[derive(Debug, Clone, Default)]
+pub struct DateTimeFormats {
+    formats: Vec<DateTimeFormat>,
+}
+
+impl DateTimeFormats {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns true if no formats are registered.
+    pub fn is_empty(&self) -> bool {
+        self.formats.is_empty()
+    }
+
+    /// Register a format.
+    pub fn push(&mut self, format: DateTimeFormat) {
+        self.formats.push(format);
+    }
+
+    /// Parse and register `TYPE[@PATH]=FORMAT` specs.
+    pub fn from_specs<S: AsRef<str>>(specs: &[S]) -> Result<Self> {
+        let mut formats = Self::new();
+        for spec in specs {
+            formats.push(DateTimeFormat::parse_spec(spec.as_ref())?);
+        }
+        Ok(formats)
+    }
+
+    /// Returns true if any format is limited to a field path.
+    pub fn has_scoped(&self) -> bool {
+        self.formats.iter().any(|f| f.scope.is_some())
+    }
+
+    /// The formats that apply to every field.
+    pub fn unscoped(&self) -> Self {
+        Self {
+            formats: self
+                .formats
+                .iter()
+                .filter(|f| f.scope.is_none())
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// The formats that apply to `path`, scoped ones first, or `None` if no
+    /// scoped format applies (so `unscoped()` already covers the field).
+    pub fn for_path(&self, path: &str) -> Option<Self> {
+        let path = path.to_lowercase();
+        let scoped = self
+            .formats
+            .iter()
+            .filter(|f| f.scope.as_ref().is_some_and(|scope| scope.matches(&path)));
+        let mut formats: Vec<DateTimeFormat> = scoped.cloned().collect();
+        if formats.is_empty() {
+            return None;
+        }
+        formats.extend(self.formats.iter().filter(|f| f.scope.is_none()).cloned());
+        Some(Self { formats })
+    }
+
+    /// Return the type of the first format matching `s`, ignoring field scopes.
+    pub fn infer(&self, s: &str) -> Option<BqType> {
+        self.formats
+            .iter()
+            .find(|f| f.is_match(s))
+            .map(|f| f.bq_type.clone())
+    }
+
+    /// Check if `s` is in a format registered for `bq_type` that applies to `path`.
+    pub fn is_valid(&self, s: &str, bq_type: &BqType, path: &str) -> bool {
+        self.formats
+            .iter()
+            .any(|f| f.bq_type == *bq_type && f.applies_to(path) && f.is_match(s))
+    }
+}
+
+/// Translate a strftime-style layout into an unanchored regex.
+fn strftime_to_regex(format: &str) -> Result<String> {
+    let mut regex = String::new();
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            regex.push_str(&regex::escape(c.encode_utf8(&mut [0; 4])));
+            continue;
+        }
+        let directive = match chars.next() {
+            Some('.') => match chars.next() {
+                Some('f') => ".f".to_string(),
+                other => format!(".{}", other.map(String::from).unwrap_or_default()),
+            },
+            Some(d) => d.to_string(),
+            None => String::new(),
+        };
+        let piece = match directive.as_str() {
+            "Y" => r"\d{4}".to_string(),
+            "y" => r"\d{2}".to_string(),
+            "m" => "(?:0?[1-9]|1[0-2])".to_string(),
+            "d" => "(?:0?[1-9]|[12][0-9]|3[01])".to_string(),
+            "e" => "(?: ?[1-9]|[12][0-9]|3[01])".to_string(),
+            "j" => r"(?:00[1-9]|0[1-9]\d|[12]\d\d|3[0-5]\d|36[0-6])".to_string(),
+            "H" => "(?:[01]?[0-9]|2[0-3])".to_string(),
+            "I" => "(?:0?[1-9]|1[0-2])".to_string(),
+            "M" => "[0-5]?[0-9]".to_string(),
+            "S" => "(?:[0-5]?[0-9]|60)".to_string(),
+            "f" => r"\d{1,9}".to_string(),
+            ".f" => r"(?:\.\d{1,9})?".to_string(),
+            "p" => "(?i:AM|PM)".to_string(),
+            "z" => r"(?:Z|[+-]\d{2}:?\d{2})".to_string(),
+            "Z" => "[A-Za-z]{1,5}".to_string(),
+            "a" => format!("(?i:{})", WEEKDAYS_ABBR),
+            "A" => format!("(?i:{})", WEEKDAYS),
+            "b" | "h" => format!("(?i:{})", MONTHS_ABBR),
+            "B" => format!("(?i:{})", MONTHS),
+            "F" => strftime_to_regex("%Y-%m-%d")?,
+            "T" => strftime_to_regex("%H:%M:%S")?,
+            "%" => "%".to_string(),
+            _ => {
+                return Err(Error::DateTimeFormat(format!(
+                    "Unsupported directive '%{}' in format '{}'",
+                    directive, format
+                )))
+            }
+        };
+        regex.push_str(&piece);
+    }
+    Ok(regex)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strftime_formats() {
+        let us_date = DateTimeFormat::new(BqType::Date, "%m/%d/%Y", None).unwrap();
+        assert!(us_date.is_match("03/15/2024"));
+        assert!(us_date.is_match("3/5/2024"));
+        assert!(!us_date.is_match("15/03/2024"));
+        assert!(!us_date.is_match("03/15/2024 10:00"));
+
+        let eu_datetime = DateTimeFormat::new(BqType::Datetime, "%d.%m.%Y %H:%M", None).unwrap();
+        assert!(eu_datetime.is_match("15.03.2024 14:22"));
+        assert!(!eu_datetime.is_match("15.03.2024"));
+
+        let rfc2822 = DateTimeFormat::new(BqType::Timestamp, "%a, %d %b %Y %T %z", None).unwrap();
+        assert!(rfc2822.is_match("Fri, 15 Mar 2024 14:22:01 +0000"));
+        assert!(rfc2822.is_match("fri, 15 MAR 2024 14:22:01 -05:00"));
+        assert!(!rfc2822.is_match("Fri, 15 Mar 2024 14:22:01"));
+
+        let fractional = DateTimeFormat::new(BqType::Time, "%I:%M:%S%.f %p", None).unwrap();
+        assert!(fractional.is_match("2:22:01 PM"));
+        assert!(fractional.is_match("02:22:01.123 am"));
+        assert!(!fractional.is_match("14:22:01 PM"));
+
+        let percent = DateTimeFormat::new(BqType::Date, "%Y%%%j", None).unwrap();
+        assert!(percent.is_match("2024%075"));
+    }
+
+    #[test]
+    fn test_regex_formats() {
+        let compact = DateTimeFormat::new(BqType::Date, r"regex:\d{8}", None).unwrap();
+        assert!(compact.is_match("20240315"));
+        // Regexes are anchored to the whole value
+        assert!(!compact.is_match("x20240315"));
+        assert!(!compact.is_match("202403150"));
+    }
+
+    #[test]
+    fn test_invalid_formats() {
+        assert!(matches!(
+            DateTimeFormat::new(BqType::String, "%Y", None),
+            Err(Error::DateTimeFormat(_))
+        ));
+        for spec in [
+            "DATE",
+            "STRING=%Y",
+            "DATE=%Q",
+            "DATE=%Y%",
+            "DATE=regex:(",
+            "DATE@[a=%Y",
+        ] {
+            assert!(
+                matches!(
+                    DateTimeFormat::parse_spec(spec),
+                    Err(Error::DateTimeFormat(_))
+                ),
+                "{}",
+                spec
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_spec() {
+        let format = DateTimeFormat::parse_spec("timestamp@Vendor.*=%d.%m.%Y %H:%M").unwrap();
+        assert_eq!(format.bq_type, BqType::Timestamp);
+        assert_eq!(format.path.as_deref(), Some("Vendor.*"));
+        assert_eq!(format.format, "%d.%m.%Y %H:%M");
+        assert!(format.applies_to("vendor.created"));
+        assert!(!format.applies_to("created"));
+
+        // Everything after the first '=' is the format
+        let format = DateTimeFormat::parse_spec(r"DATE=regex:\d{4}=\d{2}").unwrap();
+        assert!(format.is_match("2024=03"));
+    }
+
+    #[test]
+    fn test_registry() {
+        let formats = DateTimeFormats::from_specs(&[
+            "DATE=%m/%d/%Y",
+            "DATETIME@legacy.*=%m/%d/%Y %H:%M",
+            "TIMESTAMP@legacy.seen=%m/%d/%Y",
+        ])
+        .unwrap();
+        assert!(formats.has_scoped());
+
+        let unscoped = formats.unscoped();
+        assert!(!unscoped.has_scoped());
+        assert_eq!(unscoped.infer("03/15/2024"), Some(BqType::Date));
+        assert_eq!(unscoped.infer("03/15/2024 10:00"), None);
+
+        assert!(formats.for_path("other").is_none());
+        let legacy = formats.for_path("Legacy.Seen").unwrap();
+        // Scoped formats win over unscoped ones
+        assert_eq!(legacy.infer("03/15/2024"), Some(BqType::Timestamp));
+        assert_eq!(legacy.infer("03/15/2024 10:00"), Some(BqType::Datetime));
+
+        assert!(formats.is_valid("03/15/2024", &BqType::Date, "any"));
+        assert!(formats.is_valid("03/15/2024 10:00", &BqType::Datetime, "legacy.x"));
+        assert!(!formats.is_valid("03/15/2024 10:00", &BqType::Datetime, "other"));
+        assert!(!formats.is_valid("03/15/2024", &BqType::Time, "any"));
+    }
+}
@@ -0,0 +1,375 @@
+//! Detection of GEOGRAPHY values.
+//!
+//! BigQuery loads GEOGRAPHY columns from WKT strings like `POINT(-122.3 47.6)`
+//! and from GeoJSON geometry objects. These checks are strict enough to be used
+//! both for inference and for validation: coordinates must be longitude/latitude
+//! pairs within range, line strings need two points and polygon rings must be
+//! closed.
+
+use serde_json::Value;
+
+/// Check if a string is a well-formed WKT geometry.
+pub fn is_wkt(s: &str) -> bool {
+    let mut parser = WktParser::new(s);
+    parser.parse_geometry() && parser.at_end()
+}
+
+/// Check if a JSON value is a well-formed GeoJSON geometry object.
+///
+/// Only geometry objects qualify (`Point`, `LineString`, `Polygon`, their
+/// `Multi*` forms and `GeometryCollection`); `Feature` objects are records.
+/// Apart from `type` and `coordinates`/`geometries`, only `bbox` may appear so
+/// that ordinary records with a `type` field are not mistaken for geometries.
+pub fn is_geojson_geometry(value: &Value) -> bool {
+    let obj = match value {
+        Value::Object(obj) => obj,
+        _ => return false,
+    };
+    let geometry_type = match obj.get("type") {
+        Some(Value::String(t)) => t.as_str(),
+        _ => return false,
+    };
+    let member = if geometry_type == "GeometryCollection" {
+        "geometries"
+    } else {
+        "coordinates"
+    };
+    if obj
+        .keys()
+        .any(|k| k != "type" && k != member && k != "bbox")
+    {
+        return false;
+    }
+    let body = match obj.get(member) {
+        Some(body) => body,
+        None => return false,
+    };
+
+    match geometry_type {
+        "Point" => is_position(body),
+        "LineString" => is_line_string(body),
+        "Polygon" => is_polygon(body),
+        "MultiPoint" => all_elements(body, is_position),
+        "MultiLineString" => all_elements(body, is_line_string),
+        "MultiPolygon" => all_elements(body, is_polygon),
+        "GeometryCollection" => all_elements(body, is_geojson_geometry),
+        _ => false,
+    }
+}
+
+/// Check that a value is an array whose elements all satisfy `check`.
+fn all_elements(value: &Value, check: fn(&Value) -> bool) -> bool {
+    matches!(value, Value::Array(items) if items.iter().all(check))
+}
+
+/// A GeoJSON position: `[longitude, latitude]` with an optional altitude.
+fn is_position(value: &Value) -> bool {
+    let coords = match value {
+        Value::Array(coords) if (2..=3).contains(&coords.len()) => coords,
+        _ => return false,
+    };
+    let numbers: Option<Vec<f64>> = coords.iter().map(Value::as_f64).collect();
+    numbers.is_some_and(|n| is_valid_lon_lat(n[0], n[1]))
+}
+
+fn is_line_string(value: &Value) -> bool {
+    matches!(value, Value::Array(points) if points.len() >= 2 && points.iter().all(is_position))
+}
+
+fn is_linear_ring(value: &Value) -> bool {
+    match value {
+        Value::Array(points) => {
+            points.len() >= 4 && points.iter().all(is_position) && points.first() == points.last()
+        }
+        _ => false,
+    }
+}
+
+fn is_polygon(value: &Value) -> bool {
+    matches!(value, Value::Array(rings) if !rings.is_empty() && rings.iter().all(is_linear_ring))
+}
+
+fn is_valid_lon_lat(lon: f64, lat: f64) -> bool {
+    (-180.0..=180.0).contains(&lon) && (-90.0..=90.0).contains(&lat)
+}
+
+/// Minimal recursive-descent parser for the WKT geometry grammar.
+struct WktParser<'a> {
+    input: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> WktParser<'a> {
+    fn new(s: &'a str) -> Self {
+        Self {
+            input: s.as_bytes(),
+            pos: 0,
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.pos < self.input.len() && self.input[self.pos].is_ascii_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    fn at_end(&mut self) -> bool {
+        self.skip_whitespace();
+        self.pos == self.input.len()
+    }
+
+    /// Consume `c` (after optional whitespace) if it is the next character.
+    fn eat(&mut self, c: u8) -> bool {
+        self.skip_whitespace();
+        if self.input.get(self.pos) == Some(&c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Read an upper-cased keyword, without consuming anything if there is none.
+    fn keyword(&mut self) -> Option<String> {
+        self.skip_whitespace();
+        let start = self.pos;
+        let mut end = start;
+        while end < self.input.len() && self.input[end].is_ascii_alphabetic() {
+            end += 1;
+        }
+        if end == start {
+            return None;
+        }
+        Some(String::from_utf8_lossy(&self.input[start..end]).to_ascii_uppercase())
+    }
+
+    /// Consume `word` if it is the next keyword.
+    fn eat_keyword(&mut self, word: &str) -> bool {
+        match self.keyword() {
+            Some(k) if k == word => {
+                self.pos += word.len();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn number(&mut self) -> Option<f64> {
+        self.skip_whitespace();
+        let start = self.pos;
+        while self.pos < self.input.len()
+            && matches!(
+                self.input[self.pos],
+                b'0'..=b'9' | b'.' | b'-' | b'+' | b'e' | b'E'
+            )
+        {
+            self.pos += 1;
+        }
+        std::str::from_utf8(&self.input[start..self.pos])
+            .ok()?
+            .parse()
+            .ok()
+    }
+
+    /// A coordinate: longitude and latitude, plus `extra` Z/M ordinates.
+    fn coordinate(&mut self, extra: usize) -> Option<(f64, f64)> {
+        let lon = self.number()?;
+        let lat = self.number()?;
+        for _ in 0..extra {
+            self.number()?;
+        }
+        is_valid_lon_lat(lon, lat).then_some((lon, lat))
+    }
+
+    /// `( coord, coord, ... )`, returning the coordinates.
+    fn coordinate_list(&mut self, extra: usize) -> Option<Vec<(f64, f64)>> {
+        if !self.eat(b'(') {
+            return None;
+        }
+        let mut coords = vec![self.coordinate(extra)?];
+        while self.eat(b',') {
+            coords.push(self.coordinate(extra)?);
+        }
+        self.eat(b')').then_some(coords)
+    }
+
+    fn line_string(&mut self, extra: usize) -> bool {
+        self.coordinate_list(extra).is_some_and(|c| c.len() >= 2)
+    }
+
+    fn linear_ring(&mut self, extra: usize) -> bool {
+        self.coordinate_list(extra)
+            .is_some_and(|c| c.len() >= 4 && c.first() == c.last())
+    }
+
+    /// `( item, item, ... )` where each item may also be `EMPTY`.
+    fn list_of(&mut self, extra: usize, item: fn(&mut Self, usize) -> bool) -> bool {
+        if !self.eat(b'(') {
+            return false;
+        }
+        loop {
+            if !self.eat_keyword("EMPTY") && !item(self, extra) {
+                return false;
+            }
+            if !self.eat(b',') {
+                break;
+            }
+        }
+        self.eat(b')')
+    }
+
+    fn polygon(&mut self, extra: usize) -> bool {
+        self.list_of(extra, Self::linear_ring)
+    }
+
+    /// A MULTIPOINT member: either `(x y)` or a bare `x y`.
+    fn multi_point_member(&mut self, extra: usize) -> bool {
+        if self.eat(b'(') {
+            self.coordinate(extra).is_some() && self.eat(b')')
+        } else {
+            self.coordinate(extra).is_some()
+        }
+    }
+
+    fn parse_geometry(&mut self) -> bool {
+        let geometry_type = match self.keyword() {
+            Some(k) => k,
+            None => return false,
+        };
+        self.pos += geometry_type.len();
+
+        // Dimension suffix may be attached ("POINTZ") or separate ("POINT Z")
+        let (base, mut extra) = match geometry_type.strip_suffix("ZM") {
+            Some(base) => (base.to_string(), 2),
+            None => match geometry_type.strip_suffix(['Z', 'M']) {
+                Some(base) if is_geometry_keyword(base) => (base.to_string(), 1),
+                _ => (geometry_type, 0),
+            },
+        };
+        if !is_geometry_keyword(&base) {
+            return false;
+        }
+        if extra == 0 {
+            if self.eat_keyword("ZM") {
+                extra = 2;
+            } else if self.eat_keyword("Z") || self.eat_keyword("M") {
+                extra = 1;
+            }
+        }
+
+        if self.eat_keyword("EMPTY") {
+            return true;
+        }
+
+        match base.as_str() {
+            "POINT" => self.coordinate_list(extra).is_some_and(|c| c.len() == 1),
+            "LINESTRING" => self.line_string(extra),
+            "POLYGON" => self.polygon(extra),
+            "MULTIPOINT" => self.list_of(extra, Self::multi_point_member),
+            "MULTILINESTRING" => self.list_of(extra, Self::line_string),
+            "MULTIPOLYGON" => self.list_of(extra, Self::polygon),
+            "GEOMETRYCOLLECTION" => self.list_of(extra, |parser, _| parser.parse_geometry()),
+            _ => false,
+        }
+    }
+}
+
+fn is_geometry_keyword(word: &str) -> bool {
+    matches!(
+        word,
+        "POINT"
+            | "LINESTRING"
+            | "POLYGON"
+            | "MULTIPOINT"
+            | "MULTILINESTRING"
+            | "MULTIPOLYGON"
+            | "GEOMETRYCOLLECTION"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_wkt_valid() {
+        assert!(is_wkt("POINT(-122.3 47.6)"));
+        assert!(is_wkt("point (1 2)"));
+        assert!(is_wkt("POINT Z (1 2 3)"));
+        assert!(is_wkt("POINT EMPTY"));
+        assert!(is_wkt("LINESTRING(0 0, 1 1, 2 2)"));
+        assert!(is_wkt("POLYGON((0 0, 1 0, 1 1, 0 0))"));
+        assert!(is_wkt(
+            "POLYGON((0 0, 4 0, 4 4, 0 0), (1 1, 2 1, 2 2, 1 1))"
+        ));
+        assert!(is_wkt("MULTIPOINT(0 0, 1 1)"));
+        assert!(is_wkt("MULTIPOINT((0 0), (1 1))"));
+        assert!(is_wkt("MULTILINESTRING((0 0, 1 1), (2 2, 3 3))"));
+        assert!(is_wkt("MULTIPOLYGON(((0 0, 1 0, 1 1, 0 0)), EMPTY)"));
+        assert!(is_wkt(
+            "GEOMETRYCOLLECTION(POINT(1 2), LINESTRING(0 0, 1 1))"
+        ));
+    }
+
+    #[test]
+    fn test_wkt_invalid() {
+        assert!(!is_wkt(""));
+        assert!(!is_wkt("POINT"));
+        assert!(!is_wkt("POINT(1)"));
+        assert!(!is_wkt("POINT(1 2"));
+        assert!(!is_wkt("POINT(1 2) trailing"));
+        assert!(!is_wkt("POINT(200 0)"));
+        assert!(!is_wkt("POINT(0 91)"));
+        assert!(!is_wkt("CIRCLE(0 0)"));
+        assert!(!is_wkt("LINESTRING(0 0)"));
+        assert!(!is_wkt("POLYGON((0 0, 1 0, 1 1, 0 1))"));
+        assert!(!is_wkt("Pointless remark"));
+    }
+
+    #[test]
+    fn test_geojson_valid() {
+        assert!(is_geojson_geometry(
+            &json!({"type": "Point", "coordinates": [-122.3, 47.6]})
+        ));
+        assert!(is_geojson_geometry(
+            &json!({"type": "LineString", "coordinates": [[0, 0], [1, 1]]})
+        ));
+        assert!(is_geojson_geometry(&json!({
+            "type": "Polygon",
+            "coordinates": [[[0, 0], [1, 0], [1, 1], [0, 0]]]
+        })));
+        assert!(is_geojson_geometry(&json!({
+            "type": "MultiPoint",
+            "coordinates": [[0, 0], [1, 1]],
+            "bbox": [0, 0, 1, 1]
+        })));
+        assert!(is_geojson_geometry(&json!({
+            "type": "GeometryCollection",
+            "geometries": [{"type": "Point", "coordinates": [1, 2]}]
+        })));
+    }
+
+    #[test]
+    fn test_geojson_invalid() {
+        assert!(!is_geojson_geometry(&json!("POINT(1 2)")));
+        assert!(!is_geojson_geometry(
+            &json!({"type": "Point", "coordinates": [1]})
+        ));
+        assert!(!is_geojson_geometry(
+            &json!({"type": "Point", "coordinates": [500, 0]})
+        ));
+        assert!(!is_geojson_geometry(
+            &json!({"type": "Point", "coordinates": [1, 2], "name": "x"})
+        ));
+        assert!(!is_geojson_geometry(&json!({
+            "type": "Feature",
+            "geometry": {"type": "Point", "coordinates": [1, 2]}
+        })));
+        assert!(!is_geojson_geometry(&json!({
+            "type": "Polygon",
+            "coordinates": [[[0, 0], [1, 0], [1, 1], [0, 1]]]
+        })));
+        assert!(!is_geojson_geometry(&json!({"type": "Point"})));
+    }
+}
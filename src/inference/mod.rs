@@ -3,6 +3,18 @@
 //! This module handles inferring BigQuery types from JSON/CSV values,
 //! matching the behavior of the Python `bigquery-schema-generator`.
 
+mod calendar;
+mod epoch;
+mod formats;
+mod geography;
+
+pub use calendar::{
+    is_calendar_date, is_calendar_datetime, is_calendar_time, is_calendar_timestamp,
+};
+pub use epoch::{parse_epoch_bound, EpochDetection, EpochUnit};
+pub use formats::{DateTimeFormat, DateTimeFormats};
+pub use geography::{is_geojson_geometry, is_wkt};
+
 use once_cell::sync::Lazy;
 use regex::Regex;
 
@@ -14,6 +26,68 @@ pub const INTEGER_MAX_VALUE: i64 = i64::MAX; // 2^63 - 1 = 9223372036854775807
 /// Minimum signed 64-bit integer value supported by BigQuery.
 pub const INTEGER_MIN_VALUE: i64 = i64::MIN; // -2^63 = -9223372036854775808
 
+/// Maximum digits left of the decimal point for NUMERIC (precision 38, scale 9).
+pub const NUMERIC_MAX_INTEGER_DIGITS: u8 = 29;
+
+/// Maximum digits right of the decimal point for NUMERIC.
+pub const NUMERIC_MAX_SCALE: u8 = 9;
+
+/// Maximum digits left of the decimal point for BIGNUMERIC (precision 76.76, scale 38).
+pub const BIGNUMERIC_MAX_INTEGER_DIGITS: u8 = 38;
+
+/// Maximum digits right of the decimal point for BIGNUMERIC.
+pub const BIGNUMERIC_MAX_SCALE: u8 = 38;
+
+/// Number of integer digits needed to hold any INTEGER (INT64) value.
+const INTEGER_DIGITS: u8 = 19;
+
+/// Options controlling optional type inference rules.
+///
+/// The plain `infer_*` functions only take `quoted_values_are_strings`; the
+/// `*_with_options` variants accept the full set of options.
+#[derive(Debug, Clone, Default)]
+pub struct InferenceOptions {
+    /// Treat quoted values as strings (don't infer types)
+    pub quoted_values_are_strings: bool,
+    /// Infer NUMERIC/BIGNUMERIC for decimals that would lose precision as FLOAT
+    pub infer_numeric: bool,
+    /// When base64 strings are inferred as BYTES
+    pub bytes: BytesInference,
+    /// Infer GEOGRAPHY for WKT strings and GeoJSON geometry objects
+    pub infer_geography: bool,
+    /// Infer TIMESTAMP for integers that look like Unix epoch times
+    pub epoch: Option<EpochDetection>,
+    /// Extra date/time formats, tried after the built-in ISO patterns
+    pub datetime_formats: DateTimeFormats,
+    /// Only match date/time layouts, without checking that the date and time
+    /// exist (the behavior before calendar checking)
+    pub regex_only_dates: bool,
+}
+
+/// Controls when strings that decode as base64 are inferred as BYTES.
+///
+/// Short words like `"test"` are valid base64 too, so detection is opt-in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BytesInference {
+    /// Never infer BYTES
+    #[default]
+    Off,
+    /// Base64 strings of at least this many characters that would otherwise be STRING
+    MinLength(usize),
+    /// Every base64 string, ahead of quoted INTEGER/FLOAT/BOOLEAN detection.
+    /// Used for fields known to hold binary data.
+    Always,
+}
+
+impl InferenceOptions {
+    fn quoted(quoted_values_are_strings: bool) -> Self {
+        Self {
+            quoted_values_are_strings,
+            ..Default::default()
+        }
+    }
+}
+
 // Regex patterns matching the Python implementation exactly
 
 /// Detect a TIMESTAMP field of the form:
@@ -24,6 +98,12 @@ static TIMESTAMP_REGEX: Lazy<Regex> = Lazy::new(|| {
     ).unwrap()
 });
 
+/// Detect a DATETIME field, a TIMESTAMP without a time zone:
+/// `YYYY-[M]M-[D]D( |T)[H]H:[M]M:[S]S[.DDDDDD]`
+static DATETIME_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\d{4}-\d{1,2}-\d{1,2}[T ]\d{1,2}:\d{1,2}:\d{1,2}(\.\d{1,6})?$").unwrap()
+});
+
 /// Detect a DATE field of the form `YYYY-[M]M-[D]D`.
 static DATE_REGEX: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"^\d{4}-(?:[1-9]|0[1-9]|1[012])-(?:[1-9]|0[1-9]|[12][0-9]|3[01])$").unwrap()
@@ -33,6 +113,11 @@ static DATE_REGEX: Lazy<Regex> = Lazy::new(|| {
 static TIME_REGEX: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"^\d{1,2}:\d{1,2}:\d{1,2}(\.\d{1,6})?$").unwrap());
 
+/// Detect standard, padded base64.
+static BASE64_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(?:[A-Za-z0-9+/]{4})*(?:[A-Za-z0-9+/]{2}==|[A-Za-z0-9+/]{3}=)?$").unwrap()
+});
+
 /// Detect integers inside quotes.
 static INTEGER_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[-+]?\d+$").unwrap());
 
@@ -41,10 +126,17 @@ static FLOAT_REGEX: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"^[-+]?(?:\d+\.?\d*|\.\d+)(?:[eE][-+]?\d+)?$").unwrap());
 
 /// Check if a string matches the TIMESTAMP pattern.
+///
+/// The time zone is optional, so every DATETIME string also matches.
 pub fn is_timestamp(s: &str) -> bool {
     TIMESTAMP_REGEX.is_match(s)
 }
 
+/// Check if a string matches the DATETIME pattern (no time zone).
+pub fn is_datetime(s: &str) -> bool {
+    DATETIME_REGEX.is_match(s)
+}
+
 /// Check if a string matches the DATE pattern.
 pub fn is_date(s: &str) -> bool {
     DATE_REGEX.is_match(s)
@@ -65,12 +157,142 @@ pub fn is_float_string(s: &str) -> bool {
     FLOAT_REGEX.is_match(s)
 }
 
+/// Check if a non-empty string is valid standard base64 (with padding).
+pub fn is_base64(s: &str) -> bool {
+    !s.is_empty() && BASE64_REGEX.is_match(s)
+}
+
 /// Check if a string represents a boolean value.
 pub fn is_boolean_string(s: &str) -> bool {
     let lower = s.to_lowercase();
     lower == "true" || lower == "false"
 }
 
+/// Split a decimal literal into its significant digits and the position of
+/// the decimal point relative to those digits.
+///
+/// Leading and trailing zeros are dropped, so `"00120.500"` and `"1.205e2"`
+/// both normalize to `("1205", 3)`. Zero normalizes to `("", 0)`.
+fn normalize_decimal(s: &str) -> Option<(String, i64)> {
+    let unsigned = s.strip_prefix(['-', '+']).unwrap_or(s);
+    let (mantissa, exponent) = match unsigned.find(['e', 'E']) {
+        Some(idx) => (&unsigned[..idx], unsigned[idx + 1..].parse::<i64>().ok()?),
+        None => (unsigned, 0),
+    };
+    let (int_part, frac_part) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    if int_part.is_empty() && frac_part.is_empty() {
+        return None;
+    }
+    if !int_part
+        .bytes()
+        .chain(frac_part.bytes())
+        .all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+
+    let digits = format!("{}{}", int_part, frac_part);
+    let leading = digits.len() - digits.trim_start_matches('0').len();
+    let significant = digits.trim_matches('0');
+    if significant.is_empty() {
+        return Some((String::new(), 0));
+    }
+    let point = int_part.len() as i64 - leading as i64 + exponent;
+    Some((significant.to_string(), point))
+}
+
+/// Returns the `(precision, scale)` needed to store a decimal literal exactly.
+///
+/// Precision counts every digit from the most significant integer digit down to
+/// the last non-zero fractional digit, matching BigQuery's NUMERIC parameters.
+pub fn decimal_precision_scale(s: &str) -> Option<(u32, u32)> {
+    let (digits, point) = normalize_decimal(s)?;
+    if digits.is_empty() {
+        return Some((1, 0));
+    }
+    let len = digits.len() as i64;
+    let integer_digits = point.max(0);
+    let scale = (len - point).max(0);
+    let precision = integer_digits + scale;
+    Some((
+        precision.min(u32::MAX as i64) as u32,
+        scale.min(u32::MAX as i64) as u32,
+    ))
+}
+
+/// Check whether a decimal literal survives a round trip through `f64` unchanged.
+pub fn f64_round_trips(s: &str) -> bool {
+    let parsed = match s.parse::<f64>() {
+        Ok(f) if f.is_finite() => f,
+        _ => return false,
+    };
+    normalize_decimal(s) == normalize_decimal(&format!("{:e}", parsed))
+}
+
+/// Pick NUMERIC or BIGNUMERIC for the given precision and scale.
+///
+/// Returns `None` when the value does not fit in BIGNUMERIC either.
+fn decimal_type(precision: u32, scale: u32, quoted: bool) -> Option<BqType> {
+    let integer_digits = precision.saturating_sub(scale);
+    if integer_digits <= NUMERIC_MAX_INTEGER_DIGITS as u32 && scale <= NUMERIC_MAX_SCALE as u32 {
+        let (precision, scale) = (precision as u8, scale as u8);
+        return Some(if quoted {
+            BqType::QNumeric { precision, scale }
+        } else {
+            BqType::Numeric { precision, scale }
+        });
+    }
+    if integer_digits <= BIGNUMERIC_MAX_INTEGER_DIGITS as u32
+        && scale <= BIGNUMERIC_MAX_SCALE as u32
+    {
+        let (precision, scale) = (precision as u8, scale as u8);
+        return Some(if quoted {
+            BqType::QBigNumeric { precision, scale }
+        } else {
+            BqType::BigNumeric { precision, scale }
+        });
+    }
+    None
+}
+
+/// Infer NUMERIC/BIGNUMERIC for a number that does not fit in INTEGER.
+///
+/// Integral literals always need an exact decimal type since they overflow
+/// INT64; fractional literals only do when `f64` would lose digits. Returns
+/// `None` when FLOAT is good enough or the value exceeds BIGNUMERIC.
+fn infer_decimal_type(s: &str, quoted: bool) -> Option<BqType> {
+    let integral = !s.contains(['.', 'e', 'E']);
+    if !integral && f64_round_trips(s) {
+        return None;
+    }
+    let (precision, scale) = decimal_precision_scale(s)?;
+    decimal_type(precision, scale, quoted)
+}
+
+/// Build a decimal literal that infers back to the given NUMERIC/BIGNUMERIC spec.
+///
+/// Used where schema entries are turned back into representative JSON values.
+pub fn decimal_literal(precision: u8, scale: u8) -> String {
+    let integer_digits = precision.saturating_sub(scale) as usize;
+    let int_part = if integer_digits == 0 {
+        "0".to_string()
+    } else {
+        "9".repeat(integer_digits)
+    };
+    if scale == 0 {
+        int_part
+    } else {
+        format!("{}.{}", int_part, "9".repeat(scale as usize))
+    }
+}
+
+/// Merge two decimal `(precision, scale)` pairs into one wide enough for both.
+fn widen_decimal(a: (u8, u8), b: (u8, u8)) -> (u32, u32) {
+    let integer_digits = (a.0.saturating_sub(a.1)).max(b.0.saturating_sub(b.1)) as u32;
+    let scale = a.1.max(b.1) as u32;
+    (integer_digits + scale, scale)
+}
+
 /// Infer the BigQuery type from a serde_json Value.
 ///
 /// Returns the inferred type. For arrays, returns `None` if the array
@@ -81,27 +303,35 @@ pub fn is_boolean_string(s: &str) -> bool {
 pub fn infer_type_from_json(
     value: &serde_json::Value,
     quoted_values_are_strings: bool,
+) -> Option<BqType> {
+    infer_type_from_json_with_options(value, &InferenceOptions::quoted(quoted_values_are_strings))
+}
+
+/// Infer the BigQuery type from a serde_json Value using the given options.
+pub fn infer_type_from_json_with_options(
+    value: &serde_json::Value,
+    options: &InferenceOptions,
 ) -> Option<BqType> {
     match value {
         serde_json::Value::Null => Some(BqType::Null),
         serde_json::Value::Bool(_) => Some(BqType::Boolean),
         serde_json::Value::Number(n) => {
             if n.is_i64() {
+                if options.epoch.as_ref().is_some_and(|e| e.matches_number(n)) {
+                    return Some(BqType::Timestamp);
+                }
                 // i64 values are always within BigQuery INTEGER range (INT64)
-                Some(BqType::Integer)
-            } else if n.is_u64() {
-                let u = n.as_u64().unwrap();
-                // u64 values > i64::MAX become FLOAT
-                if u > INTEGER_MAX_VALUE as u64 {
-                    Some(BqType::Float)
-                } else {
-                    Some(BqType::Integer)
+                return Some(BqType::Integer);
+            }
+            if options.infer_numeric {
+                if let Some(decimal) = infer_decimal_type(&n.to_string(), false) {
+                    return Some(decimal);
                 }
-            } else {
-                Some(BqType::Float)
             }
+            // Everything else (fractions, integers beyond i64) becomes FLOAT
+            Some(BqType::Float)
         }
-        serde_json::Value::String(s) => Some(infer_type_from_string(s, quoted_values_are_strings)),
+        serde_json::Value::String(s) => Some(infer_type_from_string_with_options(s, options)),
         serde_json::Value::Array(arr) => {
             if arr.is_empty() {
                 Some(BqType::EmptyArray)
@@ -113,6 +343,8 @@ pub fn infer_type_from_json(
         serde_json::Value::Object(obj) => {
             if obj.is_empty() {
                 Some(BqType::EmptyRecord)
+            } else if options.infer_geography && is_geojson_geometry(value) {
+                Some(BqType::Geography)
             } else {
                 // For non-empty objects, return a marker - actual fields handled elsewhere
                 Some(BqType::Record(Default::default()))
@@ -121,47 +353,135 @@ pub fn infer_type_from_json(
     }
 }
 
+/// Infer the BigQuery type of a JSON number from its text.
+///
+/// Gives the same type as `infer_type_from_json_with_options` on the parsed
+/// number, except that `infer_numeric` sees every digit of the literal rather
+/// than the nearest `f64`.
+pub fn infer_type_from_number_with_options(s: &str, options: &InferenceOptions) -> BqType {
+    match s.parse::<i64>() {
+        // serde_json reads negative zero as a float
+        Ok(0) if s.starts_with('-') => return BqType::Float,
+        Ok(n) => {
+            if options.epoch.as_ref().is_some_and(|e| e.matches(n)) {
+                return BqType::Timestamp;
+            }
+            return BqType::Integer;
+        }
+        Err(_) => {}
+    }
+    if options.infer_numeric {
+        if let Some(decimal) = infer_decimal_type(s, false) {
+            return decimal;
+        }
+    }
+    BqType::Float
+}
+
 /// Infer the BigQuery type from a string value.
 ///
 /// This handles type inference for:
-/// - Date/time types (TIMESTAMP, DATE, TIME)
+/// - Date/time types (TIMESTAMP, DATETIME, DATE, TIME)
+/// - WKT geometries (when GEOGRAPHY inference is enabled)
 /// - Quoted primitives (when `quoted_values_are_strings` is false)
 pub fn infer_type_from_string(s: &str, quoted_values_are_strings: bool) -> BqType {
+    infer_type_from_string_with_options(s, &InferenceOptions::quoted(quoted_values_are_strings))
+}
+
+/// Infer the BigQuery type from a string value using the given options.
+///
+/// Extra `datetime_formats` are tried after the built-in date/time patterns.
+pub fn infer_type_from_string_with_options(s: &str, options: &InferenceOptions) -> BqType {
     // Always check date/time patterns first
-    if is_timestamp(s) {
-        return BqType::Timestamp;
+    if let Some(bq_type) = infer_temporal_type(s, options.regex_only_dates) {
+        return bq_type;
+    }
+    if let Some(bq_type) = options.datetime_formats.infer(s) {
+        return bq_type;
     }
-    if is_date(s) {
-        return BqType::Date;
+    if options.infer_geography && is_wkt(s) {
+        return BqType::Geography;
+    }
+
+    if options.bytes == BytesInference::Always && is_base64(s) {
+        return BqType::Bytes;
     }
-    if is_time(s) {
-        return BqType::Time;
+
+    let quoted_type = if options.quoted_values_are_strings {
+        // Don't infer numeric/boolean types
+        None
+    } else {
+        infer_quoted_type(s, options)
+    };
+
+    match quoted_type {
+        Some(bq_type) => bq_type,
+        None => match options.bytes {
+            BytesInference::MinLength(min) if s.len() >= min && is_base64(s) => BqType::Bytes,
+            _ => BqType::String,
+        },
     }
+}
 
-    // If quoted_values_are_strings is true, don't infer numeric/boolean types
-    if quoted_values_are_strings {
-        return BqType::String;
+/// Infer the built-in DATETIME/TIMESTAMP/DATE/TIME type of a string, if any.
+///
+/// Zone-less timestamps are DATETIME. Unless `regex_only` is set, values
+/// outside the calendar (`2024-02-30`, `25:00:00`) don't match.
+fn infer_temporal_type(s: &str, regex_only: bool) -> Option<BqType> {
+    let matches = |regex: fn(&str) -> bool, calendar: fn(&str) -> bool| {
+        if regex_only {
+            regex(s)
+        } else {
+            calendar(s)
+        }
+    };
+    if matches(is_datetime, is_calendar_datetime) {
+        Some(BqType::Datetime)
+    } else if matches(is_timestamp, is_calendar_timestamp) {
+        Some(BqType::Timestamp)
+    } else if matches(is_date, is_calendar_date) {
+        Some(BqType::Date)
+    } else if matches(is_time, is_calendar_time) {
+        Some(BqType::Time)
+    } else {
+        None
     }
+}
 
+/// Infer the quoted INTEGER/FLOAT/BOOLEAN (or decimal) type of a string, if any.
+fn infer_quoted_type(s: &str, options: &InferenceOptions) -> Option<BqType> {
     // Try to infer type from quoted value
     if is_integer_string(s) {
         // If it parses as i64, it's within BigQuery INTEGER range (INT64)
-        if s.parse::<i64>().is_ok() {
-            return BqType::QInteger;
+        if let Ok(n) = s.parse::<i64>() {
+            if options.epoch.as_ref().is_some_and(|e| e.matches(n)) {
+                return Some(BqType::Timestamp);
+            }
+            return Some(BqType::QInteger);
+        }
+        if options.infer_numeric {
+            if let Some(decimal) = infer_decimal_type(s, true) {
+                return Some(decimal);
+            }
         }
         // Overflow (doesn't fit in i64) - treat as float
-        return BqType::QFloat;
+        return Some(BqType::QFloat);
     }
 
     if is_float_string(s) {
-        return BqType::QFloat;
+        if options.infer_numeric {
+            if let Some(decimal) = infer_decimal_type(s, true) {
+                return Some(decimal);
+            }
+        }
+        return Some(BqType::QFloat);
     }
 
     if is_boolean_string(s) {
-        return BqType::QBoolean;
+        return Some(BqType::QBoolean);
     }
 
-    BqType::String
+    None
 }
 
 /// Infer the BigQuery type and mode from a JSON value.
@@ -171,6 +491,14 @@ pub fn infer_type_from_string(s: &str, quoted_values_are_strings: bool) -> BqTyp
 pub fn infer_bigquery_type(
     value: &serde_json::Value,
     quoted_values_are_strings: bool,
+) -> Option<(BqMode, BqType)> {
+    infer_bigquery_type_with_options(value, &InferenceOptions::quoted(quoted_values_are_strings))
+}
+
+/// Infer the BigQuery type and mode from a JSON value using the given options.
+pub fn infer_bigquery_type_with_options(
+    value: &serde_json::Value,
+    options: &InferenceOptions,
 ) -> Option<(BqMode, BqType)> {
     match value {
         serde_json::Value::Array(arr) => {
@@ -179,7 +507,7 @@ pub fn infer_bigquery_type(
             }
 
             // Infer array element type
-            let element_type = infer_array_type(arr, quoted_values_are_strings)?;
+            let element_type = infer_array_type_with_options(arr, options)?;
 
             // Disallow arrays of special types (except empty records which bq load allows)
             if element_type.is_internal() && element_type != BqType::EmptyRecord {
@@ -189,7 +517,7 @@ pub fn infer_bigquery_type(
             Some((BqMode::Repeated, element_type))
         }
         _ => {
-            let bq_type = infer_type_from_json(value, quoted_values_are_strings)?;
+            let bq_type = infer_type_from_json_with_options(value, options)?;
             Some((BqMode::Nullable, bq_type))
         }
     }
@@ -201,6 +529,17 @@ pub fn infer_bigquery_type(
 pub fn infer_array_type(
     elements: &[serde_json::Value],
     quoted_values_are_strings: bool,
+) -> Option<BqType> {
+    infer_array_type_with_options(
+        elements,
+        &InferenceOptions::quoted(quoted_values_are_strings),
+    )
+}
+
+/// Infer the common type for all elements in an array using the given options.
+pub fn infer_array_type_with_options(
+    elements: &[serde_json::Value],
+    options: &InferenceOptions,
 ) -> Option<BqType> {
     if elements.is_empty() {
         return None; // Should not happen - caller should check
@@ -218,14 +557,7 @@ pub fn infer_array_type(
                     return None;
                 }
             }
-            serde_json::Value::Object(obj) => {
-                if obj.is_empty() {
-                    BqType::EmptyRecord
-                } else {
-                    BqType::Record(Default::default())
-                }
-            }
-            _ => infer_type_from_json(elem, quoted_values_are_strings)?,
+            _ => infer_type_from_json_with_options(elem, options)?,
         };
 
         candidate_type = match candidate_type {
@@ -247,7 +579,10 @@ pub fn infer_array_type(
 /// - `[Q]INTEGER + [Q]INTEGER` -> INTEGER
 /// - `[Q]FLOAT + [Q]FLOAT` -> FLOAT
 /// - QINTEGER + QFLOAT -> QFLOAT
+/// - `DATETIME + TIMESTAMP` -> TIMESTAMP
 /// - `[Q]INTEGER + [Q]FLOAT` -> FLOAT (except QINTEGER + QFLOAT)
+/// - `[Q]NUMERIC/[Q]BIGNUMERIC` with each other or `[Q]INTEGER` -> widest decimal type
+/// - `[Q]NUMERIC/[Q]BIGNUMERIC + [Q]FLOAT` -> the decimal type
 /// - String-compatible types -> STRING
 /// - Otherwise -> None (incompatible)
 pub fn convert_type(atype: &BqType, btype: &BqType) -> Option<BqType> {
@@ -256,6 +591,19 @@ pub fn convert_type(atype: &BqType, btype: &BqType) -> Option<BqType> {
         return Some(atype.clone());
     }
 
+    // JSON holds any value
+    if *atype == BqType::Json || *btype == BqType::Json {
+        return Some(BqType::Json);
+    }
+
+    // DATETIME + TIMESTAMP -> TIMESTAMP
+    if matches!(
+        (atype, btype),
+        (BqType::Datetime, BqType::Timestamp) | (BqType::Timestamp, BqType::Datetime)
+    ) {
+        return Some(BqType::Timestamp);
+    }
+
     // [Q]BOOLEAN + [Q]BOOLEAN -> BOOLEAN
     if matches!(atype, BqType::Boolean | BqType::QBoolean)
         && matches!(btype, BqType::Boolean | BqType::QBoolean)
@@ -294,6 +642,11 @@ pub fn convert_type(atype: &BqType, btype: &BqType) -> Option<BqType> {
         return Some(BqType::Float);
     }
 
+    // Decimal types absorb integers and floats, widening precision as needed
+    if let Some(decimal) = convert_decimal_type(atype, btype) {
+        return Some(decimal);
+    }
+
     // String-compatible types all convert to STRING
     if atype.is_string_compatible() && btype.is_string_compatible() {
         return Some(BqType::String);
@@ -316,6 +669,33 @@ pub fn convert_type(atype: &BqType, btype: &BqType) -> Option<BqType> {
     None
 }
 
+/// Merge a NUMERIC/BIGNUMERIC type with another numeric type.
+///
+/// The result stays quoted only if both inputs were quoted. Decimals too wide
+/// for BIGNUMERIC fall back to FLOAT.
+fn convert_decimal_type(atype: &BqType, btype: &BqType) -> Option<BqType> {
+    let integer_spec =
+        |t: &BqType| matches!(t, BqType::Integer | BqType::QInteger).then_some((INTEGER_DIGITS, 0));
+    let is_float_like = |t: &BqType| matches!(t, BqType::Float | BqType::QFloat);
+    let quoted = atype.is_quoted() && btype.is_quoted();
+
+    let (a, b) = match (atype.decimal_spec(), btype.decimal_spec()) {
+        (Some(a), Some(b)) => (a, b),
+        (Some(a), None) if is_float_like(btype) => (a, a),
+        (None, Some(b)) if is_float_like(atype) => (b, b),
+        (Some(a), None) => (a, integer_spec(btype)?),
+        (None, Some(b)) => (integer_spec(atype)?, b),
+        (None, None) => return None,
+    };
+
+    let (precision, scale) = widen_decimal(a, b);
+    decimal_type(precision, scale, quoted).or(Some(if quoted {
+        BqType::QFloat
+    } else {
+        BqType::Float
+    }))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -351,6 +731,32 @@ mod tests {
         assert!(!is_timestamp("2017-05-22T12:33:01 UT"));
     }
 
+    #[test]
+    fn test_datetime_matcher() {
+        assert!(is_datetime("2017-05-22T12:33:01"));
+        assert!(is_datetime("2017-05-22 12:33:01.123456"));
+        assert!(is_datetime("2017-5-2T1:3:1"));
+        assert!(!is_datetime("2017-05-22T12:33:01Z"));
+        assert!(!is_datetime("2017-05-22 12:33:01 UTC"));
+        assert!(!is_datetime("2017-05-22 12:33:01-07:00"));
+        assert!(!is_datetime("2017-05-22"));
+    }
+
+    #[test]
+    fn test_base64_matcher() {
+        assert!(is_base64("aGVsbG8gd29ybGQ="));
+        assert!(is_base64("aGVsbG8gd29ybGQh"));
+        assert!(is_base64("aGk="));
+        assert!(is_base64("YQ=="));
+        assert!(is_base64("+/+/"));
+        assert!(!is_base64(""));
+        assert!(!is_base64("aGVsbG8"));
+        assert!(!is_base64("YQ="));
+        assert!(!is_base64("aGVs=G8="));
+        assert!(!is_base64("hello world!"));
+        assert!(!is_base64("aGVsbG8_d29ybGQ="));
+    }
+
     #[test]
     fn test_date_matcher_valid() {
         assert!(is_date("2017-05-22"));
@@ -440,6 +846,10 @@ mod tests {
         );
         assert_eq!(
             infer_type_from_json(&json!("2018-02-08T12:34:56"), false),
+            Some(BqType::Datetime)
+        );
+        assert_eq!(
+            infer_type_from_json(&json!("2018-02-08T12:34:56Z"), false),
             Some(BqType::Timestamp)
         );
 
@@ -519,6 +929,20 @@ mod tests {
             convert_type(&BqType::Date, &BqType::Timestamp),
             Some(BqType::String)
         );
+        assert_eq!(
+            convert_type(&BqType::Date, &BqType::Datetime),
+            Some(BqType::String)
+        );
+
+        // DATETIME widens to TIMESTAMP
+        assert_eq!(
+            convert_type(&BqType::Datetime, &BqType::Timestamp),
+            Some(BqType::Timestamp)
+        );
+        assert_eq!(
+            convert_type(&BqType::Timestamp, &BqType::Datetime),
+            Some(BqType::Timestamp)
+        );
 
         // Incompatible
         assert_eq!(convert_type(&BqType::Integer, &BqType::Boolean), None);
@@ -759,6 +1183,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_convert_type_json_absorbs_everything() {
+        assert_eq!(
+            convert_type(&BqType::Json, &BqType::Integer),
+            Some(BqType::Json)
+        );
+        assert_eq!(
+            convert_type(&BqType::String, &BqType::Json),
+            Some(BqType::Json)
+        );
+        assert_eq!(
+            convert_type(&BqType::Json, &BqType::Record(Default::default())),
+            Some(BqType::Json)
+        );
+    }
+
     #[test]
     fn test_is_boolean_string_case_insensitive() {
         assert!(is_boolean_string("true"));
@@ -811,8 +1251,10 @@ mod tests {
 
     #[test]
     fn test_infer_type_datetime_patterns_priority() {
-        // Timestamp pattern should take priority over date
-        let ts = "2024-01-15T12:30:45";
+        // Timestamp patterns should take priority over date; zone-less is DATETIME
+        let dt = "2024-01-15T12:30:45";
+        assert_eq!(infer_type_from_string(dt, false), BqType::Datetime);
+        let ts = "2024-01-15T12:30:45+02:00";
         assert_eq!(infer_type_from_string(ts, false), BqType::Timestamp);
 
         // Date only
@@ -823,4 +1265,446 @@ mod tests {
         let time = "12:30:45";
         assert_eq!(infer_type_from_string(time, false), BqType::Time);
     }
+
+    fn numeric_options() -> InferenceOptions {
+        InferenceOptions {
+            infer_numeric: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_decimal_precision_scale() {
+        assert_eq!(decimal_precision_scale("123.45"), Some((5, 2)));
+        assert_eq!(decimal_precision_scale("-0.001"), Some((3, 3)));
+        assert_eq!(decimal_precision_scale("00120.500"), Some((4, 1)));
+        assert_eq!(decimal_precision_scale("1.5e3"), Some((4, 0)));
+        assert_eq!(decimal_precision_scale("0"), Some((1, 0)));
+        assert_eq!(decimal_precision_scale("abc"), None);
+    }
+
+    #[test]
+    fn test_f64_round_trips() {
+        assert!(f64_round_trips("1.5"));
+        assert!(f64_round_trips("0.1"));
+        assert!(f64_round_trips("1e10"));
+        assert!(!f64_round_trips("12345678901234567.89"));
+        assert!(!f64_round_trips("0.12345678901234567890"));
+    }
+
+    #[test]
+    fn test_infer_numeric_disabled_by_default() {
+        let value: serde_json::Value = serde_json::from_str("12345678901234567.89").unwrap();
+        assert_eq!(infer_type_from_json(&value, false), Some(BqType::Float));
+        assert_eq!(
+            infer_type_from_string("12345678901234567.89", false),
+            BqType::QFloat
+        );
+    }
+
+    #[test]
+    fn test_infer_numeric_from_json() {
+        let options = numeric_options();
+        let parse = |s: &str| serde_json::from_str::<serde_json::Value>(s).unwrap();
+
+        // Values that round-trip through f64 stay FLOAT
+        assert_eq!(
+            infer_type_from_json_with_options(&parse("1.5"), &options),
+            Some(BqType::Float)
+        );
+        // i64 values stay INTEGER
+        assert_eq!(
+            infer_type_from_json_with_options(&parse("42"), &options),
+            Some(BqType::Integer)
+        );
+        // Integers beyond i64 need NUMERIC
+        assert_eq!(
+            infer_type_from_json_with_options(&parse("18446744073709551615"), &options),
+            Some(BqType::Numeric {
+                precision: 20,
+                scale: 0
+            })
+        );
+        // Too wide for BIGNUMERIC falls back to FLOAT
+        let huge = format!("1{}.5", "0".repeat(45));
+        assert_eq!(
+            infer_type_from_json_with_options(&parse(&huge), &options),
+            Some(BqType::Float)
+        );
+    }
+
+    #[test]
+    fn test_infer_numeric_from_number_text() {
+        let options = numeric_options();
+        let infer = |s: &str| infer_type_from_number_with_options(s, &options);
+
+        assert_eq!(infer("1.5"), BqType::Float);
+        assert_eq!(infer("42"), BqType::Integer);
+        assert_eq!(
+            infer("12345678901234567.89"),
+            BqType::Numeric {
+                precision: 19,
+                scale: 2
+            }
+        );
+        assert_eq!(
+            infer("18446744073709551615"),
+            BqType::Numeric {
+                precision: 20,
+                scale: 0
+            }
+        );
+        // Scale beyond 9 needs BIGNUMERIC
+        assert_eq!(
+            infer("0.12345678901234567891"),
+            BqType::BigNumeric {
+                precision: 20,
+                scale: 20
+            }
+        );
+        // Too wide for BIGNUMERIC falls back to FLOAT
+        assert_eq!(infer(&format!("1{}.5", "0".repeat(45))), BqType::Float);
+        assert_eq!(infer(&format!("1{}", "0".repeat(80))), BqType::Float);
+        assert_eq!(infer("1e400"), BqType::Float);
+        assert_eq!(infer("1e-400"), BqType::Float);
+    }
+
+    #[test]
+    fn test_number_text_matches_parsed_number() {
+        let epoch = InferenceOptions {
+            epoch: Some(EpochDetection::default()),
+            ..Default::default()
+        };
+        for options in [InferenceOptions::default(), epoch] {
+            for s in [
+                "0",
+                "-0",
+                "-0.0",
+                "7",
+                "-7",
+                "1.0",
+                "1e2",
+                "1E-2",
+                "-2.5e+3",
+                "1700000000",
+                "1700000000000",
+                "1700000000.5",
+                "9223372036854775807",
+                "-9223372036854775808",
+                "9223372036854775808",
+                "123456789012345678901234567890",
+            ] {
+                let value: serde_json::Value = serde_json::from_str(s).unwrap();
+                assert_eq!(
+                    Some(infer_type_from_number_with_options(s, &options)),
+                    infer_type_from_json_with_options(&value, &options),
+                    "{}",
+                    s
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_infer_numeric_from_quoted_string() {
+        let options = numeric_options();
+        assert_eq!(
+            infer_type_from_string_with_options("12345678901234567.89", &options),
+            BqType::QNumeric {
+                precision: 19,
+                scale: 2
+            }
+        );
+        assert_eq!(
+            infer_type_from_string_with_options("99999999999999999999", &options),
+            BqType::QNumeric {
+                precision: 20,
+                scale: 0
+            }
+        );
+        assert_eq!(
+            infer_type_from_string_with_options("1.5", &options),
+            BqType::QFloat
+        );
+
+        // quoted_values_are_strings still wins
+        let options = InferenceOptions {
+            quoted_values_are_strings: true,
+            infer_numeric: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            infer_type_from_string_with_options("12345678901234567.89", &options),
+            BqType::String
+        );
+    }
+
+    #[test]
+    fn test_convert_type_numeric_combinations() {
+        let n = |precision, scale| BqType::Numeric { precision, scale };
+        let qn = |precision, scale| BqType::QNumeric { precision, scale };
+
+        // Widen integer digits and scale independently
+        assert_eq!(convert_type(&n(5, 2), &n(4, 3)), Some(n(6, 3)));
+        // Quoted only if both quoted
+        assert_eq!(convert_type(&qn(5, 2), &qn(4, 3)), Some(qn(6, 3)));
+        assert_eq!(convert_type(&qn(5, 2), &n(4, 3)), Some(n(6, 3)));
+        // INTEGER needs 19 integer digits
+        assert_eq!(convert_type(&n(5, 2), &BqType::Integer), Some(n(21, 2)));
+        assert_eq!(convert_type(&BqType::QInteger, &qn(5, 2)), Some(qn(21, 2)));
+        // FLOAT is absorbed by the decimal type
+        assert_eq!(convert_type(&n(5, 2), &BqType::Float), Some(n(5, 2)));
+        assert_eq!(convert_type(&BqType::QFloat, &qn(5, 2)), Some(qn(5, 2)));
+        // NUMERIC widens to BIGNUMERIC when the scale exceeds 9
+        assert_eq!(
+            convert_type(
+                &n(5, 2),
+                &BqType::BigNumeric {
+                    precision: 12,
+                    scale: 12
+                }
+            ),
+            Some(BqType::BigNumeric {
+                precision: 15,
+                scale: 12
+            })
+        );
+        // Integer and fractional digits are widened independently
+        assert_eq!(
+            convert_type(
+                &BqType::BigNumeric {
+                    precision: 38,
+                    scale: 0
+                },
+                &BqType::BigNumeric {
+                    precision: 38,
+                    scale: 38
+                }
+            ),
+            Some(BqType::BigNumeric {
+                precision: 76,
+                scale: 38
+            })
+        );
+        // Quoted decimals are string-compatible, unquoted are not
+        assert_eq!(
+            convert_type(&qn(5, 2), &BqType::String),
+            Some(BqType::String)
+        );
+        assert_eq!(convert_type(&n(5, 2), &BqType::String), None);
+        assert_eq!(convert_type(&n(5, 2), &BqType::Boolean), None);
+    }
+
+    #[test]
+    fn test_infer_bytes() {
+        let blob = "aGVsbG8gd29ybGQgYmFzZTY0IGRhdGE=";
+
+        // Off by default
+        assert_eq!(infer_type_from_string(blob, false), BqType::String);
+
+        // Minimum length threshold
+        let options = InferenceOptions {
+            bytes: BytesInference::MinLength(16),
+            ..Default::default()
+        };
+        assert_eq!(
+            infer_type_from_string_with_options(blob, &options),
+            BqType::Bytes
+        );
+        assert_eq!(
+            infer_type_from_string_with_options("dGVzdA==", &options),
+            BqType::String
+        );
+        assert_eq!(
+            infer_type_from_string_with_options("not base64 at all, right?", &options),
+            BqType::String
+        );
+        // Quoted numbers keep their type even when they happen to be base64
+        assert_eq!(
+            infer_type_from_string_with_options("1234567890123456", &options),
+            BqType::QInteger
+        );
+
+        // Always mode wins over quoted types
+        let options = InferenceOptions {
+            bytes: BytesInference::Always,
+            ..Default::default()
+        };
+        assert_eq!(
+            infer_type_from_string_with_options("1234", &options),
+            BqType::Bytes
+        );
+        assert_eq!(
+            infer_type_from_string_with_options("2024-01-01", &options),
+            BqType::Date
+        );
+
+        // BYTES merges with other strings into STRING
+        assert_eq!(
+            convert_type(&BqType::Bytes, &BqType::String),
+            Some(BqType::String)
+        );
+        assert_eq!(convert_type(&BqType::Bytes, &BqType::Integer), None);
+    }
+
+    #[test]
+    fn test_infer_geography() {
+        let options = InferenceOptions {
+            infer_geography: true,
+            ..Default::default()
+        };
+        let point = json!({"type": "Point", "coordinates": [-122.3, 47.6]});
+
+        // Off by default
+        assert_eq!(
+            infer_type_from_string("POINT(-122.3 47.6)", false),
+            BqType::String
+        );
+        assert_eq!(
+            infer_type_from_json(&point, false),
+            Some(BqType::Record(Default::default()))
+        );
+
+        assert_eq!(
+            infer_type_from_string_with_options("POINT(-122.3 47.6)", &options),
+            BqType::Geography
+        );
+        assert_eq!(
+            infer_bigquery_type_with_options(&point, &options),
+            Some((BqMode::Nullable, BqType::Geography))
+        );
+        assert_eq!(
+            infer_bigquery_type_with_options(&json!([point, "POINT(1 2)"]), &options),
+            Some((BqMode::Repeated, BqType::Geography))
+        );
+        // Objects that are not geometries stay records
+        assert_eq!(
+            infer_type_from_json_with_options(&json!({"type": "car", "wheels": 4}), &options),
+            Some(BqType::Record(Default::default()))
+        );
+    }
+
+    #[test]
+    fn test_infer_epoch_timestamps() {
+        let options = InferenceOptions {
+            epoch: Some(EpochDetection::default()),
+            ..Default::default()
+        };
+
+        // Off by default
+        assert_eq!(
+            infer_type_from_json(&json!(1700000000), false),
+            Some(BqType::Integer)
+        );
+
+        for value in [json!(1700000000), json!(1700000000123_i64)] {
+            assert_eq!(
+                infer_type_from_json_with_options(&value, &options),
+                Some(BqType::Timestamp)
+            );
+        }
+        // Out of range integers and fractions keep their usual types
+        assert_eq!(
+            infer_type_from_json_with_options(&json!(42), &options),
+            Some(BqType::Integer)
+        );
+        assert_eq!(
+            infer_type_from_json_with_options(&json!(1700000000.5), &options),
+            Some(BqType::Float)
+        );
+
+        // Quoted epochs (and CSV cells) too, unless quoted values are strings
+        assert_eq!(
+            infer_type_from_string_with_options("1700000000", &options),
+            BqType::Timestamp
+        );
+        assert_eq!(
+            infer_type_from_string_with_options("42", &options),
+            BqType::QInteger
+        );
+        let quoted_strings = InferenceOptions {
+            quoted_values_are_strings: true,
+            ..options.clone()
+        };
+        assert_eq!(
+            infer_type_from_string_with_options("1700000000", &quoted_strings),
+            BqType::String
+        );
+
+        assert_eq!(
+            infer_bigquery_type_with_options(&json!([1700000000, 1700000001]), &options),
+            Some((BqMode::Repeated, BqType::Timestamp))
+        );
+    }
+
+    #[test]
+    fn test_infer_custom_datetime_formats() {
+        let options = InferenceOptions {
+            datetime_formats: DateTimeFormats::from_specs(&[
+                "DATE=%m/%d/%Y",
+                "DATETIME=%d.%m.%Y %H:%M",
+                "TIMESTAMP=%a, %d %b %Y %T %z",
+            ])
+            .unwrap(),
+            ..Default::default()
+        };
+
+        // Off by default
+        assert_eq!(infer_type_from_string("03/15/2024", false), BqType::String);
+
+        assert_eq!(
+            infer_type_from_string_with_options("03/15/2024", &options),
+            BqType::Date
+        );
+        assert_eq!(
+            infer_type_from_string_with_options("15.03.2024 14:22", &options),
+            BqType::Datetime
+        );
+        assert_eq!(
+            infer_type_from_string_with_options("Fri, 15 Mar 2024 14:22:01 +0000", &options),
+            BqType::Timestamp
+        );
+        // Built-in patterns still apply
+        assert_eq!(
+            infer_type_from_string_with_options("2024-03-15", &options),
+            BqType::Date
+        );
+        assert_eq!(
+            infer_type_from_string_with_options("March 15", &options),
+            BqType::String
+        );
+    }
+
+    #[test]
+    fn test_infer_calendar_checked_dates() {
+        // Values with a date/time layout that don't exist are strings
+        for (value, expected) in [
+            ("2024-02-29", BqType::Date),
+            ("2024-02-30", BqType::String),
+            ("0000-01-01", BqType::String),
+            ("2024-01-15 10:00:00", BqType::Datetime),
+            ("2024-01-15T25:00:00Z", BqType::String),
+            ("25:61:61", BqType::String),
+        ] {
+            assert_eq!(infer_type_from_string(value, false), expected, "{}", value);
+        }
+
+        // The compatibility flag restores layout-only matching
+        let options = InferenceOptions {
+            regex_only_dates: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            infer_type_from_string_with_options("2024-02-30", &options),
+            BqType::Date
+        );
+        assert_eq!(
+            infer_type_from_string_with_options("25:61:61", &options),
+            BqType::Time
+        );
+        assert_eq!(
+            infer_type_from_string_with_options("2024-01-15T25:00:00Z", &options),
+            BqType::Timestamp
+        );
+    }
 }
@@ -0,0 +1,200 @@
+//! Transparent decompression of gzip, zstd, bzip2 and xz input.
+//!
+//! Compression is detected from the file extension, falling back to the
+//! magic bytes at the start of the stream, so renamed files and compressed
+//! stdin are handled too. Decoders accept concatenated members/frames, as
+//! produced by `cat a.gz b.gz` or parallel compressors.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+
+/// Compression format of an input stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    /// Uncompressed
+    None,
+    /// gzip (`.gz`)
+    Gzip,
+    /// Zstandard (`.zst`)
+    Zstd,
+    /// bzip2 (`.bz2`)
+    Bzip2,
+    /// xz (`.xz`)
+    Xz,
+}
+
+impl Compression {
+    /// Detect compression from a file extension.
+    pub fn from_extension(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "gz" | "gzip" => Some(Compression::Gzip),
+            "zst" | "zstd" => Some(Compression::Zstd),
+            "bz2" => Some(Compression::Bzip2),
+            "xz" => Some(Compression::Xz),
+            _ => None,
+        }
+    }
+
+    /// Detect compression from the first bytes of a stream.
+    pub fn from_magic(bytes: &[u8]) -> Self {
+        if bytes.starts_with(&[0x1f, 0x8b]) {
+            Compression::Gzip
+        } else if bytes.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Compression::Zstd
+        } else if bytes.starts_with(b"BZh") {
+            Compression::Bzip2
+        } else if bytes.starts_with(&[0xfd, b'7', b'z', b'X', b'Z', 0x00]) {
+            Compression::Xz
+        } else {
+            Compression::None
+        }
+    }
+}
+
+/// Open an input file, decompressing it if it is compressed.
+pub fn open_input(path: &Path) -> io::Result<Box<dyn Read + Send>> {
+    let file = File::open(path)?;
+    match Compression::from_extension(path) {
+        Some(compression) => wrap(BufReader::new(file), compression),
+        None => decompress(file),
+    }
+}
+
+/// Wrap a reader (e.g. stdin) in a decoder chosen from its magic bytes.
+pub fn decompress<R: Read + Send + 'static>(reader: R) -> io::Result<Box<dyn Read + Send>> {
+    let mut reader = BufReader::new(reader);
+    let compression = Compression::from_magic(reader.fill_buf()?);
+    wrap(reader, compression)
+}
+
+/// Strip a compression extension: `data.json.gz` becomes `data.json`.
+pub fn strip_compression_extension(path: &Path) -> PathBuf {
+    match Compression::from_extension(path) {
+        Some(_) => path.with_extension(""),
+        None => path.to_owned(),
+    }
+}
+
+fn wrap<R: BufRead + Send + 'static>(
+    reader: R,
+    compression: Compression,
+) -> io::Result<Box<dyn Read + Send>> {
+    Ok(match compression {
+        Compression::None => Box::new(reader),
+        Compression::Gzip => Box::new(flate2::bufread::MultiGzDecoder::new(reader)),
+        Compression::Zstd => Box::new(zstd::Decoder::with_buffer(reader)?),
+        Compression::Bzip2 => Box::new(bzip2::bufread::MultiBzDecoder::new(reader)),
+        Compression::Xz => Box::new(liblzma::bufread::XzDecoder::new_multi_decoder(reader)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+
+    const DATA: &str = "{\"a\": 1}\n{\"b\": 2}\n";
+
+    fn compress(compression: Compression, data: &[u8]) -> Vec<u8> {
+        match compression {
+            Compression::None => data.to_vec(),
+            Compression::Gzip => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(data).unwrap();
+                encoder.finish().unwrap()
+            }
+            Compression::Zstd => zstd::encode_all(data, 0).unwrap(),
+            Compression::Bzip2 => {
+                let mut encoder =
+                    bzip2::write::BzEncoder::new(Vec::new(), bzip2::Compression::default());
+                encoder.write_all(data).unwrap();
+                encoder.finish().unwrap()
+            }
+            Compression::Xz => {
+                let mut encoder = liblzma::write::XzEncoder::new(Vec::new(), 6);
+                encoder.write_all(data).unwrap();
+                encoder.finish().unwrap()
+            }
+        }
+    }
+
+    fn read_all(mut reader: Box<dyn Read + Send>) -> String {
+        let mut out = String::new();
+        reader.read_to_string(&mut out).unwrap();
+        out
+    }
+
+    const ALL: [(Compression, &str); 5] = [
+        (Compression::None, "json"),
+        (Compression::Gzip, "gz"),
+        (Compression::Zstd, "zst"),
+        (Compression::Bzip2, "bz2"),
+        (Compression::Xz, "xz"),
+    ];
+
+    #[test]
+    fn test_from_extension() {
+        assert_eq!(
+            Compression::from_extension(Path::new("a.json.GZ")),
+            Some(Compression::Gzip)
+        );
+        assert_eq!(
+            Compression::from_extension(Path::new("a.csv.zst")),
+            Some(Compression::Zstd)
+        );
+        assert_eq!(Compression::from_extension(Path::new("a.json")), None);
+        assert_eq!(Compression::from_extension(Path::new("a")), None);
+    }
+
+    #[test]
+    fn test_decompress_by_magic_bytes() {
+        for (compression, _) in ALL {
+            let data = compress(compression, DATA.as_bytes());
+            assert_eq!(Compression::from_magic(&data), compression);
+            let reader = decompress(Cursor::new(data)).unwrap();
+            assert_eq!(read_all(reader), DATA, "{:?}", compression);
+        }
+    }
+
+    #[test]
+    fn test_open_input_by_extension() {
+        let dir = tempfile::tempdir().unwrap();
+        for (compression, extension) in ALL {
+            let path = dir.path().join(format!("data.{}", extension));
+            std::fs::write(&path, compress(compression, DATA.as_bytes())).unwrap();
+            assert_eq!(
+                read_all(open_input(&path).unwrap()),
+                DATA,
+                "{:?}",
+                compression
+            );
+        }
+
+        // Magic bytes are used when the extension doesn't say
+        let path = dir.path().join("renamed.json");
+        std::fs::write(&path, compress(Compression::Zstd, DATA.as_bytes())).unwrap();
+        assert_eq!(read_all(open_input(&path).unwrap()), DATA);
+    }
+
+    #[test]
+    fn test_concatenated_members() {
+        let mut data = compress(Compression::Gzip, b"{\"a\": 1}\n");
+        data.extend(compress(Compression::Gzip, b"{\"b\": 2}\n"));
+        assert_eq!(read_all(decompress(Cursor::new(data)).unwrap()), DATA);
+    }
+
+    #[test]
+    fn test_strip_compression_extension() {
+        assert_eq!(
+            strip_compression_extension(Path::new("/d/data.json.gz")),
+            PathBuf::from("/d/data.json")
+        );
+        assert_eq!(
+            strip_compression_extension(Path::new("/d/data.json")),
+            PathBuf::from("/d/data.json")
+        );
+    }
+}
@@ -1,42 +1,148 @@
 //! CSV input reader.
 //!
-//! This module provides CSV parsing with header support.
+//! This module provides CSV parsing with header support. The dialect
+//! (delimiter, quoting, header row, preamble, comments and null markers) is
+//! configurable through [`CsvDialect`].
 
-use std::io::Read;
+use std::io::{BufRead, BufReader, Read};
 
 use crate::error::{Error, Result};
 
+/// How a CSV file is laid out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvDialect {
+    /// Field delimiter
+    pub delimiter: u8,
+    /// Quote character
+    pub quote: u8,
+    /// Escape character inside quoted fields (quotes are escaped by doubling if unset)
+    pub escape: Option<u8>,
+    /// Lines to skip before the header (or the first record), e.g. a preamble
+    pub skip_leading_rows: usize,
+    /// Whether the first row holds the column names
+    pub has_header: bool,
+    /// Lines starting with this character are skipped
+    pub comment: Option<u8>,
+    /// Field values read as null, e.g. `\N` or `NULL` (like `bq load --null_marker`)
+    pub null_markers: Vec<String>,
+    /// Read empty fields as null instead of empty strings
+    pub empty_as_null: bool,
+}
+
+impl Default for CsvDialect {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            quote: b'"',
+            escape: None,
+            skip_leading_rows: 0,
+            has_header: true,
+            comment: None,
+            null_markers: Vec::new(),
+            empty_as_null: false,
+        }
+    }
+}
+
+impl CsvDialect {
+    /// Name of the column at `index` when the file has no header.
+    pub fn synthetic_column_name(index: usize) -> String {
+        format!("column_{}", index + 1)
+    }
+
+    /// Check if a field value stands for null.
+    pub fn is_null(&self, field: &str) -> bool {
+        (self.empty_as_null && field.is_empty()) || self.null_markers.iter().any(|m| m == field)
+    }
+
+    fn reader_builder(&self) -> csv::ReaderBuilder {
+        let mut builder = csv::ReaderBuilder::new();
+        builder
+            .delimiter(self.delimiter)
+            .quote(self.quote)
+            .escape(self.escape)
+            .comment(self.comment)
+            .has_headers(self.has_header)
+            .flexible(true);
+        builder
+    }
+}
+
 /// CSV reader that converts rows to JSON-like objects.
 pub struct CsvReader<R: Read> {
-    reader: csv::Reader<R>,
+    reader: csv::Reader<BufReader<R>>,
     headers: Vec<String>,
+    dialect: CsvDialect,
+    /// Lines consumed before the CSV parser saw the input
+    skipped_lines: usize,
     line_number: usize,
 }
 
 impl<R: Read> CsvReader<R> {
-    /// Create a new CSV reader.
+    /// Create a new CSV reader for comma-separated input with a header row.
     pub fn new(reader: R) -> Result<Self> {
-        let mut csv_reader = csv::ReaderBuilder::new()
-            .has_headers(true)
-            .flexible(true)
-            .from_reader(reader);
+        Self::with_dialect(reader, &CsvDialect::default())
+    }
+
+    /// Create a new CSV reader for the given dialect.
+    pub fn with_dialect(reader: R, dialect: &CsvDialect) -> Result<Self> {
+        let mut reader = BufReader::new(reader);
+        let mut skipped_lines = 0;
+        let mut line = Vec::new();
+        while skipped_lines < dialect.skip_leading_rows {
+            line.clear();
+            if reader.read_until(b'\n', &mut line).map_err(Error::Io)? == 0 {
+                break;
+            }
+            skipped_lines += 1;
+        }
+
+        let mut csv_reader = dialect.reader_builder().from_reader(reader);
 
         // Read headers
-        let headers: Vec<String> = csv_reader
-            .headers()
-            .map_err(|e| Error::CsvParse(e.to_string()))?
-            .iter()
-            .map(|s| s.to_string())
-            .collect();
+        let headers: Vec<String> = if dialect.has_header {
+            csv_reader
+                .headers()
+                .map_err(|e| Error::CsvParse(e.to_string()))?
+                .iter()
+                .map(|s| s.to_string())
+                .collect()
+        } else {
+            Vec::new()
+        };
 
         Ok(Self {
             reader: csv_reader,
             headers,
-            line_number: 1, // Header is line 1
+            dialect: dialect.clone(),
+            skipped_lines,
+            // Header is the line after the skipped rows
+            line_number: skipped_lines + usize::from(dialect.has_header),
         })
     }
 
+    /// Create a reader for rows that continue an earlier read, such as rows
+    /// appended to a file.
+    ///
+    /// There is no preamble or header row; `headers` are the ones the earlier
+    /// read found. Line numbers count from the start of `reader`.
+    pub fn with_headers(reader: R, dialect: &CsvDialect, headers: Vec<String>) -> Self {
+        let mut builder = dialect.reader_builder();
+        let csv_reader = builder
+            .has_headers(false)
+            .from_reader(BufReader::new(reader));
+        Self {
+            reader: csv_reader,
+            headers,
+            dialect: dialect.clone(),
+            skipped_lines: 0,
+            line_number: 0,
+        }
+    }
+
     /// Get the headers.
+    ///
+    /// Without a header row these are the synthetic names of the columns seen so far.
     pub fn headers(&self) -> &[String] {
         &self.headers
     }
@@ -52,21 +158,44 @@ impl<R: Read> CsvReader<R> {
 
         match self.reader.read_record(&mut record) {
             Ok(true) => {
-                self.line_number += 1;
+                // Blank lines and quoted newlines shift rows, so ask the parser where the record started
+                self.line_number = match record.position() {
+                    Some(position) => self.skipped_lines + position.line() as usize,
+                    None => self.line_number + 1,
+                };
+
+                // Without a header, every column gets a synthetic name
+                if !self.dialect.has_header {
+                    for i in self.headers.len()..record.len() {
+                        self.headers.push(CsvDialect::synthetic_column_name(i));
+                    }
+                }
 
                 // Convert to JSON object
                 let mut obj = serde_json::Map::new();
                 for (i, field) in record.iter().enumerate() {
                     if i < self.headers.len() {
                         let key = self.headers[i].clone();
-                        obj.insert(key, serde_json::Value::String(field.to_string()));
+                        let value = if self.dialect.is_null(field) {
+                            serde_json::Value::Null
+                        } else {
+                            serde_json::Value::String(field.to_string())
+                        };
+                        obj.insert(key, value);
                     }
                 }
 
                 Ok(Some(serde_json::Value::Object(obj)))
             }
             Ok(false) => Ok(None),
-            Err(e) => Err(Error::CsvParse(e.to_string())),
+            Err(e) => {
+                // Point at the row that failed so callers can report it
+                self.line_number = match e.position() {
+                    Some(position) => self.skipped_lines + position.line() as usize,
+                    None => self.line_number + 1,
+                };
+                Err(Error::CsvParse(e.to_string()))
+            }
         }
     }
 }
@@ -83,6 +212,22 @@ impl<R: Read> CsvRecordIterator<R> {
         })
     }
 
+    /// Create an iterator that reads its input with the given dialect.
+    pub fn with_dialect(reader: R, dialect: &CsvDialect) -> Result<Self> {
+        Ok(Self {
+            reader: CsvReader::with_dialect(reader, dialect)?,
+        })
+    }
+
+    /// Create an iterator over rows that continue an earlier read.
+    ///
+    /// See [`CsvReader::with_headers`].
+    pub fn with_headers(reader: R, dialect: &CsvDialect, headers: Vec<String>) -> Self {
+        Self {
+            reader: CsvReader::with_headers(reader, dialect, headers),
+        }
+    }
+
     pub fn line_number(&self) -> usize {
         self.reader.line_number()
     }
@@ -241,6 +386,115 @@ mod tests {
         assert_eq!(records[2].as_ref().unwrap().0, 4);
     }
 
+    #[test]
+    fn test_csv_parse_error_line_number() {
+        let input = b"col\na\n\xff\nc\n".to_vec();
+        let mut iter = CsvRecordIterator::new(Cursor::new(input)).unwrap();
+
+        assert_eq!(iter.next().unwrap().unwrap().0, 2);
+        assert!(iter.next().unwrap().is_err());
+        assert_eq!(iter.line_number(), 3);
+        assert_eq!(iter.next().unwrap().unwrap().0, 4);
+    }
+
+    #[test]
+    fn test_csv_dialect() {
+        let input = "exported 2024-01-01\nrows: 3\nname;note;count\n# a comment\n'a;b';\\N;1\n'it\\'s';NULL;\n";
+        let dialect = CsvDialect {
+            delimiter: b';',
+            quote: b'\'',
+            escape: Some(b'\\'),
+            skip_leading_rows: 2,
+            comment: Some(b'#'),
+            null_markers: vec!["\\N".to_string(), "NULL".to_string()],
+            ..Default::default()
+        };
+        let iter = CsvRecordIterator::with_dialect(Cursor::new(input), &dialect).unwrap();
+        assert_eq!(iter.headers(), &["name", "note", "count"]);
+
+        let records: Vec<_> = iter.map(|r| r.unwrap()).collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].1["name"], "a;b");
+        assert!(records[0].1["note"].is_null());
+        assert_eq!(records[0].1["count"], "1");
+        assert_eq!(records[1].1["name"], "it's");
+        assert!(records[1].1["note"].is_null());
+        assert_eq!(records[1].1["count"], "");
+    }
+
+    #[test]
+    fn test_csv_empty_as_null() {
+        let input = "a,b\n,x\nNULL,\n";
+        let read = |dialect: &CsvDialect| -> Vec<serde_json::Value> {
+            CsvRecordIterator::with_dialect(Cursor::new(input), dialect)
+                .unwrap()
+                .map(|r| r.unwrap().1)
+                .collect()
+        };
+
+        let markers_only = CsvDialect {
+            null_markers: vec!["NULL".to_string()],
+            ..Default::default()
+        };
+        let records = read(&markers_only);
+        assert_eq!(records[0]["a"], "");
+        assert!(records[1]["a"].is_null());
+        assert_eq!(records[1]["b"], "");
+
+        let empty_as_null = CsvDialect {
+            empty_as_null: true,
+            ..markers_only
+        };
+        let records = read(&empty_as_null);
+        assert!(records[0]["a"].is_null());
+        assert!(records[1]["a"].is_null());
+        assert!(records[1]["b"].is_null());
+    }
+
+    #[test]
+    fn test_csv_without_header() {
+        let input = "preamble\n1\tx\n2\ty\tz\n";
+        let dialect = CsvDialect {
+            delimiter: b'\t',
+            skip_leading_rows: 1,
+            has_header: false,
+            ..Default::default()
+        };
+        let iter = CsvRecordIterator::with_dialect(Cursor::new(input), &dialect).unwrap();
+        let records: Vec<_> = iter.map(|r| r.unwrap()).collect();
+
+        assert_eq!(records[0].0, 2);
+        assert_eq!(records[1].0, 3);
+        assert_eq!(
+            records[0].1,
+            serde_json::json!({"column_1": "1", "column_2": "x"})
+        );
+        // Wider rows add columns
+        assert_eq!(records[1].1["column_3"], "z");
+    }
+
+    #[test]
+    fn test_csv_with_headers() {
+        let headers = vec!["id".to_string(), "name".to_string()];
+        let records: Vec<_> = CsvRecordIterator::with_headers(
+            Cursor::new("3,carol\n4,\\N\n"),
+            &CsvDialect {
+                null_markers: vec!["\\N".to_string()],
+                ..Default::default()
+            },
+            headers,
+        )
+        .map(|r| r.unwrap())
+        .collect();
+
+        // The first row is data, not a header
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].0, 1);
+        assert_eq!(records[0].1["id"], "3");
+        assert_eq!(records[0].1["name"], "carol");
+        assert!(records[1].1["name"].is_null());
+    }
+
     #[test]
     fn test_csv_newlines_in_quoted_fields() {
         let input = "name,bio\nJohn,\"Line 1\nLine 2\"";
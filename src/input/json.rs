@@ -1,68 +1,423 @@
 //! JSON input reader for newline-delimited JSON files.
 //!
-//! This module provides streaming JSON parsing, reading one record per line.
+//! This module provides streaming JSON parsing. Newline-delimited JSON is read
+//! one record per line. Whole-file JSON arrays and concatenated multi-line
+//! values are read one value at a time by a small scanner that only buffers
+//! the current record, so large arrays don't have to fit in memory.
 
 use std::io::BufRead;
 
 use crate::error::{Error, Result};
+use crate::input::raw::RawRecord;
 
 /// Result of reading a single JSON line.
-pub enum JsonLineResult {
+pub enum JsonLineResult<T = serde_json::Value> {
     /// Successfully parsed JSON object
-    Record(serde_json::Value),
+    Record(T),
     /// Parse error on this line (can be skipped with ignore_invalid_lines)
     ParseError { line: usize, error: String },
     /// End of input
     EndOfInput,
 }
 
-/// Streaming JSON reader for newline-delimited JSON.
+/// How records are laid out in JSON input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JsonLayout {
+    /// Detect from the start of the input: streamed if it opens with `[` or
+    /// the first value spans several lines, newline-delimited otherwise
+    #[default]
+    Auto,
+    /// One value per line (NDJSON)
+    Lines,
+    /// Top-level arrays and concatenated values, possibly pretty-printed;
+    /// the elements of a top-level array are read as separate records
+    Stream,
+}
+
+/// Streaming JSON reader for newline-delimited JSON, JSON arrays and
+/// concatenated JSON values.
 pub struct JsonReader<R: BufRead> {
     reader: R,
     line_number: usize,
     buffer: String,
+    layout: JsonLayout,
+    /// Bytes read while detecting the layout, replayed before `reader`
+    pending: Vec<u8>,
+    pending_pos: usize,
+    /// Line of the next unread byte (stream layout)
+    scan_line: usize,
+    /// Inside a top-level array (stream layout)
+    in_array: bool,
+    /// An array element was read and a `,` or `]` is expected (stream layout)
+    after_element: bool,
+    /// The input is exhausted (stream layout)
+    finished: bool,
 }
 
 impl<R: BufRead> JsonReader<R> {
-    /// Create a new JSON reader.
+    /// Create a new JSON reader that detects the layout of its input.
     pub fn new(reader: R) -> Self {
+        Self::with_layout(reader, JsonLayout::Auto)
+    }
+
+    /// Create a new JSON reader for the given layout.
+    pub fn with_layout(reader: R, layout: JsonLayout) -> Self {
         Self {
             reader,
             line_number: 0,
             buffer: String::new(),
+            layout,
+            pending: Vec::new(),
+            pending_pos: 0,
+            scan_line: 1,
+            in_array: false,
+            after_element: false,
+            finished: false,
         }
     }
 
+    /// Number lines from `line` instead of 1, for input that starts partway
+    /// through a file.
+    pub fn starting_at_line(mut self, line: usize) -> Self {
+        self.line_number = line.saturating_sub(1);
+        self.scan_line = line;
+        self
+    }
+
     /// Get the current line number.
+    ///
+    /// In the stream layout this is the line on which the last record started.
     pub fn line_number(&self) -> usize {
         self.line_number
     }
 
+    /// Get the layout being read.
+    ///
+    /// With [`JsonLayout::Auto`] this is the detected layout once the first
+    /// record has been read.
+    pub fn layout(&self) -> JsonLayout {
+        self.layout
+    }
+
     /// Read the next JSON record.
     pub fn read_record(&mut self) -> Result<JsonLineResult> {
-        self.buffer.clear();
+        if self.layout == JsonLayout::Auto {
+            self.layout = self.detect_layout()?;
+        }
+        match self.layout {
+            JsonLayout::Stream => self.read_stream_record(),
+            _ => self.read_line_record(),
+        }
+    }
+
+    /// Read the next record without parsing the values of its fields, and
+    /// pass it to `f` with its line number.
+    ///
+    /// The record borrows the reader's buffer, so it is only lent to `f`. See
+    /// [`RawRecord`].
+    pub fn read_raw_record<T>(
+        &mut self,
+        f: impl FnOnce(usize, RawRecord<'_>) -> T,
+    ) -> Result<JsonLineResult<T>> {
+        if self.layout == JsonLayout::Auto {
+            self.layout = self.detect_layout()?;
+        }
+        if self.layout == JsonLayout::Stream {
+            return Ok(match self.read_stream_value()? {
+                JsonLineResult::Record(value) => {
+                    let parsed = match std::str::from_utf8(&value) {
+                        Ok(json) => RawRecord::parse(json),
+                        Err(_) => serde_json::from_slice(&value).map(RawRecord::Value),
+                    };
+                    match parsed {
+                        Ok(record) => JsonLineResult::Record(f(self.line_number, record)),
+                        Err(e) => JsonLineResult::ParseError {
+                            line: self.line_number,
+                            error: e.to_string(),
+                        },
+                    }
+                }
+                JsonLineResult::ParseError { line, error } => {
+                    JsonLineResult::ParseError { line, error }
+                }
+                JsonLineResult::EndOfInput => JsonLineResult::EndOfInput,
+            });
+        }
+
+        if !self.read_nonempty_line()? {
+            return Ok(JsonLineResult::EndOfInput);
+        }
+        Ok(match RawRecord::parse(self.buffer.trim()) {
+            Ok(record) => JsonLineResult::Record(f(self.line_number, record)),
+            Err(e) => JsonLineResult::ParseError {
+                line: self.line_number,
+                error: e.to_string(),
+            },
+        })
+    }
+
+    /// Read the next record of newline-delimited JSON.
+    fn read_line_record(&mut self) -> Result<JsonLineResult> {
+        if !self.read_nonempty_line()? {
+            return Ok(JsonLineResult::EndOfInput);
+        }
+        Ok(match serde_json::from_str(self.buffer.trim()) {
+            Ok(value) => JsonLineResult::Record(value),
+            Err(e) => JsonLineResult::ParseError {
+                line: self.line_number,
+                error: e.to_string(),
+            },
+        })
+    }
 
-        match self.reader.read_line(&mut self.buffer) {
-            Ok(0) => Ok(JsonLineResult::EndOfInput),
-            Ok(_) => {
-                self.line_number += 1;
-                let line = self.buffer.trim();
+    /// Read lines into the buffer until one isn't blank; false at the end of input.
+    fn read_nonempty_line(&mut self) -> Result<bool> {
+        loop {
+            self.buffer.clear();
+
+            let read = if self.pending_pos < self.pending.len() {
+                let rest = &self.pending[self.pending_pos..];
+                let len = rest
+                    .iter()
+                    .position(|&b| b == b'\n')
+                    .map_or(rest.len(), |i| i + 1);
+                let line = std::str::from_utf8(&rest[..len]).map_err(|e| {
+                    Error::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+                })?;
+                self.buffer.push_str(line);
+                self.pending_pos += len;
+                len
+            } else {
+                self.reader.read_line(&mut self.buffer).map_err(Error::Io)?
+            };
+            if read == 0 {
+                return Ok(false);
+            }
+
+            self.line_number += 1;
+            // Skip empty lines, try next
+            if !self.buffer.trim().is_empty() {
+                return Ok(true);
+            }
+        }
+    }
 
-                if line.is_empty() {
-                    // Skip empty lines, try next
-                    return self.read_record();
+    /// Pick the layout from the start of the input.
+    ///
+    /// Leading whitespace is consumed. The input is streamed if it starts with
+    /// `[`, or if its first line ends in the middle of a value that the
+    /// following lines complete. A first line that is cut short and followed
+    /// by other records is read as an invalid line of newline-delimited JSON.
+    fn detect_layout(&mut self) -> Result<JsonLayout> {
+        loop {
+            match self.peek_byte()? {
+                Some(b'[') => return Ok(JsonLayout::Stream),
+                Some(b) if b.is_ascii_whitespace() => {
+                    self.next_byte()?;
                 }
+                Some(_) => break,
+                None => return Ok(JsonLayout::Lines),
+            }
+        }
+
+        // The blank lines skipped above still count towards line numbers
+        self.line_number = self.scan_line - 1;
+
+        let mut lines = Vec::new();
+        let mut line_count = 0usize;
+        let layout = loop {
+            let read = self
+                .reader
+                .read_until(b'\n', &mut lines)
+                .map_err(Error::Io)?;
+            line_count += 1;
+            // Checking after 1, 2, 4, 8... lines keeps the total work linear
+            if read > 0 && !line_count.is_power_of_two() {
+                continue;
+            }
+            let first = serde_json::Deserializer::from_slice(&lines)
+                .into_iter::<serde::de::IgnoredAny>()
+                .next();
+            match first {
+                // Only a value spanning several lines needs the stream layout
+                Some(Ok(_)) if line_count > 1 => break JsonLayout::Stream,
+                Some(Err(e)) if e.is_eof() && read > 0 => {}
+                _ => break JsonLayout::Lines,
+            }
+        };
+        self.pending = lines;
+        self.pending_pos = 0;
+        Ok(layout)
+    }
 
-                match serde_json::from_str(line) {
-                    Ok(value) => Ok(JsonLineResult::Record(value)),
-                    Err(e) => Ok(JsonLineResult::ParseError {
-                        line: self.line_number,
-                        error: e.to_string(),
-                    }),
+    /// Read the next value of a JSON array or of concatenated JSON values.
+    fn read_stream_record(&mut self) -> Result<JsonLineResult> {
+        Ok(match self.read_stream_value()? {
+            JsonLineResult::Record(value) => match serde_json::from_slice(&value) {
+                Ok(value) => JsonLineResult::Record(value),
+                Err(e) => JsonLineResult::ParseError {
+                    line: self.line_number,
+                    error: e.to_string(),
+                },
+            },
+            JsonLineResult::ParseError { line, error } => {
+                JsonLineResult::ParseError { line, error }
+            }
+            JsonLineResult::EndOfInput => JsonLineResult::EndOfInput,
+        })
+    }
+
+    /// Collect the bytes of the next value of a JSON array or of concatenated
+    /// JSON values, and set the line number to the line it starts on.
+    fn read_stream_value(&mut self) -> Result<JsonLineResult<Vec<u8>>> {
+        loop {
+            if self.finished {
+                return Ok(JsonLineResult::EndOfInput);
+            }
+
+            while self.peek_byte()?.is_some_and(|b| b.is_ascii_whitespace()) {
+                self.next_byte()?;
+            }
+
+            let Some(byte) = self.peek_byte()? else {
+                self.finished = true;
+                if self.in_array {
+                    return Ok(JsonLineResult::ParseError {
+                        line: self.scan_line,
+                        error: "EOF while parsing a list".to_string(),
+                    });
+                }
+                return Ok(JsonLineResult::EndOfInput);
+            };
+
+            if self.in_array {
+                match byte {
+                    b']' => {
+                        self.next_byte()?;
+                        self.in_array = false;
+                        continue;
+                    }
+                    b',' if self.after_element => {
+                        self.next_byte()?;
+                        self.after_element = false;
+                        continue;
+                    }
+                    _ if self.after_element => {
+                        // Report the missing separator, then read on from here
+                        self.after_element = false;
+                        return Ok(JsonLineResult::ParseError {
+                            line: self.scan_line,
+                            error: "expected `,` or `]` between array elements".to_string(),
+                        });
+                    }
+                    _ => {}
                 }
+            } else if byte == b'[' {
+                self.next_byte()?;
+                self.in_array = true;
+                self.after_element = false;
+                continue;
+            }
+
+            let line = self.scan_line;
+            let value = self.scan_value()?;
+            self.after_element = self.in_array;
+            self.line_number = line;
+            return Ok(JsonLineResult::Record(value));
+        }
+    }
+
+    /// Collect the bytes of the next value, without parsing it.
+    ///
+    /// Objects and arrays are read up to their matching close bracket, strings
+    /// up to their closing quote and anything else up to the next delimiter.
+    /// A value cut short by the end of input is returned as is.
+    fn scan_value(&mut self) -> Result<Vec<u8>> {
+        let mut value = Vec::new();
+        let Some(first) = self.next_byte()? else {
+            return Ok(value);
+        };
+        value.push(first);
+
+        match first {
+            b'{' | b'[' => {
+                let mut depth = 1usize;
+                let mut in_string = false;
+                let mut escaped = false;
+                while depth > 0 {
+                    let Some(b) = self.next_byte()? else {
+                        break;
+                    };
+                    value.push(b);
+                    if in_string {
+                        match b {
+                            _ if escaped => escaped = false,
+                            b'\\' => escaped = true,
+                            b'"' => in_string = false,
+                            _ => {}
+                        }
+                    } else {
+                        match b {
+                            b'"' => in_string = true,
+                            b'{' | b'[' => depth += 1,
+                            b'}' | b']' => depth -= 1,
+                            _ => {}
+                        }
+                    }
+                }
+            }
+            b'"' => {
+                let mut escaped = false;
+                while let Some(b) = self.next_byte()? {
+                    value.push(b);
+                    match b {
+                        _ if escaped => escaped = false,
+                        b'\\' => escaped = true,
+                        b'"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            // A stray delimiter is a value of its own, so it gets reported
+            b',' | b']' | b'}' => {}
+            _ => {
+                while let Some(b) = self.peek_byte()? {
+                    if b.is_ascii_whitespace() || b",[]{}\"".contains(&b) {
+                        break;
+                    }
+                    value.push(b);
+                    self.next_byte()?;
+                }
+            }
+        }
+        Ok(value)
+    }
+
+    /// Look at the next byte without consuming it.
+    fn peek_byte(&mut self) -> Result<Option<u8>> {
+        if let Some(&b) = self.pending.get(self.pending_pos) {
+            return Ok(Some(b));
+        }
+        let buf = self.reader.fill_buf().map_err(Error::Io)?;
+        Ok(buf.first().copied())
+    }
+
+    /// Consume the next byte, counting lines.
+    fn next_byte(&mut self) -> Result<Option<u8>> {
+        let byte = if let Some(&b) = self.pending.get(self.pending_pos) {
+            self.pending_pos += 1;
+            Some(b)
+        } else {
+            let b = self.reader.fill_buf().map_err(Error::Io)?.first().copied();
+            if b.is_some() {
+                self.reader.consume(1);
             }
-            Err(e) => Err(Error::Io(e)),
+            b
+        };
+        if byte == Some(b'\n') {
+            self.scan_line += 1;
         }
+        Ok(byte)
     }
 }
 
@@ -74,15 +429,68 @@ pub struct JsonRecordIterator<R: BufRead> {
 
 impl<R: BufRead> JsonRecordIterator<R> {
     pub fn new(reader: R, ignore_invalid_lines: bool) -> Self {
+        Self::with_layout(reader, ignore_invalid_lines, JsonLayout::Auto)
+    }
+
+    /// Create an iterator that reads its input with the given layout.
+    pub fn with_layout(reader: R, ignore_invalid_lines: bool, layout: JsonLayout) -> Self {
         Self {
-            reader: JsonReader::new(reader),
+            reader: JsonReader::with_layout(reader, layout),
             ignore_invalid_lines,
         }
     }
 
+    /// Number lines from `line` instead of 1. See [`JsonReader::starting_at_line`].
+    pub fn starting_at_line(mut self, line: usize) -> Self {
+        self.reader = self.reader.starting_at_line(line);
+        self
+    }
+
     pub fn line_number(&self) -> usize {
         self.reader.line_number()
     }
+
+    /// Get the layout being read. See [`JsonReader::layout`].
+    pub fn layout(&self) -> JsonLayout {
+        self.reader.layout()
+    }
+
+    /// Like `next`, but without parsing the values of the record's fields;
+    /// the record and its line number are passed to `f`.
+    ///
+    /// Invalid lines are skipped or returned as errors as by `next`. See
+    /// [`JsonReader::read_raw_record`].
+    pub fn next_raw<T>(
+        &mut self,
+        mut f: impl FnMut(usize, RawRecord<'_>) -> T,
+    ) -> Option<Result<T>> {
+        loop {
+            match self.reader.read_raw_record(&mut f) {
+                Ok(JsonLineResult::Record(result)) => return Some(Ok(result)),
+                Ok(JsonLineResult::ParseError { line, error }) => {
+                    if let Some(err) = self.parse_error(line, error) {
+                        return Some(Err(err));
+                    }
+                }
+                Ok(JsonLineResult::EndOfInput) => return None,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+
+    /// Warn about a skipped invalid line, or return it as an error.
+    fn parse_error(&self, line: usize, error: String) -> Option<Error> {
+        if self.ignore_invalid_lines {
+            // Log and continue
+            eprintln!("Warning: Skipping invalid JSON on line {}: {}", line, error);
+            None
+        } else {
+            Some(Error::JsonParse {
+                line,
+                message: error,
+            })
+        }
+    }
 }
 
 impl<R: BufRead> Iterator for JsonRecordIterator<R> {
@@ -95,15 +503,8 @@ impl<R: BufRead> Iterator for JsonRecordIterator<R> {
                     return Some(Ok((self.reader.line_number(), value)));
                 }
                 Ok(JsonLineResult::ParseError { line, error }) => {
-                    if self.ignore_invalid_lines {
-                        // Log and continue
-                        eprintln!("Warning: Skipping invalid JSON on line {}: {}", line, error);
-                        continue;
-                    } else {
-                        return Some(Err(Error::JsonParse {
-                            line,
-                            message: error,
-                        }));
+                    if let Some(err) = self.parse_error(line, error) {
+                        return Some(Err(err));
                     }
                 }
                 Ok(JsonLineResult::EndOfInput) => return None,
@@ -310,6 +711,20 @@ not valid json
         assert_eq!(records[2].as_ref().unwrap().0, 5); // Line 4 was empty
     }
 
+    #[test]
+    fn test_line_number_starting_at_line() {
+        let input = "{\"a\": 1}\n\nnot json\n";
+        let iter = JsonRecordIterator::with_layout(Cursor::new(input), false, JsonLayout::Lines)
+            .starting_at_line(101);
+        let records: Vec<_> = iter.collect();
+
+        assert_eq!(records[0].as_ref().unwrap().0, 101);
+        match &records[1] {
+            Err(Error::JsonParse { line, .. }) => assert_eq!(*line, 103),
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_malformed_json_error_message() {
         let input = "{missing: quotes}";
@@ -335,13 +750,157 @@ not valid json
 
     #[test]
     fn test_json_array_at_root() {
-        // Arrays at root should be parsed (though may not be typical input)
+        // The elements of a top-level array are separate records
         let input = r#"[1, 2, 3]"#;
+        let cursor = Cursor::new(input);
+        let records: Vec<_> = JsonRecordIterator::new(cursor, false)
+            .map(|r| r.unwrap().1)
+            .collect();
+
+        assert_eq!(records, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_pretty_printed_array() {
+        let input = r#"
+[
+  {
+    "name": "a, [b]",
+    "tags": ["x", "y"]
+  },
+  {
+    "name": "quote \" and } brace",
+    "nested": {"deep": [1, {"k": null}]}
+  }
+]
+"#;
+        let cursor = Cursor::new(input);
+        let records: Vec<_> = JsonRecordIterator::new(cursor, false)
+            .map(|r| r.unwrap())
+            .collect();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].0, 3);
+        assert_eq!(records[0].1["name"], "a, [b]");
+        assert_eq!(records[1].0, 7);
+        assert_eq!(records[1].1["name"], "quote \" and } brace");
+        assert!(records[1].1["nested"]["deep"][1]["k"].is_null());
+    }
+
+    #[test]
+    fn test_concatenated_multiline_values() {
+        let input = "{\n  \"a\": 1\n}\n{\n  \"b\": 2\n}{\"c\": 3}\n";
+        let cursor = Cursor::new(input);
+        let records: Vec<_> = JsonRecordIterator::new(cursor, false)
+            .map(|r| r.unwrap())
+            .collect();
+
+        let lines: Vec<_> = records.iter().map(|(line, _)| *line).collect();
+        assert_eq!(lines, vec![1, 4, 6]);
+        assert_eq!(records[2].1["c"], 3);
+    }
+
+    #[test]
+    fn test_ndjson_detected_after_blank_lines() {
+        let input = "\n\n{\"a\": 1}\n{\"b\": 2}\n";
+        let cursor = Cursor::new(input);
+        let records: Vec<_> = JsonRecordIterator::new(cursor, false)
+            .map(|r| r.unwrap().0)
+            .collect();
+
+        assert_eq!(records, vec![3, 4]);
+    }
+
+    #[test]
+    fn test_truncated_first_line_is_ndjson() {
+        // The next lines don't complete the first one, so they are records of their own
+        let input = "{\"a\": 1, \"b\": \n{\"c\": 2}\n{\"d\": \"x\"}\n";
+        let mut reader = JsonReader::new(Cursor::new(input));
+        assert!(matches!(
+            reader.read_record().unwrap(),
+            JsonLineResult::ParseError { line: 1, .. }
+        ));
+        assert_eq!(reader.layout(), JsonLayout::Lines);
+        let mut records = Vec::new();
+        while let JsonLineResult::Record(value) = reader.read_record().unwrap() {
+            records.push((reader.line_number(), value));
+        }
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].0, 2);
+        assert_eq!(records[1].1["d"], "x");
+
+        // A value spanning many lines is still streamed
+        let pretty = format!("{{\n{}\"z\": 0\n}}\n", "\"k\": 1,\n".repeat(40));
+        let mut reader = JsonReader::new(Cursor::new(pretty.clone() + &pretty));
+        assert!(matches!(
+            reader.read_record().unwrap(),
+            JsonLineResult::Record(_)
+        ));
+        assert_eq!(reader.layout(), JsonLayout::Stream);
+        assert_eq!(reader.line_number(), 1);
+        assert!(matches!(
+            reader.read_record().unwrap(),
+            JsonLineResult::Record(_)
+        ));
+        assert_eq!(reader.line_number(), 44);
+    }
+
+    #[test]
+    fn test_forced_stream_layout() {
+        // A single-line first value would be detected as NDJSON
+        let input = "{\"a\": 1}\n{\n\"b\": 2\n}\n";
+        let cursor = Cursor::new(input);
+        let records: Vec<_> = JsonRecordIterator::with_layout(cursor, false, JsonLayout::Stream)
+            .map(|r| r.unwrap().0)
+            .collect();
+        assert_eq!(records, vec![1, 2]);
+
+        // Forced NDJSON keeps the whole array as one record
+        let cursor = Cursor::new("[1, 2]");
+        let mut iter = JsonRecordIterator::with_layout(cursor, false, JsonLayout::Lines);
+        assert!(iter.next().unwrap().unwrap().1.is_array());
+    }
+
+    #[test]
+    fn test_stream_invalid_element() {
+        let input = "[\n  {\"a\": 1},\n  {bad},\n  {\"b\": 2}\n]";
+
         let cursor = Cursor::new(input);
         let mut iter = JsonRecordIterator::new(cursor, false);
+        assert!(iter.next().unwrap().is_ok());
+        match iter.next().unwrap() {
+            Err(Error::JsonParse { line, .. }) => assert_eq!(line, 3),
+            other => panic!("Expected JsonParse error, got {:?}", other),
+        }
 
-        let result = iter.next().unwrap().unwrap();
-        assert!(result.1.is_array());
+        let cursor = Cursor::new(input);
+        let records: Vec<_> = JsonRecordIterator::new(cursor, true)
+            .map(|r| r.unwrap().0)
+            .collect();
+        assert_eq!(records, vec![2, 4]);
+    }
+
+    #[test]
+    fn test_stream_truncated_array() {
+        let input = "[{\"a\": 1}, {\"b\": ";
+        let cursor = Cursor::new(input);
+        let results: Vec<_> = JsonRecordIterator::new(cursor, false).collect();
+
+        assert_eq!(results.len(), 3);
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+        assert!(results[2].is_err());
+    }
+
+    #[test]
+    fn test_stream_missing_separator() {
+        let input = "[{\"a\": 1} {\"b\": 2}]";
+        let cursor = Cursor::new(input);
+        let results: Vec<_> = JsonRecordIterator::new(cursor, true).collect();
+
+        // The missing comma is reported and skipped; both elements are kept
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|r| r.is_ok()));
     }
 
     #[test]
@@ -1,7 +1,11 @@
 //! Input readers for JSON and CSV formats.
 
+pub mod compression;
 pub mod csv;
 pub mod json;
+pub mod raw;
 
-pub use self::csv::{CsvReader, CsvRecordIterator};
-pub use self::json::{JsonReader, JsonRecordIterator};
+pub use self::compression::{decompress, open_input, strip_compression_extension, Compression};
+pub use self::csv::{CsvDialect, CsvReader, CsvRecordIterator};
+pub use self::json::{JsonLayout, JsonReader, JsonRecordIterator};
+pub use self::raw::RawRecord;
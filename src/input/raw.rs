@@ -0,0 +1,391 @@
+//! JSON records parsed only as far as their top-level fields.
+//!
+//! Parsing a record into a `serde_json::Value` allocates a map for every
+//! object and a `String` for every key and string value, which dominates
+//! schema generation on wide or nested records. A [`RawRecord`] keeps each
+//! field's value as a slice of the input instead, so the generator can read
+//! strings in place and only parse nested objects when it walks into them.
+
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::fmt;
+
+use serde::de::{self, Deserializer, MapAccess, Visitor};
+use serde::Deserialize;
+use serde_json::value::RawValue;
+
+/// Objects up to this size are checked for duplicate keys without hashing.
+const LINEAR_DEDUP_MAX_FIELDS: usize = 16;
+
+/// Nesting depth beyond which records are also checked as a `serde_json::Value`.
+///
+/// `serde_json` rejects values nested more than 128 deep, but skipping over a
+/// raw value doesn't check the depth, so such records are parsed fully as
+/// well to fail the same way.
+const MAX_RAW_DEPTH: usize = 100;
+
+/// Decimal exponent from which a number literal may be out of `f64` range.
+///
+/// `serde_json` rejects numbers beyond `f64::MAX`, but skipping over a raw
+/// value doesn't parse them, so records with a number this large are checked
+/// as a `serde_json::Value` as well.
+const MAX_RAW_EXPONENT: i64 = 300;
+
+/// Fields of a JSON object in input order, with their values left unparsed.
+pub type RawFields<'a> = Vec<(Cow<'a, str>, &'a RawValue)>;
+
+/// A JSON record parsed only as far as its top-level fields.
+///
+/// The whole record is validated when it is parsed, so reading the values
+/// later can't fail.
+#[derive(Debug)]
+pub enum RawRecord<'a> {
+    /// An object's fields
+    Object(RawFields<'a>),
+    /// Any other value, fully parsed
+    Value(serde_json::Value),
+}
+
+impl<'a> RawRecord<'a> {
+    /// Parse a JSON record, keeping an object's field values as slices of `json`.
+    ///
+    /// Errors are the ones `serde_json::from_str::<Value>` reports.
+    pub fn parse(json: &'a str) -> serde_json::Result<Self> {
+        if !json.trim_start().starts_with('{') {
+            return serde_json::from_str(json).map(RawRecord::Value);
+        }
+        let (fields, exceeds_limits) = parse_fields(json, true).map_err(|e| {
+            match serde_json::from_str::<serde_json::Value>(json) {
+                Err(value_error) => value_error,
+                Ok(_) => e,
+            }
+        })?;
+        if exceeds_limits {
+            serde_json::from_str::<serde_json::Value>(json)?;
+        }
+        Ok(RawRecord::Object(fields))
+    }
+
+    /// Parse the record fully.
+    pub fn to_value(&self) -> serde_json::Value {
+        match self {
+            RawRecord::Object(fields) => serde_json::Value::Object(
+                fields
+                    .iter()
+                    .map(|(key, value)| {
+                        let value = serde_json::from_str(value.get())
+                            .expect("raw values are validated when parsed");
+                        (key.to_string(), value)
+                    })
+                    .collect(),
+            ),
+            RawRecord::Value(value) => value.clone(),
+        }
+    }
+}
+
+/// Parse a JSON object into its fields, leaving the values unparsed.
+///
+/// A repeated key keeps its first position and takes its last value, like
+/// `serde_json::Map` does.
+pub fn parse_raw_object(json: &str) -> serde_json::Result<RawFields<'_>> {
+    parse_fields(json, false).map(|(fields, _)| fields)
+}
+
+/// Parse a JSON object into its fields, and with `check_limits`, whether any
+/// value exceeds the limits of [`exceeds_raw_limits`].
+fn parse_fields(json: &str, check_limits: bool) -> serde_json::Result<(RawFields<'_>, bool)> {
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    let (fields, exceeds_limits) =
+        deserializer.deserialize_map(RawFieldsVisitor { check_limits })?;
+    deserializer.end()?;
+    Ok((dedup_fields(fields), exceeds_limits))
+}
+
+/// Check if a field value of a record nests more than [`MAX_RAW_DEPTH`] deep
+/// or has a number literal that may reach `10^MAX_RAW_EXPONENT`.
+///
+/// A number is checked by its own literal; strings, booleans and null pass.
+/// The magnitude is estimated from the integer digits and the exponent, so
+/// some smaller numbers, like ones with many leading fractional zeros, count too.
+fn exceeds_raw_limits(value: &str) -> bool {
+    match value.as_bytes().first() {
+        Some(b'-' | b'0'..=b'9') => return number_magnitude(value) >= MAX_RAW_EXPONENT,
+        Some(b'{' | b'[') => {}
+        _ => return false,
+    }
+
+    let bytes = value.as_bytes();
+    // The record's own braces are the first level
+    let mut depth = 1usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        i += 1;
+        if in_string {
+            match b {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match b {
+            b'"' => in_string = true,
+            b'{' | b'[' => {
+                depth += 1;
+                if depth > MAX_RAW_DEPTH {
+                    return true;
+                }
+            }
+            b'}' | b']' => depth = depth.saturating_sub(1),
+            b'-' | b'0'..=b'9' => {
+                let start = i - 1;
+                while i < bytes.len()
+                    && matches!(bytes[i], b'0'..=b'9' | b'.' | b'e' | b'E' | b'+' | b'-')
+                {
+                    i += 1;
+                }
+                if number_magnitude(&value[start..i]) >= MAX_RAW_EXPONENT {
+                    return true;
+                }
+            }
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Estimate the decimal exponent of a number literal's most significant digit.
+///
+/// Zero and fractions count their integer digits as one.
+fn number_magnitude(literal: &str) -> i64 {
+    let unsigned = literal.trim_start_matches('-');
+    let (mantissa, exponent) = match unsigned.find(['e', 'E']) {
+        Some(idx) => {
+            let exponent = &unsigned[idx + 1..];
+            let digits = exponent.trim_start_matches(['+', '-']);
+            // Exponents too long for an i64 only need their sign
+            let value = digits.parse::<i64>().unwrap_or(i64::MAX);
+            let value = if exponent.starts_with('-') {
+                -value
+            } else {
+                value
+            };
+            (&unsigned[..idx], value)
+        }
+        None => (unsigned, 0),
+    };
+    let integer_digits = mantissa.split('.').next().unwrap_or("").len().max(1) as i64;
+    (integer_digits - 1).saturating_add(exponent)
+}
+
+/// Replace earlier values of repeated keys with the last one, in place.
+fn dedup_fields(fields: RawFields<'_>) -> RawFields<'_> {
+    let has_duplicates = if fields.len() <= LINEAR_DEDUP_MAX_FIELDS {
+        fields
+            .iter()
+            .enumerate()
+            .any(|(i, (key, _))| fields[..i].iter().any(|(seen, _)| seen == key))
+    } else {
+        let mut seen = HashSet::with_capacity(fields.len());
+        !fields.iter().all(|(key, _)| seen.insert(key.as_ref()))
+    };
+    if !has_duplicates {
+        return fields;
+    }
+
+    let mut deduped: indexmap::IndexMap<Cow<'_, str>, &RawValue> =
+        indexmap::IndexMap::with_capacity(fields.len());
+    for (key, value) in fields {
+        deduped.insert(key, value);
+    }
+    deduped.into_iter().collect()
+}
+
+/// Collects an object's fields without parsing their values.
+///
+/// With `check_limits`, also reports whether any value exceeds the limits
+/// of [`exceeds_raw_limits`], as the value is collected.
+struct RawFieldsVisitor {
+    check_limits: bool,
+}
+
+impl<'de> Visitor<'de> for RawFieldsVisitor {
+    type Value = (RawFields<'de>, bool);
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a JSON object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut fields = Vec::with_capacity(map.size_hint().unwrap_or(0));
+        let mut exceeds_limits = false;
+        while let Some(RawKey(key)) = map.next_key()? {
+            let value: &RawValue = map.next_value()?;
+            exceeds_limits =
+                exceeds_limits || (self.check_limits && exceeds_raw_limits(value.get()));
+            fields.push((key, value));
+        }
+        Ok((fields, exceeds_limits))
+    }
+}
+
+/// An object key, borrowed from the input unless it has escapes.
+struct RawKey<'de>(Cow<'de, str>);
+
+impl<'de> Deserialize<'de> for RawKey<'de> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct KeyVisitor;
+
+        impl<'de> Visitor<'de> for KeyVisitor {
+            type Value = RawKey<'de>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a string key")
+            }
+
+            fn visit_borrowed_str<E: de::Error>(self, s: &'de str) -> Result<Self::Value, E> {
+                Ok(RawKey(Cow::Borrowed(s)))
+            }
+
+            fn visit_str<E: de::Error>(self, s: &str) -> Result<Self::Value, E> {
+                Ok(RawKey(Cow::Owned(s.to_string())))
+            }
+
+            fn visit_string<E: de::Error>(self, s: String) -> Result<Self::Value, E> {
+                Ok(RawKey(Cow::Owned(s)))
+            }
+        }
+
+        deserializer.deserialize_str(KeyVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn keys<'a>(fields: &'a RawFields<'_>) -> Vec<(&'a str, &'a str)> {
+        fields
+            .iter()
+            .map(|(key, value)| (key.as_ref(), value.get()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_object_fields() {
+        let json = r#"{"a": 1, "b\u0021": "x", "c": {"d": [1, 2]}}"#;
+        let RawRecord::Object(fields) = RawRecord::parse(json).unwrap() else {
+            panic!("expected an object");
+        };
+        assert_eq!(
+            keys(&fields),
+            vec![("a", "1"), ("b!", "\"x\""), ("c", "{\"d\": [1, 2]}")]
+        );
+        assert!(matches!(fields[0].0, Cow::Borrowed(_)));
+        assert!(matches!(fields[1].0, Cow::Owned(_)));
+        assert_eq!(
+            RawRecord::parse(json).unwrap().to_value(),
+            serde_json::from_str::<serde_json::Value>(json).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_non_object() {
+        assert!(matches!(
+            RawRecord::parse("[1, 2]").unwrap(),
+            RawRecord::Value(value) if value == json!([1, 2])
+        ));
+    }
+
+    #[test]
+    fn test_duplicate_keys_match_serde_json() {
+        let small = r#"{"a": 1, "b": 2, "a": "x"}"#;
+        let wide = format!(
+            "{{{}, \"k3\": true}}",
+            (0..20)
+                .map(|i| format!("\"k{}\": {}", i, i))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        for json in [small, wide.as_str()] {
+            let record = RawRecord::parse(json).unwrap();
+            let expected: serde_json::Value = serde_json::from_str(json).unwrap();
+            assert_eq!(record.to_value(), expected);
+            let value_keys: Vec<_> = expected.as_object().unwrap().keys().cloned().collect();
+            let RawRecord::Object(fields) = record else {
+                panic!("expected an object");
+            };
+            let raw_keys: Vec<_> = fields.iter().map(|(key, _)| key.to_string()).collect();
+            assert_eq!(raw_keys, value_keys);
+        }
+    }
+
+    #[test]
+    fn test_errors_match_serde_json() {
+        let deep = format!("{}1{}", "{\"a\": ".repeat(200), "}".repeat(200));
+        for json in [
+            deep.as_str(),
+            r#"{"a": 1"#,
+            r#"{"a": 1,}"#,
+            r#"{"a": [1, }"#,
+            r#"{"a": 1} x"#,
+            r#"{"a": tru}"#,
+            "[1,",
+            r#"{"a": 1e400}"#,
+            r#"{"a": {"b": [-1.8e308]}}"#,
+            r#"{"a": 1, "b": 1e99999999999999999999}"#,
+            &format!(r#"{{"a": {}}}"#, "9".repeat(400)),
+        ] {
+            let expected = serde_json::from_str::<serde_json::Value>(json)
+                .unwrap_err()
+                .to_string();
+            assert_eq!(
+                RawRecord::parse(json).unwrap_err().to_string(),
+                expected,
+                "{}",
+                json
+            );
+        }
+    }
+
+    #[test]
+    fn test_large_numbers_in_range() {
+        for json in [
+            r#"{"a": 1.7976931348623157e308, "b": "1e400"}"#,
+            r#"{"a": 1e-400, "b": 0e400, "c": 0.001e305}"#,
+            r#"{"a": 123456789012345678901234567890.5}"#,
+        ] {
+            let expected: serde_json::Value = serde_json::from_str(json).unwrap();
+            assert_eq!(
+                RawRecord::parse(json).unwrap().to_value(),
+                expected,
+                "{}",
+                json
+            );
+        }
+    }
+
+    #[test]
+    fn test_number_magnitude() {
+        assert!(!exceeds_raw_limits("12345"));
+        assert!(!exceeds_raw_limits(r#""1e999""#));
+        assert!(!exceeds_raw_limits(r#"{"b": "1e999", "c": [true, null]}"#));
+        assert!(exceeds_raw_limits("2e300"));
+        assert!(exceeds_raw_limits(r#"[1, {"c": 2e300}]"#));
+        let deep = |levels: usize| format!("{}1{}", "[".repeat(levels), "]".repeat(levels));
+        assert!(!exceeds_raw_limits(&deep(MAX_RAW_DEPTH - 1)));
+        assert!(exceeds_raw_limits(&deep(MAX_RAW_DEPTH)));
+        assert_eq!(number_magnitude("0"), 0);
+        assert_eq!(number_magnitude("-12.5"), 1);
+        assert_eq!(number_magnitude("0.001e5"), 5);
+        assert_eq!(number_magnitude("12E+3"), 4);
+        assert_eq!(number_magnitude("1e-400"), -400);
+        assert_eq!(number_magnitude("1e99999999999999999999"), i64::MAX);
+    }
+}
@@ -23,26 +23,35 @@
 //! let schema = generator.flatten_schema(&schema_map);
 //! ```
 
+pub mod cache;
 pub mod diff;
 pub mod error;
 pub mod inference;
 pub mod input;
 pub mod output;
 pub mod schema;
+pub mod stats;
 pub mod validate;
 pub mod watch;
 
 // Re-export commonly used types
+pub use cache::{CacheLookup, FileSchema, FileStamp, SchemaCache};
 pub use error::{Error, ErrorLog, Result};
-pub use input::{CsvRecordIterator, JsonRecordIterator};
+pub use inference::{DateTimeFormat, DateTimeFormats, EpochDetection, EpochUnit};
+pub use input::{CsvDialect, CsvRecordIterator, JsonLayout, JsonRecordIterator, RawRecord};
 pub use output::{
-    schema_to_json_string, write_schema_ddl, write_schema_debug_map, write_schema_json,
-    write_schema_json_schema, OutputFormat,
+    per_file_schema_path, schema_to_json_string, write_schema_ddl, write_schema_debug_map,
+    write_schema_debug_map_with_provenance, write_schema_json, write_schema_json_schema,
+    OutputFormat,
 };
 pub use schema::{
-    bq_schema_to_map, read_existing_schema_from_file, BqMode, BqSchemaField, BqType, EntryStatus,
-    GeneratorConfig, InputFormat, SchemaEntry, SchemaGenerator, SchemaMap,
+    bq_schema_to_map, read_existing_schema_from_file, read_schema_state,
+    read_schema_state_from_file, write_schema_state, BqMode, BqSchemaField, BqType, ConflictPolicy,
+    ConflictResolution, EntryStatus, FieldConflict, FieldProvenance, GeneratorConfig, InputFormat,
+    Provenance, SchemaChange, SchemaEntry, SchemaGenerator, SchemaMap, SourceLocation, TypeHint,
+    TypeHints, SCHEMA_STATE_VERSION,
 };
+pub use stats::{write_stats_json, write_stats_markdown, DataProfile, FieldStats, StatsFormat};
 pub use validate::{
     validate_json_data, SchemaValidator, ValidationError, ValidationErrorType, ValidationOptions,
     ValidationResult,
@@ -65,18 +74,18 @@ pub fn generate_schema_from_json<R: BufRead, W: Write>(
     let mut generator = SchemaGenerator::new(config);
     let mut schema_map = existing_schema.unwrap_or_default();
 
-    let iter = JsonRecordIterator::new(input, ignore_invalid_lines);
-
-    for result in iter {
-        let (line_num, record) = result?;
+    let mut iter = JsonRecordIterator::new(input, ignore_invalid_lines);
 
+    while let Some(result) = iter.next_raw(|line_num, record| {
         if let Some(interval) = debugging_interval {
             if line_num % interval == 0 {
                 eprintln!("Processing line {}", line_num);
             }
         }
 
-        if let Err(e) = generator.process_record(&record, &mut schema_map) {
+        generator.process_raw_record(&record, &mut schema_map)
+    }) {
+        if let Err(e) = result? {
             if !ignore_invalid_lines {
                 return Err(e);
             }
@@ -101,11 +110,10 @@ pub fn generate_schema_from_csv<R: Read, W: Write>(
     debugging_interval: Option<usize>,
     existing_schema: Option<SchemaMap>,
 ) -> Result<Vec<ErrorLog>> {
+    let iter = CsvRecordIterator::with_dialect(input, &config.csv_dialect)?;
     let mut generator = SchemaGenerator::new(config);
     let mut schema_map = existing_schema.unwrap_or_default();
 
-    let iter = CsvRecordIterator::new(input)?;
-
     for result in iter {
         let (line_num, record) = result?;
 
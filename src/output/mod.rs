@@ -5,14 +5,17 @@
 //! - DDL: BigQuery CREATE TABLE statement
 //! - Debug Map: Internal schema representation for debugging
 //! - JSON Schema: JSON Schema draft-07 format
+//! - State: Versioned inference state, readable with `read_schema_state`
 
 use std::io::Write;
+use std::path::{Path, PathBuf};
 
 use serde::Serialize;
 
 use crate::error::Result;
+use crate::input::strip_compression_extension;
 use crate::schema::types::{BqType, EntryStatus, SchemaEntry, SchemaMap};
-use crate::schema::BqSchemaField;
+use crate::schema::{BqSchemaField, FieldProvenance, Provenance};
 
 /// Output format for the generated schema.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -26,6 +29,8 @@ pub enum OutputFormat {
     DebugMap,
     /// JSON Schema draft-07 format
     JsonSchema,
+    /// Versioned internal schema state, for merging or resuming later
+    State,
 }
 
 impl std::str::FromStr for OutputFormat {
@@ -37,6 +42,7 @@ impl std::str::FromStr for OutputFormat {
             "ddl" => Ok(OutputFormat::Ddl),
             "debug-map" | "debug_map" | "debugmap" => Ok(OutputFormat::DebugMap),
             "json-schema" | "json_schema" | "jsonschema" => Ok(OutputFormat::JsonSchema),
+            "state" => Ok(OutputFormat::State),
             _ => Err(format!("Unknown output format: {}", s)),
         }
     }
@@ -59,6 +65,23 @@ pub fn schema_to_json_string(schema: &[BqSchemaField]) -> Result<String> {
     serde_json::to_string_pretty(schema).map_err(|e| crate::error::Error::SchemaFile(e.to_string()))
 }
 
+/// Path of the schema written for one input file in per-file mode.
+///
+/// `data.json` (or `data.json.gz`) gets `data.schema.json`, next to the input
+/// or in `output_dir`.
+pub fn per_file_schema_path(input_path: &Path, output_dir: Option<&Path>) -> PathBuf {
+    let file_stem = strip_compression_extension(input_path)
+        .file_stem()
+        .unwrap_or_default()
+        .to_owned();
+    let schema_filename = format!("{}.schema.json", file_stem.to_string_lossy());
+
+    match output_dir {
+        Some(dir) => dir.join(schema_filename),
+        None => input_path.with_file_name(schema_filename),
+    }
+}
+
 // =============================================================================
 // DDL Output
 // =============================================================================
@@ -97,7 +120,7 @@ pub fn write_schema_ddl<W: Write>(
 
 /// Convert a single field to DDL format.
 fn field_to_ddl(field: &BqSchemaField) -> String {
-    let type_str = bq_type_to_standard_sql(&field.field_type);
+    let type_str = field_to_standard_sql(field);
     let mode = field.mode.as_str();
 
     match mode {
@@ -146,7 +169,7 @@ fn fields_to_struct(fields: &[BqSchemaField]) -> String {
     fields
         .iter()
         .map(|f| {
-            let type_str = bq_type_to_standard_sql(&f.field_type);
+            let type_str = field_to_standard_sql(f);
             if f.field_type == "RECORD" {
                 let nested = f
                     .fields
@@ -168,6 +191,17 @@ fn fields_to_struct(fields: &[BqSchemaField]) -> String {
         .join(", ")
 }
 
+/// Get the Standard SQL type of a non-RECORD field, with its declared
+/// decimal parameters, like `NUMERIC(10, 2)`.
+fn field_to_standard_sql(field: &BqSchemaField) -> String {
+    let type_str = bq_type_to_standard_sql(&field.field_type);
+    match (&field.precision, &field.scale) {
+        (Some(precision), Some(scale)) => format!("{}({}, {})", type_str, precision, scale),
+        (Some(precision), None) => format!("{}({})", type_str, precision),
+        _ => type_str.to_string(),
+    }
+}
+
 /// Convert legacy BigQuery type names to Standard SQL types.
 fn bq_type_to_standard_sql(legacy_type: &str) -> &'static str {
     match legacy_type {
@@ -180,6 +214,10 @@ fn bq_type_to_standard_sql(legacy_type: &str) -> &'static str {
         "DATE" => "DATE",
         "TIME" => "TIME",
         "DATETIME" => "DATETIME",
+        "NUMERIC" => "NUMERIC",
+        "BIGNUMERIC" => "BIGNUMERIC",
+        "GEOGRAPHY" => "GEOGRAPHY",
+        "JSON" => "JSON",
         "RECORD" => "STRUCT",
         _ => "STRING", // Fallback
     }
@@ -198,11 +236,18 @@ struct DebugSchemaEntry {
     bq_type: String,
     mode: String,
     #[serde(skip_serializing_if = "Option::is_none")]
+    precision: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    scale: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     fields: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    provenance: Option<FieldProvenance>,
 }
 
-impl From<&SchemaEntry> for DebugSchemaEntry {
-    fn from(entry: &SchemaEntry) -> Self {
+impl DebugSchemaEntry {
+    /// Build the debug entry for the field at canonical `path`.
+    fn new(entry: &SchemaEntry, path: &str, provenance: Option<&Provenance>) -> Self {
         let status = match entry.status {
             EntryStatus::Hard => "Hard",
             EntryStatus::Soft => "Soft",
@@ -210,27 +255,40 @@ impl From<&SchemaEntry> for DebugSchemaEntry {
         };
 
         let fields = if let BqType::Record(map) = &entry.bq_type {
-            Some(schema_map_to_debug_value(map))
+            Some(schema_map_to_debug_value(map, Some(path), provenance))
         } else {
             None
         };
 
+        let decimal_spec = entry.bq_type.decimal_spec();
+
         DebugSchemaEntry {
             status: status.to_string(),
             filled: entry.filled,
             name: entry.name.clone(),
             bq_type: entry.bq_type.as_str().to_string(),
             mode: entry.mode.as_str().to_string(),
+            precision: decimal_spec.map(|(precision, _)| precision),
+            scale: decimal_spec.map(|(_, scale)| scale),
             fields,
+            provenance: provenance.and_then(|p| p.get(path)).cloned(),
         }
     }
 }
 
 /// Convert a SchemaMap to debug JSON value.
-fn schema_map_to_debug_value(map: &SchemaMap) -> serde_json::Value {
+fn schema_map_to_debug_value(
+    map: &SchemaMap,
+    base_path: Option<&str>,
+    provenance: Option<&Provenance>,
+) -> serde_json::Value {
     let mut obj = serde_json::Map::new();
     for (key, entry) in map {
-        let debug_entry = DebugSchemaEntry::from(entry);
+        let path = match base_path {
+            Some(base) => format!("{}.{}", base, key),
+            None => key.clone(),
+        };
+        let debug_entry = DebugSchemaEntry::new(entry, &path, provenance);
         obj.insert(
             key.clone(),
             serde_json::to_value(&debug_entry).unwrap_or(serde_json::Value::Null),
@@ -244,7 +302,25 @@ fn schema_map_to_debug_value(map: &SchemaMap) -> serde_json::Value {
 /// This shows the internal representation including entry status (Hard/Soft/Ignore),
 /// filled state, and other metadata useful for debugging.
 pub fn write_schema_debug_map<W: Write>(schema_map: &SchemaMap, writer: &mut W) -> Result<()> {
-    let debug_value = schema_map_to_debug_value(schema_map);
+    write_debug_value(schema_map_to_debug_value(schema_map, None, None), writer)
+}
+
+/// Write the internal schema map as debug JSON output, with each field's provenance.
+///
+/// Fields with recorded provenance get a `provenance` object holding where they
+/// first appeared and every change to their mode or type.
+pub fn write_schema_debug_map_with_provenance<W: Write>(
+    schema_map: &SchemaMap,
+    provenance: &Provenance,
+    writer: &mut W,
+) -> Result<()> {
+    write_debug_value(
+        schema_map_to_debug_value(schema_map, None, Some(provenance)),
+        writer,
+    )
+}
+
+fn write_debug_value<W: Write>(debug_value: serde_json::Value, writer: &mut W) -> Result<()> {
     let json = serde_json::to_string_pretty(&debug_value)
         .map_err(|e| crate::error::Error::SchemaFile(e.to_string()))?;
     writeln!(writer, "{}", json)?;
@@ -315,7 +391,10 @@ fn field_to_json_schema(field: &BqSchemaField) -> (serde_json::Value, bool) {
             arr_schema.insert("items".to_string(), record_to_json_schema(field));
         } else {
             let mut items = serde_json::Map::new();
-            items.insert("type".to_string(), serde_json::Value::String(base_type));
+            if field.field_type != "JSON" {
+                items.insert("type".to_string(), serde_json::Value::String(base_type));
+            }
+            insert_decimal_bounds(field, &mut items);
             arr_schema.insert("items".to_string(), serde_json::Value::Object(items));
         }
 
@@ -323,15 +402,41 @@ fn field_to_json_schema(field: &BqSchemaField) -> (serde_json::Value, bool) {
     } else if field.field_type == "RECORD" {
         record_to_json_schema(field)
     } else {
-        // Simple type
+        // Simple type; a JSON column accepts any value, so it has no type constraint
         let mut prop = serde_json::Map::new();
-        prop.insert("type".to_string(), serde_json::Value::String(base_type));
+        if field.field_type != "JSON" {
+            prop.insert("type".to_string(), serde_json::Value::String(base_type));
+        }
+        insert_decimal_bounds(field, &mut prop);
         serde_json::Value::Object(prop)
     };
 
     (schema, is_required)
 }
 
+/// Constrain a number to a field's declared decimal precision and scale.
+///
+/// `multipleOf` limits the fractional digits and the exclusive bounds the
+/// integer digits.
+fn insert_decimal_bounds(
+    field: &BqSchemaField,
+    schema: &mut serde_json::Map<String, serde_json::Value>,
+) {
+    let parse = |param: &Option<String>| param.as_deref().and_then(|p| p.parse::<i32>().ok());
+    let Some(precision) = parse(&field.precision) else {
+        return;
+    };
+    let scale = parse(&field.scale).unwrap_or(0);
+
+    let bound = 10f64.powi(precision - scale);
+    schema.insert(
+        "multipleOf".to_string(),
+        serde_json::json!(10f64.powi(-scale)),
+    );
+    schema.insert("exclusiveMinimum".to_string(), serde_json::json!(-bound));
+    schema.insert("exclusiveMaximum".to_string(), serde_json::json!(bound));
+}
+
 /// Convert a RECORD field to JSON Schema object.
 fn record_to_json_schema(field: &BqSchemaField) -> serde_json::Value {
     let mut obj = serde_json::Map::new();
@@ -369,10 +474,11 @@ fn bq_type_to_json_schema_type(bq_type: &str) -> String {
     match bq_type {
         "STRING" => "string",
         "INTEGER" => "integer",
-        "FLOAT" => "number",
+        "FLOAT" | "NUMERIC" | "BIGNUMERIC" => "number",
         "BOOLEAN" => "boolean",
         "TIMESTAMP" | "DATE" | "TIME" | "DATETIME" => "string", // DateTime types as strings
         "BYTES" => "string",
+        "GEOGRAPHY" => "string", // WKT, as BigQuery exports it
         "RECORD" => "object",
         _ => "string",
     }
@@ -387,6 +493,31 @@ fn bq_type_to_json_schema_type(bq_type: &str) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_per_file_schema_path() {
+        let input = PathBuf::from("/data/file.json");
+        assert_eq!(
+            per_file_schema_path(&input, None),
+            PathBuf::from("/data/file.schema.json")
+        );
+
+        // Compressed input gets the same name as uncompressed
+        assert_eq!(
+            per_file_schema_path(Path::new("/data/file.json.gz"), None),
+            PathBuf::from("/data/file.schema.json")
+        );
+
+        assert_eq!(
+            per_file_schema_path(Path::new("/data/file"), None),
+            PathBuf::from("/data/file.schema.json")
+        );
+
+        assert_eq!(
+            per_file_schema_path(Path::new("/a/b/c/file.json"), Some(Path::new("/out"))),
+            PathBuf::from("/out/file.schema.json")
+        );
+    }
+
     #[test]
     fn test_write_schema_json() {
         let schema = vec![BqSchemaField::new(
@@ -512,6 +643,10 @@ mod tests {
             "json-schema".parse::<OutputFormat>().unwrap(),
             OutputFormat::JsonSchema
         );
+        assert_eq!(
+            "state".parse::<OutputFormat>().unwrap(),
+            OutputFormat::State
+        );
         assert!("invalid".parse::<OutputFormat>().is_err());
     }
 
@@ -981,6 +1116,34 @@ mod tests {
         assert_eq!(parsed["test_field"]["mode"], "NULLABLE");
     }
 
+    #[test]
+    fn test_debug_map_decimal_precision_scale() {
+        use crate::schema::types::{BqMode, BqType, EntryStatus, SchemaEntry, SchemaMap};
+
+        let mut schema_map = SchemaMap::new();
+        schema_map.insert(
+            "amount".to_string(),
+            SchemaEntry {
+                status: EntryStatus::Hard,
+                filled: true,
+                name: "amount".to_string(),
+                bq_type: BqType::Numeric {
+                    precision: 19,
+                    scale: 5,
+                },
+                mode: BqMode::Nullable,
+            },
+        );
+
+        let mut output = Vec::new();
+        write_schema_debug_map(&schema_map, &mut output).unwrap();
+        let parsed: serde_json::Value = serde_json::from_slice(&output).unwrap();
+
+        assert_eq!(parsed["amount"]["bq_type"], "NUMERIC");
+        assert_eq!(parsed["amount"]["precision"], 19);
+        assert_eq!(parsed["amount"]["scale"], 5);
+    }
+
     #[test]
     fn test_debug_map_with_record() {
         use crate::schema::types::{BqMode, BqType, EntryStatus, SchemaEntry, SchemaMap};
@@ -1018,6 +1181,39 @@ mod tests {
         assert!(parsed["record_field"]["fields"]["nested_field"].is_object());
     }
 
+    #[test]
+    fn test_debug_map_with_provenance() {
+        use crate::schema::{GeneratorConfig, SchemaGenerator, SchemaMap};
+        use serde_json::json;
+
+        let mut generator = SchemaGenerator::new(GeneratorConfig {
+            track_provenance: true,
+            ..Default::default()
+        });
+        let mut schema_map = SchemaMap::new();
+        generator.set_source_file(Some("events.json".to_string()));
+        for record in [json!({"user": {"id": 1}}), json!({"user": {"id": 2.5}})] {
+            generator.process_record(&record, &mut schema_map).unwrap();
+        }
+
+        let mut output = Vec::new();
+        write_schema_debug_map_with_provenance(&schema_map, generator.provenance(), &mut output)
+            .unwrap();
+        let parsed: serde_json::Value = serde_json::from_slice(&output).unwrap();
+
+        let id = &parsed["user"]["fields"]["id"]["provenance"];
+        assert_eq!(id["path"], "user.id");
+        assert_eq!(id["first_seen"], json!({"file": "events.json", "line": 1}));
+        assert_eq!(id["initial"], "NULLABLE INTEGER");
+        assert_eq!(id["changes"][0]["location"]["line"], 2);
+        assert_eq!(id["changes"][0]["to"], "NULLABLE FLOAT");
+
+        // Without provenance the debug map is unchanged
+        let mut output = Vec::new();
+        write_schema_debug_map(&schema_map, &mut output).unwrap();
+        assert!(!String::from_utf8(output).unwrap().contains("provenance"));
+    }
+
     #[test]
     fn test_debug_map_all_entry_statuses() {
         use crate::schema::types::{BqMode, BqType, EntryStatus, SchemaEntry, SchemaMap};
@@ -1129,6 +1325,121 @@ mod tests {
         assert!(json_str.contains("\"type\": \"STRING\""));
     }
 
+    #[test]
+    fn test_decimal_types_ddl_and_json_schema() {
+        let schema = vec![
+            BqSchemaField::new(
+                "amount".to_string(),
+                "NUMERIC".to_string(),
+                "NULLABLE".to_string(),
+            ),
+            BqSchemaField::new(
+                "big_amount".to_string(),
+                "BIGNUMERIC".to_string(),
+                "REPEATED".to_string(),
+            ),
+        ];
+
+        let mut output = Vec::new();
+        write_schema_ddl(&schema, "dataset.table", &mut output).unwrap();
+        let ddl = String::from_utf8(output).unwrap();
+        assert!(ddl.contains("amount NUMERIC"));
+        assert!(ddl.contains("big_amount ARRAY<BIGNUMERIC>"));
+
+        let json_schema = bq_schema_to_json_schema(&schema);
+        assert_eq!(json_schema["properties"]["amount"]["type"], "number");
+        assert_eq!(
+            json_schema["properties"]["big_amount"]["items"]["type"],
+            "number"
+        );
+    }
+
+    #[test]
+    fn test_decimal_params_in_all_writers() {
+        let schema = vec![
+            BqSchemaField::new(
+                "price".to_string(),
+                "NUMERIC".to_string(),
+                "NULLABLE".to_string(),
+            )
+            .with_decimal_params(10, 2),
+            BqSchemaField::new(
+                "rates".to_string(),
+                "BIGNUMERIC".to_string(),
+                "REPEATED".to_string(),
+            )
+            .with_decimal_params(40, 20),
+        ];
+
+        let json: serde_json::Value =
+            serde_json::from_str(&schema_to_json_string(&schema).unwrap()).unwrap();
+        assert_eq!(json[0]["precision"], "10");
+        assert_eq!(json[0]["scale"], "2");
+        assert_eq!(json[1]["precision"], "40");
+        let fields: Vec<BqSchemaField> = serde_json::from_value(json).unwrap();
+        assert_eq!(fields[0].precision.as_deref(), Some("10"));
+
+        let mut output = Vec::new();
+        write_schema_ddl(&schema, "dataset.table", &mut output).unwrap();
+        let ddl = String::from_utf8(output).unwrap();
+        assert!(ddl.contains("price NUMERIC(10, 2),"));
+        assert!(ddl.contains("rates ARRAY<BIGNUMERIC(40, 20)>"));
+
+        let json_schema = bq_schema_to_json_schema(&schema);
+        let price = &json_schema["properties"]["price"];
+        assert_eq!(price["multipleOf"], 0.01);
+        assert_eq!(price["exclusiveMaximum"], 1e8);
+        assert_eq!(price["exclusiveMinimum"], -1e8);
+        assert_eq!(
+            json_schema["properties"]["rates"]["items"]["exclusiveMaximum"],
+            1e20
+        );
+    }
+
+    #[test]
+    fn test_geography_ddl() {
+        let schema = vec![BqSchemaField::new(
+            "location".to_string(),
+            "GEOGRAPHY".to_string(),
+            "NULLABLE".to_string(),
+        )];
+
+        let mut output = Vec::new();
+        write_schema_ddl(&schema, "dataset.table", &mut output).unwrap();
+        let ddl = String::from_utf8(output).unwrap();
+        assert!(ddl.contains("location GEOGRAPHY"));
+    }
+
+    #[test]
+    fn test_json_type_ddl_and_json_schema() {
+        let schema = vec![
+            BqSchemaField::new(
+                "payload".to_string(),
+                "JSON".to_string(),
+                "NULLABLE".to_string(),
+            ),
+            BqSchemaField::new(
+                "events".to_string(),
+                "JSON".to_string(),
+                "REPEATED".to_string(),
+            ),
+        ];
+
+        let mut output = Vec::new();
+        write_schema_ddl(&schema, "dataset.table", &mut output).unwrap();
+        let ddl = String::from_utf8(output).unwrap();
+        assert!(ddl.contains("payload JSON"));
+        assert!(ddl.contains("events ARRAY<JSON>"));
+
+        // JSON columns accept any value, so they carry no type constraint
+        let json_schema = bq_schema_to_json_schema(&schema);
+        assert_eq!(json_schema["properties"]["payload"], serde_json::json!({}));
+        assert_eq!(
+            json_schema["properties"]["events"],
+            serde_json::json!({"type": "array", "items": {}})
+        );
+    }
+
     #[test]
     fn test_bq_type_to_standard_sql_fallback() {
         // Test the fallback case for unknown types
@@ -23,11 +23,17 @@ fn normalize_bq_type(type_name: &str) -> Result<&'static str> {
         "DATE" => Ok("DATE"),
         "TIME" => Ok("TIME"),
         "DATETIME" => Ok("DATETIME"),
+        "NUMERIC" => Ok("NUMERIC"),
+        "BIGNUMERIC" => Ok("BIGNUMERIC"),
+        "GEOGRAPHY" => Ok("GEOGRAPHY"),
+        "JSON" => Ok("JSON"),
         "RECORD" => Ok("RECORD"),
         // Type aliases (Standard SQL names)
         "INT64" => Ok("INTEGER"),
         "FLOAT64" => Ok("FLOAT"),
         "BOOL" => Ok("BOOLEAN"),
+        "DECIMAL" => Ok("NUMERIC"),
+        "BIGDECIMAL" => Ok("BIGNUMERIC"),
         "STRUCT" => Ok("RECORD"),
         other => Err(Error::SchemaFile(format!(
             "Unknown BigQuery type: {}",
@@ -45,6 +51,101 @@ fn parse_mode(mode: Option<&str>) -> BqMode {
     }
 }
 
+/// Parse the optional `precision`/`scale` parameters of a NUMERIC or BIGNUMERIC field.
+///
+/// BigQuery writes these as strings, but plain numbers are accepted too.
+/// Missing parameters default to the widest value the type allows, which
+/// are also the limits checked: `max_precision` and `max_scale`.
+fn parse_decimal_params(
+    field_obj: &serde_json::Map<String, serde_json::Value>,
+    max_precision: u8,
+    max_scale: u8,
+) -> Result<(u8, u8)> {
+    let param = |key: &str| -> Result<Option<u8>> {
+        let value = match field_obj.get(key) {
+            None | Some(serde_json::Value::Null) => return Ok(None),
+            Some(serde_json::Value::String(s)) => s.parse::<u8>().ok(),
+            Some(serde_json::Value::Number(n)) => n.as_u64().and_then(|n| u8::try_from(n).ok()),
+            Some(_) => None,
+        };
+        value
+            .map(Some)
+            .ok_or_else(|| Error::SchemaFile(format!("Invalid '{}' for decimal field", key)))
+    };
+
+    let scale = param("scale")?;
+    if let Some(scale) = scale.filter(|&scale| scale > max_scale) {
+        return Err(Error::SchemaFile(format!(
+            "Decimal scale {} exceeds the maximum of {}",
+            scale, max_scale
+        )));
+    }
+
+    let (precision, scale) = match (param("precision")?, scale) {
+        (Some(precision), scale) => (precision, scale.unwrap_or(0)),
+        // Only the scale is given: keep the type's integer digits
+        (None, Some(scale)) => {
+            let precision = (max_precision - max_scale)
+                .checked_add(scale)
+                .ok_or_else(|| Error::SchemaFile(format!("Invalid decimal scale {}", scale)))?;
+            (precision, scale)
+        }
+        (None, None) => (max_precision, max_scale),
+    };
+
+    if precision > max_precision {
+        return Err(Error::SchemaFile(format!(
+            "Decimal precision {} exceeds the maximum of {}",
+            precision, max_precision
+        )));
+    }
+    if precision < scale {
+        return Err(Error::SchemaFile(format!(
+            "Decimal precision {} is smaller than scale {}",
+            precision, scale
+        )));
+    }
+    Ok((precision, scale))
+}
+
+/// Convert a normalized non-decimal, non-RECORD type name to its BqType.
+fn scalar_type(normalized_type: &str) -> Result<BqType> {
+    match normalized_type {
+        "STRING" => Ok(BqType::String),
+        "INTEGER" => Ok(BqType::Integer),
+        "FLOAT" => Ok(BqType::Float),
+        "BOOLEAN" => Ok(BqType::Boolean),
+        "TIMESTAMP" => Ok(BqType::Timestamp),
+        "DATE" => Ok(BqType::Date),
+        "TIME" => Ok(BqType::Time),
+        "DATETIME" => Ok(BqType::Datetime),
+        "BYTES" => Ok(BqType::Bytes),
+        "GEOGRAPHY" => Ok(BqType::Geography),
+        "JSON" => Ok(BqType::Json),
+        _ => Err(Error::SchemaFile(format!(
+            "Unsupported type: {}",
+            normalized_type
+        ))),
+    }
+}
+
+/// Parse a type name or alias that is not a RECORD.
+///
+/// NUMERIC and BIGNUMERIC get the widest precision and scale their type allows.
+pub(crate) fn parse_scalar_type(type_name: &str) -> Result<BqType> {
+    match normalize_bq_type(type_name)? {
+        "NUMERIC" => Ok(BqType::Numeric {
+            precision: 38,
+            scale: 9,
+        }),
+        "BIGNUMERIC" => Ok(BqType::BigNumeric {
+            precision: 76,
+            scale: 38,
+        }),
+        other => scalar_type(other),
+    }
+}
+
 /// Convert a BigQuery schema JSON to internal SchemaMap.
 pub fn bq_schema_to_map(schema: &serde_json::Value) -> Result<SchemaMap> {
     let fields = match schema {
@@ -92,21 +193,15 @@ pub fn bq_schema_to_map(schema: &serde_json::Value) -> Result<SchemaMap> {
             BqType::Record(nested_map)
         } else {
             match normalized_type {
-                "STRING" => BqType::String,
-                "INTEGER" => BqType::Integer,
-                "FLOAT" => BqType::Float,
-                "BOOLEAN" => BqType::Boolean,
-                "TIMESTAMP" => BqType::Timestamp,
-                "DATE" => BqType::Date,
-                "TIME" => BqType::Time,
-                "DATETIME" => BqType::Timestamp, // DATETIME maps to TIMESTAMP
-                "BYTES" => BqType::String,       // BYTES maps to STRING for our purposes
-                _ => {
-                    return Err(Error::SchemaFile(format!(
-                        "Unsupported type: {}",
-                        normalized_type
-                    )))
+                "NUMERIC" => {
+                    let (precision, scale) = parse_decimal_params(field_obj, 38, 9)?;
+                    BqType::Numeric { precision, scale }
+                }
+                "BIGNUMERIC" => {
+                    let (precision, scale) = parse_decimal_params(field_obj, 76, 38)?;
+                    BqType::BigNumeric { precision, scale }
                 }
+                other => scalar_type(other)?,
             }
         };
 
@@ -272,19 +367,100 @@ mod tests {
 
         // Check type mappings
         assert!(matches!(map.get("a").unwrap().bq_type, BqType::String));
-        assert!(matches!(map.get("b").unwrap().bq_type, BqType::String)); // BYTES -> STRING
+        assert!(matches!(map.get("b").unwrap().bq_type, BqType::Bytes));
         assert!(matches!(map.get("c").unwrap().bq_type, BqType::Integer));
         assert!(matches!(map.get("d").unwrap().bq_type, BqType::Float));
         assert!(matches!(map.get("e").unwrap().bq_type, BqType::Boolean));
         assert!(matches!(map.get("f").unwrap().bq_type, BqType::Timestamp));
         assert!(matches!(map.get("g").unwrap().bq_type, BqType::Date));
         assert!(matches!(map.get("h").unwrap().bq_type, BqType::Time));
-        assert!(matches!(map.get("i").unwrap().bq_type, BqType::Timestamp)); // DATETIME -> TIMESTAMP
+        assert!(matches!(map.get("i").unwrap().bq_type, BqType::Datetime));
         assert!(matches!(map.get("j").unwrap().bq_type, BqType::Integer)); // INT64 -> INTEGER
         assert!(matches!(map.get("k").unwrap().bq_type, BqType::Float)); // FLOAT64 -> FLOAT
         assert!(matches!(map.get("l").unwrap().bq_type, BqType::Boolean)); // BOOL -> BOOLEAN
     }
 
+    #[test]
+    fn test_decimal_types() {
+        let schema = json!([
+            {"name": "a", "type": "NUMERIC"},
+            {"name": "b", "type": "BIGNUMERIC"},
+            {"name": "c", "type": "NUMERIC", "precision": "10", "scale": "2"},
+            {"name": "d", "type": "DECIMAL", "scale": 4},
+            {"name": "e", "type": "BIGDECIMAL", "precision": 50}
+        ]);
+
+        let map = bq_schema_to_map(&schema).unwrap();
+        assert_eq!(
+            map.get("a").unwrap().bq_type,
+            BqType::Numeric {
+                precision: 38,
+                scale: 9
+            }
+        );
+        assert_eq!(
+            map.get("b").unwrap().bq_type,
+            BqType::BigNumeric {
+                precision: 76,
+                scale: 38
+            }
+        );
+        assert_eq!(
+            map.get("c").unwrap().bq_type,
+            BqType::Numeric {
+                precision: 10,
+                scale: 2
+            }
+        );
+        assert_eq!(
+            map.get("d").unwrap().bq_type,
+            BqType::Numeric {
+                precision: 33,
+                scale: 4
+            }
+        );
+        assert_eq!(
+            map.get("e").unwrap().bq_type,
+            BqType::BigNumeric {
+                precision: 50,
+                scale: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_decimal_invalid_params() {
+        let schema = json!([{"name": "a", "type": "NUMERIC", "precision": "2", "scale": "5"}]);
+        let err = bq_schema_to_map(&schema).unwrap_err();
+        assert!(err.to_string().contains("smaller than scale"), "{}", err);
+
+        for (field, expected) in [
+            (
+                json!({"name": "a", "type": "BIGNUMERIC", "scale": "250"}),
+                "scale 250 exceeds the maximum of 38",
+            ),
+            (
+                json!({"name": "a", "type": "NUMERIC", "precision": 30, "scale": 10}),
+                "scale 10 exceeds the maximum of 9",
+            ),
+            (
+                json!({"name": "a", "type": "NUMERIC", "precision": "200", "scale": "5"}),
+                "precision 200 exceeds the maximum of 38",
+            ),
+            (
+                json!({"name": "a", "type": "BIGDECIMAL", "precision": 77}),
+                "precision 77 exceeds the maximum of 76",
+            ),
+        ] {
+            let err = bq_schema_to_map(&json!([field])).unwrap_err();
+            assert!(err.to_string().contains(expected), "{}", err);
+        }
+
+        let schema = json!([{"name": "a", "type": "NUMERIC", "precision": "abc"}]);
+        let err = bq_schema_to_map(&schema).unwrap_err();
+        assert!(err.to_string().contains("Invalid 'precision'"));
+    }
+
     #[test]
     fn test_missing_name_error() {
         let schema = json!([
@@ -407,6 +583,24 @@ mod tests {
         assert!(matches!(map.get("d").unwrap().bq_type, BqType::Timestamp));
     }
 
+    #[test]
+    fn test_geography_type() {
+        let schema = json!([{"name": "location", "type": "GEOGRAPHY", "mode": "REPEATED"}]);
+
+        let map = bq_schema_to_map(&schema).unwrap();
+        let entry = map.get("location").unwrap();
+        assert_eq!(entry.bq_type, BqType::Geography);
+        assert_eq!(entry.mode, BqMode::Repeated);
+    }
+
+    #[test]
+    fn test_json_type() {
+        let schema = json!([{"name": "payload", "type": "json", "mode": "NULLABLE"}]);
+
+        let map = bq_schema_to_map(&schema).unwrap();
+        assert_eq!(map.get("payload").unwrap().bq_type, BqType::Json);
+    }
+
     #[test]
     fn test_case_insensitive_mode() {
         let schema = json!([
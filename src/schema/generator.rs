@@ -3,12 +3,28 @@
 //! This module contains the `SchemaGenerator` struct which processes
 //! JSON/CSV records and builds a BigQuery-compatible schema.
 
+use std::borrow::Cow;
+use std::collections::HashSet;
+
 use once_cell::sync::Lazy;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
 
 use crate::error::{Error, ErrorLog, Result};
-use crate::inference::{convert_type, infer_bigquery_type};
+use crate::inference::{
+    convert_type, infer_bigquery_type_with_options, infer_type_from_number_with_options,
+    infer_type_from_string_with_options, BytesInference, DateTimeFormats, EpochDetection,
+    InferenceOptions,
+};
+use crate::input::raw::{parse_raw_object, RawFields};
+use crate::input::{CsvDialect, JsonLayout, RawRecord};
+use crate::schema::hints::TypeHints;
+use crate::schema::provenance::{
+    describe_entry, FieldProvenance, Provenance, SchemaChange, SourceLocation,
+};
 use crate::schema::types::{BqMode, BqSchemaField, BqType, EntryStatus, SchemaEntry, SchemaMap};
+use crate::stats::DataProfile;
 
 /// Valid BigQuery field name pattern.
 static FIELD_NAME_SANITIZER: Lazy<Regex> = Lazy::new(|| Regex::new(r"[^a-zA-Z0-9_]").unwrap());
@@ -16,7 +32,7 @@ static FIELD_NAME_SANITIZER: Lazy<Regex> = Lazy::new(|| Regex::new(r"[^a-zA-Z0-9
 /// Configuration options for schema generation.
 #[derive(Debug, Clone)]
 pub struct GeneratorConfig {
-    /// Input format: "json", "csv"
+    /// Input format: "json", "json-array", "csv"
     pub input_format: InputFormat,
     /// Infer REQUIRED mode for CSV fields that are always filled
     pub infer_mode: bool,
@@ -28,6 +44,34 @@ pub struct GeneratorConfig {
     pub sanitize_names: bool,
     /// Preserve input field order instead of sorting alphabetically
     pub preserve_input_sort_order: bool,
+    /// Infer NUMERIC/BIGNUMERIC for decimals that would lose precision as FLOAT
+    pub infer_numeric: bool,
+    /// Infer BYTES for base64 strings at least this many characters long
+    pub bytes_min_length: Option<usize>,
+    /// Infer BYTES for any base64 string in fields whose name matches this pattern
+    pub bytes_field_pattern: Option<Regex>,
+    /// Infer GEOGRAPHY for WKT strings and GeoJSON geometry objects
+    pub infer_geography: bool,
+    /// Infer TIMESTAMP for integers that look like Unix epoch times
+    pub epoch_detection: Option<EpochDetection>,
+    /// Restrict epoch detection to fields whose name matches this pattern
+    pub epoch_field_pattern: Option<Regex>,
+    /// Extra date/time formats, optionally limited to field paths
+    pub datetime_formats: DateTimeFormats,
+    /// Match date/time layouts only, without calendar and clock range checks
+    pub regex_only_dates: bool,
+    /// How to resolve fields whose type or mode conflicts across records
+    pub conflict_policy: ConflictPolicy,
+    /// Dotted field paths (e.g. `payload.attributes`) that are always typed JSON
+    pub json_paths: Vec<String>,
+    /// Forced types and modes for field paths, used instead of inference
+    pub type_hints: TypeHints,
+    /// Record where each field first appeared and where its type or mode changed
+    pub track_provenance: bool,
+    /// Collect per-field statistics while processing records
+    pub collect_stats: bool,
+    /// Delimiter, quoting, header and null markers of CSV input
+    pub csv_dialect: CsvDialect,
 }
 
 impl Default for GeneratorConfig {
@@ -39,6 +83,20 @@ impl Default for GeneratorConfig {
             quoted_values_are_strings: false,
             sanitize_names: false,
             preserve_input_sort_order: false,
+            infer_numeric: false,
+            bytes_min_length: None,
+            bytes_field_pattern: None,
+            infer_geography: false,
+            epoch_detection: None,
+            epoch_field_pattern: None,
+            datetime_formats: DateTimeFormats::default(),
+            regex_only_dates: false,
+            conflict_policy: ConflictPolicy::Ignore,
+            json_paths: Vec::new(),
+            type_hints: TypeHints::default(),
+            track_provenance: false,
+            collect_stats: false,
+            csv_dialect: CsvDialect::default(),
         }
     }
 }
@@ -47,14 +105,122 @@ impl Default for GeneratorConfig {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum InputFormat {
     Json,
+    /// JSON read as a top-level array or concatenated multi-line values
+    JsonArray,
     Csv,
 }
 
+impl InputFormat {
+    /// Layout used to read JSON input in this format.
+    pub fn json_layout(self) -> JsonLayout {
+        match self {
+            InputFormat::JsonArray => JsonLayout::Stream,
+            _ => JsonLayout::Auto,
+        }
+    }
+}
+
+/// How the generator resolves a field whose type or mode conflicts across records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConflictPolicy {
+    /// Log the conflict and drop the field from the schema (default)
+    #[default]
+    Ignore,
+    /// Widen conflicting scalar types to STRING; mode conflicts are still dropped
+    String,
+    /// Fall back to a JSON column that can hold any of the conflicting values
+    Json,
+    /// Return an `Error::FieldConflict` from `process_record`
+    Fail,
+    /// Keep the first-seen type and mode and skip conflicting values
+    KeepFirst,
+}
+
+impl std::str::FromStr for ConflictPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "ignore" => Ok(ConflictPolicy::Ignore),
+            "string" => Ok(ConflictPolicy::String),
+            "json" => Ok(ConflictPolicy::Json),
+            "fail" => Ok(ConflictPolicy::Fail),
+            "keep-first" | "keep_first" => Ok(ConflictPolicy::KeepFirst),
+            _ => Err(format!("Unknown conflict policy: {}", s)),
+        }
+    }
+}
+
+/// How a conflicting field was resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConflictResolution {
+    /// The field was dropped from the schema
+    Dropped,
+    /// The field was widened to STRING
+    CoercedToString,
+    /// The field was widened to JSON
+    CoercedToJson,
+    /// The first-seen type was kept and conflicting values skipped
+    KeptFirst,
+}
+
+impl std::fmt::Display for ConflictResolution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConflictResolution::Dropped => write!(f, "dropped"),
+            ConflictResolution::CoercedToString => write!(f, "coerced to STRING"),
+            ConflictResolution::CoercedToJson => write!(f, "coerced to JSON"),
+            ConflictResolution::KeptFirst => write!(f, "kept first type"),
+        }
+    }
+}
+
+/// A field path whose values conflicted, and how it was resolved.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldConflict {
+    /// Dotted path of the field
+    pub path: String,
+    /// How the conflict was resolved
+    pub resolution: ConflictResolution,
+    /// Number of values affected, including values skipped after a drop
+    pub count: usize,
+}
+
+/// A top-level key as it was before an update: its position and entry, or
+/// `None` if the update added it.
+type SavedEntry = (String, Option<(usize, SchemaEntry)>);
+
 /// Schema generator that processes records and builds a BigQuery schema.
 pub struct SchemaGenerator {
     config: GeneratorConfig,
+    /// Lowercased `json_paths` from the config
+    json_paths: HashSet<String>,
+    inference_options: InferenceOptions,
+    /// Inference options for fields known to hold BYTES
+    bytes_inference_options: InferenceOptions,
+    /// Inference options for fields matching `epoch_field_pattern`
+    epoch_inference_options: InferenceOptions,
+    /// Inference options for fields that are both BYTES and epoch fields
+    bytes_epoch_inference_options: InferenceOptions,
+    /// Inference options for fields already recorded as GEOGRAPHY
+    geography_inference_options: InferenceOptions,
+    /// Fields known before any record, such as an existing schema, which
+    /// inference consults without merging them into the schema map
+    known_schema: SchemaMap,
     line_number: usize,
     error_logs: Vec<ErrorLog>,
+    conflicts: Vec<FieldConflict>,
+    /// First conflict seen in the current record under `ConflictPolicy::Fail`
+    pending_conflict: Option<Error>,
+    /// Top-level entries replaced by the current update under
+    /// `ConflictPolicy::Fail`, to put back on a conflict
+    undo_log: Option<Vec<SavedEntry>>,
+    provenance: Provenance,
+    /// Input file currently being processed, for provenance
+    source_file: Option<String>,
+    /// Value of `line_number` when `source_file` was set
+    source_start_line: usize,
+    profile: DataProfile,
 }
 
 impl SchemaGenerator {
@@ -68,10 +234,65 @@ impl SchemaGenerator {
             config.keep_nulls = true;
         }
 
+        let inference_options = InferenceOptions {
+            quoted_values_are_strings: config.quoted_values_are_strings,
+            infer_numeric: config.infer_numeric,
+            bytes: config
+                .bytes_min_length
+                .map_or(BytesInference::Off, BytesInference::MinLength),
+            infer_geography: config.infer_geography,
+            // With a field pattern, only matching fields detect epochs
+            epoch: config
+                .epoch_detection
+                .clone()
+                .filter(|_| config.epoch_field_pattern.is_none()),
+            // Formats limited to field paths are added per field
+            datetime_formats: config.datetime_formats.unscoped(),
+            regex_only_dates: config.regex_only_dates,
+        };
+        let bytes_inference_options = InferenceOptions {
+            bytes: BytesInference::Always,
+            ..inference_options.clone()
+        };
+        let epoch_inference_options = InferenceOptions {
+            epoch: config.epoch_detection.clone(),
+            ..inference_options.clone()
+        };
+        let bytes_epoch_inference_options = InferenceOptions {
+            bytes: BytesInference::Always,
+            ..epoch_inference_options.clone()
+        };
+        let geography_inference_options = InferenceOptions {
+            infer_geography: true,
+            ..inference_options.clone()
+        };
+
+        let profile = DataProfile::new(!config.quoted_values_are_strings);
+
+        let json_paths = config
+            .json_paths
+            .iter()
+            .map(|path| path.to_lowercase())
+            .collect();
+
         Self {
             config,
+            json_paths,
+            inference_options,
+            bytes_inference_options,
+            epoch_inference_options,
+            bytes_epoch_inference_options,
+            geography_inference_options,
+            known_schema: SchemaMap::new(),
             line_number: 0,
             error_logs: Vec::new(),
+            conflicts: Vec::new(),
+            pending_conflict: None,
+            undo_log: None,
+            provenance: Provenance::new(),
+            source_file: None,
+            source_start_line: 0,
+            profile,
         }
     }
 
@@ -80,6 +301,11 @@ impl SchemaGenerator {
         Self::new(GeneratorConfig::default())
     }
 
+    /// Get the configuration.
+    pub fn config(&self) -> &GeneratorConfig {
+        &self.config
+    }
+
     /// Get the current line number.
     pub fn line_number(&self) -> usize {
         self.line_number
@@ -90,6 +316,99 @@ impl SchemaGenerator {
         &self.error_logs
     }
 
+    /// Get the fields whose values conflicted, in the order first seen.
+    pub fn conflicts(&self) -> &[FieldConflict] {
+        &self.conflicts
+    }
+
+    /// Get the provenance of every field seen, if `track_provenance` is set.
+    pub fn provenance(&self) -> &Provenance {
+        &self.provenance
+    }
+
+    /// Get the per-field statistics, if `collect_stats` is set.
+    pub fn profile(&self) -> &DataProfile {
+        &self.profile
+    }
+
+    /// Set the input file that subsequent records come from.
+    ///
+    /// Provenance line numbers restart at 1 for each file.
+    pub fn set_source_file(&mut self, file: Option<String>) {
+        self.source_file = file;
+        self.source_start_line = self.line_number;
+    }
+
+    /// Set the fields known before any record is processed.
+    ///
+    /// Type rules that depend on a field's earlier type, such as keeping an
+    /// existing BYTES or GEOGRAPHY column, then apply to records processed
+    /// into a schema map that starts out without those fields.
+    pub fn set_known_schema(&mut self, known_schema: SchemaMap) {
+        self.known_schema = known_schema;
+    }
+
+    /// Record a change in `path`'s schema from `before` (None if new) to `after`.
+    fn record_provenance(&mut self, path: String, before: Option<String>, after: String) {
+        let location = SourceLocation {
+            file: self.source_file.clone(),
+            line: self.line_number - self.source_start_line,
+        };
+        let change = match &before {
+            Some(before) if *before != after => Some(SchemaChange {
+                location: location.clone(),
+                from: before.clone(),
+                to: after.clone(),
+            }),
+            _ => None,
+        };
+
+        let key = path.to_lowercase();
+        match self.provenance.get_mut(&key) {
+            Some(field) => field.changes.extend(change),
+            None => {
+                // A field that already had an entry came from an existing schema
+                let first_seen = before.is_none().then_some(location);
+                self.provenance.insert(
+                    key,
+                    FieldProvenance {
+                        path,
+                        first_seen,
+                        initial: before.unwrap_or(after),
+                        changes: change.into_iter().collect(),
+                    },
+                );
+            }
+        }
+    }
+
+    /// Record a conflict for `path`, or fail the current record under `ConflictPolicy::Fail`.
+    fn record_conflict(&mut self, path: String, resolution: ConflictResolution, message: String) {
+        if self.config.conflict_policy == ConflictPolicy::Fail {
+            if self.pending_conflict.is_none() {
+                self.pending_conflict = Some(Error::FieldConflict {
+                    line: self.line_number,
+                    path,
+                    message,
+                });
+            }
+            return;
+        }
+
+        match self
+            .conflicts
+            .iter_mut()
+            .find(|c| c.path == path && c.resolution == resolution)
+        {
+            Some(conflict) => conflict.count += 1,
+            None => self.conflicts.push(FieldConflict {
+                path,
+                resolution,
+                count: 1,
+            }),
+        }
+    }
+
     /// Log an error at the current line.
     fn log_error(&mut self, msg: String) {
         self.error_logs.push(ErrorLog {
@@ -99,6 +418,11 @@ impl SchemaGenerator {
     }
 
     /// Process a single JSON record and update the schema map.
+    ///
+    /// A parsed number only keeps its nearest `f64` (or integer), so
+    /// `infer_numeric` can't see digits beyond that: `12345678901234567.89` is
+    /// typed FLOAT here but NUMERIC by `process_raw_record`. Records read from
+    /// text should go through `RawRecord::parse` and `process_raw_record`.
     pub fn process_record(
         &mut self,
         record: &serde_json::Value,
@@ -108,8 +432,14 @@ impl SchemaGenerator {
 
         match record {
             serde_json::Value::Object(obj) => {
-                self.deduce_schema_for_record(obj, schema_map, None);
-                Ok(())
+                if self.config.collect_stats {
+                    self.profile.record_count += 1;
+                }
+                self.apply_or_restore(schema_map, |generator, schema_map| {
+                    let known = std::mem::take(&mut generator.known_schema);
+                    generator.deduce_schema_for_record(obj, schema_map, None, Some(&known));
+                    generator.known_schema = known;
+                })
             }
             _ => {
                 let msg = format!(
@@ -122,42 +452,314 @@ impl SchemaGenerator {
         }
     }
 
+    /// Merge a schema map built by another generator into `target`.
+    ///
+    /// Entries are merged directly, with the same rules as records, so their
+    /// status (soft, hard or ignored), `filled` flag and quoted types are kept.
+    /// Under `ConflictPolicy::Fail` the first conflict is returned as an error.
+    pub fn merge_schema_maps(&mut self, target: &mut SchemaMap, source: SchemaMap) -> Result<()> {
+        self.apply_or_restore(target, |generator, target| {
+            for (key, entry) in source {
+                generator.merge_field(target, key, entry, None);
+            }
+        })
+    }
+
+    /// Apply `update` to `schema_map`, returning the conflict it hit under `ConflictPolicy::Fail`.
+    ///
+    /// A failed update leaves `schema_map` as it was, so callers that catch
+    /// the error can carry on with the schema built so far. Only the entries
+    /// the update touches are saved for that.
+    fn apply_or_restore(
+        &mut self,
+        schema_map: &mut SchemaMap,
+        update: impl FnOnce(&mut Self, &mut SchemaMap),
+    ) -> Result<()> {
+        self.undo_log = (self.config.conflict_policy == ConflictPolicy::Fail).then(Vec::new);
+        update(self, schema_map);
+        let undo_log = self.undo_log.take();
+        match self.pending_conflict.take() {
+            Some(err) => {
+                // Undo the latest change first, so a key changed twice ends up as it started
+                for (key, saved) in undo_log.into_iter().flatten().rev() {
+                    match saved {
+                        Some((index, entry)) => match schema_map.get_mut(&key) {
+                            Some(slot) => *slot = entry,
+                            None => {
+                                schema_map.shift_insert(index, key, entry);
+                            }
+                        },
+                        None => {
+                            schema_map.shift_remove(&key);
+                        }
+                    }
+                }
+                Err(err)
+            }
+            None => Ok(()),
+        }
+    }
+
     /// Deduce schema for a single record (JSON object).
+    ///
+    /// `known_fields` holds fields not in `schema_map` whose types inference
+    /// should still see: the known schema at the top level, or the previously
+    /// seen fields of a nested record, whose entries are only merged in afterwards.
     fn deduce_schema_for_record(
         &mut self,
         obj: &serde_json::Map<String, serde_json::Value>,
         schema_map: &mut SchemaMap,
         base_path: Option<&str>,
+        known_fields: Option<&SchemaMap>,
     ) {
         for (key, value) in obj {
             let sanitized_key = self.sanitize_name(key);
             let canonical_key = sanitized_key.to_lowercase();
 
-            let new_entry = match self.get_schema_entry(&sanitized_key, value, base_path) {
-                Some(entry) => entry,
-                None => continue, // Unsupported type, skip
-            };
+            if self.config.collect_stats {
+                self.profile
+                    .observe(&json_full_path(base_path, &sanitized_key), value);
+            }
+
+            let known_entry = schema_map
+                .get(&canonical_key)
+                .or_else(|| known_fields.and_then(|fields| fields.get(&canonical_key)));
+            let new_entry =
+                match self.get_schema_entry(&sanitized_key, value, base_path, known_entry) {
+                    Some(entry) => entry,
+                    None => continue, // Unsupported type, skip
+                };
+
+            self.merge_field(schema_map, canonical_key, new_entry, base_path);
+        }
+    }
+
+    /// Process a record read without parsing its field values and update the schema map.
+    ///
+    /// The result is the same as `process_record` on the fully parsed record,
+    /// but nested objects, arrays of them and strings without escapes are read
+    /// straight from the input instead of being built into a `serde_json::Value`.
+    /// Numbers are typed from their text, so `infer_numeric` sees every digit.
+    pub fn process_raw_record(
+        &mut self,
+        record: &RawRecord<'_>,
+        schema_map: &mut SchemaMap,
+    ) -> Result<()> {
+        let fields = match record {
+            RawRecord::Object(fields) => fields,
+            RawRecord::Value(value) => return self.process_record(value, schema_map),
+        };
+
+        self.line_number += 1;
+        if self.config.collect_stats {
+            self.profile.record_count += 1;
+        }
+        self.apply_or_restore(schema_map, |generator, schema_map| {
+            let known = std::mem::take(&mut generator.known_schema);
+            generator.deduce_schema_for_raw_record(fields, schema_map, None, Some(&known));
+            generator.known_schema = known;
+        })
+    }
 
-            // Check if entry exists - if so, merge in place to preserve order
-            if let Some(existing_entry) = schema_map.get(&canonical_key).cloned() {
-                let merged_entry =
-                    self.merge_schema_entry(Some(existing_entry), new_entry, base_path);
+    /// Deduce schema for the fields of a raw record. See `deduce_schema_for_record`.
+    fn deduce_schema_for_raw_record(
+        &mut self,
+        fields: &RawFields<'_>,
+        schema_map: &mut SchemaMap,
+        base_path: Option<&str>,
+        known_fields: Option<&SchemaMap>,
+    ) {
+        for (key, value) in fields {
+            let sanitized_key = self.sanitize_name(key);
+            let canonical_key = sanitized_key.to_lowercase();
 
-                if let Some(entry) = merged_entry {
-                    // Update in place to preserve order
-                    if let Some(slot) = schema_map.get_mut(&canonical_key) {
-                        *slot = entry;
+            if self.config.collect_stats {
+                let path = json_full_path(base_path, &sanitized_key);
+                match value.get().as_bytes().first() {
+                    // Only an object's presence is recorded; its fields are observed below
+                    Some(b'{') => self
+                        .profile
+                        .observe(&path, &serde_json::Value::Object(Default::default())),
+                    _ => {
+                        let value = serde_json::from_str(value.get())
+                            .expect("raw values are validated when parsed");
+                        self.profile.observe(&path, &value);
                     }
-                } else {
-                    // Remove if merge resulted in None (shouldn't happen normally)
-                    schema_map.shift_remove(&canonical_key);
                 }
-            } else {
-                // New field - just insert
-                let merged_entry = self.merge_schema_entry(None, new_entry, base_path);
-                if let Some(entry) = merged_entry {
-                    schema_map.insert(canonical_key, entry);
+            }
+
+            let known_entry = schema_map
+                .get(&canonical_key)
+                .or_else(|| known_fields.and_then(|fields| fields.get(&canonical_key)));
+            let new_entry =
+                match self.get_raw_schema_entry(&sanitized_key, value, base_path, known_entry) {
+                    Some(entry) => entry,
+                    None => continue, // Unsupported type, skip
+                };
+
+            self.merge_field(schema_map, canonical_key, new_entry, base_path);
+        }
+    }
+
+    /// Get a schema entry for a field whose value hasn't been parsed.
+    ///
+    /// Plain strings, numbers, objects and arrays of numbers or objects are
+    /// handled in place; anything else, and any field with a type hint or JSON
+    /// path, is parsed and goes through `get_schema_entry`.
+    fn get_raw_schema_entry(
+        &mut self,
+        key: &str,
+        value: &RawValue,
+        base_path: Option<&str>,
+        known_entry: Option<&SchemaEntry>,
+    ) -> Option<SchemaEntry> {
+        let json = value.get();
+        let plain = (self.config.type_hints.is_empty() && self.json_paths.is_empty()) || {
+            let path = json_full_path(base_path, key);
+            self.config.type_hints.lookup(&path).is_none()
+                && !self.json_paths.contains(&path.to_lowercase())
+        };
+
+        if plain {
+            match json.as_bytes().first() {
+                // Without escapes, the string is the text between the quotes
+                Some(b'"') if !json.contains('\\') => {
+                    let s = &json[1..json.len() - 1];
+                    let options = self.inference_options(key, base_path, known_entry);
+                    let bq_type = infer_type_from_string_with_options(s, &options);
+                    return Some(self.typed_entry(key, BqMode::Nullable, bq_type, s.is_empty()));
+                }
+                Some(b'-' | b'0'..=b'9') => {
+                    let options = self.inference_options(key, base_path, known_entry);
+                    let bq_type = infer_type_from_number_with_options(json, &options);
+                    return Some(self.typed_entry(key, BqMode::Nullable, bq_type, false));
+                }
+                Some(b'[') => {
+                    if let Some(entry) = self.raw_array_entry(key, json, base_path, known_entry) {
+                        return Some(entry);
+                    }
+                }
+                // GeoJSON detection needs the parsed object
+                Some(b'{') if !self.is_geography_field(known_entry) => {
+                    if let Some(fields) = parse_raw_object(json).ok().filter(|f| !f.is_empty()) {
+                        return Some(self.record_entry(
+                            key,
+                            BqMode::Nullable,
+                            base_path,
+                            known_entry,
+                            |generator, record_fields, path, known_fields| {
+                                generator.deduce_schema_for_raw_record(
+                                    &fields,
+                                    record_fields,
+                                    Some(path),
+                                    known_fields,
+                                );
+                            },
+                        ));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let value: serde_json::Value = serde_json::from_str(json).ok()?;
+        self.get_schema_entry(key, &value, base_path, known_entry)
+    }
+
+    /// Get a schema entry for an unparsed array of numbers or of non-empty objects.
+    ///
+    /// Returns None for any other array, which is parsed instead so that it is
+    /// typed, or rejected, as `get_schema_entry` does.
+    fn raw_array_entry(
+        &mut self,
+        key: &str,
+        json: &str,
+        base_path: Option<&str>,
+        known_entry: Option<&SchemaEntry>,
+    ) -> Option<SchemaEntry> {
+        let items: Vec<&RawValue> = serde_json::from_str(json).ok()?;
+        let is_number = |item: &&RawValue| matches!(item.get().as_bytes()[0], b'-' | b'0'..=b'9');
+        let first = items.first()?;
+
+        if is_number(first) {
+            if !items.iter().all(is_number) {
+                return None;
+            }
+            let options = self.inference_options(key, base_path, known_entry);
+            let mut bq_type = infer_type_from_number_with_options(items[0].get(), &options);
+            for item in &items[1..] {
+                let item_type = infer_type_from_number_with_options(item.get(), &options);
+                bq_type = convert_type(&bq_type, &item_type)?;
+            }
+            return Some(self.typed_entry(key, BqMode::Repeated, bq_type, false));
+        }
+
+        // GeoJSON detection needs the parsed objects
+        if !first.get().starts_with('{') || self.is_geography_field(known_entry) {
+            return None;
+        }
+        let records = items
+            .iter()
+            .map(|item| parse_raw_object(item.get()).ok().filter(|f| !f.is_empty()))
+            .collect::<Option<Vec<_>>>()?;
+        Some(self.record_entry(
+            key,
+            BqMode::Repeated,
+            base_path,
+            known_entry,
+            |generator, record_fields, path, known_fields| {
+                for fields in &records {
+                    generator.deduce_schema_for_raw_record(
+                        fields,
+                        record_fields,
+                        Some(path),
+                        known_fields,
+                    );
+                }
+            },
+        ))
+    }
+
+    /// Merge a field's new entry into `schema_map`, in place if it already exists.
+    fn merge_field(
+        &mut self,
+        schema_map: &mut SchemaMap,
+        canonical_key: String,
+        new_entry: SchemaEntry,
+        base_path: Option<&str>,
+    ) {
+        if base_path.is_none() {
+            if let Some(undo_log) = &mut self.undo_log {
+                let saved = schema_map
+                    .get_full(&canonical_key)
+                    .map(|(index, _, entry)| (index, entry.clone()));
+                undo_log.push((canonical_key.clone(), saved));
+            }
+        }
+
+        // Check if entry exists - if so, merge in place to preserve order
+        if let Some(slot) = schema_map.get_mut(&canonical_key) {
+            // Take the entry out rather than cloning its nested fields
+            let existing_entry = std::mem::replace(
+                slot,
+                SchemaEntry::new(String::new(), BqType::Null, BqMode::Nullable),
+            );
+            let merged_entry = self.merge_schema_entry(Some(existing_entry), new_entry, base_path);
+
+            if let Some(entry) = merged_entry {
+                // Update in place to preserve order
+                if let Some(slot) = schema_map.get_mut(&canonical_key) {
+                    *slot = entry;
                 }
+            } else {
+                // Remove if merge resulted in None (shouldn't happen normally)
+                schema_map.shift_remove(&canonical_key);
+            }
+        } else {
+            // New field - just insert
+            let merged_entry = self.merge_schema_entry(None, new_entry, base_path);
+            if let Some(entry) = merged_entry {
+                schema_map.insert(canonical_key, entry);
             }
         }
     }
@@ -177,18 +779,63 @@ impl SchemaGenerator {
         }
     }
 
-    /// Get a schema entry for a key-value pair.
+    /// Get a schema entry for a key-value pair, applying any type hint for its path.
+    ///
+    /// `known_entry` is the entry already recorded for this field, if any.
     fn get_schema_entry(
         &mut self,
         key: &str,
         value: &serde_json::Value,
         base_path: Option<&str>,
+        known_entry: Option<&SchemaEntry>,
+    ) -> Option<SchemaEntry> {
+        let hint = if self.config.type_hints.is_empty() {
+            None
+        } else {
+            self.config
+                .type_hints
+                .lookup(&json_full_path(base_path, key))
+                .map(|hint| (hint.bq_type.clone(), hint.mode))
+        };
+
+        match hint {
+            Some((Some(bq_type), mode)) => Some(hinted_entry(key, value, bq_type, mode)),
+            Some((None, Some(mode))) => {
+                let mut entry = self.infer_schema_entry(key, value, base_path, known_entry)?;
+                entry.mode = mode;
+                Some(entry)
+            }
+            _ => self.infer_schema_entry(key, value, base_path, known_entry),
+        }
+    }
+
+    /// Infer a schema entry for a key-value pair.
+    fn infer_schema_entry(
+        &mut self,
+        key: &str,
+        value: &serde_json::Value,
+        base_path: Option<&str>,
+        known_entry: Option<&SchemaEntry>,
     ) -> Option<SchemaEntry> {
-        let result = infer_bigquery_type(value, self.config.quoted_values_are_strings);
+        if !value.is_null()
+            && !self.json_paths.is_empty()
+            && self
+                .json_paths
+                .contains(&json_full_path(base_path, key).to_lowercase())
+        {
+            return Some(json_entry(key, BqMode::Nullable));
+        }
+
+        let options = self.inference_options(key, base_path, known_entry);
+        let result = infer_bigquery_type_with_options(value, &options);
 
         let (mode, bq_type) = match result {
             Some(r) => r,
             None => {
+                if let Some(entry) = self.resolve_unsupported_array(key, value, base_path) {
+                    return Some(entry);
+                }
+
                 // Log error for unsupported types
                 if let serde_json::Value::Array(arr) = value {
                     // Check what kind of array error
@@ -214,140 +861,354 @@ impl SchemaGenerator {
             }
         };
 
-        match &bq_type {
-            BqType::Record(_) => {
-                // Recursively process nested record
-                let new_base_path = json_full_path(base_path, key);
-                let mut fields = SchemaMap::new();
-
-                if mode == BqMode::Nullable {
-                    // Single object
-                    if let serde_json::Value::Object(obj) = value {
-                        self.deduce_schema_for_record(obj, &mut fields, Some(&new_base_path));
-                    }
-                } else {
-                    // Array of objects (REPEATED)
-                    if let serde_json::Value::Array(arr) = value {
-                        for item in arr {
-                            if let serde_json::Value::Object(obj) = item {
-                                self.deduce_schema_for_record(
-                                    obj,
-                                    &mut fields,
-                                    Some(&new_base_path),
-                                );
+        if let BqType::Record(_) = bq_type {
+            // Recursively process nested record
+            return Some(self.record_entry(
+                key,
+                mode,
+                base_path,
+                known_entry,
+                |generator, fields, path, known_fields| {
+                    if mode == BqMode::Nullable {
+                        // Single object
+                        if let serde_json::Value::Object(obj) = value {
+                            generator.deduce_schema_for_record(
+                                obj,
+                                fields,
+                                Some(path),
+                                known_fields,
+                            );
+                        }
+                    } else {
+                        // Array of objects (REPEATED)
+                        if let serde_json::Value::Array(arr) = value {
+                            for item in arr {
+                                if let serde_json::Value::Object(obj) = item {
+                                    generator.deduce_schema_for_record(
+                                        obj,
+                                        fields,
+                                        Some(path),
+                                        known_fields,
+                                    );
+                                }
                             }
                         }
                     }
-                }
+                },
+            ));
+        }
 
-                Some(SchemaEntry {
-                    status: EntryStatus::Hard,
-                    filled: true,
-                    name: key.to_string(),
-                    bq_type: BqType::Record(fields),
-                    mode,
-                })
-            }
-            BqType::Null => Some(SchemaEntry {
+        let empty_string = value.as_str().is_some_and(str::is_empty);
+        Some(self.typed_entry(key, mode, bq_type, empty_string))
+    }
+
+    /// Build a RECORD entry whose fields are filled in by `deduce`.
+    ///
+    /// `deduce` gets the fields to fill, the record's path and the fields
+    /// previously seen for it.
+    fn record_entry(
+        &mut self,
+        key: &str,
+        mode: BqMode,
+        base_path: Option<&str>,
+        known_entry: Option<&SchemaEntry>,
+        deduce: impl FnOnce(&mut Self, &mut SchemaMap, &str, Option<&SchemaMap>),
+    ) -> SchemaEntry {
+        let new_base_path = json_full_path(base_path, key);
+        let mut fields = SchemaMap::new();
+        let known_fields = known_entry.and_then(|entry| match &entry.bq_type {
+            BqType::Record(known) => Some(known),
+            _ => None,
+        });
+        deduce(self, &mut fields, &new_base_path, known_fields);
+
+        SchemaEntry {
+            status: EntryStatus::Hard,
+            filled: true,
+            name: key.to_string(),
+            bq_type: BqType::Record(fields),
+            mode,
+        }
+    }
+
+    /// Build the entry for an inferred type other than RECORD.
+    ///
+    /// `empty_string` is set if the value was an empty string, which doesn't
+    /// count as filled in CSV input.
+    fn typed_entry(
+        &self,
+        key: &str,
+        mode: BqMode,
+        bq_type: BqType,
+        empty_string: bool,
+    ) -> SchemaEntry {
+        match bq_type {
+            BqType::Null => SchemaEntry {
                 status: EntryStatus::Soft,
                 filled: false,
                 name: key.to_string(),
                 bq_type: BqType::String,
                 mode: BqMode::Nullable,
-            }),
-            BqType::EmptyArray => Some(SchemaEntry {
+            },
+            BqType::EmptyArray => SchemaEntry {
                 status: EntryStatus::Soft,
                 filled: false,
                 name: key.to_string(),
                 bq_type: BqType::String,
                 mode: BqMode::Repeated,
-            }),
-            BqType::EmptyRecord => Some(SchemaEntry {
+            },
+            BqType::EmptyRecord => SchemaEntry {
                 status: EntryStatus::Soft,
                 filled: false,
                 name: key.to_string(),
                 bq_type: BqType::Record(SchemaMap::new()),
                 mode,
-            }),
+            },
             _ => {
                 // Check for empty string in CSV mode
-                let (status, filled) = if self.config.input_format == InputFormat::Csv {
-                    if let serde_json::Value::String(s) = value {
-                        if s.is_empty() {
-                            (EntryStatus::Soft, false)
-                        } else {
-                            (EntryStatus::Hard, true)
-                        }
+                let (status, filled) =
+                    if self.config.input_format == InputFormat::Csv && empty_string {
+                        (EntryStatus::Soft, false)
                     } else {
                         (EntryStatus::Hard, true)
-                    }
-                } else {
-                    (EntryStatus::Hard, true)
-                };
+                    };
 
-                Some(SchemaEntry {
+                SchemaEntry {
                     status,
                     filled,
                     name: key.to_string(),
                     bq_type,
                     mode,
-                })
+                }
             }
         }
     }
 
-    /// Merge a new schema entry with an existing one.
-    fn merge_schema_entry(
+    /// Type an array that has no BigQuery equivalent according to the conflict policy.
+    ///
+    /// Under the JSON policy nested arrays and mixed elements become a JSON
+    /// column; under the STRING policy an array of mixed scalars becomes a
+    /// REPEATED STRING. Returns None if the array should be dropped.
+    fn resolve_unsupported_array(
         &mut self,
-        old_entry: Option<SchemaEntry>,
-        new_entry: SchemaEntry,
+        key: &str,
+        value: &serde_json::Value,
         base_path: Option<&str>,
     ) -> Option<SchemaEntry> {
-        let mut old_entry = match old_entry {
-            Some(e) => e,
-            None => return Some(new_entry),
+        let arr = value.as_array()?;
+        let entry = match self.config.conflict_policy {
+            ConflictPolicy::Json => Some(json_entry(key, BqMode::Nullable)),
+            ConflictPolicy::String => {
+                let all_scalars = arr.iter().all(|v| {
+                    matches!(
+                        v,
+                        serde_json::Value::Bool(_)
+                            | serde_json::Value::Number(_)
+                            | serde_json::Value::String(_)
+                    )
+                });
+                all_scalars.then(|| SchemaEntry {
+                    status: EntryStatus::Hard,
+                    filled: true,
+                    name: key.to_string(),
+                    bq_type: BqType::String,
+                    mode: BqMode::Repeated,
+                })
+            }
+            ConflictPolicy::Ignore | ConflictPolicy::Fail | ConflictPolicy::KeepFirst => None,
         };
 
-        // Track filled status
-        if !new_entry.filled || !old_entry.filled {
-            old_entry.filled = false;
-        }
+        let resolution = match &entry {
+            Some(entry) if entry.bq_type == BqType::Json => ConflictResolution::CoercedToJson,
+            Some(_) => ConflictResolution::CoercedToString,
+            None => ConflictResolution::Dropped,
+        };
+        self.record_conflict(
+            json_full_path(base_path, key),
+            resolution,
+            "array elements have mixed or nested types".to_string(),
+        );
+        entry
+    }
 
-        // If old was ignored, keep ignoring
-        if old_entry.status == EntryStatus::Ignore {
-            return Some(old_entry);
+    /// Pick the inference options for a field based on its name and known type.
+    fn options_for(&self, key: &str, known_entry: Option<&SchemaEntry>) -> &InferenceOptions {
+        if !self.config.infer_geography && self.is_geography_field(known_entry) {
+            return &self.geography_inference_options;
+        }
+        match (
+            self.is_bytes_field(key, known_entry),
+            self.is_epoch_field(key, known_entry),
+        ) {
+            (false, false) => &self.inference_options,
+            (true, false) => &self.bytes_inference_options,
+            (false, true) => &self.epoch_inference_options,
+            (true, true) => &self.bytes_epoch_inference_options,
         }
+    }
 
-        // Hard -> Soft: keep old hard
-        if old_entry.status == EntryStatus::Hard && new_entry.status == EntryStatus::Soft {
-            if let Some(mode) = self.merge_mode(&old_entry, &new_entry, base_path) {
-                old_entry.mode = mode;
-                return Some(old_entry);
-            } else {
-                old_entry.status = EntryStatus::Ignore;
-                return Some(old_entry);
-            }
+    /// Inference options for a field, with any date/time formats limited to its path.
+    fn inference_options(
+        &self,
+        key: &str,
+        base_path: Option<&str>,
+        known_entry: Option<&SchemaEntry>,
+    ) -> Cow<'_, InferenceOptions> {
+        let options = self.options_for(key, known_entry);
+        match self.scoped_datetime_formats(key, base_path) {
+            Some(datetime_formats) => Cow::Owned(InferenceOptions {
+                datetime_formats,
+                ..options.clone()
+            }),
+            None => Cow::Borrowed(options),
         }
+    }
 
-        // Soft -> Hard: use new hard
+    /// Date/time formats for a field, if any format is limited to its path.
+    fn scoped_datetime_formats(
+        &self,
+        key: &str,
+        base_path: Option<&str>,
+    ) -> Option<DateTimeFormats> {
+        if !self.config.datetime_formats.has_scoped() {
+            return None;
+        }
+        self.config
+            .datetime_formats
+            .for_path(&json_full_path(base_path, key))
+    }
+
+    /// Returns true if integers in this field may be inferred as epoch TIMESTAMPs.
+    ///
+    /// That is the case for fields matching `epoch_field_pattern` and for fields
+    /// already recorded as TIMESTAMP. Without a pattern, every field qualifies
+    /// and the plain inference options already detect epochs.
+    fn is_epoch_field(&self, key: &str, known_entry: Option<&SchemaEntry>) -> bool {
+        let Some(pattern) = &self.config.epoch_field_pattern else {
+            return false;
+        };
+        self.config.epoch_detection.is_some()
+            && (known_entry.is_some_and(|entry| entry.bq_type == BqType::Timestamp)
+                || pattern.is_match(key))
+    }
+
+    /// Returns true if base64 strings in this field should always be inferred as BYTES.
+    ///
+    /// That is the case for fields matching `bytes_field_pattern` and for fields
+    /// already recorded as BYTES, e.g. from an existing schema.
+    fn is_bytes_field(&self, key: &str, known_entry: Option<&SchemaEntry>) -> bool {
+        known_entry.is_some_and(|entry| entry.bq_type == BqType::Bytes)
+            || self
+                .config
+                .bytes_field_pattern
+                .as_ref()
+                .is_some_and(|pattern| pattern.is_match(key))
+    }
+
+    /// Returns true if WKT strings and GeoJSON objects in this field are GEOGRAPHY.
+    ///
+    /// That is the case for every field with `infer_geography`, and for fields
+    /// already recorded as GEOGRAPHY, e.g. from an existing schema.
+    fn is_geography_field(&self, known_entry: Option<&SchemaEntry>) -> bool {
+        self.config.infer_geography
+            || known_entry.is_some_and(|entry| entry.bq_type == BqType::Geography)
+    }
+
+    /// Merge a new schema entry with an existing one, recording provenance if enabled.
+    fn merge_schema_entry(
+        &mut self,
+        old_entry: Option<SchemaEntry>,
+        new_entry: SchemaEntry,
+        base_path: Option<&str>,
+    ) -> Option<SchemaEntry> {
+        if !self.config.track_provenance {
+            return self.merge_entries(old_entry, new_entry, base_path);
+        }
+
+        let path = json_full_path(base_path, &new_entry.name);
+        let before = old_entry.as_ref().map(describe_entry);
+        let merged = self.merge_entries(old_entry, new_entry, base_path);
+        if let Some(entry) = &merged {
+            self.record_provenance(path, before, describe_entry(entry));
+        }
+        merged
+    }
+
+    /// Merge a new schema entry with an existing one.
+    fn merge_entries(
+        &mut self,
+        old_entry: Option<SchemaEntry>,
+        new_entry: SchemaEntry,
+        base_path: Option<&str>,
+    ) -> Option<SchemaEntry> {
+        let mut old_entry = match old_entry {
+            Some(e) => e,
+            None => return Some(new_entry),
+        };
+
+        // Track filled status
+        if !new_entry.filled || !old_entry.filled {
+            old_entry.filled = false;
+        }
+
+        // If old was ignored, keep ignoring
+        if old_entry.status == EntryStatus::Ignore {
+            if new_entry.status == EntryStatus::Hard
+                && self.config.conflict_policy != ConflictPolicy::Fail
+            {
+                let full_name = json_full_path(base_path, &old_entry.name);
+                self.record_conflict(full_name, ConflictResolution::Dropped, String::new());
+            }
+            return Some(old_entry);
+        }
+
+        // A field ignored in a merged schema map stays ignored
+        if new_entry.status == EntryStatus::Ignore {
+            let filled = old_entry.filled;
+            let mut entry = new_entry;
+            entry.filled = filled;
+            return Some(entry);
+        }
+
+        // JSON holds any value, so it absorbs the other side whatever its type
+        if old_entry.bq_type == BqType::Json || new_entry.bq_type == BqType::Json {
+            old_entry.mode = if old_entry.status == EntryStatus::Soft {
+                new_entry.mode
+            } else if new_entry.status == EntryStatus::Soft || old_entry.mode == new_entry.mode {
+                old_entry.mode
+            } else {
+                BqMode::Nullable
+            };
+            old_entry.status = EntryStatus::Hard;
+            old_entry.bq_type = BqType::Json;
+            return Some(old_entry);
+        }
+
+        // Hard -> Soft: keep old hard
+        if old_entry.status == EntryStatus::Hard && new_entry.status == EntryStatus::Soft {
+            if let Some(mode) = self.merge_mode(&old_entry, &new_entry, base_path) {
+                old_entry.mode = mode;
+                return Some(old_entry);
+            } else {
+                return Some(self.resolve_conflict(old_entry, &new_entry, None, base_path));
+            }
+        }
+
+        // Soft -> Hard: use new hard
         if old_entry.status == EntryStatus::Soft && new_entry.status == EntryStatus::Hard {
-            let mut result = new_entry;
-            result.filled = old_entry.filled;
-            if let Some(mode) = self.merge_mode(&old_entry, &result, base_path) {
+            if let Some(mode) = self.merge_mode(&old_entry, &new_entry, base_path) {
+                let mut result = new_entry;
+                result.filled = old_entry.filled;
                 result.mode = mode;
                 return Some(result);
             } else {
-                old_entry.status = EntryStatus::Ignore;
-                return Some(old_entry);
+                return Some(self.resolve_conflict(old_entry, &new_entry, None, base_path));
             }
         }
 
         // Same status - merge types
-        let old_type = &old_entry.bq_type;
-        let new_type = &new_entry.bq_type;
-
         // Handle RECORD + RECORD merging
-        if let (BqType::Record(old_fields), BqType::Record(new_fields)) = (old_type, new_type) {
+        if let (BqType::Record(_), BqType::Record(_)) = (&old_entry.bq_type, &new_entry.bq_type) {
             // Allow NULLABLE RECORD -> REPEATED RECORD
             if old_entry.mode == BqMode::Nullable && new_entry.mode == BqMode::Repeated {
                 let full_name = json_full_path(base_path, &old_entry.name);
@@ -364,44 +1225,36 @@ impl SchemaGenerator {
                 ));
             }
 
-            // Merge the record fields
-            let mut merged_fields = old_fields.clone();
+            // Merge the record fields, taking them out of both entries
+            let (BqType::Record(mut merged_fields), BqType::Record(new_fields)) = (
+                std::mem::replace(&mut old_entry.bq_type, BqType::Null),
+                new_entry.bq_type,
+            ) else {
+                unreachable!("both entries are records");
+            };
             let new_base_path = json_full_path(base_path, &old_entry.name);
 
             for (key, new_field_entry) in new_fields {
-                if let Some(existing) = merged_fields.get(key).cloned() {
-                    // Update existing field in place to preserve order
-                    if let Some(merged) = self.merge_schema_entry(
-                        Some(existing),
-                        new_field_entry.clone(),
-                        Some(&new_base_path),
-                    ) {
-                        if let Some(slot) = merged_fields.get_mut(key) {
-                            *slot = merged;
-                        }
-                    } else {
-                        merged_fields.shift_remove(key);
-                    }
-                } else {
-                    // New field - insert at end
-                    if let Some(merged) =
-                        self.merge_schema_entry(None, new_field_entry.clone(), Some(&new_base_path))
-                    {
-                        merged_fields.insert(key.clone(), merged);
-                    }
-                }
+                self.merge_field(
+                    &mut merged_fields,
+                    key,
+                    new_field_entry,
+                    Some(&new_base_path),
+                );
             }
 
             old_entry.bq_type = BqType::Record(merged_fields);
             return Some(old_entry);
         }
 
+        let old_type = &old_entry.bq_type;
+        let new_type = &new_entry.bq_type;
+
         // Merge mode
         let merged_mode = match self.merge_mode(&old_entry, &new_entry, base_path) {
             Some(m) => m,
             None => {
-                old_entry.status = EntryStatus::Ignore;
-                return Some(old_entry);
+                return Some(self.resolve_conflict(old_entry, &new_entry, None, base_path));
             }
         };
 
@@ -416,13 +1269,32 @@ impl SchemaGenerator {
                 None => {
                     let full_old_name = json_full_path(base_path, &old_entry.name);
                     let full_new_name = json_full_path(base_path, &new_entry.name);
+                    let action =
+                        match self.conflict_resolution(&old_entry, &new_entry, Some(merged_mode)) {
+                            ConflictResolution::CoercedToString => {
+                                "Converting field with mismatched type to STRING"
+                            }
+                            ConflictResolution::CoercedToJson => {
+                                "Converting field with mismatched type to JSON"
+                            }
+                            ConflictResolution::KeptFirst => {
+                                "Keeping first type for field with mismatched type"
+                            }
+                            ConflictResolution::Dropped => "Ignoring field with mismatched type",
+                        };
                     self.log_error(format!(
-                        "Ignoring field with mismatched type: old=({:?},{},{},{:?}); new=({:?},{},{},{:?})",
-                        old_entry.status, full_old_name, old_entry.mode, old_entry.bq_type,
-                        new_entry.status, full_new_name, new_entry.mode, new_entry.bq_type
+                        "{}: old=({:?},{},{},{:?}); new=({:?},{},{},{:?})",
+                        action,
+                        old_entry.status,
+                        full_old_name,
+                        old_entry.mode,
+                        old_entry.bq_type,
+                        new_entry.status,
+                        full_new_name,
+                        new_entry.mode,
+                        new_entry.bq_type
                     ));
-                    old_entry.status = EntryStatus::Ignore;
-                    Some(old_entry)
+                    Some(self.resolve_conflict(old_entry, &new_entry, Some(merged_mode), base_path))
                 }
             }
         } else {
@@ -431,6 +1303,91 @@ impl SchemaGenerator {
         }
     }
 
+    /// Decide how the configured policy resolves a type or mode conflict.
+    ///
+    /// `merged_mode` is None when the modes themselves could not be merged.
+    fn conflict_resolution(
+        &self,
+        entry: &SchemaEntry,
+        new_entry: &SchemaEntry,
+        merged_mode: Option<BqMode>,
+    ) -> ConflictResolution {
+        let is_record = |e: &SchemaEntry| matches!(e.bq_type, BqType::Record(_));
+        match self.config.conflict_policy {
+            ConflictPolicy::Json => ConflictResolution::CoercedToJson,
+            ConflictPolicy::String
+                if merged_mode.is_some() && !is_record(entry) && !is_record(new_entry) =>
+            {
+                ConflictResolution::CoercedToString
+            }
+            ConflictPolicy::KeepFirst => ConflictResolution::KeptFirst,
+            _ => ConflictResolution::Dropped,
+        }
+    }
+
+    /// Resolve a type or mode conflict according to the configured policy.
+    ///
+    /// `merged_mode` is None when the modes themselves could not be merged.
+    /// Conflicts that the policy cannot resolve leave the entry ignored.
+    fn resolve_conflict(
+        &mut self,
+        mut entry: SchemaEntry,
+        new_entry: &SchemaEntry,
+        merged_mode: Option<BqMode>,
+        base_path: Option<&str>,
+    ) -> SchemaEntry {
+        let full_name = json_full_path(base_path, &entry.name);
+        let message = format!(
+            "was {} {}, now {} {}",
+            entry.mode,
+            entry.bq_type.as_str(),
+            new_entry.mode,
+            new_entry.bq_type.as_str()
+        );
+        let resolution = self.conflict_resolution(&entry, new_entry, merged_mode);
+
+        match resolution {
+            ConflictResolution::CoercedToString | ConflictResolution::CoercedToJson => {
+                let (bq_type, mode) = if resolution == ConflictResolution::CoercedToJson {
+                    (BqType::Json, merged_mode.unwrap_or(BqMode::Nullable))
+                } else {
+                    (BqType::String, merged_mode.unwrap_or(entry.mode))
+                };
+                if merged_mode.is_none() {
+                    self.log_error(format!(
+                        "Converting schema for \"{}\" with mismatched mode into {} {}",
+                        full_name,
+                        mode,
+                        bq_type.as_str()
+                    ));
+                }
+                entry.status = EntryStatus::Hard;
+                entry.bq_type = bq_type;
+                entry.mode = mode;
+            }
+            ConflictResolution::KeptFirst => {
+                // A soft entry has no type of its own yet, so the first real value wins
+                if entry.status == EntryStatus::Soft {
+                    let filled = entry.filled;
+                    entry = new_entry.clone();
+                    entry.filled = filled;
+                }
+                if merged_mode.is_none() {
+                    self.log_error(format!(
+                        "Keeping schema for \"{}\" as {} {}",
+                        full_name,
+                        entry.mode,
+                        entry.bq_type.as_str()
+                    ));
+                }
+            }
+            ConflictResolution::Dropped => entry.status = EntryStatus::Ignore,
+        }
+
+        self.record_conflict(full_name, resolution, message);
+        entry
+    }
+
     /// Merge field modes, returning None if incompatible.
     fn merge_mode(
         &mut self,
@@ -566,11 +1523,17 @@ impl SchemaGenerator {
                 };
                 BqSchemaField::record(entry.name.clone(), mode.as_str().to_string(), nested_fields)
             }
-            _ => BqSchemaField::new(
-                entry.name.clone(),
-                entry.bq_type.as_str().to_string(),
-                mode.as_str().to_string(),
-            ),
+            _ => {
+                let field = BqSchemaField::new(
+                    entry.name.clone(),
+                    entry.bq_type.as_str().to_string(),
+                    mode.as_str().to_string(),
+                );
+                match entry.bq_type.decimal_params() {
+                    Some((precision, scale)) => field.with_decimal_params(precision, scale),
+                    None => field,
+                }
+            }
         }
     }
 }
@@ -587,6 +1550,47 @@ fn json_type_name(value: &serde_json::Value) -> &'static str {
     }
 }
 
+/// A hard, filled JSON entry.
+fn json_entry(key: &str, mode: BqMode) -> SchemaEntry {
+    SchemaEntry {
+        status: EntryStatus::Hard,
+        filled: true,
+        name: key.to_string(),
+        bq_type: BqType::Json,
+        mode,
+    }
+}
+
+/// An entry with a hinted type, whatever the value looks like.
+///
+/// Without a hinted mode, arrays are REPEATED (except for JSON, which holds
+/// them whole). Nulls and empty arrays give soft entries.
+fn hinted_entry(
+    key: &str,
+    value: &serde_json::Value,
+    bq_type: BqType,
+    mode: Option<BqMode>,
+) -> SchemaEntry {
+    let (inferred_mode, filled) = match value {
+        serde_json::Value::Null => (BqMode::Nullable, false),
+        serde_json::Value::Array(_) if bq_type == BqType::Json => (BqMode::Nullable, true),
+        serde_json::Value::Array(arr) => (BqMode::Repeated, !arr.is_empty()),
+        _ => (BqMode::Nullable, true),
+    };
+
+    SchemaEntry {
+        status: if filled {
+            EntryStatus::Hard
+        } else {
+            EntryStatus::Soft
+        },
+        filled,
+        name: key.to_string(),
+        bq_type,
+        mode: mode.unwrap_or(inferred_mode),
+    }
+}
+
 /// Build full JSON path for nested fields.
 fn json_full_path(base_path: Option<&str>, key: &str) -> String {
     match base_path {
@@ -598,6 +1602,7 @@ fn json_full_path(base_path: Option<&str>, key: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::schema::provenance::SourceLocation;
     use serde_json::json;
 
     #[test]
@@ -683,4 +1688,931 @@ mod tests {
             assert!(!field.name.contains('.'));
         }
     }
+
+    #[test]
+    fn test_infer_numeric_widens_across_records() {
+        let config = GeneratorConfig {
+            infer_numeric: true,
+            ..Default::default()
+        };
+        let mut generator = SchemaGenerator::new(config);
+        let mut schema_map = SchemaMap::new();
+
+        // Exact digits come from the raw number text
+        for line in [
+            r#"{"amount": 12345678901234567.89, "price": 1.5}"#,
+            r#"{"amount": 1.123456789012345678, "price": 2.25}"#,
+            r#"{"amount": 7}"#,
+        ] {
+            let record = RawRecord::parse(line).unwrap();
+            generator
+                .process_raw_record(&record, &mut schema_map)
+                .unwrap();
+        }
+
+        assert_eq!(
+            schema_map.get("amount").unwrap().bq_type,
+            BqType::BigNumeric {
+                precision: 37,
+                scale: 18
+            }
+        );
+        assert_eq!(schema_map.get("price").unwrap().bq_type, BqType::Float);
+
+        let schema = generator.flatten_schema(&schema_map);
+        assert_eq!(schema[0].field_type, "BIGNUMERIC");
+        assert_eq!(schema[1].field_type, "FLOAT");
+    }
+
+    #[test]
+    fn test_raw_numbers_in_arrays_and_records() {
+        let config = GeneratorConfig {
+            infer_numeric: true,
+            ..Default::default()
+        };
+        let mut generator = SchemaGenerator::new(config);
+        let mut schema_map = SchemaMap::new();
+
+        let line = r#"{"n": 1, "f": -2.5e3, "z": -0, "big": 123456789012345678901234567890,
+            "ints": [1, -2], "mixed": [1, 2.5], "exact": [1, 0.12345678901234567891],
+            "items": [{"price": 12345678901234567.89}, {"price": 1}]}"#;
+        let record = RawRecord::parse(line).unwrap();
+        generator
+            .process_raw_record(&record, &mut schema_map)
+            .unwrap();
+
+        let types: Vec<_> = schema_map
+            .values()
+            .map(|entry| (entry.name.as_str(), entry.mode, entry.bq_type.clone()))
+            .collect();
+        let price = match &schema_map["items"].bq_type {
+            BqType::Record(fields) => fields["price"].bq_type.clone(),
+            other => panic!("expected a record, got {:?}", other),
+        };
+        assert_eq!(
+            types[..7],
+            [
+                ("n", BqMode::Nullable, BqType::Integer),
+                ("f", BqMode::Nullable, BqType::Float),
+                ("z", BqMode::Nullable, BqType::Float),
+                (
+                    "big",
+                    BqMode::Nullable,
+                    BqType::BigNumeric {
+                        precision: 30,
+                        scale: 0
+                    }
+                ),
+                ("ints", BqMode::Repeated, BqType::Integer),
+                ("mixed", BqMode::Repeated, BqType::Float),
+                (
+                    "exact",
+                    BqMode::Repeated,
+                    // INTEGER widens the decimal to hold any INT64
+                    BqType::BigNumeric {
+                        precision: 39,
+                        scale: 20
+                    }
+                ),
+            ]
+        );
+        assert_eq!(schema_map["items"].mode, BqMode::Repeated);
+        assert_eq!(
+            price,
+            BqType::Numeric {
+                precision: 21,
+                scale: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_deep_and_streamed_records_keep_number_text() {
+        let config = GeneratorConfig {
+            infer_numeric: true,
+            ..Default::default()
+        };
+        let exact = BqType::Numeric {
+            precision: 19,
+            scale: 2,
+        };
+
+        // Deeper than the raw depth check, but within serde_json's limit
+        let mut generator = SchemaGenerator::new(config.clone());
+        let mut schema_map = SchemaMap::new();
+        let deep = format!(
+            "{{\"amount\": 12345678901234567.89, \"nested\": {}1{}}}",
+            "{\"a\": ".repeat(110),
+            "}".repeat(110)
+        );
+        let record = RawRecord::parse(&deep).unwrap();
+        assert!(matches!(record, RawRecord::Object(_)));
+        generator
+            .process_raw_record(&record, &mut schema_map)
+            .unwrap();
+        assert_eq!(schema_map["amount"].bq_type, exact);
+
+        let mut generator = SchemaGenerator::new(config);
+        let mut schema_map = SchemaMap::new();
+        let input = "[\n  {\"amount\": 12345678901234567.89},\n  {\"amount\": 1.5}\n]\n";
+        let mut reader = crate::input::JsonReader::new(std::io::Cursor::new(input));
+        while let crate::input::json::JsonLineResult::Record(result) = reader
+            .read_raw_record(|_, record| generator.process_raw_record(&record, &mut schema_map))
+            .unwrap()
+        {
+            result.unwrap();
+        }
+        assert_eq!(reader.layout(), JsonLayout::Stream);
+        assert_eq!(schema_map["amount"].bq_type, exact);
+    }
+
+    #[test]
+    fn test_infer_bytes_by_length_and_field_pattern() {
+        let config = GeneratorConfig {
+            bytes_min_length: Some(16),
+            bytes_field_pattern: Some(Regex::new("_b64$").unwrap()),
+            ..Default::default()
+        };
+        let mut generator = SchemaGenerator::new(config);
+        let mut schema_map = SchemaMap::new();
+
+        let record = json!({
+            "blob": "aGVsbG8gd29ybGQgYmFzZTY0IGRhdGE=",
+            "short": "dGVzdA==",
+            "key_b64": "dGVzdA==",
+            "tags_b64": ["YQ==", "Yg=="],
+            "name": "hello world"
+        });
+        generator.process_record(&record, &mut schema_map).unwrap();
+
+        assert_eq!(schema_map.get("blob").unwrap().bq_type, BqType::Bytes);
+        assert_eq!(schema_map.get("short").unwrap().bq_type, BqType::String);
+        assert_eq!(schema_map.get("key_b64").unwrap().bq_type, BqType::Bytes);
+        assert_eq!(schema_map.get("tags_b64").unwrap().bq_type, BqType::Bytes);
+        assert_eq!(schema_map.get("tags_b64").unwrap().mode, BqMode::Repeated);
+        assert_eq!(schema_map.get("name").unwrap().bq_type, BqType::String);
+
+        // A non-base64 value widens the column to STRING
+        let record = json!({"key_b64": "not base64!"});
+        generator.process_record(&record, &mut schema_map).unwrap();
+        assert_eq!(schema_map.get("key_b64").unwrap().bq_type, BqType::String);
+    }
+
+    #[test]
+    fn test_existing_bytes_fields_are_kept() {
+        let mut generator = SchemaGenerator::new(GeneratorConfig::default());
+        let mut schema_map = crate::schema::bq_schema_to_map(&json!([
+            {"name": "payload", "type": "BYTES"},
+            {"name": "meta", "type": "RECORD", "fields": [
+                {"name": "digest", "type": "BYTES"}
+            ]}
+        ]))
+        .unwrap();
+
+        let record = json!({"payload": "1234", "meta": {"digest": "YWJj"}});
+        generator.process_record(&record, &mut schema_map).unwrap();
+
+        let schema = generator.flatten_schema(&schema_map);
+        assert_eq!(schema[0].name, "meta");
+        assert_eq!(schema[0].fields.as_ref().unwrap()[0].field_type, "BYTES");
+        assert_eq!(schema[1].field_type, "BYTES");
+    }
+
+    #[test]
+    fn test_known_schema_is_seen_but_not_merged() {
+        let known = crate::schema::bq_schema_to_map(&json!([
+            {"name": "payload", "type": "BYTES"},
+            {"name": "meta", "type": "RECORD", "fields": [
+                {"name": "digest", "type": "BYTES"}
+            ]},
+            {"name": "legacy", "type": "STRING"}
+        ]))
+        .unwrap();
+        let record = r#"{"payload": "1234", "meta": {"digest": "YWJj"}}"#;
+
+        for raw in [false, true] {
+            let mut generator = SchemaGenerator::new(GeneratorConfig::default());
+            generator.set_known_schema(known.clone());
+            let mut schema_map = SchemaMap::new();
+            if raw {
+                let record = crate::input::RawRecord::parse(record).unwrap();
+                generator
+                    .process_raw_record(&record, &mut schema_map)
+                    .unwrap();
+            } else {
+                let record: serde_json::Value = serde_json::from_str(record).unwrap();
+                generator.process_record(&record, &mut schema_map).unwrap();
+            }
+
+            assert!(!schema_map.contains_key("legacy"), "raw: {}", raw);
+            assert_eq!(schema_map["payload"].bq_type, BqType::Bytes, "raw: {}", raw);
+            let schema = generator.flatten_schema(&schema_map);
+            assert_eq!(schema[0].fields.as_ref().unwrap()[0].field_type, "BYTES");
+        }
+    }
+
+    #[test]
+    fn test_existing_geography_fields_are_kept() {
+        let existing = crate::schema::bq_schema_to_map(&json!([
+            {"name": "shape", "type": "GEOGRAPHY"},
+            {"name": "area", "type": "GEOGRAPHY"},
+            {"name": "note", "type": "STRING"}
+        ]))
+        .unwrap();
+        let record = r#"{"shape": "POINT(1 2)", "area": {"type": "Point", "coordinates": [1, 2]}, "note": "POINT(3 4)"}"#;
+
+        // Without --infer-geography, only the known GEOGRAPHY fields detect geometries
+        for raw in [false, true] {
+            let mut generator = SchemaGenerator::new(GeneratorConfig::default());
+            let mut schema_map = existing.clone();
+            if raw {
+                let record = crate::input::RawRecord::parse(record).unwrap();
+                generator
+                    .process_raw_record(&record, &mut schema_map)
+                    .unwrap();
+            } else {
+                let record: serde_json::Value = serde_json::from_str(record).unwrap();
+                generator.process_record(&record, &mut schema_map).unwrap();
+            }
+
+            assert!(generator.conflicts().is_empty(), "raw: {}", raw);
+            let types: Vec<_> = generator
+                .flatten_schema(&schema_map)
+                .into_iter()
+                .map(|field| (field.name, field.field_type))
+                .collect();
+            assert_eq!(
+                types,
+                vec![
+                    ("area".to_string(), "GEOGRAPHY".to_string()),
+                    ("note".to_string(), "STRING".to_string()),
+                    ("shape".to_string(), "GEOGRAPHY".to_string()),
+                ],
+                "raw: {}",
+                raw
+            );
+        }
+    }
+
+    #[test]
+    fn test_infer_geography() {
+        let config = GeneratorConfig {
+            infer_geography: true,
+            ..Default::default()
+        };
+        let mut generator = SchemaGenerator::new(config);
+        let mut schema_map = SchemaMap::new();
+
+        let records = [
+            json!({"wkt": "POINT(-122.3 47.6)", "geo": {"type": "Point", "coordinates": [-122.3, 47.6]}}),
+            json!({"wkt": "LINESTRING(0 0, 1 1)", "geo": {"type": "Polygon", "coordinates": [[[0, 0], [1, 0], [1, 1], [0, 0]]]}}),
+        ];
+        for record in &records {
+            generator.process_record(record, &mut schema_map).unwrap();
+        }
+
+        let schema = generator.flatten_schema(&schema_map);
+        assert_eq!(schema.len(), 2);
+        assert!(schema.iter().all(|f| f.field_type == "GEOGRAPHY"));
+        assert!(schema.iter().all(|f| f.fields.is_none()));
+
+        // Without the option, GeoJSON stays a RECORD
+        let mut generator = SchemaGenerator::default_config();
+        let mut schema_map = SchemaMap::new();
+        generator
+            .process_record(&records[0], &mut schema_map)
+            .unwrap();
+        let schema = generator.flatten_schema(&schema_map);
+        assert_eq!(schema[0].field_type, "RECORD");
+        assert_eq!(schema[1].field_type, "STRING");
+    }
+
+    fn generate_with(config: GeneratorConfig, records: &[serde_json::Value]) -> SchemaMap {
+        let mut generator = SchemaGenerator::new(config);
+        let mut schema_map = SchemaMap::new();
+        for record in records {
+            generator.process_record(record, &mut schema_map).unwrap();
+        }
+        schema_map
+    }
+
+    #[test]
+    fn test_epoch_detection() {
+        let records = [
+            json!({"created_at": 1700000000, "updated_ms": 1700000000123_i64, "count": 7, "user_id": 1234567890}),
+            json!({"created_at": 1700000001, "updated_ms": 1700000000456_i64, "count": 9, "user_id": 1234567891}),
+        ];
+
+        // Off by default
+        let schema_map = generate_with(GeneratorConfig::default(), &records);
+        assert_eq!(
+            schema_map.get("created_at").unwrap().bq_type,
+            BqType::Integer
+        );
+
+        // Every field in range
+        let config = GeneratorConfig {
+            epoch_detection: Some(EpochDetection::default()),
+            ..Default::default()
+        };
+        let schema_map = generate_with(config, &records);
+        assert_eq!(
+            schema_map.get("created_at").unwrap().bq_type,
+            BqType::Timestamp
+        );
+        assert_eq!(
+            schema_map.get("updated_ms").unwrap().bq_type,
+            BqType::Timestamp
+        );
+        assert_eq!(schema_map.get("count").unwrap().bq_type, BqType::Integer);
+        assert_eq!(
+            schema_map.get("user_id").unwrap().bq_type,
+            BqType::Timestamp
+        );
+
+        // Only fields matching the pattern
+        let config = GeneratorConfig {
+            epoch_detection: Some(EpochDetection::default()),
+            epoch_field_pattern: Some(Regex::new("_(at|ts|ms)$").unwrap()),
+            ..Default::default()
+        };
+        let schema_map = generate_with(config, &records);
+        assert_eq!(
+            schema_map.get("created_at").unwrap().bq_type,
+            BqType::Timestamp
+        );
+        assert_eq!(
+            schema_map.get("updated_ms").unwrap().bq_type,
+            BqType::Timestamp
+        );
+        assert_eq!(schema_map.get("user_id").unwrap().bq_type, BqType::Integer);
+    }
+
+    #[test]
+    fn test_epoch_detection_known_timestamp_field() {
+        // A field already typed TIMESTAMP accepts epochs even without a pattern match
+        let config = GeneratorConfig {
+            epoch_detection: Some(EpochDetection::default()),
+            epoch_field_pattern: Some(Regex::new("_at$").unwrap()),
+            ..Default::default()
+        };
+        let records = [
+            json!({"event": "2024-01-15T10:30:00Z"}),
+            json!({"event": 1700000000}),
+        ];
+        let schema_map = generate_with(config, &records);
+        let entry = schema_map.get("event").unwrap();
+        assert_eq!(entry.status, EntryStatus::Hard);
+        assert_eq!(entry.bq_type, BqType::Timestamp);
+    }
+
+    #[test]
+    fn test_custom_datetime_formats() {
+        let config = GeneratorConfig {
+            datetime_formats: DateTimeFormats::from_specs(&[
+                "DATE=%m/%d/%Y",
+                "TIMESTAMP@vendor.*=%d.%m.%Y %H:%M",
+            ])
+            .unwrap(),
+            ..Default::default()
+        };
+        let records = [
+            json!({"day": "03/15/2024", "note": "15.03.2024 14:22", "vendor": {"seen": "15.03.2024 14:22", "days": ["03/15/2024"]}}),
+            json!({"day": "2024-03-16", "note": "15.03.2024 09:00", "vendor": {"seen": "16.03.2024 09:00", "days": []}}),
+        ];
+        let schema_map = generate_with(config, &records);
+
+        // Custom formats merge with the built-in ones
+        assert_eq!(schema_map.get("day").unwrap().bq_type, BqType::Date);
+        // Scoped formats only apply to matching paths
+        assert_eq!(schema_map.get("note").unwrap().bq_type, BqType::String);
+        let BqType::Record(vendor) = &schema_map.get("vendor").unwrap().bq_type else {
+            panic!("vendor should be a RECORD");
+        };
+        assert_eq!(vendor.get("seen").unwrap().bq_type, BqType::Timestamp);
+        let days = vendor.get("days").unwrap();
+        assert_eq!(days.bq_type, BqType::Date);
+        assert_eq!(days.mode, BqMode::Repeated);
+    }
+
+    #[test]
+    fn test_conflict_policy_from_str() {
+        assert_eq!("ignore".parse(), Ok(ConflictPolicy::Ignore));
+        assert_eq!("STRING".parse(), Ok(ConflictPolicy::String));
+        assert_eq!("json".parse(), Ok(ConflictPolicy::Json));
+        assert_eq!("fail".parse(), Ok(ConflictPolicy::Fail));
+        assert_eq!("keep-first".parse(), Ok(ConflictPolicy::KeepFirst));
+        assert_eq!("keep_first".parse(), Ok(ConflictPolicy::KeepFirst));
+        assert!("widen".parse::<ConflictPolicy>().is_err());
+    }
+
+    #[test]
+    fn test_conflict_policy_ignore_drops_field() {
+        let records = [json!({"value": 1, "tags": [[1]]}), json!({"value": "a"})];
+        let schema_map = generate_with(GeneratorConfig::default(), &records);
+
+        assert_eq!(schema_map.get("value").unwrap().status, EntryStatus::Ignore);
+        assert!(schema_map.get("tags").is_none());
+    }
+
+    #[test]
+    fn test_conflict_policy_string() {
+        let config = GeneratorConfig {
+            conflict_policy: ConflictPolicy::String,
+            ..Default::default()
+        };
+        let records = [
+            json!({"value": 1, "flag": true, "mixed": [1, "a"], "rec": {"a": 1}}),
+            json!({"value": "a", "flag": 2, "rec": 5}),
+            json!({"value": 3.5}),
+        ];
+        let schema_map = generate_with(config, &records);
+
+        let value = schema_map.get("value").unwrap();
+        assert_eq!(value.bq_type, BqType::String);
+        assert_eq!(value.status, EntryStatus::Hard);
+        assert_eq!(schema_map.get("flag").unwrap().bq_type, BqType::String);
+
+        let mixed = schema_map.get("mixed").unwrap();
+        assert_eq!(mixed.bq_type, BqType::String);
+        assert_eq!(mixed.mode, BqMode::Repeated);
+
+        // Records cannot be widened to STRING
+        assert_eq!(schema_map.get("rec").unwrap().status, EntryStatus::Ignore);
+    }
+
+    #[test]
+    fn test_conflict_policy_json() {
+        let config = GeneratorConfig {
+            conflict_policy: ConflictPolicy::Json,
+            ..Default::default()
+        };
+        let records = [
+            json!({"value": 1, "rec": {"a": 1}, "matrix": [[1, 2], [3]], "items": [1, 2]}),
+            json!({"value": "a", "rec": [{"b": 2}, 3], "items": 5}),
+            json!({"value": {"nested": true}, "rec": null}),
+        ];
+        let schema_map = generate_with(config, &records);
+
+        for name in ["value", "rec", "matrix", "items"] {
+            let entry = schema_map.get(name).unwrap();
+            assert_eq!(entry.bq_type, BqType::Json, "{}", name);
+            assert_eq!(entry.mode, BqMode::Nullable, "{}", name);
+            assert_eq!(entry.status, EntryStatus::Hard, "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_conflict_policy_fail() {
+        let config = GeneratorConfig {
+            conflict_policy: ConflictPolicy::Fail,
+            ..Default::default()
+        };
+        let mut generator = SchemaGenerator::new(config);
+        let mut schema_map = SchemaMap::new();
+
+        generator
+            .process_record(&json!({"a": {"b": 1}, "d": 1}), &mut schema_map)
+            .unwrap();
+        let before = schema_map.clone();
+        let err = generator
+            .process_record(
+                &json!({"d": null, "a": {"b": "x"}, "e": 1}),
+                &mut schema_map,
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::FieldConflict { line: 2, ref path, .. } if path == "a.b"
+        ));
+        // The failed record leaves the schema untouched
+        assert_eq!(schema_map, before);
+        assert!(schema_map.keys().eq(before.keys()));
+
+        let err = generator
+            .process_record(&json!({"c": [[1]]}), &mut schema_map)
+            .unwrap_err();
+        assert!(matches!(err, Error::FieldConflict { line: 3, .. }));
+        assert!(generator.conflicts().is_empty());
+    }
+
+    #[test]
+    fn test_conflict_policy_fail_restores_touched_fields() {
+        let config = GeneratorConfig {
+            conflict_policy: ConflictPolicy::Fail,
+            sanitize_names: true,
+            ..Default::default()
+        };
+        let mut generator = SchemaGenerator::new(config);
+        let mut schema_map = SchemaMap::new();
+
+        generator
+            .process_record(
+                &json!({"a": 1, "x_y": null, "r": {"n": 1}, "c": true}),
+                &mut schema_map,
+            )
+            .unwrap();
+        let before = schema_map.clone();
+        // New fields, a field set twice and a changed record come before the conflict
+        let err = generator
+            .process_record(
+                &json!({"new": 1, "x-y": 1, "x_y": 2.5, "r": {"m": "s"}, "c": 1}),
+                &mut schema_map,
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::FieldConflict { ref path, .. } if path == "c"
+        ));
+        assert_eq!(schema_map, before);
+        assert!(schema_map.keys().eq(before.keys()));
+
+        // The schema carries on from where it was
+        generator
+            .process_record(&json!({"x_y": 1}), &mut schema_map)
+            .unwrap();
+        assert_eq!(schema_map["x_y"].bq_type, BqType::Integer);
+        assert!(!schema_map.contains_key("new"));
+    }
+
+    #[test]
+    fn test_conflict_policy_keep_first() {
+        let config = GeneratorConfig {
+            conflict_policy: ConflictPolicy::KeepFirst,
+            ..Default::default()
+        };
+        let records = [
+            json!({"value": 1, "rec": {"a": 1}, "ids": []}),
+            json!({"value": "a", "rec": 5, "ids": 3}),
+            json!({"value": true, "rec": {"b": 2}, "ids": "x"}),
+        ];
+        let mut generator = SchemaGenerator::new(config);
+        let mut schema_map = SchemaMap::new();
+        for record in &records {
+            generator.process_record(record, &mut schema_map).unwrap();
+        }
+
+        let value = schema_map.get("value").unwrap();
+        assert_eq!(value.bq_type, BqType::Integer);
+        assert_eq!(value.status, EntryStatus::Hard);
+
+        let BqType::Record(fields) = &schema_map.get("rec").unwrap().bq_type else {
+            panic!("rec should stay a RECORD");
+        };
+        assert_eq!(fields.len(), 2);
+
+        // An empty array has no type yet, so the first real value wins
+        let ids = schema_map.get("ids").unwrap();
+        assert_eq!(ids.bq_type, BqType::Integer);
+        assert_eq!(ids.mode, BqMode::Nullable);
+        assert_eq!(ids.status, EntryStatus::Hard);
+
+        let counts: Vec<_> = generator
+            .conflicts()
+            .iter()
+            .map(|c| (c.path.as_str(), c.resolution, c.count))
+            .collect();
+        assert_eq!(
+            counts,
+            vec![
+                ("value", ConflictResolution::KeptFirst, 2),
+                ("rec", ConflictResolution::KeptFirst, 1),
+                ("ids", ConflictResolution::KeptFirst, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_conflicts_count_dropped_values() {
+        let mut generator = SchemaGenerator::new(GeneratorConfig::default());
+        let mut schema_map = SchemaMap::new();
+        for record in [
+            json!({"value": 1, "tags": [1, "a"]}),
+            json!({"value": "a", "tags": [[1]]}),
+            json!({"value": 2}),
+            json!({"value": null}),
+        ] {
+            generator.process_record(&record, &mut schema_map).unwrap();
+        }
+
+        assert_eq!(
+            generator.conflicts(),
+            &[
+                FieldConflict {
+                    path: "tags".to_string(),
+                    resolution: ConflictResolution::Dropped,
+                    count: 2,
+                },
+                FieldConflict {
+                    path: "value".to_string(),
+                    resolution: ConflictResolution::Dropped,
+                    count: 2,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_json_policy_keeps_repeated_mode() {
+        let config = GeneratorConfig {
+            conflict_policy: ConflictPolicy::Json,
+            ..Default::default()
+        };
+        let records = [json!({"ids": [1, 2]}), json!({"ids": ["a"]})];
+        let schema_map = generate_with(config, &records);
+
+        let ids = schema_map.get("ids").unwrap();
+        assert_eq!(ids.bq_type, BqType::Json);
+        assert_eq!(ids.mode, BqMode::Repeated);
+    }
+
+    #[test]
+    fn test_json_paths() {
+        let config = GeneratorConfig {
+            json_paths: vec!["Payload".to_string(), "meta.attributes".to_string()],
+            ..Default::default()
+        };
+        let records = [
+            json!({"payload": {"a": 1}, "meta": {"attributes": {"x": 1}, "id": 1}}),
+            json!({"payload": [[1], "two"], "meta": {"attributes": null, "id": 2}}),
+        ];
+        let schema_map = generate_with(config.clone(), &records);
+
+        assert_eq!(schema_map.get("payload").unwrap().bq_type, BqType::Json);
+        let generator = SchemaGenerator::new(config);
+        let schema = generator.flatten_schema(&schema_map);
+        let meta = schema.iter().find(|f| f.name == "meta").unwrap();
+        let fields = meta.fields.as_ref().unwrap();
+        assert_eq!(fields[0].name, "attributes");
+        assert_eq!(fields[0].field_type, "JSON");
+        assert_eq!(fields[1].field_type, "INTEGER");
+    }
+
+    #[test]
+    fn test_type_hints_force_type_and_mode() {
+        let config = GeneratorConfig {
+            type_hints: TypeHints::from_json_str(
+                r#"{
+                    "zip_code": "STRING",
+                    "*_ts": "TIMESTAMP",
+                    "user.tags": {"mode": "REPEATED"},
+                    "user.id": {"type": "STRING", "mode": "REQUIRED"}
+                }"#,
+            )
+            .unwrap(),
+            ..Default::default()
+        };
+        let schema_map = generate_with(
+            config.clone(),
+            &[
+                json!({"zip_code": 94103, "event_ts": 1700000000, "user": {"id": 7, "tags": "a"}}),
+                json!({"zip_code": null, "event_ts": 1700000001, "user": {"id": null, "tags": ["b"]}}),
+            ],
+        );
+
+        let generator = SchemaGenerator::new(config);
+        let fields = generator.flatten_schema(&schema_map);
+        let summary: Vec<_> = fields
+            .iter()
+            .map(|f| (f.name.as_str(), f.field_type.as_str(), f.mode.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("event_ts", "TIMESTAMP", "NULLABLE"),
+                ("user", "RECORD", "NULLABLE"),
+                ("zip_code", "STRING", "NULLABLE"),
+            ]
+        );
+        let user: Vec<_> = fields[1]
+            .fields
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|f| (f.name.as_str(), f.field_type.as_str(), f.mode.as_str()))
+            .collect();
+        assert_eq!(
+            user,
+            vec![("id", "STRING", "REQUIRED"), ("tags", "STRING", "REPEATED")]
+        );
+    }
+
+    #[test]
+    fn test_type_hints_json_keeps_arrays_whole() {
+        let config = GeneratorConfig {
+            type_hints: TypeHints::from_json_str(r#"{"payload": "JSON"}"#).unwrap(),
+            ..Default::default()
+        };
+        let schema_map = generate_with(
+            config,
+            &[json!({"payload": [[1], "a"]}), json!({"payload": {"k": 1}})],
+        );
+        let entry = schema_map.get("payload").unwrap();
+        assert_eq!(entry.bq_type, BqType::Json);
+        assert_eq!(entry.mode, BqMode::Nullable);
+    }
+
+    #[test]
+    fn test_provenance_disabled_by_default() {
+        let mut generator = SchemaGenerator::new(GeneratorConfig::default());
+        let mut schema_map = SchemaMap::new();
+        generator
+            .process_record(&json!({"a": 1}), &mut schema_map)
+            .unwrap();
+        assert!(generator.provenance().is_empty());
+    }
+
+    #[test]
+    fn test_provenance_tracks_changes_per_file() {
+        let config = GeneratorConfig {
+            track_provenance: true,
+            ..Default::default()
+        };
+        let mut generator = SchemaGenerator::new(config);
+        let mut schema_map = crate::schema::bq_schema_to_map(&json!([
+            {"name": "legacy", "type": "INTEGER"}
+        ]))
+        .unwrap();
+
+        generator.set_source_file(Some("a.json".to_string()));
+        for record in [json!({"Tags": null}), json!({"tags": ["x"], "legacy": 1.5})] {
+            generator.process_record(&record, &mut schema_map).unwrap();
+        }
+        generator.set_source_file(Some("b.json".to_string()));
+        generator
+            .process_record(&json!({"tags": "y"}), &mut schema_map)
+            .unwrap();
+
+        let tags = generator.provenance().get("tags").unwrap();
+        assert_eq!(tags.path, "Tags");
+        assert_eq!(
+            tags.first_seen,
+            Some(SourceLocation {
+                file: Some("a.json".to_string()),
+                line: 1,
+            })
+        );
+        assert_eq!(tags.initial, "NULLABLE STRING (soft)");
+        let changes: Vec<_> = tags
+            .changes
+            .iter()
+            .map(|c| (c.location.to_string(), c.to.as_str()))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                ("a.json:2".to_string(), "REPEATED STRING"),
+                ("b.json:1".to_string(), "ignored"),
+            ]
+        );
+
+        // Fields from an existing schema have no first appearance
+        let legacy = generator.provenance().get("legacy").unwrap();
+        assert_eq!(legacy.first_seen, None);
+        assert_eq!(legacy.initial, "NULLABLE INTEGER");
+        assert_eq!(legacy.changes[0].to, "NULLABLE FLOAT");
+    }
+
+    #[test]
+    fn test_collect_stats_per_path() {
+        let config = GeneratorConfig {
+            collect_stats: true,
+            ..Default::default()
+        };
+        let mut generator = SchemaGenerator::new(config);
+        let mut schema_map = SchemaMap::new();
+        for record in [
+            json!({"id": 3, "user": {"Name": "ann"}, "items": [{"qty": 2}, {"qty": 7}]}),
+            json!({"id": 1, "user": null, "items": []}),
+        ] {
+            generator.process_record(&record, &mut schema_map).unwrap();
+        }
+
+        let profile = generator.profile();
+        assert_eq!(profile.record_count, 2);
+        let paths: Vec<_> = profile.fields.values().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["id", "user", "user.Name", "items", "items.qty"]);
+
+        let id = &profile.fields["id"];
+        assert_eq!(id.min(), Some(json!(1)));
+        assert_eq!(id.max(), Some(json!(3)));
+        assert_eq!(profile.fields["user"].null_count, 1);
+        assert_eq!(profile.fields["items"].max_array_length, Some(2));
+        assert_eq!(profile.fields["items.qty"].presence_count, 2);
+    }
+
+    #[test]
+    fn test_collect_stats_disabled_by_default() {
+        let mut generator = SchemaGenerator::new(GeneratorConfig::default());
+        let mut schema_map = SchemaMap::new();
+        generator
+            .process_record(&json!({"a": 1}), &mut schema_map)
+            .unwrap();
+        assert!(generator.profile().fields.is_empty());
+    }
+
+    #[test]
+    fn test_existing_json_field_absorbs_any_value() {
+        let mut generator = SchemaGenerator::new(GeneratorConfig::default());
+        let mut schema_map = crate::schema::bq_schema_to_map(&json!([
+            {"name": "payload", "type": "JSON"}
+        ]))
+        .unwrap();
+
+        for record in [json!({"payload": 1}), json!({"payload": [{"a": [1]}]})] {
+            generator.process_record(&record, &mut schema_map).unwrap();
+        }
+
+        let entry = schema_map.get("payload").unwrap();
+        assert_eq!(entry.bq_type, BqType::Json);
+        assert_eq!(entry.mode, BqMode::Nullable);
+        assert_eq!(entry.status, EntryStatus::Hard);
+    }
+
+    #[test]
+    fn test_merge_schema_maps_matches_single_pass() {
+        let csv_required = GeneratorConfig {
+            input_format: InputFormat::Csv,
+            infer_mode: true,
+            ..Default::default()
+        };
+        let cases = [
+            // Ignored in the first file, so it must not come back
+            (
+                GeneratorConfig::default(),
+                vec![json!({"a": 1}), json!({"a": true})],
+                vec![json!({"a": 2})],
+            ),
+            // Quoted values keep their quoted types
+            (
+                GeneratorConfig::default(),
+                vec![json!({"q": "true"})],
+                vec![json!({"q": "1"})],
+            ),
+            // An empty CSV cell in the second file makes the field NULLABLE
+            (
+                csv_required.clone(),
+                vec![json!({"x": "1"})],
+                vec![json!({"x": ""})],
+            ),
+            (
+                csv_required,
+                vec![json!({"x": "1"})],
+                vec![json!({"x": "2"})],
+            ),
+        ];
+
+        for (config, first, second) in cases {
+            let mut single = SchemaGenerator::new(config.clone());
+            let mut single_map = SchemaMap::new();
+            for record in first.iter().chain(&second) {
+                single.process_record(record, &mut single_map).unwrap();
+            }
+
+            let mut merged = SchemaGenerator::new(config.clone());
+            let mut merged_map = SchemaMap::new();
+            for records in [&first, &second] {
+                let mut generator = SchemaGenerator::new(config.clone());
+                let mut schema_map = SchemaMap::new();
+                for record in records {
+                    generator.process_record(record, &mut schema_map).unwrap();
+                }
+                merged
+                    .merge_schema_maps(&mut merged_map, schema_map)
+                    .unwrap();
+            }
+
+            assert_eq!(
+                serde_json::to_value(merged.flatten_schema(&merged_map)).unwrap(),
+                serde_json::to_value(single.flatten_schema(&single_map)).unwrap(),
+                "{:?} then {:?}",
+                first,
+                second
+            );
+            assert_eq!(merged_map, single_map);
+        }
+    }
+
+    #[test]
+    fn test_merge_schema_maps_fail_policy() {
+        let mut generator = SchemaGenerator::new(GeneratorConfig {
+            conflict_policy: ConflictPolicy::Fail,
+            ..Default::default()
+        });
+        let mut target = SchemaMap::new();
+        generator
+            .process_record(&json!({"a": 1}), &mut target)
+            .unwrap();
+
+        let mut source = SchemaMap::new();
+        SchemaGenerator::new(GeneratorConfig::default())
+            .process_record(&json!({"b": 1, "a": true}), &mut source)
+            .unwrap();
+        let before = target.clone();
+        let err = generator
+            .merge_schema_maps(&mut target, source)
+            .unwrap_err();
+        assert!(matches!(err, Error::FieldConflict { .. }));
+        assert_eq!(target, before);
+    }
 }
@@ -0,0 +1,297 @@
+//! Type hints - forced types and modes for field paths.
+//!
+//! A hints file maps dotted field paths or glob patterns to a type and/or
+//! mode. The generator uses a hinted type instead of inferring one, and the
+//! validator reports values that don't satisfy it.
+//!
+//! ```json
+//! {
+//!   "zip_code": "STRING",
+//!   "event_ts": {"type": "TIMESTAMP"},
+//!   "*.id": {"type": "STRING", "mode": "REQUIRED"},
+//!   "tags": {"mode": "REPEATED"}
+//! }
+//! ```
+//!
+//! The same mapping can be written in TOML; dotted paths must be quoted
+//! keys (`"user.zip" = "STRING"`).
+
+use std::fs;
+use std::path::Path;
+
+use glob::Pattern;
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use crate::error::{Error, Result};
+use crate::schema::existing::parse_scalar_type;
+use crate::schema::types::{BqMode, BqType};
+
+/// A forced type and/or mode for the field paths matching `pattern`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeHint {
+    /// Dotted path or glob pattern, as written in the hints file
+    pub pattern: String,
+    /// Forced type, or None to infer it
+    pub bq_type: Option<BqType>,
+    /// Forced mode, or None to infer it
+    pub mode: Option<BqMode>,
+}
+
+/// A set of type hints.
+///
+/// Paths match case-insensitively. An exact path takes precedence over glob
+/// patterns, which are tried in file order; `*` also matches across dots.
+#[derive(Debug, Clone, Default)]
+pub struct TypeHints {
+    /// Hints for exact paths, keyed by lowercased path
+    exact: IndexMap<String, TypeHint>,
+    /// Hints for glob patterns, with the lowercased compiled pattern
+    globs: Vec<(Pattern, TypeHint)>,
+}
+
+/// A hint as written in the file: a type name or a table with `type` and `mode`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum HintSpec {
+    Type(String),
+    Full(HintFields),
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct HintFields {
+    #[serde(rename = "type")]
+    bq_type: Option<String>,
+    mode: Option<String>,
+}
+
+impl TypeHints {
+    /// Create an empty set of hints.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns true if there are no hints.
+    pub fn is_empty(&self) -> bool {
+        self.exact.is_empty() && self.globs.is_empty()
+    }
+
+    /// Add a hint, replacing any earlier hint for the same pattern.
+    pub fn insert(&mut self, hint: TypeHint) -> Result<()> {
+        if hint.bq_type.is_none() && hint.mode.is_none() {
+            return Err(Error::TypeHints(format!(
+                "Hint for '{}' must set a type or a mode",
+                hint.pattern
+            )));
+        }
+
+        let key = hint.pattern.to_lowercase();
+        if !key.contains(['*', '?', '[']) {
+            self.exact.insert(key, hint);
+            return Ok(());
+        }
+
+        let pattern = Pattern::new(&key)
+            .map_err(|e| Error::TypeHints(format!("Invalid pattern '{}': {}", hint.pattern, e)))?;
+        match self.globs.iter_mut().find(|(p, _)| *p == pattern) {
+            Some(slot) => slot.1 = hint,
+            None => self.globs.push((pattern, hint)),
+        }
+        Ok(())
+    }
+
+    /// Find the hint for a dotted field path, if any.
+    pub fn lookup(&self, path: &str) -> Option<&TypeHint> {
+        let path = path.to_lowercase();
+        self.exact.get(&path).or_else(|| {
+            self.globs
+                .iter()
+                .find(|(pattern, _)| pattern.matches(&path))
+                .map(|(_, hint)| hint)
+        })
+    }
+
+    /// Parse hints from a JSON object.
+    pub fn from_json_str(content: &str) -> Result<Self> {
+        let specs: IndexMap<String, HintSpec> = serde_json::from_str(content)
+            .map_err(|e| Error::TypeHints(format!("Cannot parse hints JSON: {}", e)))?;
+        Self::from_specs(specs)
+    }
+
+    /// Parse hints from a TOML table.
+    pub fn from_toml_str(content: &str) -> Result<Self> {
+        let specs: IndexMap<String, HintSpec> = toml::from_str(content)
+            .map_err(|e| Error::TypeHints(format!("Cannot parse hints TOML: {}", e)))?;
+        Self::from_specs(specs)
+    }
+
+    /// Read hints from a file; `.toml` files are parsed as TOML, anything else as JSON.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .map_err(|e| Error::TypeHints(format!("Cannot open hints file: {}", e)))?;
+
+        let is_toml = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("toml"));
+        if is_toml {
+            Self::from_toml_str(&content)
+        } else {
+            Self::from_json_str(&content)
+        }
+    }
+
+    fn from_specs(specs: IndexMap<String, HintSpec>) -> Result<Self> {
+        let mut hints = Self::new();
+        for (pattern, spec) in specs {
+            let (bq_type, mode) = match spec {
+                HintSpec::Type(bq_type) => (Some(bq_type), None),
+                HintSpec::Full(fields) => (fields.bq_type, fields.mode),
+            };
+            let bq_type = bq_type
+                .map(|name| parse_hint_type(&pattern, &name))
+                .transpose()?;
+            let mode = mode
+                .map(|name| parse_hint_mode(&pattern, &name))
+                .transpose()?;
+            hints.insert(TypeHint {
+                pattern,
+                bq_type,
+                mode,
+            })?;
+        }
+        Ok(hints)
+    }
+}
+
+fn parse_hint_type(pattern: &str, name: &str) -> Result<BqType> {
+    parse_scalar_type(name)
+        .map_err(|_| Error::TypeHints(format!("Invalid type '{}' for '{}'", name, pattern)))
+}
+
+fn parse_hint_mode(pattern: &str, name: &str) -> Result<BqMode> {
+    match name.to_uppercase().as_str() {
+        "NULLABLE" => Ok(BqMode::Nullable),
+        "REQUIRED" => Ok(BqMode::Required),
+        "REPEATED" => Ok(BqMode::Repeated),
+        _ => Err(Error::TypeHints(format!(
+            "Invalid mode '{}' for '{}'",
+            name, pattern
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_json_str() {
+        let hints = TypeHints::from_json_str(
+            r#"{
+                "zip_code": "string",
+                "Event_TS": {"type": "TIMESTAMP"},
+                "tags": {"mode": "REPEATED"},
+                "amount": {"type": "DECIMAL", "mode": "REQUIRED"}
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            hints.lookup("zip_code").unwrap().bq_type,
+            Some(BqType::String)
+        );
+        let event_ts = hints.lookup("event_ts").unwrap();
+        assert_eq!(event_ts.pattern, "Event_TS");
+        assert_eq!(event_ts.bq_type, Some(BqType::Timestamp));
+        assert_eq!(event_ts.mode, None);
+        assert_eq!(hints.lookup("tags").unwrap().mode, Some(BqMode::Repeated));
+        assert_eq!(
+            hints.lookup("amount").unwrap().bq_type,
+            Some(BqType::Numeric {
+                precision: 38,
+                scale: 9
+            })
+        );
+        assert!(hints.lookup("other").is_none());
+    }
+
+    #[test]
+    fn test_from_toml_str() {
+        let hints = TypeHints::from_toml_str(
+            r#"
+            zip_code = "STRING"
+            "user.id" = { type = "STRING", mode = "REQUIRED" }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            hints.lookup("zip_code").unwrap().bq_type,
+            Some(BqType::String)
+        );
+        let id = hints.lookup("User.Id").unwrap();
+        assert_eq!(id.bq_type, Some(BqType::String));
+        assert_eq!(id.mode, Some(BqMode::Required));
+    }
+
+    #[test]
+    fn test_glob_patterns() {
+        let hints = TypeHints::from_json_str(
+            r#"{"*_ts": "TIMESTAMP", "*.id": "STRING", "order.id": "INTEGER"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            hints.lookup("created_ts").unwrap().bq_type,
+            Some(BqType::Timestamp)
+        );
+        assert_eq!(
+            hints.lookup("user.address.id").unwrap().bq_type,
+            Some(BqType::String)
+        );
+        // An exact path wins over a matching glob
+        assert_eq!(
+            hints.lookup("order.id").unwrap().bq_type,
+            Some(BqType::Integer)
+        );
+        assert!(hints.lookup("id").is_none());
+    }
+
+    #[test]
+    fn test_invalid_hints() {
+        for content in [
+            r#"{"a": "VARCHAR"}"#,
+            r#"{"a": "RECORD"}"#,
+            r#"{"a": {"mode": "OPTIONAL"}}"#,
+            r#"{"a": {}}"#,
+            r#"{"a": {"typ": "STRING"}}"#,
+            r#"{"[a": "STRING"}"#,
+            r#"["a"]"#,
+        ] {
+            let result = TypeHints::from_json_str(content);
+            assert!(
+                matches!(result, Err(Error::TypeHints(_))),
+                "{}: {:?}",
+                content,
+                result
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let toml_path = dir.path().join("hints.toml");
+        fs::write(&toml_path, "zip = \"STRING\"\n").unwrap();
+        let json_path = dir.path().join("hints.json");
+        fs::write(&json_path, r#"{"zip": "STRING"}"#).unwrap();
+
+        for path in [&toml_path, &json_path] {
+            let hints = TypeHints::from_file(path).unwrap();
+            assert_eq!(hints.lookup("zip").unwrap().bq_type, Some(BqType::String));
+        }
+        assert!(TypeHints::from_file(dir.path().join("missing.json")).is_err());
+    }
+}
@@ -2,8 +2,19 @@
 
 pub mod existing;
 pub mod generator;
+pub mod hints;
+pub mod provenance;
+pub mod state;
 pub mod types;
 
 pub use existing::{bq_schema_to_map, read_existing_schema_from_file};
-pub use generator::{GeneratorConfig, InputFormat, SchemaGenerator};
+pub use generator::{
+    ConflictPolicy, ConflictResolution, FieldConflict, GeneratorConfig, InputFormat,
+    SchemaGenerator,
+};
+pub use hints::{TypeHint, TypeHints};
+pub use provenance::{FieldProvenance, Provenance, SchemaChange, SourceLocation};
+pub use state::{
+    read_schema_state, read_schema_state_from_file, write_schema_state, SCHEMA_STATE_VERSION,
+};
 pub use types::{BqMode, BqSchemaField, BqType, EntryStatus, SchemaEntry, SchemaMap};
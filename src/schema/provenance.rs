@@ -0,0 +1,100 @@
+//! Field provenance - where each field appeared and changed type or mode.
+//!
+//! When `GeneratorConfig::track_provenance` is set, the generator records for
+//! every field path the file and line where it first appeared and every
+//! point at which its mode, type or status changed.
+
+use std::fmt;
+
+use indexmap::IndexMap;
+use serde::Serialize;
+
+use crate::schema::types::{EntryStatus, SchemaEntry};
+
+/// A position in the input: an optional file and a 1-based line within it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SourceLocation {
+    /// Input file, or None for stdin and records passed in directly
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    /// Line (record) number within the file
+    pub line: usize,
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.file {
+            Some(file) => write!(f, "{}:{}", file, self.line),
+            None => write!(f, "line {}", self.line),
+        }
+    }
+}
+
+/// A change to a field's schema, e.g. a type widening or mode change.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SchemaChange {
+    /// Record that caused the change
+    pub location: SourceLocation,
+    /// Schema before the change, e.g. `NULLABLE INTEGER`
+    pub from: String,
+    /// Schema after the change
+    pub to: String,
+}
+
+/// The history of a single field path.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldProvenance {
+    /// Dotted field path as first seen
+    pub path: String,
+    /// Where the field first appeared, or None if it came from an existing schema
+    pub first_seen: Option<SourceLocation>,
+    /// Schema of the field when first seen
+    pub initial: String,
+    /// Every subsequent change, in input order
+    pub changes: Vec<SchemaChange>,
+}
+
+/// Provenance for every field path, keyed by lowercased dotted path.
+pub type Provenance = IndexMap<String, FieldProvenance>;
+
+/// Describe an entry's mode, type and status for provenance records.
+pub(crate) fn describe_entry(entry: &SchemaEntry) -> String {
+    match entry.status {
+        EntryStatus::Hard => format!("{} {}", entry.mode, entry.bq_type),
+        EntryStatus::Soft => format!("{} {} (soft)", entry.mode, entry.bq_type),
+        EntryStatus::Ignore => "ignored".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::types::{BqMode, BqType};
+
+    #[test]
+    fn test_source_location_display() {
+        let location = SourceLocation {
+            file: Some("data.json".to_string()),
+            line: 12,
+        };
+        assert_eq!(location.to_string(), "data.json:12");
+
+        let location = SourceLocation {
+            file: None,
+            line: 3,
+        };
+        assert_eq!(location.to_string(), "line 3");
+    }
+
+    #[test]
+    fn test_describe_entry() {
+        let mut entry = SchemaEntry::new("id".to_string(), BqType::Integer, BqMode::Nullable);
+        assert_eq!(describe_entry(&entry), "NULLABLE INTEGER");
+
+        entry.status = EntryStatus::Soft;
+        assert_eq!(describe_entry(&entry), "NULLABLE INTEGER (soft)");
+
+        entry.status = EntryStatus::Ignore;
+        assert_eq!(describe_entry(&entry), "ignored");
+    }
+}
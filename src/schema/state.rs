@@ -0,0 +1,459 @@
+//! Saving and restoring the schema generator's inference state.
+//!
+//! A state file holds a [`SchemaMap`] with everything the BigQuery schema
+//! output drops: soft and ignored entries, the `filled` flag behind
+//! `--infer-mode`, quoted types and decimal precision. Reading it back and
+//! merging it with [`SchemaGenerator::merge_schema_maps`] continues inference
+//! as if the records had been processed in the same run, so separate workers
+//! can each write a state and a final step can merge them.
+//!
+//! The file is a JSON object with a format `version` and the `schema` map.
+//! Types use their BigQuery names; the inference-only types use the names of
+//! the Python `bigquery-schema-generator` (`__null__`, `__empty_array__`,
+//! `__empty_record__`, `QINTEGER`, ...):
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "schema": {
+//!     "id": {"status": "hard", "filled": true, "name": "id", "type": "INTEGER", "mode": "NULLABLE"},
+//!     "price": {"status": "hard", "filled": true, "name": "price", "type": "QNUMERIC", "precision": 20, "scale": 2, "mode": "NULLABLE"},
+//!     "user": {"status": "hard", "filled": true, "name": "user", "type": "RECORD", "fields": {}, "mode": "NULLABLE"}
+//!   }
+//! }
+//! ```
+//!
+//! [`SchemaGenerator::merge_schema_maps`]: crate::schema::SchemaGenerator::merge_schema_maps
+
+use std::fs::File;
+use std::io::{BufReader, Read, Write};
+use std::path::Path;
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::schema::types::{BqMode, BqType, EntryStatus, SchemaEntry, SchemaMap};
+
+/// Version of the state format written by [`write_schema_state`].
+///
+/// Bumped whenever a change to the state format would make older states read
+/// differently; other versions are rejected.
+pub const SCHEMA_STATE_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+struct StateRef<'a> {
+    version: u32,
+    #[serde(with = "schema_map_format")]
+    schema: &'a SchemaMap,
+}
+
+#[derive(Deserialize)]
+struct StateVersion {
+    version: u32,
+}
+
+#[derive(Deserialize)]
+struct State {
+    #[serde(with = "schema_map_format")]
+    schema: SchemaMap,
+}
+
+/// A [`SchemaEntry`] as written to a state file.
+///
+/// States outlive the build that wrote them, so every name is spelled out
+/// here instead of following the Rust names of the internal types.
+#[derive(Serialize, Deserialize)]
+struct EntryState {
+    status: StatusState,
+    filled: bool,
+    name: String,
+    #[serde(flatten)]
+    bq_type: TypeState,
+    mode: ModeState,
+}
+
+#[derive(Serialize, Deserialize)]
+enum StatusState {
+    #[serde(rename = "hard")]
+    Hard,
+    #[serde(rename = "soft")]
+    Soft,
+    #[serde(rename = "ignore")]
+    Ignore,
+}
+
+#[derive(Serialize, Deserialize)]
+enum ModeState {
+    #[serde(rename = "NULLABLE")]
+    Nullable,
+    #[serde(rename = "REQUIRED")]
+    Required,
+    #[serde(rename = "REPEATED")]
+    Repeated,
+}
+
+/// A [`BqType`], tagged by its `type` name.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type")]
+enum TypeState {
+    #[serde(rename = "BOOLEAN")]
+    Boolean,
+    #[serde(rename = "INTEGER")]
+    Integer,
+    #[serde(rename = "FLOAT")]
+    Float,
+    #[serde(rename = "STRING")]
+    String,
+    #[serde(rename = "BYTES")]
+    Bytes,
+    #[serde(rename = "GEOGRAPHY")]
+    Geography,
+    #[serde(rename = "TIMESTAMP")]
+    Timestamp,
+    #[serde(rename = "DATETIME")]
+    Datetime,
+    #[serde(rename = "DATE")]
+    Date,
+    #[serde(rename = "TIME")]
+    Time,
+    #[serde(rename = "NUMERIC")]
+    Numeric { precision: u8, scale: u8 },
+    #[serde(rename = "BIGNUMERIC")]
+    BigNumeric { precision: u8, scale: u8 },
+    #[serde(rename = "RECORD")]
+    Record {
+        fields: IndexMap<String, EntryState>,
+    },
+    #[serde(rename = "JSON")]
+    Json,
+    #[serde(rename = "__null__")]
+    Null,
+    #[serde(rename = "__empty_array__")]
+    EmptyArray,
+    #[serde(rename = "__empty_record__")]
+    EmptyRecord,
+    #[serde(rename = "QBOOLEAN")]
+    QBoolean,
+    #[serde(rename = "QINTEGER")]
+    QInteger,
+    #[serde(rename = "QFLOAT")]
+    QFloat,
+    #[serde(rename = "QNUMERIC")]
+    QNumeric { precision: u8, scale: u8 },
+    #[serde(rename = "QBIGNUMERIC")]
+    QBigNumeric { precision: u8, scale: u8 },
+}
+
+impl From<&SchemaEntry> for EntryState {
+    fn from(entry: &SchemaEntry) -> Self {
+        Self {
+            status: match entry.status {
+                EntryStatus::Hard => StatusState::Hard,
+                EntryStatus::Soft => StatusState::Soft,
+                EntryStatus::Ignore => StatusState::Ignore,
+            },
+            filled: entry.filled,
+            name: entry.name.clone(),
+            bq_type: TypeState::from(&entry.bq_type),
+            mode: match entry.mode {
+                BqMode::Nullable => ModeState::Nullable,
+                BqMode::Required => ModeState::Required,
+                BqMode::Repeated => ModeState::Repeated,
+            },
+        }
+    }
+}
+
+impl From<EntryState> for SchemaEntry {
+    fn from(state: EntryState) -> Self {
+        Self {
+            status: match state.status {
+                StatusState::Hard => EntryStatus::Hard,
+                StatusState::Soft => EntryStatus::Soft,
+                StatusState::Ignore => EntryStatus::Ignore,
+            },
+            filled: state.filled,
+            name: state.name,
+            bq_type: BqType::from(state.bq_type),
+            mode: match state.mode {
+                ModeState::Nullable => BqMode::Nullable,
+                ModeState::Required => BqMode::Required,
+                ModeState::Repeated => BqMode::Repeated,
+            },
+        }
+    }
+}
+
+impl From<&BqType> for TypeState {
+    fn from(bq_type: &BqType) -> Self {
+        match *bq_type {
+            BqType::Boolean => TypeState::Boolean,
+            BqType::Integer => TypeState::Integer,
+            BqType::Float => TypeState::Float,
+            BqType::String => TypeState::String,
+            BqType::Bytes => TypeState::Bytes,
+            BqType::Geography => TypeState::Geography,
+            BqType::Timestamp => TypeState::Timestamp,
+            BqType::Datetime => TypeState::Datetime,
+            BqType::Date => TypeState::Date,
+            BqType::Time => TypeState::Time,
+            BqType::Numeric { precision, scale } => TypeState::Numeric { precision, scale },
+            BqType::BigNumeric { precision, scale } => TypeState::BigNumeric { precision, scale },
+            BqType::Record(ref fields) => TypeState::Record {
+                fields: to_state(fields),
+            },
+            BqType::Json => TypeState::Json,
+            BqType::Null => TypeState::Null,
+            BqType::EmptyArray => TypeState::EmptyArray,
+            BqType::EmptyRecord => TypeState::EmptyRecord,
+            BqType::QBoolean => TypeState::QBoolean,
+            BqType::QInteger => TypeState::QInteger,
+            BqType::QFloat => TypeState::QFloat,
+            BqType::QNumeric { precision, scale } => TypeState::QNumeric { precision, scale },
+            BqType::QBigNumeric { precision, scale } => TypeState::QBigNumeric { precision, scale },
+        }
+    }
+}
+
+impl From<TypeState> for BqType {
+    fn from(state: TypeState) -> Self {
+        match state {
+            TypeState::Boolean => BqType::Boolean,
+            TypeState::Integer => BqType::Integer,
+            TypeState::Float => BqType::Float,
+            TypeState::String => BqType::String,
+            TypeState::Bytes => BqType::Bytes,
+            TypeState::Geography => BqType::Geography,
+            TypeState::Timestamp => BqType::Timestamp,
+            TypeState::Datetime => BqType::Datetime,
+            TypeState::Date => BqType::Date,
+            TypeState::Time => BqType::Time,
+            TypeState::Numeric { precision, scale } => BqType::Numeric { precision, scale },
+            TypeState::BigNumeric { precision, scale } => BqType::BigNumeric { precision, scale },
+            TypeState::Record { fields } => BqType::Record(from_state(fields)),
+            TypeState::Json => BqType::Json,
+            TypeState::Null => BqType::Null,
+            TypeState::EmptyArray => BqType::EmptyArray,
+            TypeState::EmptyRecord => BqType::EmptyRecord,
+            TypeState::QBoolean => BqType::QBoolean,
+            TypeState::QInteger => BqType::QInteger,
+            TypeState::QFloat => BqType::QFloat,
+            TypeState::QNumeric { precision, scale } => BqType::QNumeric { precision, scale },
+            TypeState::QBigNumeric { precision, scale } => BqType::QBigNumeric { precision, scale },
+        }
+    }
+}
+
+fn to_state(schema_map: &SchemaMap) -> IndexMap<String, EntryState> {
+    schema_map
+        .iter()
+        .map(|(key, entry)| (key.clone(), EntryState::from(entry)))
+        .collect()
+}
+
+fn from_state(states: IndexMap<String, EntryState>) -> SchemaMap {
+    states
+        .into_iter()
+        .map(|(key, state)| (key, SchemaEntry::from(state)))
+        .collect()
+}
+
+/// Serde functions writing a [`SchemaMap`] in the state file format, for
+/// `#[serde(with = ...)]` on fields that store one.
+pub(crate) mod schema_map_format {
+    use indexmap::IndexMap;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{from_state, to_state, EntryState};
+    use crate::schema::types::SchemaMap;
+
+    pub fn serialize<S: Serializer>(
+        schema_map: &SchemaMap,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        to_state(schema_map).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<SchemaMap, D::Error> {
+        IndexMap::<String, EntryState>::deserialize(deserializer).map(from_state)
+    }
+}
+
+/// Write a schema map as a versioned state file.
+pub fn write_schema_state<W: Write>(schema_map: &SchemaMap, writer: &mut W) -> Result<()> {
+    let state = StateRef {
+        version: SCHEMA_STATE_VERSION,
+        schema: schema_map,
+    };
+    let json =
+        serde_json::to_string_pretty(&state).map_err(|e| Error::SchemaFile(e.to_string()))?;
+    writeln!(writer, "{}", json)?;
+    Ok(())
+}
+
+/// Read a schema map from a state written by [`write_schema_state`].
+pub fn read_schema_state<R: Read>(mut reader: R) -> Result<SchemaMap> {
+    let mut json = String::new();
+    reader.read_to_string(&mut json)?;
+
+    // Check the version first, so a newer format isn't reported as malformed
+    let StateVersion { version } = serde_json::from_str(&json)
+        .map_err(|e| Error::SchemaFile(format!("Invalid schema state: {}", e)))?;
+    if version != SCHEMA_STATE_VERSION {
+        return Err(Error::SchemaFile(format!(
+            "Unsupported schema state version {} (expected {})",
+            version, SCHEMA_STATE_VERSION
+        )));
+    }
+
+    let state: State = serde_json::from_str(&json)
+        .map_err(|e| Error::SchemaFile(format!("Invalid schema state: {}", e)))?;
+    Ok(state.schema)
+}
+
+/// Read a schema state from a file.
+pub fn read_schema_state_from_file(path: &Path) -> Result<SchemaMap> {
+    let file = File::open(path)?;
+    read_schema_state(BufReader::new(file))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::generator::{GeneratorConfig, SchemaGenerator};
+    use serde_json::json;
+
+    fn state_of(records: &[serde_json::Value]) -> (SchemaGenerator, SchemaMap) {
+        let mut generator = SchemaGenerator::new(GeneratorConfig {
+            infer_mode: true,
+            ..Default::default()
+        });
+        let mut schema_map = SchemaMap::new();
+        for record in records {
+            generator.process_record(record, &mut schema_map).unwrap();
+        }
+        (generator, schema_map)
+    }
+
+    fn round_trip(schema_map: &SchemaMap) -> SchemaMap {
+        let mut buf = Vec::new();
+        write_schema_state(schema_map, &mut buf).unwrap();
+        read_schema_state(buf.as_slice()).unwrap()
+    }
+
+    #[test]
+    fn test_round_trip_keeps_inference_state() {
+        let (_, schema_map) = state_of(&[
+            json!({"id": 1, "price": "1.5", "tags": [], "note": null, "nested": {"a": true}}),
+            json!({"id": 2, "price": "2", "x": 1}),
+            json!({"id": 3, "x": "s"}),
+        ]);
+        let restored = round_trip(&schema_map);
+        assert_eq!(restored, schema_map);
+        assert_eq!(
+            restored.keys().collect::<Vec<_>>(),
+            schema_map.keys().collect::<Vec<_>>()
+        );
+        assert_eq!(restored["price"].bq_type, BqType::QFloat);
+        assert_eq!(restored["tags"].status, EntryStatus::Soft);
+        assert_eq!(restored["x"].status, EntryStatus::Ignore);
+        assert!(restored["id"].filled);
+        assert!(!restored["note"].filled);
+    }
+
+    #[test]
+    fn test_merged_states_match_single_run() {
+        let first = [json!({"id": 1, "score": null}), json!({"id": 2})];
+        let second = [json!({"id": 3, "score": 1.5, "ok": "true"})];
+        let (_, expected) = state_of(&[first.as_slice(), second.as_slice()].concat());
+
+        let (mut generator, mut merged) = state_of(&first);
+        let (_, other) = state_of(&second);
+        generator
+            .merge_schema_maps(&mut merged, round_trip(&other))
+            .unwrap();
+        let merged = round_trip(&merged);
+
+        let flatten = |map| serde_json::to_value(generator.flatten_schema(map)).unwrap();
+        assert_eq!(flatten(&merged), flatten(&expected));
+        assert_eq!(merged["score"].mode, BqMode::Nullable);
+        assert_eq!(merged["ok"].bq_type, BqType::QBoolean);
+    }
+
+    #[test]
+    fn test_state_format_names() {
+        let (_, mut schema_map) = state_of(&[
+            json!({"id": "1", "note": null}),
+            json!({"id": "2", "user": {"ok": true}}),
+        ]);
+        let mut add = |name: &str, bq_type| {
+            let entry = SchemaEntry::soft(name.to_string(), bq_type, BqMode::Nullable);
+            schema_map.insert(name.to_string(), entry);
+        };
+        add("null", BqType::Null);
+        add("empty", BqType::EmptyRecord);
+        add(
+            "price",
+            BqType::QNumeric {
+                precision: 19,
+                scale: 5,
+            },
+        );
+        let mut buf = Vec::new();
+        write_schema_state(&schema_map, &mut buf).unwrap();
+        let state: serde_json::Value = serde_json::from_slice(&buf).unwrap();
+
+        let schema = &state["schema"];
+        assert_eq!(
+            schema["id"],
+            json!({"status": "hard", "filled": true, "name": "id", "type": "QINTEGER", "mode": "NULLABLE"})
+        );
+        assert_eq!(schema["note"]["status"], "soft");
+        assert_eq!(schema["user"]["type"], "RECORD");
+        assert_eq!(schema["user"]["fields"]["ok"]["type"], "BOOLEAN");
+        assert_eq!(schema["null"]["type"], "__null__");
+        assert_eq!(schema["empty"]["type"], "__empty_record__");
+        assert_eq!(
+            schema["price"],
+            json!({"status": "soft", "filled": false, "name": "price", "type": "QNUMERIC", "precision": 19, "scale": 5, "mode": "NULLABLE"})
+        );
+        assert_eq!(round_trip(&schema_map), schema_map);
+
+        // A state written by hand in this format reads back
+        let restored = read_schema_state(
+            r#"{"version": 1, "schema": {
+                "n": {"status": "hard", "filled": false, "name": "n", "type": "NUMERIC", "precision": 10, "scale": 2, "mode": "NULLABLE"}
+            }}"#
+            .as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(
+            restored["n"].bq_type,
+            BqType::Numeric {
+                precision: 10,
+                scale: 2
+            }
+        );
+        assert!(!restored["n"].filled);
+    }
+
+    #[test]
+    fn test_rejects_other_versions() {
+        let err = read_schema_state(r#"{"version": 2, "schema": {"a": 1}}"#.as_bytes())
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("Unsupported schema state version 2"),
+            "{}",
+            err
+        );
+
+        let err = read_schema_state(r#"[{"name": "a", "type": "STRING"}]"#.as_bytes())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Invalid schema state"), "{}", err);
+    }
+}
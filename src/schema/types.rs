@@ -18,10 +18,31 @@ pub enum BqType {
     Integer,
     Float,
     String,
+    /// Binary data, written as base64 in JSON
+    Bytes,
+    /// Geospatial data, from WKT strings or GeoJSON geometry objects
+    Geography,
     Timestamp,
+    /// Civil date and time without a time zone
+    Datetime,
     Date,
     Time,
+    /// Exact decimal with up to 29 integer digits and 9 fractional digits.
+    ///
+    /// `precision` is the total number of significant digits observed and
+    /// `scale` the number of fractional digits; both widen as records merge.
+    Numeric {
+        precision: u8,
+        scale: u8,
+    },
+    /// Exact decimal with up to 38 integer digits and 38 fractional digits.
+    BigNumeric {
+        precision: u8,
+        scale: u8,
+    },
     Record(SchemaMap),
+    /// Semi-structured JSON holding any value, for heterogeneous subtrees
+    Json,
 
     // Internal types for tracking inference state
     /// Null value - will become STRING if keep_nulls is enabled
@@ -38,6 +59,16 @@ pub enum BqType {
     QInteger,
     /// Quoted float like `"1.5"` or `"1e10"`
     QFloat,
+    /// Quoted decimal that needs NUMERIC precision, like `"12345678901234.56789"`
+    QNumeric {
+        precision: u8,
+        scale: u8,
+    },
+    /// Quoted decimal that needs BIGNUMERIC precision
+    QBigNumeric {
+        precision: u8,
+        scale: u8,
+    },
 }
 
 impl BqType {
@@ -47,11 +78,17 @@ impl BqType {
             BqType::Boolean | BqType::QBoolean => "BOOLEAN",
             BqType::Integer | BqType::QInteger => "INTEGER",
             BqType::Float | BqType::QFloat => "FLOAT",
+            BqType::Numeric { .. } | BqType::QNumeric { .. } => "NUMERIC",
+            BqType::BigNumeric { .. } | BqType::QBigNumeric { .. } => "BIGNUMERIC",
             BqType::String => "STRING",
+            BqType::Bytes => "BYTES",
+            BqType::Geography => "GEOGRAPHY",
             BqType::Timestamp => "TIMESTAMP",
+            BqType::Datetime => "DATETIME",
             BqType::Date => "DATE",
             BqType::Time => "TIME",
             BqType::Record(_) | BqType::EmptyRecord => "RECORD",
+            BqType::Json => "JSON",
             BqType::Null | BqType::EmptyArray => "STRING",
         }
     }
@@ -66,7 +103,35 @@ impl BqType {
 
     /// Returns true if this is a quoted type.
     pub fn is_quoted(&self) -> bool {
-        matches!(self, BqType::QBoolean | BqType::QInteger | BqType::QFloat)
+        matches!(
+            self,
+            BqType::QBoolean
+                | BqType::QInteger
+                | BqType::QFloat
+                | BqType::QNumeric { .. }
+                | BqType::QBigNumeric { .. }
+        )
+    }
+
+    /// Returns the `(precision, scale)` of a NUMERIC or BIGNUMERIC type, quoted or not.
+    pub fn decimal_spec(&self) -> Option<(u8, u8)> {
+        match self {
+            BqType::Numeric { precision, scale }
+            | BqType::BigNumeric { precision, scale }
+            | BqType::QNumeric { precision, scale }
+            | BqType::QBigNumeric { precision, scale } => Some((*precision, *scale)),
+            _ => None,
+        }
+    }
+
+    /// Returns the `(precision, scale)` a NUMERIC or BIGNUMERIC column has to
+    /// declare, or `None` when it spans the type's full range.
+    pub fn decimal_params(&self) -> Option<(u8, u8)> {
+        match self.decimal_spec()? {
+            (38, 9) if self.as_str() == "NUMERIC" => None,
+            (76, 38) if self.as_str() == "BIGNUMERIC" => None,
+            spec => Some(spec),
+        }
     }
 
     /// Returns true if this type can be represented as a string in BigQuery.
@@ -75,12 +140,17 @@ impl BqType {
         matches!(
             self,
             BqType::String
+                | BqType::Bytes
+                | BqType::Geography
                 | BqType::Timestamp
+                | BqType::Datetime
                 | BqType::Date
                 | BqType::Time
                 | BqType::QInteger
                 | BqType::QFloat
                 | BqType::QBoolean
+                | BqType::QNumeric { .. }
+                | BqType::QBigNumeric { .. }
         )
     }
 }
@@ -187,10 +257,39 @@ pub struct BqSchemaField {
     pub fields: Option<Vec<BqSchemaField>>,
     pub mode: String,
     pub name: String,
+    /// Declared precision of a NUMERIC or BIGNUMERIC field, written as a
+    /// string like BigQuery does
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_decimal_param"
+    )]
+    pub precision: Option<String>,
+    /// Declared scale of a NUMERIC or BIGNUMERIC field
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_decimal_param"
+    )]
+    pub scale: Option<String>,
     #[serde(rename = "type")]
     pub field_type: String,
 }
 
+/// Read a `precision` or `scale` given either as a string or a number.
+fn deserialize_decimal_param<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(
+        match Option::<serde_json::Value>::deserialize(deserializer)? {
+            Some(serde_json::Value::String(s)) => Some(s),
+            Some(serde_json::Value::Number(n)) => Some(n.to_string()),
+            _ => None,
+        },
+    )
+}
+
 impl BqSchemaField {
     /// Create a new schema field for JSON output.
     pub fn new(name: String, field_type: String, mode: String) -> Self {
@@ -198,6 +297,8 @@ impl BqSchemaField {
             fields: None,
             mode,
             name,
+            precision: None,
+            scale: None,
             field_type,
         }
     }
@@ -208,9 +309,18 @@ impl BqSchemaField {
             fields: Some(fields),
             mode,
             name,
+            precision: None,
+            scale: None,
             field_type: "RECORD".to_string(),
         }
     }
+
+    /// Set the declared precision and scale of a NUMERIC or BIGNUMERIC field.
+    pub fn with_decimal_params(mut self, precision: u8, scale: u8) -> Self {
+        self.precision = Some(precision.to_string());
+        self.scale = Some(scale.to_string());
+        self
+    }
 }
 
 #[cfg(test)]
@@ -226,13 +336,39 @@ mod tests {
         assert_eq!(BqType::Float.as_str(), "FLOAT");
         assert_eq!(BqType::QFloat.as_str(), "FLOAT");
         assert_eq!(BqType::String.as_str(), "STRING");
+        assert_eq!(BqType::Bytes.as_str(), "BYTES");
+        assert_eq!(BqType::Geography.as_str(), "GEOGRAPHY");
+        assert_eq!(BqType::Json.as_str(), "JSON");
         assert_eq!(BqType::Timestamp.as_str(), "TIMESTAMP");
+        assert_eq!(BqType::Datetime.as_str(), "DATETIME");
         assert_eq!(BqType::Date.as_str(), "DATE");
         assert_eq!(BqType::Time.as_str(), "TIME");
         assert_eq!(BqType::Null.as_str(), "STRING");
         assert_eq!(BqType::EmptyArray.as_str(), "STRING");
     }
 
+    #[test]
+    fn test_decimal_types() {
+        let numeric = BqType::Numeric {
+            precision: 19,
+            scale: 5,
+        };
+        let big = BqType::QBigNumeric {
+            precision: 40,
+            scale: 20,
+        };
+        assert_eq!(numeric.as_str(), "NUMERIC");
+        assert_eq!(big.as_str(), "BIGNUMERIC");
+        assert_eq!(numeric.decimal_spec(), Some((19, 5)));
+        assert_eq!(big.decimal_spec(), Some((40, 20)));
+        assert_eq!(BqType::Float.decimal_spec(), None);
+
+        assert!(!numeric.is_quoted());
+        assert!(big.is_quoted());
+        assert!(!numeric.is_string_compatible());
+        assert!(big.is_string_compatible());
+    }
+
     #[test]
     fn test_bq_mode_as_str() {
         assert_eq!(BqMode::Nullable.as_str(), "NULLABLE");
@@ -243,7 +379,9 @@ mod tests {
     #[test]
     fn test_is_string_compatible() {
         assert!(BqType::String.is_string_compatible());
+        assert!(BqType::Bytes.is_string_compatible());
         assert!(BqType::Timestamp.is_string_compatible());
+        assert!(BqType::Datetime.is_string_compatible());
         assert!(BqType::Date.is_string_compatible());
         assert!(BqType::Time.is_string_compatible());
         assert!(BqType::QInteger.is_string_compatible());
@@ -253,6 +391,7 @@ mod tests {
         assert!(!BqType::Boolean.is_string_compatible());
         assert!(!BqType::Integer.is_string_compatible());
         assert!(!BqType::Float.is_string_compatible());
+        assert!(!BqType::Json.is_string_compatible());
     }
 
     // ===== Additional Coverage Tests =====
@@ -310,6 +449,7 @@ mod tests {
             BqType::QFloat,
             BqType::String,
             BqType::Timestamp,
+            BqType::Datetime,
             BqType::Date,
             BqType::Time,
         ];
@@ -352,6 +492,7 @@ mod tests {
         assert_eq!(BqType::QFloat.to_string(), "FLOAT");
         assert_eq!(BqType::String.to_string(), "STRING");
         assert_eq!(BqType::Timestamp.to_string(), "TIMESTAMP");
+        assert_eq!(BqType::Datetime.to_string(), "DATETIME");
         assert_eq!(BqType::Date.to_string(), "DATE");
         assert_eq!(BqType::Time.to_string(), "TIME");
         assert_eq!(BqType::Record(SchemaMap::new()).to_string(), "RECORD");
@@ -0,0 +1,112 @@
+//! HyperLogLog distinct-count estimator.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// Number of index bits; 2^12 registers gives roughly 1.6% standard error.
+const PRECISION: u32 = 12;
+const REGISTERS: usize = 1 << PRECISION;
+
+/// Approximate distinct counter using a fixed 4 KiB of registers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HyperLogLog {
+    registers: Vec<u8>,
+}
+
+impl Default for HyperLogLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HyperLogLog {
+    /// Create an empty estimator.
+    pub fn new() -> Self {
+        Self {
+            registers: vec![0; REGISTERS],
+        }
+    }
+
+    /// Add a value to the estimator.
+    pub fn insert<T: Hash + ?Sized>(&mut self, value: &T) {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        let index = (hash >> (64 - PRECISION)) as usize;
+        // Rank of the first set bit in the remaining bits, capped for an all-zero tail
+        let rank = ((hash << PRECISION).leading_zeros() + 1).min(64 - PRECISION + 1) as u8;
+        if rank > self.registers[index] {
+            self.registers[index] = rank;
+        }
+    }
+
+    /// Merge another estimator into this one.
+    pub fn merge(&mut self, other: &HyperLogLog) {
+        for (register, other) in self.registers.iter_mut().zip(&other.registers) {
+            *register = (*register).max(*other);
+        }
+    }
+
+    /// Estimate the number of distinct values inserted.
+    pub fn estimate(&self) -> u64 {
+        let m = REGISTERS as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = self.registers.iter().map(|&r| 2f64.powi(-(r as i32))).sum();
+        let estimate = alpha * m * m / sum;
+
+        // Small-range correction: linear counting while registers are still empty
+        let zeros = self.registers.iter().filter(|&&r| r == 0).count();
+        if estimate <= 2.5 * m && zeros > 0 {
+            (m * (m / zeros as f64).ln()).round() as u64
+        } else {
+            estimate.round() as u64
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(HyperLogLog::new().estimate(), 0);
+    }
+
+    #[test]
+    fn test_duplicates_count_once() {
+        let mut hll = HyperLogLog::new();
+        for _ in 0..100 {
+            hll.insert("same");
+        }
+        assert_eq!(hll.estimate(), 1);
+    }
+
+    #[test]
+    fn test_estimate_within_error() {
+        let mut hll = HyperLogLog::new();
+        for i in 0..50_000 {
+            hll.insert(&i);
+        }
+        let estimate = hll.estimate() as f64;
+        assert!(
+            (estimate - 50_000.0).abs() / 50_000.0 < 0.05,
+            "{}",
+            estimate
+        );
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut a = HyperLogLog::new();
+        let mut b = HyperLogLog::new();
+        for i in 0..1000 {
+            a.insert(&i);
+            b.insert(&(i + 500));
+        }
+        a.merge(&b);
+        let estimate = a.estimate() as f64;
+        assert!((estimate - 1500.0).abs() / 1500.0 < 0.05, "{}", estimate);
+    }
+}
@@ -0,0 +1,469 @@
+//! Per-field data profiling collected while generating a schema.
+//!
+//! When `GeneratorConfig::collect_stats` is set, the generator feeds every
+//! field value it sees into a [`DataProfile`], which can then be written as
+//! a JSON or markdown report next to the schema.
+
+pub mod hyperloglog;
+
+use std::io::Write;
+
+use indexmap::IndexMap;
+use serde_json::{json, Value};
+
+use crate::error::Result;
+use crate::inference::{
+    is_date, is_datetime, is_float_string, is_integer_string, is_time, is_timestamp,
+};
+
+pub use hyperloglog::HyperLogLog;
+
+/// Number of distinct sample values kept per field.
+const MAX_SAMPLES: usize = 5;
+
+/// Output format for a stats report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StatsFormat {
+    #[default]
+    Json,
+    Markdown,
+}
+
+impl std::str::FromStr for StatsFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(StatsFormat::Json),
+            "markdown" | "md" => Ok(StatsFormat::Markdown),
+            _ => Err(format!("Unknown stats format: {}", s)),
+        }
+    }
+}
+
+/// Smallest and largest value of a field, compared numerically or as temporal strings.
+#[derive(Debug, Clone, PartialEq)]
+enum Extremes {
+    Numeric {
+        min: (f64, Value),
+        max: (f64, Value),
+    },
+    /// ISO dates and times order lexicographically
+    Temporal { min: String, max: String },
+}
+
+/// Statistics for a single field path.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldStats {
+    /// Dotted field path as first seen
+    pub path: String,
+    /// Number of times the field was present, including as null
+    pub presence_count: u64,
+    /// Number of times the field was null
+    pub null_count: u64,
+    /// Longest string value, in characters
+    pub max_string_length: Option<usize>,
+    /// Longest array value
+    pub max_array_length: Option<usize>,
+    /// First few distinct scalar values
+    pub samples: Vec<Value>,
+    distinct: HyperLogLog,
+    extremes: Option<Extremes>,
+}
+
+impl FieldStats {
+    fn new(path: String) -> Self {
+        Self {
+            path,
+            presence_count: 0,
+            null_count: 0,
+            max_string_length: None,
+            max_array_length: None,
+            samples: Vec::new(),
+            distinct: HyperLogLog::new(),
+            extremes: None,
+        }
+    }
+
+    /// Estimated number of distinct non-null scalar values.
+    pub fn distinct_estimate(&self) -> u64 {
+        self.distinct.estimate()
+    }
+
+    /// Smallest numeric or temporal value seen.
+    pub fn min(&self) -> Option<Value> {
+        match &self.extremes {
+            Some(Extremes::Numeric { min, .. }) => Some(min.1.clone()),
+            Some(Extremes::Temporal { min, .. }) => Some(Value::String(min.clone())),
+            None => None,
+        }
+    }
+
+    /// Largest numeric or temporal value seen.
+    pub fn max(&self) -> Option<Value> {
+        match &self.extremes {
+            Some(Extremes::Numeric { max, .. }) => Some(max.1.clone()),
+            Some(Extremes::Temporal { max, .. }) => Some(Value::String(max.clone())),
+            None => None,
+        }
+    }
+
+    /// Record a scalar value (not null, array or object).
+    fn observe_scalar(&mut self, value: &Value, numeric_strings: bool) {
+        self.distinct.insert(&value.to_string());
+
+        if let Value::String(s) = value {
+            let len = s.chars().count();
+            self.max_string_length = Some(self.max_string_length.map_or(len, |m| m.max(len)));
+        }
+
+        if self.samples.len() < MAX_SAMPLES && !self.samples.contains(value) {
+            self.samples.push(value.clone());
+        }
+
+        let numeric = match value {
+            Value::Number(n) => n.as_f64(),
+            Value::String(s) if numeric_strings && (is_integer_string(s) || is_float_string(s)) => {
+                s.parse::<f64>().ok()
+            }
+            _ => None,
+        };
+        self.update_extremes(numeric, value);
+    }
+
+    fn update_extremes(&mut self, numeric: Option<f64>, value: &Value) {
+        if let Some(n) = numeric {
+            match &mut self.extremes {
+                None => {
+                    self.extremes = Some(Extremes::Numeric {
+                        min: (n, value.clone()),
+                        max: (n, value.clone()),
+                    })
+                }
+                Some(Extremes::Numeric { min, max }) => {
+                    if n < min.0 {
+                        *min = (n, value.clone());
+                    }
+                    if n > max.0 {
+                        *max = (n, value.clone());
+                    }
+                }
+                // Mixed kinds are a schema conflict; keep the first kind seen
+                Some(Extremes::Temporal { .. }) => {}
+            }
+            return;
+        }
+
+        let Value::String(s) = value else {
+            return;
+        };
+        if !(is_timestamp(s) || is_datetime(s) || is_date(s) || is_time(s)) {
+            return;
+        }
+        match &mut self.extremes {
+            None => {
+                self.extremes = Some(Extremes::Temporal {
+                    min: s.clone(),
+                    max: s.clone(),
+                })
+            }
+            Some(Extremes::Temporal { min, max }) => {
+                if s < min {
+                    *min = s.clone();
+                }
+                if s > max {
+                    *max = s.clone();
+                }
+            }
+            Some(Extremes::Numeric { .. }) => {}
+        }
+    }
+
+    /// Merge statistics for the same path collected elsewhere.
+    fn merge(&mut self, other: FieldStats) {
+        self.presence_count += other.presence_count;
+        self.null_count += other.null_count;
+        self.max_string_length = self.max_string_length.max(other.max_string_length);
+        self.max_array_length = self.max_array_length.max(other.max_array_length);
+        self.distinct.merge(&other.distinct);
+        if let Some(min) = other.min() {
+            self.update_extremes(extremes_key(&other.extremes, true), &min);
+        }
+        if let Some(max) = other.max() {
+            self.update_extremes(extremes_key(&other.extremes, false), &max);
+        }
+        for sample in other.samples {
+            if self.samples.len() < MAX_SAMPLES && !self.samples.contains(&sample) {
+                self.samples.push(sample);
+            }
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "path": self.path,
+            "presence_count": self.presence_count,
+            "null_count": self.null_count,
+            "distinct_estimate": self.distinct_estimate(),
+            "min": self.min(),
+            "max": self.max(),
+            "max_string_length": self.max_string_length,
+            "max_array_length": self.max_array_length,
+            "samples": self.samples,
+        })
+    }
+}
+
+/// Numeric sort key of the min or max of `extremes`, if numeric.
+fn extremes_key(extremes: &Option<Extremes>, min: bool) -> Option<f64> {
+    match extremes {
+        Some(Extremes::Numeric { min: lo, max: hi }) => Some(if min { lo.0 } else { hi.0 }),
+        _ => None,
+    }
+}
+
+/// Statistics for every field path seen, keyed by lowercased dotted path.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DataProfile {
+    /// Number of records profiled
+    pub record_count: u64,
+    /// Per-field statistics in the order first seen
+    pub fields: IndexMap<String, FieldStats>,
+    /// Treat numeric strings (e.g. from CSV) as numbers for min/max
+    numeric_strings: bool,
+}
+
+impl DataProfile {
+    /// Create an empty profile.
+    ///
+    /// With `numeric_strings`, strings like `"42"` count as numbers for min/max.
+    pub fn new(numeric_strings: bool) -> Self {
+        Self {
+            numeric_strings,
+            ..Default::default()
+        }
+    }
+
+    /// Record the value of the field at `path`.
+    ///
+    /// Nested object fields are recorded separately under their own paths.
+    pub fn observe(&mut self, path: &str, value: &Value) {
+        let stats = self
+            .fields
+            .entry(path.to_lowercase())
+            .or_insert_with(|| FieldStats::new(path.to_string()));
+        stats.presence_count += 1;
+
+        match value {
+            Value::Null => stats.null_count += 1,
+            Value::Array(arr) => {
+                stats.max_array_length = Some(
+                    stats
+                        .max_array_length
+                        .map_or(arr.len(), |m| m.max(arr.len())),
+                );
+                for item in arr {
+                    if !matches!(item, Value::Null | Value::Array(_) | Value::Object(_)) {
+                        stats.observe_scalar(item, self.numeric_strings);
+                    }
+                }
+            }
+            Value::Object(_) => {}
+            _ => stats.observe_scalar(value, self.numeric_strings),
+        }
+    }
+
+    /// Merge a profile collected over other records into this one.
+    pub fn merge(&mut self, other: DataProfile) {
+        self.record_count += other.record_count;
+        for (key, stats) in other.fields {
+            match self.fields.get_mut(&key) {
+                Some(existing) => existing.merge(stats),
+                None => {
+                    self.fields.insert(key, stats);
+                }
+            }
+        }
+    }
+
+    /// Fields sorted by path.
+    fn sorted_fields(&self) -> Vec<&FieldStats> {
+        let mut fields: Vec<_> = self.fields.iter().collect();
+        fields.sort_by(|a, b| a.0.cmp(b.0));
+        fields.into_iter().map(|(_, stats)| stats).collect()
+    }
+}
+
+/// Convert the profile to a JSON report.
+pub fn profile_to_json(profile: &DataProfile) -> Value {
+    json!({
+        "record_count": profile.record_count,
+        "fields": profile
+            .sorted_fields()
+            .iter()
+            .map(|stats| stats.to_json())
+            .collect::<Vec<_>>(),
+    })
+}
+
+/// Write the profile as a pretty-printed JSON report.
+pub fn write_stats_json<W: Write>(profile: &DataProfile, writer: &mut W) -> Result<()> {
+    let json = serde_json::to_string_pretty(&profile_to_json(profile))
+        .map_err(|e| crate::error::Error::SchemaFile(e.to_string()))?;
+    writeln!(writer, "{}", json)?;
+    Ok(())
+}
+
+/// Write the profile as a markdown table.
+pub fn write_stats_markdown<W: Write>(profile: &DataProfile, writer: &mut W) -> Result<()> {
+    writeln!(writer, "# Field statistics")?;
+    writeln!(writer)?;
+    writeln!(writer, "{} records profiled.", profile.record_count)?;
+    writeln!(writer)?;
+    writeln!(
+        writer,
+        "| Field | Present | Null | Distinct (est.) | Min | Max | Max length | Max array length | Samples |"
+    )?;
+    writeln!(writer, "|---|---:|---:|---:|---|---|---:|---:|---|")?;
+
+    let cell = |value: Option<Value>| value.map_or(String::new(), |v| markdown_code(&v));
+    let count = |n: Option<usize>| n.map_or(String::new(), |n| n.to_string());
+    for stats in profile.sorted_fields() {
+        let samples: Vec<_> = stats.samples.iter().map(markdown_code).collect();
+        writeln!(
+            writer,
+            "| `{}` | {} | {} | {} | {} | {} | {} | {} | {} |",
+            stats.path,
+            stats.presence_count,
+            stats.null_count,
+            stats.distinct_estimate(),
+            cell(stats.min()),
+            cell(stats.max()),
+            count(stats.max_string_length),
+            count(stats.max_array_length),
+            samples.join(", ")
+        )?;
+    }
+    Ok(())
+}
+
+/// Format a value as inline markdown code, escaping table separators.
+fn markdown_code(value: &Value) -> String {
+    let text = match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    format!("`{}`", text.replace('|', "\\|").replace('`', "'"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile_of(values: &[Value]) -> FieldStats {
+        let mut profile = DataProfile::new(false);
+        for value in values {
+            profile.observe("f", value);
+        }
+        profile.fields.shift_remove("f").unwrap()
+    }
+
+    #[test]
+    fn test_stats_format_from_str() {
+        assert_eq!("json".parse(), Ok(StatsFormat::Json));
+        assert_eq!("Markdown".parse(), Ok(StatsFormat::Markdown));
+        assert_eq!("md".parse(), Ok(StatsFormat::Markdown));
+        assert!("csv".parse::<StatsFormat>().is_err());
+    }
+
+    #[test]
+    fn test_numeric_field() {
+        let stats = profile_of(&[json!(3), json!(-1.5), json!(null), json!(10), json!(3)]);
+        assert_eq!(stats.presence_count, 5);
+        assert_eq!(stats.null_count, 1);
+        assert_eq!(stats.distinct_estimate(), 3);
+        assert_eq!(stats.min(), Some(json!(-1.5)));
+        assert_eq!(stats.max(), Some(json!(10)));
+        assert_eq!(stats.samples, vec![json!(3), json!(-1.5), json!(10)]);
+        assert_eq!(stats.max_string_length, None);
+    }
+
+    #[test]
+    fn test_temporal_and_string_field() {
+        let stats = profile_of(&[
+            json!("2024-03-01"),
+            json!("2023-12-31"),
+            json!("2024-01-15"),
+        ]);
+        assert_eq!(stats.min(), Some(json!("2023-12-31")));
+        assert_eq!(stats.max(), Some(json!("2024-03-01")));
+        assert_eq!(stats.max_string_length, Some(10));
+
+        // Plain strings have no min/max
+        let stats = profile_of(&[json!("beta"), json!("alpha")]);
+        assert_eq!(stats.min(), None);
+    }
+
+    #[test]
+    fn test_numeric_strings() {
+        let mut profile = DataProfile::new(true);
+        for value in ["42", "7", "x"] {
+            profile.observe("n", &json!(value));
+        }
+        let stats = &profile.fields["n"];
+        assert_eq!(stats.min(), Some(json!("7")));
+        assert_eq!(stats.max(), Some(json!("42")));
+    }
+
+    #[test]
+    fn test_array_field() {
+        let stats = profile_of(&[json!([1, 2, 3]), json!([]), json!([5, null])]);
+        assert_eq!(stats.max_array_length, Some(3));
+        assert_eq!(stats.distinct_estimate(), 4);
+        assert_eq!(stats.max(), Some(json!(5)));
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut a = DataProfile::new(false);
+        a.record_count = 2;
+        a.observe("x", &json!(5));
+        a.observe("x", &json!(null));
+        let mut b = DataProfile::new(false);
+        b.record_count = 1;
+        b.observe("x", &json!(-2));
+        b.observe("y", &json!("text"));
+
+        a.merge(b);
+        assert_eq!(a.record_count, 3);
+        let x = &a.fields["x"];
+        assert_eq!(x.presence_count, 3);
+        assert_eq!(x.null_count, 1);
+        assert_eq!(x.min(), Some(json!(-2)));
+        assert_eq!(x.max(), Some(json!(5)));
+        assert_eq!(x.distinct_estimate(), 2);
+        assert!(a.fields.contains_key("y"));
+    }
+
+    #[test]
+    fn test_write_reports() {
+        let mut profile = DataProfile::new(false);
+        profile.record_count = 1;
+        profile.observe("b", &json!("a|b"));
+        profile.observe("a", &json!(1));
+
+        let mut output = Vec::new();
+        write_stats_json(&profile, &mut output).unwrap();
+        let parsed: Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(parsed["record_count"], 1);
+        assert_eq!(parsed["fields"][0]["path"], "a");
+        assert_eq!(parsed["fields"][0]["min"], 1);
+        assert_eq!(parsed["fields"][1]["max"], Value::Null);
+
+        let mut output = Vec::new();
+        write_stats_markdown(&profile, &mut output).unwrap();
+        let markdown = String::from_utf8(output).unwrap();
+        assert!(markdown.contains("| `a` | 1 | 0 | 1 | `1` | `1` |  |  | `1` |"));
+        assert!(markdown.contains("`a\\|b`"));
+    }
+}
@@ -13,6 +13,8 @@ pub enum ValidationErrorType {
     TypeMismatch { expected: String, actual: String },
     /// Field exists in data but not in schema
     UnknownField,
+    /// Value doesn't satisfy the type or mode hinted for its path
+    HintMismatch { expected: String, actual: String },
 }
 
 impl fmt::Display for ValidationErrorType {
@@ -23,6 +25,9 @@ impl fmt::Display for ValidationErrorType {
                 write!(f, "expected {}, got {}", expected, actual)
             }
             ValidationErrorType::UnknownField => write!(f, "unknown field"),
+            ValidationErrorType::HintMismatch { expected, actual } => {
+                write!(f, "hinted {}, got {}", expected, actual)
+            }
         }
     }
 }
@@ -73,6 +78,28 @@ impl ValidationError {
         }
     }
 
+    /// Create a new validation error for a value that doesn't satisfy a type hint.
+    pub fn hint_mismatch(
+        line: usize,
+        path: &str,
+        expected: &str,
+        actual: &str,
+        value: &str,
+    ) -> Self {
+        Self {
+            line,
+            path: path.to_string(),
+            error_type: ValidationErrorType::HintMismatch {
+                expected: expected.to_string(),
+                actual: actual.to_string(),
+            },
+            message: format!(
+                "Field '{}' hinted as {}, got {} (\"{}\")",
+                path, expected, actual, value
+            ),
+        }
+    }
+
     /// Create a new validation error for an unknown field.
     pub fn unknown_field(line: usize, path: &str) -> Self {
         Self {
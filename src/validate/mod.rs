@@ -5,15 +5,20 @@
 //! - Required fields presence
 //! - Type compatibility
 //! - Unknown fields
+//! - Values that don't satisfy a type hint
 
 pub mod error;
 
 pub use error::{ValidationError, ValidationErrorType, ValidationResult};
 
 use crate::inference::{
-    is_boolean_string, is_date, is_float_string, is_integer_string, is_time, is_timestamp,
+    decimal_precision_scale, is_base64, is_boolean_string, is_calendar_date, is_calendar_datetime,
+    is_calendar_time, is_calendar_timestamp, is_date, is_datetime, is_float_string,
+    is_geojson_geometry, is_integer_string, is_time, is_timestamp, is_wkt, DateTimeFormats,
+    EpochDetection, BIGNUMERIC_MAX_INTEGER_DIGITS, NUMERIC_MAX_INTEGER_DIGITS,
 };
-use crate::schema::types::BqSchemaField;
+use crate::schema::hints::{TypeHint, TypeHints};
+use crate::schema::types::{BqMode, BqSchemaField, BqType};
 use serde_json::Value;
 use std::collections::HashMap;
 
@@ -44,6 +49,13 @@ pub struct SchemaValidator<'a> {
     options: ValidationOptions,
     /// Schema fields indexed by lowercase name for quick lookup
     schema_map: HashMap<String, &'a BqSchemaField>,
+    type_hints: TypeHints,
+    /// When set, numeric TIMESTAMPs must be epoch times within range
+    epoch: Option<EpochDetection>,
+    /// Extra date/time formats accepted for temporal types
+    datetime_formats: DateTimeFormats,
+    /// Match date/time layouts only, without calendar and clock range checks
+    regex_only_dates: bool,
 }
 
 impl<'a> SchemaValidator<'a> {
@@ -55,9 +67,44 @@ impl<'a> SchemaValidator<'a> {
             schema,
             options,
             schema_map,
+            type_hints: TypeHints::default(),
+            epoch: None,
+            datetime_formats: DateTimeFormats::default(),
+            regex_only_dates: false,
         }
     }
 
+    /// Also check values against type hints for their paths.
+    pub fn with_type_hints(mut self, type_hints: TypeHints) -> Self {
+        self.type_hints = type_hints;
+        self
+    }
+
+    /// Check numeric TIMESTAMP values with the same epoch rules as inference.
+    ///
+    /// Integer epochs within the plausible range are accepted even with
+    /// `strict_types`, and (unless strict) so are quoted ones. Other numbers
+    /// are rejected.
+    pub fn with_epoch_detection(mut self, epoch: EpochDetection) -> Self {
+        self.epoch = Some(epoch);
+        self
+    }
+
+    /// Also accept TIMESTAMP, DATETIME, DATE and TIME strings in these formats.
+    pub fn with_datetime_formats(mut self, datetime_formats: DateTimeFormats) -> Self {
+        self.datetime_formats = datetime_formats;
+        self
+    }
+
+    /// Accept any date/time string with the right layout, like `2024-02-31`.
+    ///
+    /// By default the date must exist and the time of day and time zone
+    /// offset must be in range, as BigQuery requires on load.
+    pub fn with_regex_only_dates(mut self, regex_only_dates: bool) -> Self {
+        self.regex_only_dates = regex_only_dates;
+        self
+    }
+
     /// Validate a single record against the schema.
     ///
     /// Returns validation errors found in this record.
@@ -137,6 +184,12 @@ impl<'a> SchemaValidator<'a> {
                 return;
             }
 
+            if !self.type_hints.is_empty() {
+                if let Some(hint) = self.type_hints.lookup(&strip_indices(&path)) {
+                    self.validate_hint(value, hint, line, &path, result);
+                }
+            }
+
             match field_map.get(&key_lower) {
                 Some(field) => {
                     // Validate the value against the field definition
@@ -221,7 +274,7 @@ impl<'a> SchemaValidator<'a> {
 
         let expected_type = &field.field_type;
 
-        match expected_type.as_str() {
+        let valid = match expected_type.as_str() {
             "RECORD" => {
                 match value {
                     Value::Object(obj) => {
@@ -240,102 +293,122 @@ impl<'a> SchemaValidator<'a> {
                                 result,
                             );
                         }
+                        true
                     }
-                    _ => {
-                        result.add_error(ValidationError::type_mismatch(
-                            line,
-                            path,
-                            "RECORD",
-                            json_type_name(value),
-                            &truncate_value(value),
-                        ));
-                    }
-                }
-            }
-            "STRING" => {
-                // Most types can be coerced to string
-                if !matches!(value, Value::String(_) | Value::Number(_) | Value::Bool(_)) {
-                    result.add_error(ValidationError::type_mismatch(
-                        line,
-                        path,
-                        "STRING",
-                        json_type_name(value),
-                        &truncate_value(value),
-                    ));
-                }
-            }
-            "INTEGER" => {
-                if !self.is_valid_integer(value) {
-                    result.add_error(ValidationError::type_mismatch(
-                        line,
-                        path,
-                        "INTEGER",
-                        json_type_name(value),
-                        &truncate_value(value),
-                    ));
-                }
-            }
-            "FLOAT" => {
-                if !self.is_valid_float(value) {
-                    result.add_error(ValidationError::type_mismatch(
-                        line,
-                        path,
-                        "FLOAT",
-                        json_type_name(value),
-                        &truncate_value(value),
-                    ));
+                    _ => false,
                 }
             }
-            "BOOLEAN" => {
-                if !self.is_valid_boolean(value) {
-                    result.add_error(ValidationError::type_mismatch(
-                        line,
-                        path,
-                        "BOOLEAN",
-                        json_type_name(value),
-                        &truncate_value(value),
-                    ));
-                }
+            other => self.is_valid_scalar(value, other, path),
+        };
+
+        if !valid {
+            result.add_error(ValidationError::type_mismatch(
+                line,
+                path,
+                expected_type,
+                json_type_name(value),
+                &truncate_value(value),
+            ));
+        }
+    }
+
+    /// Validate a value against the type and mode hinted for its path.
+    fn validate_hint(
+        &self,
+        value: &Value,
+        hint: &TypeHint,
+        line: usize,
+        path: &str,
+        result: &mut ValidationResult,
+    ) {
+        if value.is_null() {
+            if hint.mode == Some(BqMode::Required) {
+                result.add_error(ValidationError::hint_mismatch(
+                    line, path, "REQUIRED", "NULL", "null",
+                ));
             }
-            "TIMESTAMP" => {
-                if !self.is_valid_timestamp(value) {
-                    result.add_error(ValidationError::type_mismatch(
-                        line,
-                        path,
-                        "TIMESTAMP",
-                        json_type_name(value),
-                        &truncate_value(value),
-                    ));
-                }
+            return;
+        }
+
+        // A NULLABLE or REQUIRED JSON column holds arrays whole
+        let is_json = hint.bq_type == Some(BqType::Json);
+        let items: Vec<(String, &Value)> = match (value, hint.mode) {
+            (Value::Array(arr), Some(BqMode::Repeated)) => indexed_items(path, arr),
+            (Value::Array(arr), None) if !is_json => indexed_items(path, arr),
+            (Value::Array(_), Some(mode)) if !is_json => {
+                result.add_error(ValidationError::hint_mismatch(
+                    line,
+                    path,
+                    mode.as_str(),
+                    "ARRAY",
+                    &truncate_value(value),
+                ));
+                return;
             }
-            "DATE" => {
-                if !self.is_valid_date(value) {
-                    result.add_error(ValidationError::type_mismatch(
-                        line,
-                        path,
-                        "DATE",
-                        json_type_name(value),
-                        &truncate_value(value),
-                    ));
-                }
+            (_, Some(BqMode::Repeated)) => {
+                result.add_error(ValidationError::hint_mismatch(
+                    line,
+                    path,
+                    "REPEATED",
+                    json_type_name(value),
+                    &truncate_value(value),
+                ));
+                return;
             }
-            "TIME" => {
-                if !self.is_valid_time(value) {
-                    result.add_error(ValidationError::type_mismatch(
-                        line,
-                        path,
-                        "TIME",
-                        json_type_name(value),
-                        &truncate_value(value),
-                    ));
-                }
+            _ => vec![(path.to_string(), value)],
+        };
+
+        let Some(bq_type) = &hint.bq_type else {
+            return;
+        };
+        for (item_path, item) in items {
+            if item.is_null() || self.is_valid_scalar(item, bq_type.as_str(), &item_path) {
+                continue;
             }
-            _ => {
-                // Unknown type - skip validation
+            result.add_error(ValidationError::hint_mismatch(
+                line,
+                &item_path,
+                bq_type.as_str(),
+                json_type_name(item),
+                &truncate_value(item),
+            ));
+            if result.reached_max_errors(self.options.max_errors) {
+                return;
             }
         }
     }
 
+    /// Check if a value at `path` is valid for a non-RECORD type.
+    fn is_valid_scalar(&self, value: &Value, field_type: &str, path: &str) -> bool {
+        match field_type {
+            // Most types can be coerced to string
+            "STRING" => matches!(value, Value::String(_) | Value::Number(_) | Value::Bool(_)),
+            "BYTES" => self.is_valid_bytes(value),
+            "GEOGRAPHY" => is_valid_geography(value),
+            // JSON columns hold any value, including objects and arrays
+            "JSON" => true,
+            "INTEGER" => self.is_valid_integer(value),
+            "FLOAT" => self.is_valid_float(value),
+            "NUMERIC" => self.is_valid_decimal(value, NUMERIC_MAX_INTEGER_DIGITS),
+            "BIGNUMERIC" => self.is_valid_decimal(value, BIGNUMERIC_MAX_INTEGER_DIGITS),
+            "BOOLEAN" => self.is_valid_boolean(value),
+            "TIMESTAMP" => self.is_valid_timestamp(value, path),
+            "DATETIME" => self.is_valid_datetime(value, path),
+            "DATE" => self.is_valid_date(value, path),
+            "TIME" => self.is_valid_time(value, path),
+            // Unknown type - skip validation
+            _ => true,
+        }
+    }
+
+    /// Check if a value is valid for BYTES type (a base64 string).
+    fn is_valid_bytes(&self, value: &Value) -> bool {
+        match value {
+            Value::String(s) => s.is_empty() || is_base64(s),
+            _ => false,
+        }
+    }
+
     /// Check if a value is valid for INTEGER type.
     fn is_valid_integer(&self, value: &Value) -> bool {
         match value {
@@ -356,6 +429,20 @@ impl<'a> SchemaValidator<'a> {
         }
     }
 
+    /// Check if a value is valid for NUMERIC/BIGNUMERIC type.
+    ///
+    /// Extra fractional digits are rounded by BigQuery on load, so only the
+    /// integer digits are checked against the type's limit.
+    fn is_valid_decimal(&self, value: &Value, max_integer_digits: u8) -> bool {
+        let literal = match value {
+            Value::Number(n) => n.to_string(),
+            Value::String(s) if !self.options.strict_types && is_float_string(s) => s.clone(),
+            _ => return false,
+        };
+        decimal_precision_scale(&literal)
+            .is_some_and(|(precision, scale)| precision - scale <= max_integer_digits as u32)
+    }
+
     /// Check if a value is valid for BOOLEAN type.
     fn is_valid_boolean(&self, value: &Value) -> bool {
         match value {
@@ -366,29 +453,101 @@ impl<'a> SchemaValidator<'a> {
     }
 
     /// Check if a value is valid for TIMESTAMP type.
-    fn is_valid_timestamp(&self, value: &Value) -> bool {
+    fn is_valid_timestamp(&self, value: &Value, path: &str) -> bool {
+        match (value, &self.epoch) {
+            (Value::String(s), _) if self.is_builtin_timestamp(s) => true,
+            (Value::String(s), _) if self.is_custom_format(s, BqType::Timestamp, path) => true,
+            (Value::String(s), Some(epoch)) => !self.options.strict_types && epoch.matches_str(s),
+            (Value::Number(n), Some(epoch)) => epoch.matches_number(n),
+            (Value::Number(_), None) => !self.options.strict_types, // Unix timestamp
+            _ => false,
+        }
+    }
+
+    /// Check if a value is valid for DATETIME type (no time zone allowed).
+    fn is_valid_datetime(&self, value: &Value, path: &str) -> bool {
         match value {
-            Value::String(s) => is_timestamp(s),
-            Value::Number(_) if !self.options.strict_types => true, // Unix timestamp
+            Value::String(s) => {
+                self.is_builtin_datetime(s)
+                    || self.is_builtin_date(s)
+                    || self.is_custom_format(s, BqType::Datetime, path)
+            }
             _ => false,
         }
     }
 
     /// Check if a value is valid for DATE type.
-    fn is_valid_date(&self, value: &Value) -> bool {
+    fn is_valid_date(&self, value: &Value, path: &str) -> bool {
         match value {
-            Value::String(s) => is_date(s),
+            Value::String(s) => {
+                self.is_builtin_date(s) || self.is_custom_format(s, BqType::Date, path)
+            }
             _ => false,
         }
     }
 
     /// Check if a value is valid for TIME type.
-    fn is_valid_time(&self, value: &Value) -> bool {
+    fn is_valid_time(&self, value: &Value, path: &str) -> bool {
         match value {
-            Value::String(s) => is_time(s),
+            Value::String(s) => {
+                self.is_builtin_time(s) || self.is_custom_format(s, BqType::Time, path)
+            }
             _ => false,
         }
     }
+
+    fn is_builtin_timestamp(&self, s: &str) -> bool {
+        if self.regex_only_dates {
+            is_timestamp(s)
+        } else {
+            is_calendar_timestamp(s)
+        }
+    }
+
+    fn is_builtin_datetime(&self, s: &str) -> bool {
+        if self.regex_only_dates {
+            is_datetime(s)
+        } else {
+            is_calendar_datetime(s)
+        }
+    }
+
+    fn is_builtin_date(&self, s: &str) -> bool {
+        if self.regex_only_dates {
+            is_date(s)
+        } else {
+            is_calendar_date(s)
+        }
+    }
+
+    fn is_builtin_time(&self, s: &str) -> bool {
+        if self.regex_only_dates {
+            is_time(s)
+        } else {
+            is_calendar_time(s)
+        }
+    }
+
+    /// Check if a string at `path` is in a registered format for `bq_type`.
+    fn is_custom_format(&self, s: &str, bq_type: BqType, path: &str) -> bool {
+        !self.datetime_formats.is_empty()
+            && self
+                .datetime_formats
+                .is_valid(s, &bq_type, &strip_indices(path))
+    }
+}
+
+/// Check if a value is valid for GEOGRAPHY type.
+///
+/// Accepts WKT strings, GeoJSON geometry objects and GeoJSON encoded as a string.
+fn is_valid_geography(value: &Value) -> bool {
+    match value {
+        Value::String(s) => {
+            is_wkt(s) || serde_json::from_str::<Value>(s).is_ok_and(|v| is_geojson_geometry(&v))
+        }
+        Value::Object(_) => is_geojson_geometry(value),
+        _ => false,
+    }
 }
 
 /// Build a field path string.
@@ -400,6 +559,29 @@ fn make_path(prefix: &str, name: &str) -> String {
     }
 }
 
+/// Pair each array element with its indexed path, e.g. `tags[0]`.
+fn indexed_items<'v>(path: &str, arr: &'v [Value]) -> Vec<(String, &'v Value)> {
+    arr.iter()
+        .enumerate()
+        .map(|(idx, item)| (format!("{}[{}]", path, idx), item))
+        .collect()
+}
+
+/// Remove array indices from a field path, e.g. `items[0].id` -> `items.id`.
+fn strip_indices(path: &str) -> String {
+    let mut stripped = String::with_capacity(path.len());
+    let mut in_index = false;
+    for c in path.chars() {
+        match c {
+            '[' => in_index = true,
+            ']' if in_index => in_index = false,
+            _ if !in_index => stripped.push(c),
+            _ => {}
+        }
+    }
+    stripped
+}
+
 /// Get a human-readable type name for a JSON value.
 fn json_type_name(value: &Value) -> &'static str {
     match value {
@@ -485,6 +667,8 @@ mod tests {
             field_type: field_type.to_string(),
             mode: mode.to_string(),
             fields: None,
+            precision: None,
+            scale: None,
         }
     }
 
@@ -494,6 +678,8 @@ mod tests {
             field_type: "RECORD".to_string(),
             mode: mode.to_string(),
             fields: Some(fields),
+            precision: None,
+            scale: None,
         }
     }
 
@@ -839,6 +1025,102 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_timestamp_epoch_detection() {
+        let schema = vec![make_field("ts", "TIMESTAMP", "NULLABLE")];
+        let check = |options: ValidationOptions, value: Value| {
+            let validator = SchemaValidator::new(&schema, options)
+                .with_epoch_detection(EpochDetection::default());
+            let mut result = ValidationResult::new();
+            validator.validate_record(&json!({ "ts": value }), 1, &mut result);
+            result.valid
+        };
+        let strict = || ValidationOptions {
+            strict_types: true,
+            ..Default::default()
+        };
+
+        // Epochs in range are valid, even in strict mode
+        assert!(check(ValidationOptions::default(), json!(1700000000)));
+        assert!(check(
+            ValidationOptions::default(),
+            json!(1700000000123_i64)
+        ));
+        assert!(check(strict(), json!(1700000000)));
+        assert!(check(
+            ValidationOptions::default(),
+            json!("2024-01-15T12:30:45Z")
+        ));
+
+        // Other numbers are not
+        assert!(!check(ValidationOptions::default(), json!(42)));
+        assert!(!check(ValidationOptions::default(), json!(1700000000.5)));
+
+        // Quoted epochs only outside strict mode
+        assert!(check(ValidationOptions::default(), json!("1700000000")));
+        assert!(!check(strict(), json!("1700000000")));
+        assert!(!check(ValidationOptions::default(), json!("42")));
+    }
+
+    #[test]
+    fn test_custom_datetime_formats() {
+        let schema = vec![
+            make_field("day", "DATE", "NULLABLE"),
+            make_field("days", "DATE", "REPEATED"),
+            make_field("seen", "TIMESTAMP", "NULLABLE"),
+            make_field("other", "TIMESTAMP", "NULLABLE"),
+        ];
+        let formats =
+            DateTimeFormats::from_specs(&["DATE=%m/%d/%Y", "TIMESTAMP@seen=%d.%m.%Y %H:%M"])
+                .unwrap();
+        let validator = SchemaValidator::new(&schema, ValidationOptions::default())
+            .with_datetime_formats(formats);
+
+        let mut result = ValidationResult::new();
+        validator.validate_record(
+            &json!({"day": "03/15/2024", "days": ["2024-03-15", "3/16/2024"], "seen": "15.03.2024 14:22"}),
+            1,
+            &mut result,
+        );
+        assert!(result.valid, "{:?}", result.errors);
+
+        // Scoped formats don't apply to other fields, and formats are per type
+        let mut result = ValidationResult::new();
+        validator.validate_record(
+            &json!({"day": "15.03.2024 14:22", "other": "15.03.2024 14:22"}),
+            1,
+            &mut result,
+        );
+        assert_eq!(result.error_count, 2);
+    }
+
+    #[test]
+    fn test_calendar_checked_dates() {
+        let schema = vec![
+            make_field("d", "DATE", "NULLABLE"),
+            make_field("t", "TIME", "NULLABLE"),
+            make_field("dt", "DATETIME", "NULLABLE"),
+            make_field("ts", "TIMESTAMP", "NULLABLE"),
+        ];
+        let record = json!({
+            "d": "2024-02-31",
+            "t": "25:61:61",
+            "dt": "2023-02-29T10:00:00",
+            "ts": "2024-01-15T10:30:00+15:00"
+        });
+
+        let validator = SchemaValidator::new(&schema, ValidationOptions::default());
+        let mut result = ValidationResult::new();
+        validator.validate_record(&record, 1, &mut result);
+        assert_eq!(result.error_count, 4);
+
+        let validator =
+            SchemaValidator::new(&schema, ValidationOptions::default()).with_regex_only_dates(true);
+        let mut result = ValidationResult::new();
+        validator.validate_record(&record, 1, &mut result);
+        assert!(result.valid, "{:?}", result.errors);
+    }
+
     #[test]
     fn test_repeated_with_nulls_in_array() {
         let schema = vec![make_field("values", "INTEGER", "REPEATED")];
@@ -1074,6 +1356,171 @@ mod tests {
         // The test documents current behavior
     }
 
+    #[test]
+    fn test_validate_bytes() {
+        let schema = vec![make_field("payload", "BYTES", "NULLABLE")];
+        let validator = SchemaValidator::new(&schema, ValidationOptions::default());
+
+        for val in ["aGVsbG8gd29ybGQ=", "YQ==", ""] {
+            let mut result = ValidationResult::new();
+            validator.validate_record(&json!({ "payload": val }), 1, &mut result);
+            assert!(result.valid, "BYTES '{}' should be valid", val);
+        }
+
+        for val in [
+            json!("hello world"),
+            json!("aGVsbG8"),
+            json!(42),
+            json!(true),
+        ] {
+            let mut result = ValidationResult::new();
+            validator.validate_record(&json!({ "payload": val }), 1, &mut result);
+            assert!(!result.valid, "BYTES {} should be invalid", val);
+            assert!(matches!(
+                &result.errors[0].error_type,
+                ValidationErrorType::TypeMismatch { expected, .. } if expected == "BYTES"
+            ));
+        }
+    }
+
+    #[test]
+    fn test_validate_geography() {
+        let schema = vec![make_field("location", "GEOGRAPHY", "NULLABLE")];
+        let validator = SchemaValidator::new(&schema, ValidationOptions::default());
+
+        let valid = [
+            json!("POINT(-122.3 47.6)"),
+            json!("POLYGON((0 0, 1 0, 1 1, 0 0))"),
+            json!({"type": "Point", "coordinates": [-122.3, 47.6]}),
+            json!(r#"{"type": "LineString", "coordinates": [[0, 0], [1, 1]]}"#),
+        ];
+        for val in valid {
+            let mut result = ValidationResult::new();
+            validator.validate_record(&json!({ "location": val }), 1, &mut result);
+            assert!(result.valid, "GEOGRAPHY {} should be valid", val);
+        }
+
+        let invalid = [
+            json!("POINT(-122.3)"),
+            json!("POLYGON((0 0, 1 0, 1 1))"),
+            json!({"type": "Point", "coordinates": [400, 0]}),
+            json!({"lat": 47.6, "lon": -122.3}),
+            json!(42),
+        ];
+        for val in invalid {
+            let mut result = ValidationResult::new();
+            validator.validate_record(&json!({ "location": val }), 1, &mut result);
+            assert!(!result.valid, "GEOGRAPHY {} should be invalid", val);
+        }
+    }
+
+    #[test]
+    fn test_validate_json() {
+        let schema = vec![make_field("payload", "JSON", "NULLABLE")];
+        let validator = SchemaValidator::new(&schema, ValidationOptions::default());
+
+        for val in [
+            json!({"nested": {"deep": [1, "two"]}}),
+            json!([[1, 2], [3]]),
+            json!("text"),
+            json!(42),
+            json!(true),
+        ] {
+            let mut result = ValidationResult::new();
+            validator.validate_record(&json!({ "payload": val }), 1, &mut result);
+            assert!(result.valid, "JSON {} should be valid", val);
+        }
+    }
+
+    #[test]
+    fn test_validate_type_hints() {
+        let schema = vec![
+            make_field("zip", "STRING", "NULLABLE"),
+            make_field("tags", "STRING", "REPEATED"),
+            make_record_field(
+                "items",
+                "REPEATED",
+                vec![make_field("sku", "STRING", "NULLABLE")],
+            ),
+        ];
+        let hints = TypeHints::from_json_str(
+            r#"{"zip": "INTEGER", "tags": {"mode": "REPEATED"}, "*.sku": {"type": "INTEGER", "mode": "REQUIRED"}}"#,
+        )
+        .unwrap();
+        let validator =
+            SchemaValidator::new(&schema, ValidationOptions::default()).with_type_hints(hints);
+
+        let mut result = ValidationResult::new();
+        validator.validate_record(
+            &json!({"zip": "94103", "tags": ["a"], "items": [{"sku": 1}]}),
+            1,
+            &mut result,
+        );
+        assert!(result.valid, "{:?}", result.errors);
+
+        let mut result = ValidationResult::new();
+        validator.validate_record(
+            &json!({"zip": "SW1A", "tags": "a", "items": [{"sku": 1}, {"sku": null}]}),
+            2,
+            &mut result,
+        );
+        let errors: Vec<_> = result
+            .errors
+            .iter()
+            .filter(|e| matches!(e.error_type, ValidationErrorType::HintMismatch { .. }))
+            .map(|e| (e.path.as_str(), e.error_type.to_string()))
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                ("zip", "hinted INTEGER, got STRING".to_string()),
+                ("tags", "hinted REPEATED, got STRING".to_string()),
+                ("items[1].sku", "hinted REQUIRED, got NULL".to_string()),
+            ]
+        );
+        assert_eq!(
+            result.errors[0].message,
+            "Field 'zip' hinted as INTEGER, got STRING (\"SW1A\")"
+        );
+    }
+
+    #[test]
+    fn test_strip_indices() {
+        assert_eq!(strip_indices("items[0].tags[12]"), "items.tags");
+        assert_eq!(strip_indices("plain.path"), "plain.path");
+    }
+
+    #[test]
+    fn test_validate_datetime() {
+        let schema = vec![make_field("dt", "DATETIME", "NULLABLE")];
+        let validator = SchemaValidator::new(&schema, ValidationOptions::default());
+
+        for val in [
+            "2024-01-15T12:30:45",
+            "2024-01-15 12:30:45.123456",
+            "2024-01-15",
+        ] {
+            let mut result = ValidationResult::new();
+            validator.validate_record(&json!({ "dt": val }), 1, &mut result);
+            assert!(result.valid, "DATETIME '{}' should be valid", val);
+        }
+
+        // Zone-bearing values are TIMESTAMPs, not DATETIMEs
+        for val in [
+            "2024-01-15T12:30:45Z",
+            "2024-01-15 12:30:45+02:00",
+            "12:30:45",
+        ] {
+            let mut result = ValidationResult::new();
+            validator.validate_record(&json!({ "dt": val }), 1, &mut result);
+            assert!(!result.valid, "DATETIME '{}' should be invalid", val);
+        }
+
+        let mut result = ValidationResult::new();
+        validator.validate_record(&json!({"dt": 1705321845}), 1, &mut result);
+        assert!(!result.valid);
+    }
+
     #[test]
     fn test_validate_deeply_nested_record_10_levels() {
         // Create deeply nested schema - 10 levels deep
@@ -1799,6 +2246,50 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_validate_numeric_and_bignumeric() {
+        let schema = vec![
+            make_field("amount", "NUMERIC", "NULLABLE"),
+            make_field("big_amount", "BIGNUMERIC", "NULLABLE"),
+        ];
+
+        let validator = SchemaValidator::new(&schema, ValidationOptions::default());
+
+        let valid_records = vec![
+            json!({"amount": 42, "big_amount": 42}),
+            json!({"amount": "12345678901234.56789"}),
+            serde_json::from_str(r#"{"amount": 12345678901234567890.123456789}"#).unwrap(),
+            json!({"big_amount": format!("{}.5", "9".repeat(38))}),
+        ];
+        for record in valid_records {
+            let mut result = ValidationResult::new();
+            validator.validate_record(&record, 1, &mut result);
+            assert!(result.valid, "{} should be valid", record);
+        }
+
+        let invalid_records = vec![
+            json!({"amount": format!("{}.5", "9".repeat(30))}),
+            json!({"big_amount": "9".repeat(39)}),
+            json!({"amount": "abc"}),
+            json!({"amount": true}),
+        ];
+        for record in invalid_records {
+            let mut result = ValidationResult::new();
+            validator.validate_record(&record, 1, &mut result);
+            assert!(!result.valid, "{} should be invalid", record);
+        }
+
+        // Strict mode rejects quoted decimals
+        let options = ValidationOptions {
+            strict_types: true,
+            ..Default::default()
+        };
+        let validator = SchemaValidator::new(&schema, options);
+        let mut result = ValidationResult::new();
+        validator.validate_record(&json!({"amount": "1.5"}), 1, &mut result);
+        assert!(!result.valid);
+    }
+
     #[test]
     fn test_validation_result_reached_max_errors() {
         let mut result = ValidationResult::new();
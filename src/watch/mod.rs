@@ -4,16 +4,16 @@
 //! automatically regenerate the schema when files are modified.
 
 use crate::diff::{diff_schemas, DiffOptions};
-use crate::input::JsonRecordIterator;
-use crate::output::write_schema_json;
-use crate::schema::{GeneratorConfig, SchemaGenerator, SchemaMap};
+use crate::input::{open_input, Compression, CsvRecordIterator, JsonLayout, JsonRecordIterator};
+use crate::output::{per_file_schema_path, write_schema_json};
+use crate::schema::{GeneratorConfig, InputFormat, SchemaGenerator, SchemaMap};
 use crate::BqSchemaField;
 
 use notify::RecursiveMode;
 use notify_debouncer_mini::{new_debouncer, DebouncedEventKind};
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 use std::fs::File;
-use std::io::BufReader;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
 use std::sync::mpsc::channel;
 use std::time::Duration;
@@ -29,6 +29,10 @@ pub struct WatchConfig {
     pub quiet: bool,
     /// Ignore invalid JSON lines
     pub ignore_invalid_lines: bool,
+    /// Write one schema per input file instead of a merged schema
+    pub per_file: bool,
+    /// Directory for per-file schemas (default: next to each input file)
+    pub output_dir: Option<PathBuf>,
 }
 
 impl Default for WatchConfig {
@@ -38,14 +42,42 @@ impl Default for WatchConfig {
             on_change: None,
             quiet: false,
             ignore_invalid_lines: false,
+            per_file: false,
+            output_dir: None,
         }
     }
 }
 
+/// Bytes before the resume offset compared to detect in-place rewrites.
+const FINGERPRINT_LEN: u64 = 64;
+
+/// Cached schema state of one watched file.
+struct FileCache {
+    /// Schema of the records read so far
+    schema_map: SchemaMap,
+    /// Generator that built `schema_map`, reused for appended records
+    generator: SchemaGenerator,
+    /// Where to resume reading, if the file can be read incrementally
+    tail: Option<TailPosition>,
+}
+
+/// Position after the last complete line read from a file.
+#[derive(Debug, Clone)]
+struct TailPosition {
+    /// Byte offset just past the last newline read
+    offset: u64,
+    /// Up to `FINGERPRINT_LEN` bytes ending at `offset`
+    fingerprint: Vec<u8>,
+    /// File identity (inode on Unix), which changes when a file is rotated
+    file_id: Option<(u64, u64)>,
+    /// CSV column names for appended rows (empty for JSON)
+    headers: Vec<String>,
+}
+
 /// State for watch mode, maintaining per-file schema caches.
 pub struct WatchState {
-    /// Per-file schema maps for incremental updates
-    file_schemas: HashMap<PathBuf, SchemaMap>,
+    /// Per-file schema caches for incremental updates
+    files: HashMap<PathBuf, FileCache>,
     /// Current merged schema
     current_schema: Vec<BqSchemaField>,
     /// Generator configuration
@@ -62,7 +94,7 @@ impl WatchState {
         watch_config: WatchConfig,
     ) -> crate::Result<Self> {
         let mut state = Self {
-            file_schemas: HashMap::new(),
+            files: HashMap::new(),
             current_schema: Vec::new(),
             config,
             watch_config,
@@ -84,19 +116,163 @@ impl WatchState {
     }
 
     /// Process a single file and update its cached schema.
+    ///
+    /// If the file only grew since it was last read, just the appended lines
+    /// are parsed; otherwise the whole file is read again.
     fn process_file(&mut self, path: &Path) -> crate::Result<()> {
-        let file = File::open(path)?;
-        let reader = BufReader::new(file);
+        match self.process_appended(path) {
+            Some(result) => result,
+            None => self.process_whole_file(path),
+        }
+    }
+
+    /// Read the whole file into a fresh cache entry.
+    fn process_whole_file(&mut self, path: &Path) -> crate::Result<()> {
+        let metadata = std::fs::metadata(path)?;
+        let len = metadata.len();
+        let plain = is_uncompressed(path)?;
 
         let mut generator = SchemaGenerator::new(self.config.clone());
         let mut schema_map = SchemaMap::new();
 
-        let iter = JsonRecordIterator::new(reader, self.watch_config.ignore_invalid_lines);
+        // Appends after this point are picked up by the next change event
+        let input: Box<dyn Read + Send> = if plain {
+            Box::new(File::open(path)?.take(len))
+        } else {
+            open_input(path)?
+        };
+
+        // Headers (CSV) or None if the records aren't one per line (JSON)
+        let line_headers = match self.config.input_format {
+            InputFormat::Csv => {
+                let mut iter = CsvRecordIterator::with_dialect(input, &self.config.csv_dialect)?;
+                self.read_records(&mut iter, &mut generator, &mut schema_map)?;
+                // Rows can't be appended before the header has been written
+                let headers = iter.headers().to_vec();
+                (!headers.is_empty() || !self.config.csv_dialect.has_header).then_some(headers)
+            }
+            _ => {
+                let mut iter = JsonRecordIterator::with_layout(
+                    BufReader::new(input),
+                    self.watch_config.ignore_invalid_lines,
+                    self.config.input_format.json_layout(),
+                );
+                self.read_json_records(&mut iter, &mut generator, &mut schema_map)?;
+                (iter.layout() != JsonLayout::Stream).then(Vec::new)
+            }
+        };
+
+        // Only resume after a complete last line of an uncompressed file
+        let tail = match line_headers {
+            Some(headers) if plain && len > 0 => {
+                let mut file = File::open(path)?;
+                let fingerprint = read_fingerprint(&mut file, len)?;
+                (fingerprint.last() == Some(&b'\n')).then_some(TailPosition {
+                    offset: len,
+                    fingerprint,
+                    file_id: file_id(&metadata),
+                    headers,
+                })
+            }
+            _ => None,
+        };
+
+        self.files.insert(
+            path.to_owned(),
+            FileCache {
+                schema_map,
+                generator,
+                tail,
+            },
+        );
+        Ok(())
+    }
+
+    /// Parse the lines appended to a file since it was last read.
+    ///
+    /// Returns `None` if the file must be read in full instead: it isn't
+    /// cached, can't be resumed, or was truncated, replaced or rewritten.
+    fn process_appended(&mut self, path: &Path) -> Option<crate::Result<()>> {
+        let tail = self.files.get(path)?.tail.as_ref()?;
+
+        let metadata = std::fs::metadata(path).ok()?;
+        let len = metadata.len();
+        if len < tail.offset || file_id(&metadata) != tail.file_id {
+            return None;
+        }
+        let mut file = File::open(path).ok()?;
+        if read_fingerprint(&mut file, tail.offset).ok()? != tail.fingerprint {
+            return None;
+        }
+
+        // A partly written last line waits for the next event
+        let end = match last_line_end(&mut file, tail.offset, len) {
+            Ok(Some(end)) => end,
+            Ok(None) => return Some(Ok(())),
+            Err(e) => return Some(Err(e.into())),
+        };
 
-        for record_result in iter {
+        let offset = tail.offset;
+        let headers = tail.headers.clone();
+        let mut cache = self.files.remove(path)?;
+        let result = file
+            .seek(SeekFrom::Start(offset))
+            .map_err(crate::Error::from)
+            .and_then(|_| {
+                let appended = file.take(end - offset);
+                match self.config.input_format {
+                    InputFormat::Csv => {
+                        let mut iter = CsvRecordIterator::with_headers(
+                            appended,
+                            &self.config.csv_dialect,
+                            headers,
+                        );
+                        self.read_records(&mut iter, &mut cache.generator, &mut cache.schema_map)?;
+                        Ok(iter.headers().to_vec())
+                    }
+                    _ => {
+                        let mut iter = JsonRecordIterator::with_layout(
+                            BufReader::new(appended),
+                            self.watch_config.ignore_invalid_lines,
+                            JsonLayout::Lines,
+                        );
+                        self.read_json_records(
+                            &mut iter,
+                            &mut cache.generator,
+                            &mut cache.schema_map,
+                        )?;
+                        Ok(headers)
+                    }
+                }
+            })
+            .and_then(|headers| {
+                let mut file = File::open(path)?;
+                Ok(TailPosition {
+                    offset: end,
+                    fingerprint: read_fingerprint(&mut file, end)?,
+                    file_id: file_id(&metadata),
+                    headers,
+                })
+            });
+
+        // After an error the cache may hold part of the appended lines, so the
+        // next change reads the whole file again
+        cache.tail = result.as_ref().ok().cloned();
+        self.files.insert(path.to_owned(), cache);
+        Some(result.map(|_| ()))
+    }
+
+    /// Feed records to a file's generator and schema map.
+    fn read_records(
+        &self,
+        records: &mut dyn Iterator<Item = crate::Result<(usize, serde_json::Value)>>,
+        generator: &mut SchemaGenerator,
+        schema_map: &mut SchemaMap,
+    ) -> crate::Result<()> {
+        for record_result in records {
             match record_result {
                 Ok((_line, record)) => {
-                    let _ = generator.process_record(&record, &mut schema_map);
+                    let _ = generator.process_record(&record, schema_map);
                 }
                 Err(e) if !self.watch_config.ignore_invalid_lines => {
                     return Err(e);
@@ -104,8 +280,26 @@ impl WatchState {
                 Err(_) => continue,
             }
         }
+        Ok(())
+    }
 
-        self.file_schemas.insert(path.to_owned(), schema_map);
+    /// Feed JSON records to a file's generator without parsing their field values.
+    fn read_json_records<R: BufRead>(
+        &self,
+        records: &mut JsonRecordIterator<R>,
+        generator: &mut SchemaGenerator,
+        schema_map: &mut SchemaMap,
+    ) -> crate::Result<()> {
+        while let Some(result) =
+            records.next_raw(|_line, record| generator.process_raw_record(&record, schema_map))
+        {
+            // Records that fail are skipped; the file's schema keeps the rest
+            if let Err(e) = result {
+                if !self.watch_config.ignore_invalid_lines {
+                    return Err(e);
+                }
+            }
+        }
         Ok(())
     }
 
@@ -114,15 +308,8 @@ impl WatchState {
         let mut generator = SchemaGenerator::new(self.config.clone());
         let mut merged_schema = SchemaMap::new();
 
-        for file_schema in self.file_schemas.values() {
-            for (_key, entry) in file_schema {
-                // Convert entry to JSON value and process through generator
-                let json_value = entry_to_json(entry);
-                let mut temp_map = serde_json::Map::new();
-                temp_map.insert(entry.name.clone(), json_value);
-                let record = serde_json::Value::Object(temp_map);
-                let _ = generator.process_record(&record, &mut merged_schema);
-            }
+        for cache in self.files.values() {
+            let _ = generator.merge_schema_maps(&mut merged_schema, cache.schema_map.clone());
         }
 
         self.current_schema = generator.flatten_schema(&merged_schema);
@@ -154,9 +341,41 @@ impl WatchState {
         }
     }
 
+    /// Handle a file change event in per-file mode.
+    ///
+    /// Returns the changes to the file's own schema rather than to the
+    /// merged schema.
+    pub fn handle_per_file_change(&mut self, path: &Path) -> Option<crate::diff::SchemaDiff> {
+        let old_schema = self.file_schema(path).unwrap_or_default();
+
+        if let Err(e) = self.process_file(path) {
+            if !self.watch_config.quiet {
+                eprintln!("Warning: Error processing '{}': {}", path.display(), e);
+            }
+            return None;
+        }
+
+        self.rebuild_schema();
+
+        let new_schema = self.file_schema(path).unwrap_or_default();
+        let diff = diff_schemas(&old_schema, &new_schema, &DiffOptions::default());
+
+        if diff.has_changes() {
+            Some(diff)
+        } else {
+            None
+        }
+    }
+
     /// Handle a file deletion event.
+    ///
+    /// A deleted directory drops every cached file under it.
     pub fn handle_file_delete(&mut self, path: &Path) -> Option<crate::diff::SchemaDiff> {
-        self.file_schemas.remove(path)?;
+        let cached = self.files.len();
+        self.files.retain(|file, _| !file.starts_with(path));
+        if self.files.len() == cached {
+            return None;
+        }
 
         let old_schema = self.current_schema.clone();
 
@@ -174,53 +393,90 @@ impl WatchState {
         }
     }
 
+    /// Check if a file, or any file under a directory, has a cached schema.
+    pub fn is_tracked(&self, path: &Path) -> bool {
+        self.files.keys().any(|file| file.starts_with(path))
+    }
+
     /// Get the current schema.
     pub fn current_schema(&self) -> &[BqSchemaField] {
         &self.current_schema
     }
+
+    /// Get the schema generated from a single file, if it was processed.
+    pub fn file_schema(&self, path: &Path) -> Option<Vec<BqSchemaField>> {
+        let cache = self.files.get(path)?;
+        Some(cache.generator.flatten_schema(&cache.schema_map))
+    }
 }
 
-/// Convert a SchemaEntry to a representative JSON value.
-fn entry_to_json(entry: &crate::schema::SchemaEntry) -> serde_json::Value {
-    use crate::schema::BqType;
-    use serde_json::Value;
+/// Check if a file is stored without compression, so byte offsets into it are
+/// offsets into its records.
+fn is_uncompressed(path: &Path) -> std::io::Result<bool> {
+    if Compression::from_extension(path).is_some() {
+        return Ok(false);
+    }
+    let mut magic = Vec::with_capacity(8);
+    File::open(path)?.take(8).read_to_end(&mut magic)?;
+    Ok(Compression::from_magic(&magic) == Compression::None)
+}
 
-    match &entry.bq_type {
-        BqType::Boolean | BqType::QBoolean => Value::Bool(true),
-        BqType::Integer | BqType::QInteger => Value::Number(serde_json::Number::from(0i64)),
-        BqType::Float | BqType::QFloat => {
-            Value::Number(serde_json::Number::from_f64(0.0).unwrap_or(serde_json::Number::from(0)))
-        }
-        BqType::String => Value::String(String::new()),
-        BqType::Timestamp => Value::String("2024-01-01T00:00:00".to_string()),
-        BqType::Date => Value::String("2024-01-01".to_string()),
-        BqType::Time => Value::String("00:00:00".to_string()),
-        BqType::Record(fields) => {
-            let mut obj = serde_json::Map::new();
-            for (_, field_entry) in fields {
-                obj.insert(field_entry.name.clone(), entry_to_json(field_entry));
-            }
-            if entry.mode == crate::schema::BqMode::Repeated {
-                Value::Array(vec![Value::Object(obj)])
-            } else {
-                Value::Object(obj)
-            }
+/// Read the up to `FINGERPRINT_LEN` bytes that end at `end`.
+fn read_fingerprint(file: &mut File, end: u64) -> std::io::Result<Vec<u8>> {
+    let start = end.saturating_sub(FINGERPRINT_LEN);
+    let mut fingerprint = vec![0; (end - start) as usize];
+    file.seek(SeekFrom::Start(start))?;
+    file.read_exact(&mut fingerprint)?;
+    Ok(fingerprint)
+}
+
+/// Find the offset just past the last newline in `start..end`, scanning back
+/// from `end`.
+fn last_line_end(file: &mut File, start: u64, end: u64) -> std::io::Result<Option<u64>> {
+    let mut block = vec![0; 8192];
+    let mut block_end = end;
+    while block_end > start {
+        let block_start = block_end.saturating_sub(block.len() as u64).max(start);
+        let bytes = &mut block[..(block_end - block_start) as usize];
+        file.seek(SeekFrom::Start(block_start))?;
+        file.read_exact(bytes)?;
+        if let Some(i) = bytes.iter().rposition(|&b| b == b'\n') {
+            return Ok(Some(block_start + i as u64 + 1));
         }
-        BqType::Null => Value::Null,
-        BqType::EmptyArray => Value::Array(vec![]),
-        BqType::EmptyRecord => Value::Object(serde_json::Map::new()),
+        block_end = block_start;
     }
+    Ok(None)
+}
+
+/// Identity of a file that changes when it is replaced, e.g. by log rotation.
+#[cfg(unix)]
+fn file_id(metadata: &std::fs::Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+/// Identity of a file that changes when it is replaced, e.g. by log rotation.
+#[cfg(not(unix))]
+fn file_id(_metadata: &std::fs::Metadata) -> Option<(u64, u64)> {
+    None
 }
 
 /// Run watch mode.
+///
+/// Patterns are watched from their directories, recursively when a directory
+/// part has a wildcard (like `data/**/*.json`), so files created or renamed
+/// into place after startup, also in new subdirectories, are picked up.
 pub fn run_watch(
     patterns: &[String],
     output_path: Option<&Path>,
     config: GeneratorConfig,
     watch_config: WatchConfig,
 ) -> crate::Result<()> {
-    // Collect initial files
-    let files = collect_files_from_patterns(patterns)?;
+    // Collect initial files, leaving out schemas written in per-file mode
+    let mut files = collect_files_from_patterns(patterns)?;
+    if watch_config.per_file {
+        files.retain(|file| !is_per_file_schema(file));
+    }
 
     if files.is_empty() {
         eprintln!("Error: No files matched the patterns");
@@ -231,7 +487,18 @@ pub fn run_watch(
     let mut state = WatchState::new(&files, config, watch_config.clone())?;
 
     // Write initial schema
-    if let Some(path) = output_path {
+    if watch_config.per_file {
+        if let Some(dir) = &watch_config.output_dir {
+            std::fs::create_dir_all(dir)?;
+        }
+        for file in &files {
+            if let Some(schema_path) = write_per_file_schema(&state, file, &watch_config)? {
+                if !watch_config.quiet {
+                    eprintln!("Initial schema written to {}", schema_path.display());
+                }
+            }
+        }
+    } else if let Some(path) = output_path {
         write_schema_to_file(path, state.current_schema())?;
         if !watch_config.quiet {
             eprintln!("Initial schema written to {}", path.display());
@@ -243,19 +510,18 @@ pub fn run_watch(
     let mut debouncer = new_debouncer(Duration::from_millis(watch_config.debounce_ms), tx)
         .map_err(|e| crate::Error::Io(std::io::Error::other(e.to_string())))?;
 
-    // Get unique directories to watch
-    let watch_dirs = get_unique_dirs(&files);
-
-    // Watch directories
-    for dir in &watch_dirs {
+    for (dir, mode) in watch_roots(patterns) {
+        if !dir.is_dir() {
+            continue;
+        }
         debouncer
             .watcher()
-            .watch(dir, RecursiveMode::NonRecursive)
+            .watch(&dir, mode)
             .map_err(|e| crate::Error::Io(std::io::Error::other(e.to_string())))?;
     }
 
-    // Create a set of file paths we're interested in
-    let watched_files: HashSet<PathBuf> = files.iter().cloned().collect();
+    // Event paths are absolute; relative patterns match them relative to here
+    let cwd = std::env::current_dir()?;
 
     println!("[{}] Watching {} files...", format_time(), files.len());
 
@@ -264,92 +530,35 @@ pub fn run_watch(
         match events {
             Ok(events) => {
                 for event in events {
-                    let path = event.path;
-
-                    // Skip if not a file we're watching
-                    if !watched_files.contains(&path) && !matches_any_pattern(&path, patterns) {
+                    if event.kind != DebouncedEventKind::Any {
                         continue;
                     }
+                    let path = event.path;
 
-                    if event.kind == DebouncedEventKind::Any {
-                        // File modified or created
-                        if path.exists() && path.is_file() {
-                            if !watch_config.quiet {
-                                println!("[{}] File changed: {}", format_time(), path.display());
-                                println!("[{}] Regenerating schema...", format_time());
-                            }
-
-                            if let Some(diff) = state.handle_file_change(&path) {
-                                // Print diff
-                                if !watch_config.quiet {
-                                    println!("[{}] Schema updated:", format_time());
-                                    print_diff_summary(&diff);
-                                }
-
-                                // Write output
-                                if let Some(out_path) = output_path {
-                                    if let Err(e) =
-                                        write_schema_to_file(out_path, state.current_schema())
-                                    {
-                                        eprintln!("Error writing schema: {}", e);
-                                    } else if !watch_config.quiet {
-                                        println!(
-                                            "[{}] Wrote {}",
-                                            format_time(),
-                                            out_path.display()
-                                        );
-                                    }
-                                }
-
-                                // Run on-change command
-                                if let Some(ref cmd) = watch_config.on_change {
-                                    if !watch_config.quiet {
-                                        println!("[{}] Running: {}", format_time(), cmd);
-                                    }
-                                    let _ = std::process::Command::new("sh")
-                                        .arg("-c")
-                                        .arg(cmd)
-                                        .status();
-                                }
-                            } else if !watch_config.quiet {
-                                println!("[{}] No schema changes", format_time());
+                    if path.is_dir() {
+                        // New or renamed directory: pick up files not seen yet
+                        for file in collect_files_from_patterns(patterns)? {
+                            let skip = state.is_tracked(&file)
+                                || (watch_config.per_file && is_per_file_schema(&file));
+                            if !skip {
+                                on_file_changed(&mut state, &file, output_path, &watch_config);
                             }
-                        } else if !path.exists() {
-                            // File deleted
-                            if !watch_config.quiet {
-                                println!("[{}] File deleted: {}", format_time(), path.display());
-                                println!("[{}] Regenerating schema...", format_time());
-                            }
-
-                            if let Some(diff) = state.handle_file_delete(&path) {
-                                if !watch_config.quiet {
-                                    println!("[{}] Schema updated:", format_time());
-                                    print_diff_summary(&diff);
-                                }
-
-                                if let Some(out_path) = output_path {
-                                    if let Err(e) =
-                                        write_schema_to_file(out_path, state.current_schema())
-                                    {
-                                        eprintln!("Error writing schema: {}", e);
-                                    } else if !watch_config.quiet {
-                                        println!(
-                                            "[{}] Wrote {}",
-                                            format_time(),
-                                            out_path.display()
-                                        );
-                                    }
-                                }
-
-                                if let Some(ref cmd) = watch_config.on_change {
-                                    if !watch_config.quiet {
-                                        println!("[{}] Running: {}", format_time(), cmd);
-                                    }
-                                    let _ = std::process::Command::new("sh")
-                                        .arg("-c")
-                                        .arg(cmd)
-                                        .status();
-                                }
+                        }
+                    } else if path.is_file() {
+                        let Some(file) = matching_path(&path, patterns, &cwd) else {
+                            continue;
+                        };
+                        // Skip our own per-file schemas
+                        if watch_config.per_file && is_per_file_schema(&file) {
+                            continue;
+                        }
+                        on_file_changed(&mut state, &file, output_path, &watch_config);
+                    } else {
+                        // Deleted or renamed away, maybe a whole directory
+                        let relative = path.strip_prefix(&cwd).unwrap_or(&path).to_owned();
+                        for removed in [path, relative] {
+                            if state.is_tracked(&removed) {
+                                on_path_removed(&mut state, &removed, output_path, &watch_config);
                             }
                         }
                     }
@@ -364,6 +573,93 @@ pub fn run_watch(
     Ok(())
 }
 
+/// Reprocess a created or modified file and publish the new schema.
+fn on_file_changed(
+    state: &mut WatchState,
+    path: &Path,
+    output_path: Option<&Path>,
+    watch_config: &WatchConfig,
+) {
+    if !watch_config.quiet {
+        println!("[{}] File changed: {}", format_time(), path.display());
+        println!("[{}] Regenerating schema...", format_time());
+    }
+
+    let diff = if watch_config.per_file {
+        state.handle_per_file_change(path)
+    } else {
+        state.handle_file_change(path)
+    };
+
+    match diff {
+        Some(diff) => publish_schema(state, &diff, path, output_path, watch_config),
+        None if !watch_config.quiet => println!("[{}] No schema changes", format_time()),
+        None => {}
+    }
+}
+
+/// Drop a deleted file, or every file under a deleted directory.
+fn on_path_removed(
+    state: &mut WatchState,
+    path: &Path,
+    output_path: Option<&Path>,
+    watch_config: &WatchConfig,
+) {
+    if watch_config.per_file {
+        // Schema files of deleted inputs are left in place
+        state.handle_file_delete(path);
+        if !watch_config.quiet {
+            println!("[{}] File deleted: {}", format_time(), path.display());
+        }
+        return;
+    }
+
+    if !watch_config.quiet {
+        println!("[{}] File deleted: {}", format_time(), path.display());
+        println!("[{}] Regenerating schema...", format_time());
+    }
+
+    if let Some(diff) = state.handle_file_delete(path) {
+        publish_schema(state, &diff, path, output_path, watch_config);
+    }
+}
+
+/// Print a schema change, write the schema and run the on-change command.
+fn publish_schema(
+    state: &WatchState,
+    diff: &crate::diff::SchemaDiff,
+    changed: &Path,
+    output_path: Option<&Path>,
+    watch_config: &WatchConfig,
+) {
+    if !watch_config.quiet {
+        println!("[{}] Schema updated:", format_time());
+        print_diff_summary(diff);
+    }
+
+    let written = if watch_config.per_file {
+        write_per_file_schema(state, changed, watch_config)
+    } else if let Some(out_path) = output_path {
+        write_schema_to_file(out_path, state.current_schema()).map(|_| Some(out_path.to_owned()))
+    } else {
+        Ok(None)
+    };
+    match written {
+        Ok(Some(path)) if !watch_config.quiet => {
+            println!("[{}] Wrote {}", format_time(), path.display());
+        }
+        Ok(_) => {}
+        Err(e) => eprintln!("Error writing schema: {}", e),
+    }
+
+    if let Some(ref cmd) = watch_config.on_change {
+        if !watch_config.quiet {
+            println!("[{}] Running: {}", format_time(), cmd);
+        }
+        let _ = std::process::Command::new("sh").arg("-c").arg(cmd).status();
+    }
+}
+
 /// Collect files matching glob patterns.
 fn collect_files_from_patterns(patterns: &[String]) -> crate::Result<Vec<PathBuf>> {
     let mut files = Vec::new();
@@ -389,24 +685,59 @@ fn collect_files_from_patterns(patterns: &[String]) -> crate::Result<Vec<PathBuf
     Ok(files)
 }
 
-/// Get unique parent directories from a list of files.
-fn get_unique_dirs(files: &[PathBuf]) -> Vec<PathBuf> {
-    let mut dirs: HashSet<PathBuf> = HashSet::new();
+/// Directories to watch for a set of patterns.
+///
+/// Each pattern is watched from its longest directory prefix without glob
+/// characters: recursively if a directory part has a wildcard, otherwise only
+/// that directory.
+fn watch_roots(patterns: &[String]) -> Vec<(PathBuf, RecursiveMode)> {
+    let mut roots: Vec<(PathBuf, RecursiveMode)> = Vec::new();
 
-    for file in files {
-        if let Some(parent) = file.parent() {
-            dirs.insert(parent.to_owned());
+    for pattern in patterns {
+        let components: Vec<_> = Path::new(pattern).components().collect();
+        let wildcard = components.iter().position(|component| {
+            component
+                .as_os_str()
+                .to_string_lossy()
+                .contains(['*', '?', '['])
+        });
+        let (dir, mode) = match wildcard {
+            Some(i) if i + 1 < components.len() => (&components[..i], RecursiveMode::Recursive),
+            Some(i) => (&components[..i], RecursiveMode::NonRecursive),
+            None => (
+                &components[..components.len().saturating_sub(1)],
+                RecursiveMode::NonRecursive,
+            ),
+        };
+        let mut dir: PathBuf = dir.iter().collect();
+        if dir.as_os_str().is_empty() {
+            dir = PathBuf::from(".");
+        }
+
+        match roots.iter_mut().find(|(root, _)| *root == dir) {
+            Some((_, root_mode)) => {
+                if mode == RecursiveMode::Recursive {
+                    *root_mode = mode;
+                }
+            }
+            None => roots.push((dir, mode)),
         }
     }
 
-    dirs.into_iter().collect()
+    roots
 }
 
 /// Check if a path matches any of the patterns.
+///
+/// As in `glob::glob`, wildcards don't match `/`; only `**` crosses directories.
 fn matches_any_pattern(path: &Path, patterns: &[String]) -> bool {
+    let options = glob::MatchOptions {
+        require_literal_separator: true,
+        ..Default::default()
+    };
     for pattern in patterns {
         if let Ok(glob_pattern) = glob::Pattern::new(pattern) {
-            if glob_pattern.matches_path(path) {
+            if glob_pattern.matches_path_with(path, options) {
                 return true;
             }
         }
@@ -414,6 +745,17 @@ fn matches_any_pattern(path: &Path, patterns: &[String]) -> bool {
     false
 }
 
+/// Return an event path in the form the patterns use, if one matches.
+///
+/// Relative patterns are matched against the path relative to `cwd`.
+fn matching_path(path: &Path, patterns: &[String], cwd: &Path) -> Option<PathBuf> {
+    if matches_any_pattern(path, patterns) {
+        return Some(path.to_owned());
+    }
+    let relative = path.strip_prefix(cwd).ok()?;
+    matches_any_pattern(relative, patterns).then(|| relative.to_owned())
+}
+
 /// Write schema to a file.
 fn write_schema_to_file(path: &Path, schema: &[BqSchemaField]) -> crate::Result<()> {
     let mut file = File::create(path)?;
@@ -421,6 +763,29 @@ fn write_schema_to_file(path: &Path, schema: &[BqSchemaField]) -> crate::Result<
     Ok(())
 }
 
+/// Write the schema of one input file in per-file mode.
+///
+/// Returns the path written, or `None` if the file has no cached schema.
+fn write_per_file_schema(
+    state: &WatchState,
+    input_path: &Path,
+    watch_config: &WatchConfig,
+) -> crate::Result<Option<PathBuf>> {
+    let Some(schema) = state.file_schema(input_path) else {
+        return Ok(None);
+    };
+    let schema_path = per_file_schema_path(input_path, watch_config.output_dir.as_deref());
+    write_schema_to_file(&schema_path, &schema)?;
+    Ok(Some(schema_path))
+}
+
+/// Check if a path is named like a per-file schema (`*.schema.json`).
+fn is_per_file_schema(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.ends_with(".schema.json"))
+}
+
 /// Format current time for log messages.
 fn format_time() -> String {
     use std::time::SystemTime;
@@ -470,6 +835,7 @@ fn print_diff_summary(diff: &crate::diff::SchemaDiff) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::input::CsvDialect;
     use std::io::Write;
     use tempfile::tempdir;
 
@@ -492,6 +858,51 @@ mod tests {
         assert!(state.current_schema().iter().any(|f| f.name == "value"));
     }
 
+    #[test]
+    fn test_watch_state_json_array() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("test.json");
+
+        let mut file = File::create(&file_path).unwrap();
+        writeln!(file, r#"{{"name": "test"}}"#).unwrap();
+        writeln!(file, "{{\n  \"value\": 42\n}}").unwrap();
+
+        let config = GeneratorConfig {
+            input_format: InputFormat::JsonArray,
+            ..Default::default()
+        };
+        let state = WatchState::new(&[file_path], config, WatchConfig::default()).unwrap();
+
+        assert!(state.current_schema().iter().any(|f| f.name == "name"));
+        assert!(state.current_schema().iter().any(|f| f.name == "value"));
+    }
+
+    #[test]
+    fn test_watch_state_csv_dialect() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("test.tsv");
+        std::fs::write(&file_path, "1\t2024-01-01\n2\tNULL\n").unwrap();
+
+        let config = GeneratorConfig {
+            input_format: InputFormat::Csv,
+            csv_dialect: CsvDialect {
+                delimiter: b'\t',
+                has_header: false,
+                null_markers: vec!["NULL".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let state = WatchState::new(&[file_path], config, WatchConfig::default()).unwrap();
+
+        let types: Vec<_> = state
+            .current_schema()
+            .iter()
+            .map(|f| (f.name.as_str(), f.field_type.as_str()))
+            .collect();
+        assert_eq!(types, vec![("column_1", "INTEGER"), ("column_2", "DATE")]);
+    }
+
     #[test]
     fn test_file_change_detection() {
         let dir = tempdir().unwrap();
@@ -674,113 +1085,24 @@ mod tests {
     }
 
     #[test]
-    fn test_get_unique_dirs() {
-        let files = vec![
-            PathBuf::from("/data/a/file1.json"),
-            PathBuf::from("/data/a/file2.json"),
-            PathBuf::from("/data/b/file3.json"),
-            PathBuf::from("/data/c/file4.json"),
-        ];
-
-        let dirs = get_unique_dirs(&files);
-
-        // Should have 3 unique directories
-        assert_eq!(dirs.len(), 3);
-        assert!(dirs.contains(&PathBuf::from("/data/a")));
-        assert!(dirs.contains(&PathBuf::from("/data/b")));
-        assert!(dirs.contains(&PathBuf::from("/data/c")));
-    }
-
-    #[test]
-    fn test_entry_to_json_all_types() {
-        use crate::schema::{BqMode, BqType, SchemaEntry, SchemaMap};
-
-        // Boolean
-        let entry = SchemaEntry::new("bool".to_string(), BqType::Boolean, BqMode::Nullable);
-        assert!(entry_to_json(&entry).is_boolean());
-
-        // QBoolean
-        let entry = SchemaEntry::new("qbool".to_string(), BqType::QBoolean, BqMode::Nullable);
-        assert!(entry_to_json(&entry).is_boolean());
-
-        // Integer
-        let entry = SchemaEntry::new("int".to_string(), BqType::Integer, BqMode::Nullable);
-        assert!(entry_to_json(&entry).is_number());
-
-        // QInteger
-        let entry = SchemaEntry::new("qint".to_string(), BqType::QInteger, BqMode::Nullable);
-        assert!(entry_to_json(&entry).is_number());
-
-        // Float
-        let entry = SchemaEntry::new("float".to_string(), BqType::Float, BqMode::Nullable);
-        assert!(entry_to_json(&entry).is_number());
-
-        // QFloat
-        let entry = SchemaEntry::new("qfloat".to_string(), BqType::QFloat, BqMode::Nullable);
-        assert!(entry_to_json(&entry).is_number());
-
-        // String
-        let entry = SchemaEntry::new("str".to_string(), BqType::String, BqMode::Nullable);
-        assert!(entry_to_json(&entry).is_string());
-
-        // Timestamp
-        let entry = SchemaEntry::new("ts".to_string(), BqType::Timestamp, BqMode::Nullable);
-        let json = entry_to_json(&entry);
-        assert!(json.is_string());
-        assert!(json.as_str().unwrap().contains("T"));
-
-        // Date
-        let entry = SchemaEntry::new("date".to_string(), BqType::Date, BqMode::Nullable);
-        let json = entry_to_json(&entry);
-        assert!(json.is_string());
-        assert!(json.as_str().unwrap().contains("-"));
-
-        // Time
-        let entry = SchemaEntry::new("time".to_string(), BqType::Time, BqMode::Nullable);
-        let json = entry_to_json(&entry);
-        assert!(json.is_string());
-        assert!(json.as_str().unwrap().contains(":"));
-
-        // Null
-        let entry = SchemaEntry::new("null".to_string(), BqType::Null, BqMode::Nullable);
-        assert!(entry_to_json(&entry).is_null());
-
-        // EmptyArray
-        let entry = SchemaEntry::new("arr".to_string(), BqType::EmptyArray, BqMode::Nullable);
-        let json = entry_to_json(&entry);
-        assert!(json.is_array());
-        assert!(json.as_array().unwrap().is_empty());
-
-        // EmptyRecord
-        let entry = SchemaEntry::new("rec".to_string(), BqType::EmptyRecord, BqMode::Nullable);
-        let json = entry_to_json(&entry);
-        assert!(json.is_object());
-        assert!(json.as_object().unwrap().is_empty());
-
-        // Record with nested fields
-        let mut nested = SchemaMap::new();
-        nested.insert(
-            "name".to_string(),
-            SchemaEntry::new("name".to_string(), BqType::String, BqMode::Nullable),
-        );
-        let entry = SchemaEntry::new("user".to_string(), BqType::Record(nested), BqMode::Nullable);
-        let json = entry_to_json(&entry);
-        assert!(json.is_object());
-        assert!(json.as_object().unwrap().contains_key("name"));
-
-        // Repeated Record
-        let mut nested = SchemaMap::new();
-        nested.insert(
-            "id".to_string(),
-            SchemaEntry::new("id".to_string(), BqType::Integer, BqMode::Nullable),
-        );
-        let entry = SchemaEntry::new(
-            "items".to_string(),
-            BqType::Record(nested),
-            BqMode::Repeated,
+    fn test_watch_roots() {
+        let roots = watch_roots(&[
+            "data/*.json".to_string(),
+            "data/**/*.csv".to_string(),
+            "logs/2024-*/app.json".to_string(),
+            "/abs/file.json".to_string(),
+            "*.json".to_string(),
+        ]);
+
+        assert_eq!(
+            roots,
+            vec![
+                (PathBuf::from("data"), RecursiveMode::Recursive),
+                (PathBuf::from("logs"), RecursiveMode::Recursive),
+                (PathBuf::from("/abs"), RecursiveMode::NonRecursive),
+                (PathBuf::from("."), RecursiveMode::NonRecursive),
+            ]
         );
-        let json = entry_to_json(&entry);
-        assert!(json.is_array());
     }
 
     #[test]
@@ -869,6 +1191,178 @@ mod tests {
         assert_eq!(field.field_type, "FLOAT");
     }
 
+    #[test]
+    fn test_handle_per_file_change() {
+        let dir = tempdir().unwrap();
+        let a = dir.path().join("a.json");
+        let b = dir.path().join("b.json");
+        std::fs::write(&a, "{\"id\": 1}\n").unwrap();
+        std::fs::write(&b, "{\"id\": 2, \"name\": \"x\"}\n").unwrap();
+
+        let watch_config = WatchConfig {
+            per_file: true,
+            output_dir: Some(dir.path().join("schemas")),
+            ..Default::default()
+        };
+        let files = vec![a.clone(), b.clone()];
+        let mut state =
+            WatchState::new(&files, GeneratorConfig::default(), watch_config.clone()).unwrap();
+        assert_eq!(state.file_schema(&a).unwrap().len(), 1);
+        assert_eq!(state.file_schema(&b).unwrap().len(), 2);
+
+        // The merged schema already has "name", but a.json's own schema changes
+        std::fs::write(&a, "{\"id\": 1, \"name\": \"y\"}\n").unwrap();
+        let diff = state.handle_per_file_change(&a).unwrap();
+        assert_eq!(diff.changes.len(), 1);
+        assert_eq!(diff.changes[0].path, "name");
+        assert!(state.handle_per_file_change(&a).is_none());
+
+        std::fs::create_dir_all(watch_config.output_dir.as_ref().unwrap()).unwrap();
+        let written = write_per_file_schema(&state, &a, &watch_config)
+            .unwrap()
+            .unwrap();
+        assert_eq!(written, dir.path().join("schemas").join("a.schema.json"));
+        let schema: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&written).unwrap()).unwrap();
+        assert_eq!(schema.as_array().unwrap().len(), 2);
+
+        assert!(is_per_file_schema(&written));
+        assert!(!is_per_file_schema(&a));
+    }
+
+    fn append(path: &Path, data: &str) {
+        let mut file = std::fs::OpenOptions::new().append(true).open(path).unwrap();
+        file.write_all(data.as_bytes()).unwrap();
+    }
+
+    fn field_names(schema: &[BqSchemaField]) -> Vec<&str> {
+        schema.iter().map(|f| f.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_handle_file_change_reads_appended_lines() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("log.json");
+        let first = format!("{{\"id\": 1, \"pad\": \"{}\"}}\n", "x".repeat(80));
+        std::fs::write(&file_path, &first).unwrap();
+
+        let mut state = WatchState::new(
+            std::slice::from_ref(&file_path),
+            GeneratorConfig::default(),
+            WatchConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            state.files[&file_path].tail.as_ref().unwrap().offset,
+            first.len() as u64
+        );
+
+        // Corrupt the start of the first line: only appended bytes are parsed,
+        // so this goes unnoticed
+        let mut file = std::fs::OpenOptions::new()
+            .write(true)
+            .open(&file_path)
+            .unwrap();
+        file.write_all(b"#").unwrap();
+        append(&file_path, "{\"name\": \"a\"}\n");
+
+        let diff = state.handle_file_change(&file_path).unwrap();
+        assert_eq!(diff.changes.len(), 1);
+        assert_eq!(field_names(state.current_schema()), ["id", "name", "pad"]);
+
+        // A partly written line waits until it is complete
+        append(&file_path, "{\"count\": ");
+        assert!(state.handle_file_change(&file_path).is_none());
+        append(&file_path, "3}\n");
+        assert!(state.handle_file_change(&file_path).is_some());
+        assert_eq!(
+            field_names(state.current_schema()),
+            ["count", "id", "name", "pad"]
+        );
+    }
+
+    #[test]
+    fn test_handle_file_change_rereads_truncated_or_rotated_file() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("log.json");
+        let pad = "x".repeat(80);
+        std::fs::write(
+            &file_path,
+            format!("{{\"old\": 1, \"pad\": \"{}\"}}\n{{\"id\": 1}}\n", pad),
+        )
+        .unwrap();
+
+        let mut state = WatchState::new(
+            std::slice::from_ref(&file_path),
+            GeneratorConfig::default(),
+            WatchConfig::default(),
+        )
+        .unwrap();
+
+        // Truncated: read again from the start
+        std::fs::write(&file_path, "{\"id\": 1}\n").unwrap();
+        state.handle_file_change(&file_path).unwrap();
+        assert_eq!(field_names(state.current_schema()), ["id"]);
+
+        // Rotated: a new file with the same tail bytes, but a different first line
+        std::fs::write(
+            &file_path,
+            format!("{{\"old\": 1, \"pad\": \"{}\"}}\n", pad),
+        )
+        .unwrap();
+        state.handle_file_change(&file_path).unwrap();
+        let rotated = dir.path().join("log.json.new");
+        std::fs::write(
+            &rotated,
+            format!("{{\"new\": 1, \"pad\": \"{}\"}}\n{{\"id\": 2}}\n", pad),
+        )
+        .unwrap();
+        std::fs::rename(&rotated, &file_path).unwrap();
+        state.handle_file_change(&file_path).unwrap();
+        assert_eq!(field_names(state.current_schema()), ["id", "new", "pad"]);
+    }
+
+    #[test]
+    fn test_handle_file_change_appended_csv_rows() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("log.csv");
+        std::fs::write(&file_path, "id,name\n1,a\n").unwrap();
+
+        let config = GeneratorConfig {
+            input_format: InputFormat::Csv,
+            ..Default::default()
+        };
+        let mut state = WatchState::new(
+            std::slice::from_ref(&file_path),
+            config,
+            WatchConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(state.current_schema()[0].field_type, "INTEGER");
+
+        // Appended rows use the header read before
+        append(&file_path, "x,b\n");
+        let diff = state.handle_file_change(&file_path).unwrap();
+        assert_eq!(diff.changes[0].path, "id");
+        assert_eq!(state.current_schema()[0].field_type, "STRING");
+        assert_eq!(field_names(state.current_schema()), ["id", "name"]);
+    }
+
+    #[test]
+    fn test_json_array_is_reread_in_full() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("data.json");
+        std::fs::write(&file_path, "[{\"id\": 1}]\n").unwrap();
+
+        let state = WatchState::new(
+            std::slice::from_ref(&file_path),
+            GeneratorConfig::default(),
+            WatchConfig::default(),
+        )
+        .unwrap();
+        assert!(state.files[&file_path].tail.is_none());
+    }
+
     // ===== Additional Coverage Tests =====
 
     #[test]
@@ -960,21 +1454,71 @@ mod tests {
     }
 
     #[test]
-    fn test_get_unique_dirs_empty() {
-        let dirs = get_unique_dirs(&[]);
-        assert!(dirs.is_empty());
+    fn test_matches_any_pattern_recursive() {
+        let patterns = vec!["data/**/*.json".to_string()];
+
+        assert!(matches_any_pattern(Path::new("data/a.json"), &patterns));
+        assert!(matches_any_pattern(Path::new("data/x/y/a.json"), &patterns));
+        assert!(!matches_any_pattern(Path::new("other/a.json"), &patterns));
+
+        // A single `*` stays within one directory, as when globbing
+        let patterns = vec!["data/*.json".to_string()];
+        assert!(!matches_any_pattern(Path::new("data/x/a.json"), &patterns));
+    }
+
+    #[test]
+    fn test_matching_path() {
+        let cwd = Path::new("/work");
+        let patterns = vec!["data/*.json".to_string(), "/abs/*.json".to_string()];
+
+        assert_eq!(
+            matching_path(Path::new("/work/data/a.json"), &patterns, cwd),
+            Some(PathBuf::from("data/a.json"))
+        );
+        assert_eq!(
+            matching_path(Path::new("/abs/b.json"), &patterns, cwd),
+            Some(PathBuf::from("/abs/b.json"))
+        );
+        assert_eq!(
+            matching_path(Path::new("/work/other/a.json"), &patterns, cwd),
+            None
+        );
     }
 
     #[test]
-    fn test_get_unique_dirs_duplicates() {
-        let files = vec![
-            PathBuf::from("/data/file1.json"),
-            PathBuf::from("/data/file2.json"),
-            PathBuf::from("/data/file3.json"),
-        ];
+    fn test_new_and_deleted_directories() {
+        let dir = tempdir().unwrap();
+        let first = dir.path().join("a.json");
+        std::fs::write(&first, "{\"id\": 1}\n").unwrap();
+        let mut state = WatchState::new(
+            std::slice::from_ref(&first),
+            GeneratorConfig::default(),
+            WatchConfig::default(),
+        )
+        .unwrap();
+
+        // A file in a directory created after startup is added
+        let sub = dir.path().join("sub").join("deeper");
+        std::fs::create_dir_all(&sub).unwrap();
+        let nested = sub.join("b.json");
+        std::fs::write(&nested, "{\"name\": \"x\"}\n").unwrap();
+        let pattern = format!("{}/**/*.json", dir.path().display());
+        let new_files: Vec<_> = collect_files_from_patterns(&[pattern])
+            .unwrap()
+            .into_iter()
+            .filter(|file| !state.is_tracked(file))
+            .collect();
+        assert_eq!(new_files, vec![nested.clone()]);
+        assert!(state.handle_file_change(&nested).is_some());
+        assert_eq!(state.current_schema().len(), 2);
 
-        let dirs = get_unique_dirs(&files);
-        assert_eq!(dirs.len(), 1);
+        // Removing the directory drops every file under it
+        assert!(state.is_tracked(&dir.path().join("sub")));
+        let diff = state.handle_file_delete(&dir.path().join("sub")).unwrap();
+        assert_eq!(diff.changes[0].path, "name");
+        assert!(!state.is_tracked(&nested));
+        assert!(state.is_tracked(&first));
+        assert!(state.handle_file_delete(&dir.path().join("sub")).is_none());
     }
 
     #[test]
@@ -1012,6 +1556,8 @@ mod tests {
             on_change: Some("echo done".to_string()),
             quiet: true,
             ignore_invalid_lines: true,
+            per_file: false,
+            output_dir: None,
         };
 
         assert_eq!(config.debounce_ms, 500);
@@ -1020,41 +1566,6 @@ mod tests {
         assert!(config.ignore_invalid_lines);
     }
 
-    #[test]
-    fn test_entry_to_json_nested_repeated_record() {
-        use crate::schema::{BqMode, BqType, SchemaEntry, SchemaMap};
-
-        // Nested repeated record
-        let mut inner_nested = SchemaMap::new();
-        inner_nested.insert(
-            "deep".to_string(),
-            SchemaEntry::new("deep".to_string(), BqType::String, BqMode::Nullable),
-        );
-
-        let mut nested = SchemaMap::new();
-        nested.insert(
-            "inner".to_string(),
-            SchemaEntry::new(
-                "inner".to_string(),
-                BqType::Record(inner_nested),
-                BqMode::Nullable,
-            ),
-        );
-
-        let entry = SchemaEntry::new(
-            "outer".to_string(),
-            BqType::Record(nested),
-            BqMode::Repeated,
-        );
-        let json = entry_to_json(&entry);
-
-        assert!(json.is_array());
-        let arr = json.as_array().unwrap();
-        assert_eq!(arr.len(), 1);
-        assert!(arr[0].is_object());
-        assert!(arr[0].as_object().unwrap().contains_key("inner"));
-    }
-
     #[test]
     fn test_watch_state_mixed_file_types() {
         let dir = tempdir().unwrap();
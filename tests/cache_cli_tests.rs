@@ -0,0 +1,267 @@
+//! CLI integration tests for the on-disk schema cache (--cache-dir).
+
+use std::fs::{self, File};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, SystemTime};
+use tempfile::tempdir;
+
+/// Helper to run the CLI with files
+fn run_cli_with_files(files: &[String], args: &[&str]) -> (String, String, bool) {
+    let output = Command::new("./target/debug/bq-schema-gen")
+        .args(files)
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .expect("Failed to run command");
+
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    (stdout, stderr, output.status.success())
+}
+
+/// Parse JSON output from CLI
+fn parse_schema(output: &str) -> serde_json::Value {
+    serde_json::from_str(output).expect("Failed to parse JSON output")
+}
+
+/// Write input files with a field whose type differs between files
+fn create_test_files(dir: &Path) -> Vec<String> {
+    let contents = [
+        "{\"id\": 1, \"name\": \"a\"}\n{\"id\": 2, \"tags\": []}\n",
+        "{\"id\": 3, \"score\": 1.5}\n",
+        "{\"id\": 4, \"score\": 2, \"created\": \"2024-01-15\"}\n",
+    ];
+    contents
+        .iter()
+        .enumerate()
+        .map(|(i, content)| {
+            let path = dir.join(format!("data{}.json", i));
+            fs::write(&path, content).expect("Failed to write file");
+            path.to_string_lossy().to_string()
+        })
+        .collect()
+}
+
+fn set_modified(path: &str, offset_secs: u64) {
+    File::options()
+        .write(true)
+        .open(path)
+        .unwrap()
+        .set_modified(SystemTime::now() + Duration::from_secs(offset_secs))
+        .unwrap();
+}
+
+#[test]
+fn test_cache_reused_between_runs() {
+    let dir = tempdir().unwrap();
+    let files = create_test_files(dir.path());
+    let cache_dir = dir.path().join("cache");
+    let cache_dir = cache_dir.to_str().unwrap();
+
+    let (expected, _, success) = run_cli_with_files(&files, &["--threads", "1", "-q"]);
+    assert!(success);
+
+    for (threads, cached) in [("1", 0), ("1", 3), ("4", 3)] {
+        let (stdout, stderr, success) =
+            run_cli_with_files(&files, &["--threads", threads, "--cache-dir", cache_dir]);
+        assert!(success, "stderr: {}", stderr);
+        assert!(
+            stderr.contains(&format!("from 3 files ({} from cache)", cached)),
+            "stderr: {}",
+            stderr
+        );
+        assert_eq!(parse_schema(&stdout), parse_schema(&expected));
+    }
+}
+
+#[test]
+fn test_cache_rereads_changed_files() {
+    let dir = tempdir().unwrap();
+    let files = create_test_files(dir.path());
+    let cache_dir = dir.path().join("cache");
+    let cache_dir = cache_dir.to_str().unwrap();
+
+    let (_, _, success) = run_cli_with_files(&files, &["--cache-dir", cache_dir]);
+    assert!(success);
+
+    fs::write(&files[1], "{\"id\": 3, \"score\": \"high\"}\n{\"id\": 5}\n").unwrap();
+    set_modified(&files[1], 10);
+    let (stdout, stderr, success) = run_cli_with_files(&files, &["--cache-dir", cache_dir]);
+    assert!(success);
+    assert!(
+        stderr.contains("Processed 5 lines from 3 files (2 from cache)"),
+        "stderr: {}",
+        stderr
+    );
+    let (expected, _, _) = run_cli_with_files(&files, &["--threads", "1", "-q"]);
+    assert_eq!(parse_schema(&stdout), parse_schema(&expected));
+
+    // Changing an inference option regenerates every file
+    let (_, stderr, success) =
+        run_cli_with_files(&files, &["--cache-dir", cache_dir, "--keep-nulls"]);
+    assert!(success);
+    assert!(stderr.contains("(0 from cache)"), "stderr: {}", stderr);
+}
+
+#[test]
+fn test_cache_hash_ignores_touched_files() {
+    let dir = tempdir().unwrap();
+    let files = create_test_files(dir.path());
+    let cache_dir = dir.path().join("cache");
+    let cache_dir = cache_dir.to_str().unwrap();
+    let args = ["--cache-dir", cache_dir, "--cache-hash"];
+
+    let (_, _, success) = run_cli_with_files(&files, &args);
+    assert!(success);
+
+    for file in &files {
+        set_modified(file, 10);
+    }
+    let (_, stderr, success) = run_cli_with_files(&files, &args);
+    assert!(success);
+    assert!(stderr.contains("(3 from cache)"), "stderr: {}", stderr);
+}
+
+#[test]
+fn test_cache_per_file() {
+    let dir = tempdir().unwrap();
+    let files = create_test_files(dir.path());
+    let cache_dir = dir.path().join("cache");
+    let out_dir = dir.path().join("out");
+    let cached_out_dir = dir.path().join("cached_out");
+
+    let (_, _, success) = run_cli_with_files(
+        &files,
+        &["--per-file", "--output-dir", out_dir.to_str().unwrap()],
+    );
+    assert!(success);
+
+    for cached in [0, 3] {
+        let (_, stderr, success) = run_cli_with_files(
+            &files,
+            &[
+                "--per-file",
+                "--output-dir",
+                cached_out_dir.to_str().unwrap(),
+                "--cache-dir",
+                cache_dir.to_str().unwrap(),
+            ],
+        );
+        assert!(success, "stderr: {}", stderr);
+        assert!(
+            stderr.contains(&format!("Read {} of 3 files from cache", cached)),
+            "stderr: {}",
+            stderr
+        );
+        for i in 0..3 {
+            let name = format!("data{}.schema.json", i);
+            assert_eq!(
+                parse_schema(&fs::read_to_string(out_dir.join(&name)).unwrap()),
+                parse_schema(&fs::read_to_string(cached_out_dir.join(&name)).unwrap()),
+                "{}",
+                name
+            );
+        }
+    }
+}
+
+#[test]
+fn test_cache_with_existing_schema() {
+    let dir = tempdir().unwrap();
+    let files = create_test_files(dir.path());
+    let cache_dir = dir.path().join("cache");
+    let existing = dir.path().join("existing.json");
+    fs::write(
+        &existing,
+        r#"[{"name": "legacy", "type": "STRING", "mode": "NULLABLE"}]"#,
+    )
+    .unwrap();
+    let args = [
+        "--existing-schema-path",
+        existing.to_str().unwrap(),
+        "--cache-dir",
+        cache_dir.to_str().unwrap(),
+    ];
+
+    for _ in 0..2 {
+        let (stdout, stderr, success) = run_cli_with_files(&files, &args);
+        assert!(success, "stderr: {}", stderr);
+        let names: Vec<String> = parse_schema(&stdout)
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|field| field["name"].as_str().unwrap().to_string())
+            .collect();
+        assert!(names.contains(&"legacy".to_string()), "{:?}", names);
+        assert!(names.contains(&"score".to_string()), "{:?}", names);
+    }
+}
+
+#[test]
+fn test_cache_matches_uncached_with_existing_schema() {
+    let dir = tempdir().unwrap();
+    let mut files = create_test_files(dir.path());
+    let blob = dir.path().join("blob.json");
+    fs::write(&blob, "{\"id\": 5, \"blob\": \"aGVsbG8=\"}\n").unwrap();
+    files.push(blob.to_string_lossy().to_string());
+    let existing = dir.path().join("existing.json");
+    fs::write(
+        &existing,
+        r#"[{"name": "blob", "type": "BYTES", "mode": "NULLABLE"}]"#,
+    )
+    .unwrap();
+    let cache_dir = dir.path().join("cache");
+    let existing_args = ["--existing-schema-path", existing.to_str().unwrap()];
+
+    let (expected, _, success) = run_cli_with_files(
+        &files,
+        &[&existing_args[..], &["--threads", "1", "-q"]].concat(),
+    );
+    assert!(success);
+    let expected = parse_schema(&expected);
+    let blob_type = expected
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|field| field["name"] == "blob")
+        .map(|field| field["type"].clone());
+    assert_eq!(blob_type, Some("BYTES".into()));
+
+    // Generated and cached schemas alike must see the existing BYTES column
+    for threads in ["1", "4", "1"] {
+        let (stdout, stderr, success) = run_cli_with_files(
+            &files,
+            &[
+                &existing_args[..],
+                &[
+                    "--threads",
+                    threads,
+                    "--cache-dir",
+                    cache_dir.to_str().unwrap(),
+                ],
+            ]
+            .concat(),
+        );
+        assert!(success, "stderr: {}", stderr);
+        assert_eq!(parse_schema(&stdout), expected, "threads: {}", threads);
+    }
+
+    // Entries generated without the existing schema aren't reused with it
+    let (_, stderr, success) =
+        run_cli_with_files(&files, &["--cache-dir", cache_dir.to_str().unwrap()]);
+    assert!(success);
+    assert!(stderr.contains("(0 from cache)"), "stderr: {}", stderr);
+}
+
+#[test]
+fn test_cache_dir_requires_files() {
+    let output = Command::new("./target/debug/bq-schema-gen")
+        .args(["--cache-dir", "/tmp/unused-cache"])
+        .stdin(Stdio::null())
+        .output()
+        .expect("Failed to run command");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--cache-dir requires input files"));
+}
@@ -15,9 +15,14 @@ fn run_cli_json(input: &str, args: &[&str]) -> (String, String, bool) {
 
     {
         let stdin = cmd.stdin.as_mut().expect("Failed to open stdin");
-        stdin
-            .write_all(input.as_bytes())
-            .expect("Failed to write to stdin");
+        // The CLI may exit on invalid arguments before reading its input
+        if let Err(e) = stdin.write_all(input.as_bytes()) {
+            assert_eq!(
+                e.kind(),
+                std::io::ErrorKind::BrokenPipe,
+                "Failed to write to stdin"
+            );
+        }
     }
 
     let output = cmd.wait_with_output().expect("Failed to read output");
@@ -135,6 +140,365 @@ fn test_cli_sanitize_names() {
     }
 }
 
+#[test]
+fn test_cli_infer_numeric_flag() {
+    let input = r#"{"amount": 12345678901234.56789, "price": 1.5}
+{"amount": "99999999999999999999"}"#;
+
+    // Without flag - lossy decimals stay FLOAT
+    let (stdout, _, _) = run_cli_json(input, &[]);
+    let schema = parse_schema(&stdout);
+    let arr = schema.as_array().unwrap();
+    assert!(arr.iter().all(|f| f["type"] == "FLOAT"));
+
+    // With flag - amount needs NUMERIC, price round-trips as FLOAT
+    let (stdout, _, success) = run_cli_json(input, &["--infer-numeric"]);
+    assert!(success);
+    let schema = parse_schema(&stdout);
+    let arr = schema.as_array().unwrap();
+    assert!(arr
+        .iter()
+        .any(|f| f["name"] == "amount" && f["type"] == "NUMERIC"));
+    assert!(arr
+        .iter()
+        .any(|f| f["name"] == "price" && f["type"] == "FLOAT"));
+
+    let (stdout, _, success) = run_cli_json(
+        input,
+        &[
+            "--infer-numeric",
+            "--output-format",
+            "ddl",
+            "--table-name",
+            "db.t",
+        ],
+    );
+    assert!(success);
+    assert!(stdout.contains("amount NUMERIC"));
+}
+
+#[test]
+fn test_cli_number_limits() {
+    // Numbers beyond the f64 range are invalid JSON, as serde_json reads them
+    let input = "{\"a\": 1e400}\n{\"a\": 1.5, \"b\": [1, 2]}";
+    let (_, stderr, success) = run_cli_json(input, &[]);
+    assert!(!success);
+    assert!(stderr.contains("number out of range"), "stderr: {}", stderr);
+
+    let (stdout, _, success) = run_cli_json(input, &["--ignore-invalid-lines"]);
+    assert!(success);
+    let schema = parse_schema(&stdout);
+    assert_eq!(schema[0]["name"], "a");
+    assert_eq!(schema[0]["type"], "FLOAT");
+    assert_eq!(schema[1]["type"], "INTEGER");
+    assert_eq!(schema[1]["mode"], "REPEATED");
+}
+
+#[test]
+fn test_cli_infer_numeric_with_stats() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let stats = dir.path().join("stats.json");
+    let input = r#"{"amount": 12345678901234.56789, "items": [{"price": 0.12345678901234567891}]}"#;
+
+    // Stats don't change how exactly numbers are read
+    let (expected, _, success) = run_cli_json(input, &["--infer-numeric"]);
+    assert!(success);
+    let (stdout, stderr, success) = run_cli_json(
+        input,
+        &["--infer-numeric", "--stats", stats.to_str().unwrap()],
+    );
+    assert!(success, "stderr: {}", stderr);
+    assert_eq!(parse_schema(&stdout), parse_schema(&expected));
+    assert_eq!(parse_schema(&stdout)[0]["type"], "NUMERIC");
+    assert_eq!(parse_schema(&stdout)[1]["fields"][0]["type"], "BIGNUMERIC");
+}
+
+#[test]
+fn test_cli_bytes_inference_flags() {
+    let input =
+        r#"{"blob": "aGVsbG8gd29ybGQgYmFzZTY0IGRhdGE=", "key_b64": "YWJj", "name": "hello"}"#;
+
+    // Without flags - everything is STRING
+    let (stdout, _, _) = run_cli_json(input, &[]);
+    let schema = parse_schema(&stdout);
+    assert!(schema
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|f| f["type"] == "STRING"));
+
+    let (stdout, _, success) = run_cli_json(
+        input,
+        &["--bytes-min-length", "16", "--bytes-field-pattern", "_b64$"],
+    );
+    assert!(success);
+    let schema = parse_schema(&stdout);
+    let arr = schema.as_array().unwrap();
+    assert!(arr
+        .iter()
+        .any(|f| f["name"] == "blob" && f["type"] == "BYTES"));
+    assert!(arr
+        .iter()
+        .any(|f| f["name"] == "key_b64" && f["type"] == "BYTES"));
+    assert!(arr
+        .iter()
+        .any(|f| f["name"] == "name" && f["type"] == "STRING"));
+
+    // Invalid pattern is reported
+    let (_, stderr, success) = run_cli_json(input, &["--bytes-field-pattern", "("]);
+    assert!(!success);
+    assert!(stderr.contains("--bytes-field-pattern"));
+}
+
+#[test]
+fn test_cli_infer_geography_flag() {
+    let input =
+        r#"{"wkt": "POINT(-122.3 47.6)", "geo": {"type": "Point", "coordinates": [-122.3, 47.6]}}"#;
+
+    let (stdout, _, success) = run_cli_json(
+        input,
+        &[
+            "--infer-geography",
+            "--output-format",
+            "ddl",
+            "--table-name",
+            "db.t",
+        ],
+    );
+    assert!(success);
+    assert!(stdout.contains("wkt GEOGRAPHY"));
+    assert!(stdout.contains("geo GEOGRAPHY"));
+}
+
+#[test]
+fn test_cli_conflict_policy() {
+    let input = r#"{"id": 1, "value": 1}
+{"id": 2, "value": "a"}
+{"id": 3, "value": 2}"#;
+
+    // Default drops the field and reports it in the summary
+    let (stdout, stderr, success) = run_cli_json(input, &[]);
+    assert!(success);
+    assert_eq!(parse_schema(&stdout).as_array().unwrap().len(), 1);
+    assert!(stderr.contains("value: dropped (2 values)"));
+
+    let (stdout, stderr, success) = run_cli_json(input, &["--conflict-policy", "string"]);
+    assert!(success);
+    assert!(stdout.contains("STRING"));
+    assert!(stderr.contains("value: coerced to STRING (2 values)"));
+
+    let (stdout, stderr, success) = run_cli_json(input, &["--conflict-policy", "keep-first"]);
+    assert!(success);
+    let schema = parse_schema(&stdout);
+    assert!(schema
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|f| f["type"] == "INTEGER"));
+    assert!(stderr.contains("value: kept first type (1 value)"));
+
+    // Fail aborts even when invalid lines are ignored
+    for args in [
+        &["--conflict-policy", "fail"][..],
+        &["--conflict-policy", "fail", "--ignore-invalid-lines"][..],
+    ] {
+        let (_, stderr, success) = run_cli_json(input, args);
+        assert!(!success);
+        assert!(stderr.contains("Conflicting schema for field \"value\" on line 2"));
+    }
+}
+
+#[test]
+fn test_cli_stats_report() {
+    let input = r#"{"id": 1, "ts": "2024-01-02T00:00:00Z", "tags": ["a", "b"]}
+{"id": 4, "ts": "2023-06-01T12:00:00Z", "tags": []}
+{"id": null, "ts": "2024-03-01T00:00:00Z"}"#;
+    let dir = tempfile::TempDir::new().unwrap();
+
+    let stats_path = dir.path().join("stats.json");
+    let (stdout, _, success) = run_cli_json(input, &["--stats", stats_path.to_str().unwrap()]);
+    assert!(success);
+    assert_eq!(parse_schema(&stdout).as_array().unwrap().len(), 3);
+
+    let report: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&stats_path).unwrap()).unwrap();
+    assert_eq!(report["record_count"], 3);
+    let fields = report["fields"].as_array().unwrap();
+    let field = |path: &str| fields.iter().find(|f| f["path"] == path).unwrap();
+    assert_eq!(field("id")["null_count"], 1);
+    assert_eq!(field("id")["min"], 1);
+    assert_eq!(field("id")["max"], 4);
+    assert_eq!(field("ts")["min"], "2023-06-01T12:00:00Z");
+    assert_eq!(field("tags")["presence_count"], 2);
+    assert_eq!(field("tags")["max_array_length"], 2);
+    assert_eq!(field("tags")["distinct_estimate"], 2);
+
+    let stats_path = dir.path().join("stats.md");
+    let (_, _, success) = run_cli_json(
+        input,
+        &[
+            "--stats",
+            stats_path.to_str().unwrap(),
+            "--stats-format",
+            "markdown",
+        ],
+    );
+    assert!(success);
+    let markdown = std::fs::read_to_string(&stats_path).unwrap();
+    assert!(markdown.contains("3 records profiled."));
+    assert!(markdown.contains("| `id` | 3 | 1 | 2 | `1` | `4` |"));
+}
+
+#[test]
+fn test_cli_type_hints() {
+    let input = r#"{"zip_code": 94103, "event_ts": 1700000000, "name": "a"}
+{"zip_code": 10001, "event_ts": 1700000001, "name": "b"}"#;
+    let dir = tempfile::TempDir::new().unwrap();
+    let hints_path = dir.path().join("hints.toml");
+    std::fs::write(
+        &hints_path,
+        "zip_code = \"STRING\"\n\"*_ts\" = { type = \"TIMESTAMP\", mode = \"REQUIRED\" }\n",
+    )
+    .unwrap();
+
+    let (stdout, _, success) = run_cli_json(input, &["--type-hints", hints_path.to_str().unwrap()]);
+    assert!(success);
+    let schema = parse_schema(&stdout);
+    let types: Vec<_> = schema
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| {
+            format!(
+                "{} {} {}",
+                f["name"].as_str().unwrap(),
+                f["type"].as_str().unwrap(),
+                f["mode"].as_str().unwrap()
+            )
+        })
+        .collect();
+    assert_eq!(
+        types,
+        vec![
+            "event_ts TIMESTAMP REQUIRED",
+            "name STRING NULLABLE",
+            "zip_code STRING NULLABLE"
+        ]
+    );
+}
+
+#[test]
+fn test_cli_epoch_detection() {
+    let input = r#"{"created_at": 1700000000, "updated_ms": 1700000000123, "user_id": 1234567890}
+{"created_at": 1700000001, "updated_ms": 1700000000456, "user_id": 1234567891}"#;
+
+    let field_types = |stdout: &str| -> Vec<String> {
+        parse_schema(stdout)
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| {
+                format!(
+                    "{} {}",
+                    f["name"].as_str().unwrap(),
+                    f["type"].as_str().unwrap()
+                )
+            })
+            .collect()
+    };
+
+    let (stdout, _, success) = run_cli_json(input, &["--infer-epochs"]);
+    assert!(success);
+    assert_eq!(
+        field_types(&stdout),
+        vec![
+            "created_at TIMESTAMP",
+            "updated_ms TIMESTAMP",
+            "user_id TIMESTAMP"
+        ]
+    );
+
+    let (stdout, _, success) = run_cli_json(
+        input,
+        &[
+            "--epoch-field-pattern",
+            "_(at|ms)$",
+            "--epoch-units",
+            "seconds",
+        ],
+    );
+    assert!(success);
+    assert_eq!(
+        field_types(&stdout),
+        vec![
+            "created_at TIMESTAMP",
+            "updated_ms INTEGER",
+            "user_id INTEGER"
+        ]
+    );
+}
+
+#[test]
+fn test_cli_datetime_formats() {
+    let input = r#"{"day": "03/15/2024", "sent": "Fri, 15 Mar 2024 14:22:01 +0000", "local": "15.03.2024 14:22"}
+{"day": "2024-03-16", "sent": "Sat, 16 Mar 2024 08:00:00 -0500", "local": "16.03.2024 08:00"}"#;
+
+    let (stdout, _, success) = run_cli_json(
+        input,
+        &[
+            "--datetime-format",
+            "DATE=%m/%d/%Y",
+            "--datetime-format",
+            "TIMESTAMP@sent=%a, %d %b %Y %T %z",
+        ],
+    );
+    assert!(success);
+    let schema = parse_schema(&stdout);
+    let types: Vec<_> = schema
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| {
+            format!(
+                "{} {}",
+                f["name"].as_str().unwrap(),
+                f["type"].as_str().unwrap()
+            )
+        })
+        .collect();
+    assert_eq!(types, vec!["day DATE", "local STRING", "sent TIMESTAMP"]);
+}
+
+#[test]
+fn test_cli_json_array_input() {
+    let pretty = r#"[
+  {
+    "name": "a",
+    "count": 1
+  },
+  {"name": "b", "active": true}
+]"#;
+    let concatenated =
+        "{\"name\": \"a\", \"count\": 1}\n{\n  \"name\": \"b\",\n  \"active\": true\n}\n";
+
+    // Arrays are detected; concatenated values after a one-line value need the flag
+    for (input, args) in [
+        (pretty, &[][..]),
+        (concatenated, &["--input-format", "json-array"][..]),
+    ] {
+        let (stdout, stderr, success) = run_cli_json(input, args);
+        assert!(success, "{}", stderr);
+        let schema = parse_schema(&stdout);
+        let names: Vec<_> = schema
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| f["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, vec!["active", "count", "name"]);
+    }
+}
+
 #[test]
 fn test_cli_ignore_invalid_lines() {
     let input = r#"{"valid": 1}
@@ -155,6 +519,23 @@ not valid json
     assert_eq!(arr.len(), 2);
 }
 
+#[test]
+fn test_cli_ignore_truncated_first_line() {
+    let input = "{\"a\": 1, \"b\": \n{\"c\": 2}\n{\"d\": \"x\"}\n";
+    let (stdout, stderr, success) = run_cli_json(input, &["--ignore_invalid_lines"]);
+    assert!(success, "stderr={}", stderr);
+    assert!(stderr.contains("Processed 2 lines"), "stderr={}", stderr);
+
+    let schema = parse_schema(&stdout);
+    let names: Vec<_> = schema
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| f["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["c", "d"]);
+}
+
 // =============================================================================
 // CLI CSV TESTS
 // =============================================================================
@@ -406,6 +787,44 @@ fn test_cli_existing_schema_path() {
         .any(|f| f["name"] == "new_field" && f["type"] == "INTEGER"));
 }
 
+#[test]
+fn test_cli_existing_schema_keeps_decimal_params() {
+    use tempfile::NamedTempFile;
+
+    let mut schema_file = NamedTempFile::new().expect("Failed to create temp file");
+    let existing_schema = r#"[{"name": "price", "type": "NUMERIC", "mode": "NULLABLE", "precision": "10", "scale": "2"}]"#;
+    schema_file
+        .write_all(existing_schema.as_bytes())
+        .expect("Failed to write schema");
+    let schema_path = schema_file.path().to_str().unwrap();
+
+    let input = r#"{"id": 1}"#;
+    let (stdout, stderr, success) = run_cli_json(input, &["--existing_schema_path", schema_path]);
+    assert!(success, "CLI should succeed: stderr={}", stderr);
+    let schema = parse_schema(&stdout);
+    let price = schema
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|f| f["name"] == "price")
+        .unwrap();
+    assert_eq!(price["type"], "NUMERIC");
+    assert_eq!(price["precision"], "10");
+    assert_eq!(price["scale"], "2");
+
+    let (stdout, _, success) = run_cli_json(
+        input,
+        &[
+            "--existing_schema_path",
+            schema_path,
+            "--output_format",
+            "ddl",
+        ],
+    );
+    assert!(success);
+    assert!(stdout.contains("price NUMERIC(10, 2)"), "{}", stdout);
+}
+
 #[test]
 fn test_cli_existing_schema_path_invalid_file() {
     let input = r#"{"test": 1}"#;
@@ -516,6 +935,22 @@ fn test_cli_output_format_debug_map() {
     assert!(name_entry.get("mode").is_some());
 }
 
+#[test]
+fn test_cli_debug_map_provenance_is_opt_in() {
+    let input = "{\"name\": \"test\"}\n{\"name\": \"x\", \"value\": 42}\n";
+
+    let (stdout, _, success) = run_cli_json(input, &["--output-format", "debug-map"]);
+    assert!(success);
+    let debug: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert!(debug["value"].get("provenance").is_none());
+
+    let (stdout, _, success) =
+        run_cli_json(input, &["--output-format", "debug-map", "--provenance"]);
+    assert!(success);
+    let debug: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(debug["value"]["provenance"]["first_seen"]["line"], 2);
+}
+
 #[test]
 fn test_cli_output_format_json_schema() {
     let input = r#"{"name": "test", "count": 42, "active": true}"#;
@@ -544,6 +979,71 @@ fn test_cli_output_format_json_schema() {
     assert_eq!(props["active"]["type"], "boolean");
 }
 
+#[test]
+fn test_cli_output_format_state_merged_with_seed_state() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let parts = [
+        "{\"id\": 1, \"score\": null, \"flag\": \"true\"}\n{\"id\": 2, \"tags\": []}",
+        "{\"id\": 3, \"score\": 1.5, \"flag\": \"false\"}",
+        "{\"id\": 4, \"tags\": [\"a\"]}",
+    ];
+
+    // Each "worker" writes the state of its own part
+    let mut states = Vec::new();
+    for (i, part) in parts.iter().enumerate() {
+        let path = dir.path().join(format!("part{}.state.json", i));
+        let (_, stderr, success) = run_cli_json(
+            part,
+            &[
+                "--infer-mode",
+                "--output-format",
+                "state",
+                "-o",
+                path.to_str().unwrap(),
+            ],
+        );
+        assert!(success, "stderr: {}", stderr);
+        let state: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(state["version"], 1);
+        states.push(path.to_str().unwrap().to_string());
+    }
+
+    let (expected, _, _) = run_cli_json(&parts.join("\n"), &["--infer-mode"]);
+
+    // Merging the states gives the schema of a single run over all parts
+    let mut args = vec!["--infer-mode"];
+    for state in &states {
+        args.extend(["--seed-state", state.as_str()]);
+    }
+    let (stdout, stderr, success) = run_cli_json("", &args);
+    assert!(success, "stderr: {}", stderr);
+    assert_eq!(parse_schema(&stdout), parse_schema(&expected));
+
+    // A state can also be resumed with more input
+    let (stdout, _, success) = run_cli_json(
+        &parts[1..].join("\n"),
+        &["--infer-mode", "--seed-state", &states[0]],
+    );
+    assert!(success);
+    assert_eq!(parse_schema(&stdout), parse_schema(&expected));
+}
+
+#[test]
+fn test_cli_seed_state_rejects_bigquery_schema() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = dir.path().join("schema.json");
+    std::fs::write(&path, r#"[{"name": "id", "type": "INTEGER"}]"#).unwrap();
+
+    let (_, stderr, success) = run_cli_json("{}", &["--seed-state", path.to_str().unwrap()]);
+    assert!(!success);
+    assert!(
+        stderr.contains("Cannot load schema state from"),
+        "stderr: {}",
+        stderr
+    );
+}
+
 #[test]
 fn test_cli_output_format_json_schema_with_required() {
     let input = r#"{"id": 1, "name": "test"}
@@ -1,6 +1,9 @@
 //! CSV-specific tests for BigQuery Schema Generator
 
-use bq_schema_gen::{generate_schema_from_csv, GeneratorConfig, InputFormat};
+use bq_schema_gen::{
+    generate_schema_from_csv, CsvDialect, CsvRecordIterator, GeneratorConfig, InputFormat,
+    SchemaGenerator, SchemaMap,
+};
 use serde_json::Value;
 use std::io::Cursor;
 
@@ -277,3 +280,66 @@ fn test_csv_complex_type_inference() {
     ));
     assert!(has_field(&schema, "score", "FLOAT", "NULLABLE"));
 }
+
+#[test]
+fn test_csv_dialect_with_null_markers() {
+    let csv = "nightly report\nid;name;score\n1;'a;b';\\N\n2;c;3.5\n";
+    let config = GeneratorConfig {
+        input_format: InputFormat::Csv,
+        infer_mode: true,
+        csv_dialect: CsvDialect {
+            delimiter: b';',
+            quote: b'\'',
+            skip_leading_rows: 1,
+            null_markers: vec!["\\N".to_string()],
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let schema = generate_csv_schema(csv, config);
+
+    assert_eq!(schema.len(), 3);
+    assert!(has_field(&schema, "id", "INTEGER", "REQUIRED"));
+    assert!(has_field(&schema, "name", "STRING", "REQUIRED"));
+    // The null marker leaves score unfilled in the first row
+    assert!(has_field(&schema, "score", "FLOAT", "NULLABLE"));
+}
+
+#[test]
+fn test_csv_empty_as_null() {
+    let csv = "id,score,note\n1,,x\n2,3.5,\n3,,y\n";
+    let config = |empty_as_null: bool| GeneratorConfig {
+        input_format: InputFormat::Csv,
+        infer_mode: true,
+        collect_stats: true,
+        csv_dialect: CsvDialect {
+            empty_as_null,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    // Mode and type inference see the same empty cells either way
+    assert_eq!(
+        generate_csv_schema(csv, config(false)),
+        generate_csv_schema(csv, config(true))
+    );
+    let schema = generate_csv_schema(csv, config(true));
+    assert!(has_field(&schema, "id", "INTEGER", "REQUIRED"));
+    assert!(has_field(&schema, "score", "FLOAT", "NULLABLE"));
+    assert!(has_field(&schema, "note", "STRING", "NULLABLE"));
+
+    // The generator receives real nulls
+    let config = config(true);
+    let iter = CsvRecordIterator::with_dialect(Cursor::new(csv), &config.csv_dialect).unwrap();
+    let mut generator = SchemaGenerator::new(config);
+    let mut schema_map = SchemaMap::new();
+    for record in iter {
+        generator
+            .process_record(&record.unwrap().1, &mut schema_map)
+            .unwrap();
+    }
+    assert_eq!(generator.profile().fields["score"].null_count, 2);
+    assert_eq!(generator.profile().fields["note"].null_count, 1);
+}
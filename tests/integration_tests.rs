@@ -3,7 +3,7 @@
 //! These tests verify the schema generation against various input scenarios,
 //! matching the behavior of the Python bigquery-schema-generator.
 
-use bq_schema_gen::{GeneratorConfig, SchemaGenerator, SchemaMap};
+use bq_schema_gen::{bq_schema_to_map, GeneratorConfig, SchemaGenerator, SchemaMap};
 use serde_json::Value;
 
 /// Helper to generate schema from JSON strings
@@ -476,6 +476,38 @@ fn test_date_time_formats() {
     assert!(has_field(&schema, "qdt", "TIMESTAMP", "NULLABLE"));
 }
 
+#[test]
+fn test_datetime_vs_timestamp() {
+    let (schema, _) = generate_schema(
+        &[
+            r#"{ "dt": "2018-12-07T21:52:00", "ts": "2018-12-07T21:52:00Z", "mixed": "2018-12-07 21:52:00" }"#,
+            r#"{ "dt": "2018-12-08 06:00:00.123", "mixed": "2018-12-07 21:52:00 UTC" }"#,
+        ],
+        GeneratorConfig::default(),
+    );
+
+    assert!(has_field(&schema, "dt", "DATETIME", "NULLABLE"));
+    assert!(has_field(&schema, "ts", "TIMESTAMP", "NULLABLE"));
+    // DATETIME + TIMESTAMP widens to TIMESTAMP
+    assert!(has_field(&schema, "mixed", "TIMESTAMP", "NULLABLE"));
+}
+
+#[test]
+fn test_existing_schema_preserves_datetime() {
+    let existing = bq_schema_to_map(&serde_json::json!([
+        {"name": "created", "type": "DATETIME", "mode": "NULLABLE"}
+    ]))
+    .unwrap();
+
+    let mut generator = SchemaGenerator::new(GeneratorConfig::default());
+    let mut schema_map = existing;
+    let record: Value = serde_json::from_str(r#"{"created": "2024-01-01 10:00:00"}"#).unwrap();
+    generator.process_record(&record, &mut schema_map).unwrap();
+
+    let schema = generator.flatten_schema(&schema_map);
+    assert_eq!(schema[0].field_type, "DATETIME");
+}
+
 // =============================================================================
 // FIELD MERGING TESTS
 // =============================================================================
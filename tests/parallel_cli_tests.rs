@@ -253,6 +253,88 @@ fn test_parallel_type_widening_across_files() {
     );
 }
 
+#[test]
+fn test_parallel_keep_first_matches_sequential() {
+    let dir = tempdir().expect("Failed to create temp dir");
+
+    // The first file makes "x" a record; the second starts with an integer "x"
+    let path1 = dir.path().join("data1.json");
+    File::create(&path1)
+        .unwrap()
+        .write_all(b"{\"x\": {\"a\": 1}}\n")
+        .unwrap();
+    let path2 = dir.path().join("data2.json");
+    File::create(&path2)
+        .unwrap()
+        .write_all(b"{\"x\": 2}\n{\"x\": {\"b\": 3}}\n")
+        .unwrap();
+    let files = [path1.to_str().unwrap(), path2.to_str().unwrap()];
+
+    let (expected, _, success) = run_cli_with_files(
+        &files,
+        &["--conflict-policy", "keep-first", "--threads", "1", "-q"],
+    );
+    assert!(success);
+    let expected = parse_schema(&expected);
+    assert_eq!(expected[0]["fields"][1]["name"], "b");
+
+    // The second file alone would keep its integer and drop "x.b"
+    let (stdout, _, success) = run_cli_with_files(
+        &files,
+        &["--conflict-policy", "keep-first", "--threads", "4", "-q"],
+    );
+    assert!(success);
+    assert_eq!(parse_schema(&stdout), expected);
+}
+
+#[test]
+fn test_parallel_existing_schema_types_match_sequential() {
+    let dir = tempdir().expect("Failed to create temp dir");
+
+    let existing = dir.path().join("existing.json");
+    File::create(&existing)
+        .unwrap()
+        .write_all(br#"[{"name": "blob", "type": "BYTES"}, {"name": "id", "type": "INTEGER"}]"#)
+        .unwrap();
+    let path1 = dir.path().join("data1.json");
+    File::create(&path1)
+        .unwrap()
+        .write_all(br#"{"id": 1, "blob": "aGVsbG8="}"#)
+        .unwrap();
+    let path2 = dir.path().join("data2.json");
+    File::create(&path2)
+        .unwrap()
+        .write_all(br#"{"id": 2, "blob": "d29ybGQ="}"#)
+        .unwrap();
+    let files = [path1.to_str().unwrap(), path2.to_str().unwrap()];
+
+    // Each file's generator must see the existing BYTES column, as a single pass does
+    let mut outputs = Vec::new();
+    for threads in ["1", "2"] {
+        let (stdout, stderr, success) = run_cli_with_files(
+            &files,
+            &[
+                "--threads",
+                threads,
+                "-q",
+                "--existing-schema-path",
+                existing.to_str().unwrap(),
+            ],
+        );
+        assert!(success, "stderr: {}", stderr);
+        outputs.push(parse_schema(&stdout));
+    }
+
+    assert_eq!(outputs[0], outputs[1]);
+    let blob = outputs[1]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|f| f["name"] == "blob")
+        .unwrap();
+    assert_eq!(blob["type"], "BYTES");
+}
+
 // =============================================================================
 // ERROR HANDLING TESTS
 // =============================================================================
@@ -393,6 +475,36 @@ fn test_parallel_csv_files() {
     assert_eq!(arr.len(), 3, "Should have id, name, value from CSV");
 }
 
+#[test]
+fn test_parallel_compressed_files() {
+    let dir = tempdir().expect("Failed to create temp dir");
+
+    let gz = dir.path().join("a.json.gz");
+    let mut encoder =
+        flate2::write::GzEncoder::new(File::create(&gz).unwrap(), flate2::Compression::default());
+    writeln!(encoder, r#"{{"id": 1, "name": "a"}}"#).unwrap();
+    encoder.finish().unwrap();
+
+    let zst = dir.path().join("b.json.zst");
+    let data = zstd::encode_all(&br#"{"id": 2, "active": true}"#[..], 0).unwrap();
+    std::fs::write(&zst, data).unwrap();
+
+    let (stdout, stderr, success) = run_cli_with_files(
+        &[gz.to_str().unwrap(), zst.to_str().unwrap()],
+        &["--threads", "2", "-q"],
+    );
+
+    assert!(success, "{}", stderr);
+    let schema = parse_schema(&stdout);
+    let names: Vec<_> = schema
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| f["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["active", "id", "name"]);
+}
+
 // =============================================================================
 // GLOB PATTERN TESTS WITH PARALLEL
 // =============================================================================
@@ -9,7 +9,8 @@ use bq_schema_gen::{
     inference::{convert_type, infer_type_from_json},
     schema::types::{BqType, SchemaMap},
     validate::{SchemaValidator, ValidationOptions, ValidationResult},
-    BqSchemaField, GeneratorConfig, SchemaGenerator,
+    BqSchemaField, ConflictPolicy, GeneratorConfig, RawRecord, SchemaGenerator, TypeHint,
+    TypeHints,
 };
 
 // =============================================================================
@@ -43,6 +44,163 @@ fn simple_json_object() -> impl Strategy<Value = serde_json::Value> {
     })
 }
 
+/// Strategy to generate the JSON text of a value, nesting objects and arrays.
+///
+/// Text is written out by hand to keep escapes and repeated keys, which a
+/// `serde_json::Value` would lose.
+fn json_text_value() -> impl Strategy<Value = String> {
+    let leaf = prop_oneof![
+        Just("null".to_string()),
+        any::<bool>().prop_map(|b| b.to_string()),
+        any::<i32>().prop_map(|i| i.to_string()),
+        (-1e6f64..1e6).prop_map(|f| format!("{:?}", f)),
+        prop_oneof![
+            Just("-0"),
+            Just("1e2"),
+            Just("-2.5E-3"),
+            Just("1700000000"),
+            Just("9223372036854775808"),
+        ]
+        .prop_map(str::to_string),
+        proptest::sample::select(&WIDE_NUMBERS[..]).prop_map(str::to_string),
+        prop_oneof![
+            Just(r#""""#),
+            Just(r#""2024-01-15""#),
+            Just(r#""2024-02-30""#),
+            Just(r#""2024-01-15T10:30:00Z""#),
+            Just(r#""10:30:00""#),
+            Just(r#""123""#),
+            Just(r#""1.5""#),
+            Just(r#""true""#),
+            Just(r#""a\"b""#),
+            Just(r#""\u0032024-01-15""#),
+            Just(r#""POINT(1 2)""#),
+            Just(r#"{"type": "Point", "coordinates": [1, 2]}"#),
+        ]
+        .prop_map(str::to_string),
+        "[a-zA-Z0-9 _]{0,12}".prop_map(|s| format!("\"{}\"", s)),
+    ];
+    leaf.prop_recursive(3, 24, 4, |inner| {
+        prop_oneof![
+            proptest::collection::vec(inner.clone(), 0..4)
+                .prop_map(|items| format!("[{}]", items.join(", "))),
+            json_text_object(inner),
+        ]
+    })
+}
+
+/// Number literals wider than an `f64` or beyond `u64`.
+///
+/// A parsed record holds them as the nearest `f64`, typed FLOAT, while the
+/// raw path types them from every digit of their text.
+const WIDE_NUMBERS: [&str; 5] = [
+    "12345678901234567.89",
+    "-98765432109876543.21",
+    "18446744073709551616",
+    "-123456789012345678901234",
+    "123456789012345678901234567890",
+];
+
+/// Write the unquoted NUMERIC and BIGNUMERIC types in a `Debug` string as FLOAT.
+///
+/// Decimals merge with other types as FLOAT does, so a schema with wide
+/// numbers typed as decimals matches one with them typed as FLOAT after this.
+fn decimals_as_float(debug: &str) -> String {
+    let debug = regex::Regex::new(r"\b(Big)?Numeric \{ precision: \d+, scale: \d+ \}")
+        .unwrap()
+        .replace_all(debug, "Float");
+    let debug = regex::Regex::new(r"\b(BIG)?NUMERIC(\(\d+(, \d+)?\))?")
+        .unwrap()
+        .replace_all(&debug, "FLOAT");
+    regex::Regex::new(r#"precision: Some\("\d+"\), scale: Some\("\d+"\)"#)
+        .unwrap()
+        .replace_all(&debug, "precision: None, scale: None")
+        .into_owned()
+}
+
+/// Strategy to generate the JSON text of an object with values from `value`.
+///
+/// Keys come from a small set so that records share fields and repeat keys;
+/// some differ only in case and one is escaped.
+fn json_text_object(value: impl Strategy<Value = String>) -> impl Strategy<Value = String> {
+    let key = prop_oneof![
+        Just(r#""a""#),
+        Just(r#""A""#),
+        Just(r#""\u0061""#),
+        Just(r#""b""#),
+        Just(r#""c d""#),
+        Just(r#""geo""#),
+    ];
+    proptest::collection::vec((key, value), 0..5).prop_map(|fields| {
+        let fields: Vec<String> = fields
+            .iter()
+            .map(|(key, value)| format!("{}: {}", key, value))
+            .collect();
+        format!("{{{}}}", fields.join(", "))
+    })
+}
+
+/// Strategy to generate configs that change how strings, numbers and objects are handled
+fn raw_record_config() -> impl Strategy<Value = GeneratorConfig> {
+    (
+        any::<bool>(),
+        any::<bool>(),
+        any::<bool>(),
+        any::<bool>(),
+        any::<bool>(),
+        0..5usize,
+        any::<bool>(),
+        any::<bool>(),
+        any::<bool>(),
+    )
+        .prop_map(
+            |(
+                keep_nulls,
+                quoted,
+                geography,
+                sanitize,
+                provenance,
+                policy,
+                paths,
+                numeric,
+                stats,
+            )| {
+                let mut type_hints = TypeHints::new();
+                let mut json_paths = Vec::new();
+                if paths {
+                    json_paths.push("b".to_string());
+                    type_hints
+                        .insert(TypeHint {
+                            pattern: "a.a".to_string(),
+                            bq_type: Some(BqType::String),
+                            mode: None,
+                        })
+                        .unwrap();
+                }
+                GeneratorConfig {
+                    keep_nulls,
+                    quoted_values_are_strings: quoted,
+                    infer_geography: geography,
+                    sanitize_names: sanitize,
+                    track_provenance: provenance,
+                    conflict_policy: [
+                        ConflictPolicy::Ignore,
+                        ConflictPolicy::String,
+                        ConflictPolicy::Json,
+                        ConflictPolicy::Fail,
+                        ConflictPolicy::KeepFirst,
+                    ][policy],
+                    json_paths,
+                    type_hints,
+                    infer_numeric: numeric,
+                    epoch_detection: numeric.then(Default::default),
+                    collect_stats: stats,
+                    ..Default::default()
+                }
+            },
+        )
+}
+
 /// Strategy to generate BqType (non-Record variants only for simplicity)
 fn simple_bq_type() -> impl Strategy<Value = BqType> {
     prop_oneof![
@@ -50,7 +208,9 @@ fn simple_bq_type() -> impl Strategy<Value = BqType> {
         Just(BqType::Integer),
         Just(BqType::Float),
         Just(BqType::String),
+        Just(BqType::Bytes),
         Just(BqType::Timestamp),
+        Just(BqType::Datetime),
         Just(BqType::Date),
         Just(BqType::Time),
         Just(BqType::QBoolean),
@@ -447,3 +607,120 @@ proptest! {
         }
     }
 }
+
+// =============================================================================
+// RAW RECORD TESTS
+// =============================================================================
+
+proptest! {
+    /// Property: Records read as raw fields give the same schema, errors,
+    /// conflicts, provenance and stats as the fully parsed records, with
+    /// decimals compared as FLOAT when the parsed records lost digits
+    #[test]
+    fn prop_raw_records_match_parsed_records(
+        records in proptest::collection::vec(json_text_object(json_text_value()), 1..6),
+        config in raw_record_config(),
+    ) {
+        let mut parsed = SchemaGenerator::new(config.clone());
+        let mut parsed_map = SchemaMap::new();
+        let mut raw = SchemaGenerator::new(config);
+        let mut raw_map = SchemaMap::new();
+
+        // Where the parsed records lose digits, compare their decimals as FLOAT
+        let wide = records
+            .iter()
+            .any(|json| WIDE_NUMBERS.iter().any(|number| json.contains(number)));
+        let debug = |value: &dyn std::fmt::Debug| {
+            let debug = format!("{:?}", value);
+            if wide {
+                decimals_as_float(&debug)
+            } else {
+                debug
+            }
+        };
+
+        for json in &records {
+            let value: serde_json::Value = serde_json::from_str(json).unwrap();
+            let record = RawRecord::parse(json).unwrap();
+            let parsed_result = parsed
+                .process_record(&value, &mut parsed_map)
+                .map_err(|e| e.to_string());
+            let raw_result = raw
+                .process_raw_record(&record, &mut raw_map)
+                .map_err(|e| e.to_string());
+            prop_assert_eq!(debug(&raw_result), debug(&parsed_result), "{}", json);
+        }
+
+        prop_assert_eq!(debug(&raw_map), debug(&parsed_map));
+        prop_assert_eq!(
+            debug(&raw.flatten_schema(&raw_map)),
+            debug(&parsed.flatten_schema(&parsed_map))
+        );
+        let logs = |generator: &SchemaGenerator| {
+            generator
+                .error_logs()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        };
+        prop_assert_eq!(debug(&logs(&raw)), debug(&logs(&parsed)));
+        prop_assert_eq!(raw.conflicts(), parsed.conflicts());
+        // A decimal widening is no change to a FLOAT
+        let provenance = |generator: &SchemaGenerator| {
+            let mut provenance = generator.provenance().clone();
+            if wide {
+                for field in provenance.values_mut() {
+                    field.initial = decimals_as_float(&field.initial);
+                    for change in &mut field.changes {
+                        change.from = decimals_as_float(&change.from);
+                        change.to = decimals_as_float(&change.to);
+                    }
+                    field.changes.retain(|change| change.from != change.to);
+                }
+            }
+            provenance
+        };
+        prop_assert_eq!(provenance(&raw), provenance(&parsed));
+        prop_assert_eq!(raw.profile(), parsed.profile());
+    }
+
+    /// Property: With infer_numeric, the raw path types a wide number from
+    /// every digit of its text, at any depth
+    #[test]
+    fn prop_raw_records_keep_wide_number_digits(
+        number in proptest::sample::select(&WIDE_NUMBERS[..]),
+        depth in 0..4usize,
+    ) {
+        let json = format!(
+            "{}{{\"n\": {}}}{}",
+            "{\"r\": ".repeat(depth),
+            number,
+            "}".repeat(depth)
+        );
+        let mut generator = SchemaGenerator::new(GeneratorConfig {
+            infer_numeric: true,
+            ..Default::default()
+        });
+        let mut schema_map = SchemaMap::new();
+        generator
+            .process_raw_record(&RawRecord::parse(&json).unwrap(), &mut schema_map)
+            .unwrap();
+
+        let mut fields = &schema_map;
+        for _ in 0..depth {
+            let BqType::Record(nested) = &fields["r"].bq_type else {
+                panic!("expected a record: {:?}", fields["r"]);
+            };
+            fields = nested;
+        }
+        let entry = &fields["n"];
+        let digits = number.bytes().filter(u8::is_ascii_digit).count();
+        let scale = number.split_once('.').map_or(0, |(_, fraction)| fraction.len());
+        prop_assert_eq!(
+            entry.bq_type.decimal_spec(),
+            Some((digits as u8, scale as u8)),
+            "{}",
+            json
+        );
+    }
+}
@@ -70,27 +70,6 @@ fn test_watch_requires_files() {
     );
 }
 
-#[test]
-fn test_watch_incompatible_with_per_file() {
-    let dir = tempdir().expect("Failed to create temp dir");
-    let file_path = dir.path().join("test.json");
-    File::create(&file_path)
-        .unwrap()
-        .write_all(r#"{"id": 1}"#.as_bytes())
-        .unwrap();
-
-    let (_, stderr, success) = run_cli(&[file_path.to_str().unwrap(), "--watch", "--per-file"]);
-
-    assert!(!success, "Watch with --per-file should fail");
-    assert!(
-        stderr.contains("cannot be used")
-            || stderr.contains("incompatible")
-            || stderr.contains("--per-file"),
-        "Should report incompatibility. stderr: {}",
-        stderr
-    );
-}
-
 // =============================================================================
 // FLAG PARSING TESTS
 // =============================================================================
@@ -256,6 +235,65 @@ fn test_watch_with_output_file() {
     assert!(schema.is_array());
 }
 
+#[test]
+fn test_watch_per_file_writes_initial_schemas() {
+    let dir = tempdir().expect("Failed to create temp dir");
+    let data_dir = dir.path().join("data");
+    std::fs::create_dir(&data_dir).unwrap();
+    std::fs::write(data_dir.join("a.json"), r#"{"id": 1}"#).unwrap();
+    std::fs::write(data_dir.join("b.csv"), "name,count\nx,2\n").unwrap();
+    let output_dir = dir.path().join("schemas");
+
+    let mut child = Command::new("./target/debug/bq-schema-gen")
+        .args([
+            data_dir.join("a.json").to_str().unwrap(),
+            "--watch",
+            "--per-file",
+            "--output-dir",
+            output_dir.to_str().unwrap(),
+        ])
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to spawn command");
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    let _ = child.kill();
+    let _ = child.wait();
+
+    let content = std::fs::read_to_string(output_dir.join("a.schema.json"))
+        .expect("Per-file schema should be written to the output directory");
+    let schema: serde_json::Value = serde_json::from_str(&content).unwrap();
+    assert_eq!(schema[0]["name"], "id");
+
+    // CSV input is read with its header, not parsed as JSON
+    let mut child = Command::new("./target/debug/bq-schema-gen")
+        .args([
+            data_dir.join("b.csv").to_str().unwrap(),
+            "--watch",
+            "--per-file",
+            "--input-format",
+            "csv",
+        ])
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to spawn command");
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    let _ = child.kill();
+    let _ = child.wait();
+
+    let content = std::fs::read_to_string(data_dir.join("b.schema.json"))
+        .expect("Per-file schema should be written next to the input");
+    let schema: serde_json::Value = serde_json::from_str(&content).unwrap();
+    let names: Vec<_> = schema
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| f["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["name", "count"]);
+}
+
 // =============================================================================
 // QUIET MODE TESTS
 // =============================================================================